@@ -0,0 +1,18 @@
+//! Runtime API for querying token balances of the assets module off chain, without decoding its
+//! storage keys.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+client::decl_runtime_apis! {
+    pub trait TokenApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Balance of `who` in asset `asset_id`, zero for accounts holding none.
+        fn balance(asset_id: u32, who: AccountId) -> Balance;
+        /// Total issuance of asset `asset_id`, `None` if there is no such asset.
+        fn total_issuance(asset_id: u32) -> Option<Balance>;
+    }
+}
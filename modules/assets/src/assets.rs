@@ -0,0 +1,345 @@
+use codec::{Codec, Decode, Encode};
+use rstd::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sr_primitives::traits::{CheckedAdd, CheckedSub, Member, SimpleArithmetic, Zero};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::EnsureOrigin,
+    Parameter, StorageMap,
+};
+use system::{self, ensure_signed};
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type Balance: Parameter + Member + SimpleArithmetic + Codec + Default + Copy;
+    /// Freezes and thaws assets and accounts, and forces transfers.
+    type AdminOrigin: EnsureOrigin<Self::Origin>;
+}
+
+// how wallets display an asset, and who created it
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetDetails<AccountId, Balance> {
+    pub symbol: Vec<u8>,
+    pub decimals: u8,
+    pub owner: AccountId,
+    pub total_supply: Balance,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // transfer units of an asset from the origin to another account
+        fn transfer(origin, asset_id: u32, to: T::AccountId, value: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+            Self::move_balance(asset_id, &sender, &to, value)?;
+            Self::deposit_event(RawEvent::Transfer(asset_id, sender, to, value));
+            Ok(())
+        }
+
+        // stop every holder of an asset from moving it
+        fn freeze_asset(origin, asset_id: u32) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(<Assets<T>>::exists(asset_id), "Unknown asset.");
+            FrozenAssets::insert(asset_id, true);
+            Self::deposit_event(RawEvent::AssetFrozen(asset_id));
+            Ok(())
+        }
+
+        fn thaw_asset(origin, asset_id: u32) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            FrozenAssets::remove(asset_id);
+            Self::deposit_event(RawEvent::AssetThawed(asset_id));
+            Ok(())
+        }
+
+        // stop an account from moving its units of an asset, it may still receive some
+        fn freeze(origin, asset_id: u32, who: T::AccountId) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(<Assets<T>>::exists(asset_id), "Unknown asset.");
+            <FrozenAccounts<T>>::insert((asset_id, who.clone()), true);
+            Self::deposit_event(RawEvent::Frozen(asset_id, who));
+            Ok(())
+        }
+
+        fn thaw(origin, asset_id: u32, who: T::AccountId) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            <FrozenAccounts<T>>::remove((asset_id, who.clone()));
+            Self::deposit_event(RawEvent::Thawed(asset_id, who));
+            Ok(())
+        }
+
+        // move units of an asset between accounts, whether they are frozen or not
+        fn force_transfer(
+            origin,
+            asset_id: u32,
+            from: T::AccountId,
+            to: T::AccountId,
+            value: T::Balance
+        ) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::do_move_balance(asset_id, &from, &to, value)?;
+            Self::deposit_event(RawEvent::ForceTransfer(asset_id, from, to, value));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Assets {
+        // details of every asset, by id
+        Assets get(asset_details): map u32 => Option<AssetDetails<T::AccountId, T::Balance>>;
+        // balances mapping for an asset and account
+        BalanceOf get(balance_of): map (u32, T::AccountId) => T::Balance;
+        // assets no account may move
+        FrozenAssets get(is_asset_frozen): map u32 => bool;
+        // accounts which may not move an asset
+        FrozenAccounts get(is_frozen): map (u32, T::AccountId) => bool;
+    }
+    add_extra_genesis {
+        // (id, symbol, decimals, owner, initial distribution) of each asset, the total supply is
+        // the sum of the distribution
+        config(assets): Vec<(u32, Vec<u8>, u8, T::AccountId, Vec<(T::AccountId, T::Balance)>)>;
+        build(|config: &GenesisConfig<T>| {
+            for (id, symbol, decimals, owner, distribution) in &config.assets {
+                assert!(!<Assets<T>>::exists(id), "asset id is used twice");
+                let mut total_supply = T::Balance::zero();
+                for (account, balance) in distribution {
+                    total_supply = total_supply
+                        .checked_add(balance)
+                        .expect("total supply of an asset overflows");
+                    <BalanceOf<T>>::mutate((*id, account.clone()), |b| *b += *balance);
+                }
+                <Assets<T>>::insert(
+                    id,
+                    AssetDetails {
+                        symbol: symbol.clone(),
+                        decimals: *decimals,
+                        owner: owner.clone(),
+                        total_supply,
+                    },
+                );
+            }
+        });
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Move units of an asset between accounts, for modules holding assets on behalf of users.
+    /// Nothing is moved if `from` holds less than `value`, or if the asset or `from` is frozen.
+    pub fn move_balance(
+        asset_id: u32,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: T::Balance,
+    ) -> Result {
+        Self::ensure_movable(asset_id, from)?;
+        Self::do_move_balance(asset_id, from, to, value)
+    }
+
+    /// Fails if the asset or `from` is frozen, for modules moving several balances to check all
+    /// of them before moving any.
+    pub fn ensure_movable(asset_id: u32, from: &T::AccountId) -> Result {
+        ensure!(!Self::is_asset_frozen(asset_id), "Asset is frozen.");
+        ensure!(
+            !Self::is_frozen((asset_id, from.clone())),
+            "Account is frozen for this asset."
+        );
+        Ok(())
+    }
+
+    /// Move units of an asset between accounts whether they are frozen or not, for modules paying
+    /// out assets they hold. Nothing is moved if `from` holds less than `value`.
+    pub fn do_move_balance(
+        asset_id: u32,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: T::Balance,
+    ) -> Result {
+        let from_bal = Self::balance_of((asset_id, from.clone()))
+            .checked_sub(&value)
+            .ok_or("Not enough balance.")?;
+        let to_bal = Self::balance_of((asset_id, to.clone()))
+            .checked_add(&value)
+            .ok_or("Balance overflow in receiver account.")?;
+        if from != to {
+            <BalanceOf<T>>::insert((asset_id, from.clone()), from_bal);
+            <BalanceOf<T>>::insert((asset_id, to.clone()), to_bal);
+        }
+        Ok(())
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = <T as Trait>::Balance,
+    {
+        // asset id, from, to, value
+        Transfer(u32, AccountId, AccountId, Balance),
+        // asset id
+        AssetFrozen(u32),
+        // asset id
+        AssetThawed(u32),
+        // asset id, account
+        Frozen(u32, AccountId),
+        // asset id, account
+        Thawed(u32, AccountId),
+        // asset id, from, to, value
+        ForceTransfer(u32, AccountId, AccountId, Balance),
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Balance = u128;
+        type AdminOrigin = system::EnsureRoot<u64>;
+    }
+    type Assets = Module<Test>;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> {
+            assets: vec![
+                (0, b"USDX".to_vec(), 6, 1, vec![(1, 1000), (2, 10)]),
+                (3, b"GLD".to_vec(), 0, 2, vec![(2, 5)]),
+            ],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn genesis_assets() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                Assets::asset_details(0),
+                Some(AssetDetails {
+                    symbol: b"USDX".to_vec(),
+                    decimals: 6,
+                    owner: 1,
+                    total_supply: 1010,
+                })
+            );
+            assert_eq!(Assets::asset_details(3).unwrap().total_supply, 5);
+            assert_eq!(Assets::asset_details(1), None);
+            assert_eq!(Assets::balance_of((0, 1)), 1000);
+            assert_eq!(Assets::balance_of((0, 2)), 10);
+            assert_eq!(Assets::balance_of((3, 2)), 5);
+            assert_eq!(Assets::balance_of((3, 1)), 0);
+        });
+    }
+
+    #[test]
+    fn transfer() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 400));
+            assert_eq!(Assets::balance_of((0, 1)), 600);
+            assert_eq!(Assets::balance_of((0, 2)), 410);
+            assert_ok!(Assets::transfer(Origin::signed(1), 0, 1, 600));
+            assert_eq!(Assets::balance_of((0, 1)), 600);
+            assert_noop!(
+                Assets::transfer(Origin::signed(1), 0, 2, 601),
+                "Not enough balance."
+            );
+            assert_noop!(
+                Assets::transfer(Origin::signed(1), 3, 2, 1),
+                "Not enough balance."
+            );
+        });
+    }
+
+    #[test]
+    fn freeze_account() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(Assets::freeze(Origin::signed(1), 0, 1).is_err());
+            assert_noop!(Assets::freeze(Origin::ROOT, 1, 1), "Unknown asset.");
+            assert_ok!(Assets::freeze(Origin::ROOT, 0, 1));
+            assert_noop!(
+                Assets::transfer(Origin::signed(1), 0, 2, 1),
+                "Account is frozen for this asset."
+            );
+            // frozen accounts still receive, and other assets are unaffected
+            assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 10));
+            assert_ok!(Assets::transfer(Origin::signed(2), 3, 1, 5));
+            assert_ok!(Assets::transfer(Origin::signed(1), 3, 2, 5));
+            assert_ok!(Assets::thaw(Origin::ROOT, 0, 1));
+            assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 1));
+        });
+    }
+
+    #[test]
+    fn freeze_asset() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Assets::freeze_asset(Origin::ROOT, 0));
+            assert_noop!(
+                Assets::transfer(Origin::signed(2), 0, 1, 1),
+                "Asset is frozen."
+            );
+            assert_ok!(Assets::transfer(Origin::signed(2), 3, 1, 1));
+            assert_ok!(Assets::thaw_asset(Origin::ROOT, 0));
+            assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 1));
+        });
+    }
+
+    #[test]
+    fn force_transfer() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Assets::freeze(Origin::ROOT, 0, 1));
+            assert!(Assets::force_transfer(Origin::signed(1), 0, 1, 2, 100).is_err());
+            assert_ok!(Assets::force_transfer(Origin::ROOT, 0, 1, 2, 100));
+            assert_eq!(Assets::balance_of((0, 1)), 900);
+            assert_eq!(Assets::balance_of((0, 2)), 110);
+            assert_noop!(
+                Assets::force_transfer(Origin::ROOT, 0, 1, 2, 901),
+                "Not enough balance."
+            );
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod assets;
+
+#[cfg(feature = "std")]
+pub use crate::assets::GenesisConfig;
+
+pub use crate::assets::{AssetDetails, Event, Module, RawEvent, Trait};
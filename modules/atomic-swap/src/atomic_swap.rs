@@ -0,0 +1,316 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use sr_primitives::traits::Saturating;
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    StorageMap,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Blake2 256 hash of the secret which claims a swap.
+pub type HashedProof = [u8; 32];
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Largest size in bytes of the secret which claims a swap.
+    type ProofLimit: Get<u32>;
+}
+
+// `balance` reserved from `source`, paid to the target if it reveals the proof before
+// `end_block` and returned to `source` otherwise
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct PendingSwap<AccountId, Balance, BlockNumber> {
+    pub source: AccountId,
+    pub balance: Balance,
+    pub end_block: BlockNumber,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // reserve `balance` of the origin for `target`, which may claim it with the secret hashing
+        // to `hashed_proof` for the next `duration` blocks
+        fn create_swap(
+            origin,
+            target: T::AccountId,
+            hashed_proof: HashedProof,
+            balance: BalanceOf<T>,
+            duration: T::BlockNumber
+        ) -> Result {
+            let source = ensure_signed(origin)?;
+            ensure!(
+                !<PendingSwaps<T>>::exists((target.clone(), hashed_proof)),
+                "a swap for this target and proof is already pending"
+            );
+            T::Currency::reserve(&source, balance)?;
+            let end_block = <system::Module<T>>::block_number().saturating_add(duration);
+            <PendingSwaps<T>>::insert(
+                (target.clone(), hashed_proof),
+                PendingSwap { source: source.clone(), balance, end_block },
+            );
+            Self::deposit_event(
+                RawEvent::NewSwap(source, target, hashed_proof, balance, end_block)
+            );
+            Ok(())
+        }
+
+        // claim a swap for the origin by revealing its secret, which the event publishes for the
+        // other side of the trade
+        fn claim_swap(origin, proof: Vec<u8>) -> Result {
+            let target = ensure_signed(origin)?;
+            ensure!(
+                proof.len() <= T::ProofLimit::get() as usize,
+                "proof is longer than the limit"
+            );
+            let hashed_proof = blake2_256(&proof);
+            let swap = Self::pending_swap((target.clone(), hashed_proof))
+                .ok_or("no swap for this proof")?;
+            ensure!(
+                <system::Module<T>>::block_number() < swap.end_block,
+                "swap has expired"
+            );
+            T::Currency::repatriate_reserved(&swap.source, &target, swap.balance)?;
+            <PendingSwaps<T>>::remove((target.clone(), hashed_proof));
+            Self::deposit_event(RawEvent::SwapClaimed(target, hashed_proof, proof, swap.balance));
+            Ok(())
+        }
+
+        // return an expired swap of the origin to it
+        fn cancel_swap(origin, target: T::AccountId, hashed_proof: HashedProof) -> Result {
+            let source = ensure_signed(origin)?;
+            let swap = Self::pending_swap((target.clone(), hashed_proof))
+                .ok_or("no swap for this target and proof")?;
+            ensure!(swap.source == source, "only the source may cancel a swap");
+            ensure!(
+                <system::Module<T>>::block_number() >= swap.end_block,
+                "swap has not expired yet"
+            );
+            T::Currency::unreserve(&source, swap.balance);
+            <PendingSwaps<T>>::remove((target.clone(), hashed_proof));
+            Self::deposit_event(RawEvent::SwapCanceled(source, target, hashed_proof));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as AtomicSwap {
+        // swaps by target and hash of their secret
+        PendingSwaps get(pending_swap):
+            map (T::AccountId, HashedProof) =>
+                Option<PendingSwap<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+        BlockNumber = <T as system::Trait>::BlockNumber,
+    {
+        // source, target, hashed proof, balance, end block
+        NewSwap(AccountId, AccountId, HashedProof, Balance, BlockNumber),
+        // target, hashed proof, proof, balance
+        SwapClaimed(AccountId, HashedProof, Vec<u8>, Balance),
+        // source, target, hashed proof
+        SwapCanceled(AccountId, AccountId, HashedProof),
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const ProofLimit: u32 = 32;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type ProofLimit = ProofLimit;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type AtomicSwap = Module<Test>;
+
+    const SECRET: &[u8] = b"the secret of the swap";
+
+    // 1 swaps 40 of its 100 for something 2 sends on the other chain, for 10 blocks
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut ext: runtime_io::TestExternalities<Blake2Hasher> =
+            balances::GenesisConfig::<Test> {
+                balances: vec![(1, 100), (2, 100)],
+                vesting: vec![],
+            }
+            .build_storage()
+            .unwrap()
+            .into();
+        with_externalities(&mut ext, || {
+            System::set_block_number(1);
+            assert_ok!(AtomicSwap::create_swap(
+                Origin::signed(1),
+                2,
+                blake2_256(SECRET),
+                40,
+                10
+            ));
+        });
+        ext
+    }
+
+    #[test]
+    fn create_swap() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                AtomicSwap::pending_swap((2, blake2_256(SECRET))),
+                Some(PendingSwap {
+                    source: 1,
+                    balance: 40,
+                    end_block: 11,
+                })
+            );
+            assert_eq!(Balances::reserved_balance(1), 40);
+            assert_noop!(
+                AtomicSwap::create_swap(Origin::signed(3), 2, blake2_256(SECRET), 0, 10),
+                "a swap for this target and proof is already pending"
+            );
+            assert!(
+                AtomicSwap::create_swap(Origin::signed(1), 3, blake2_256(SECRET), 61, 10).is_err()
+            );
+        });
+    }
+
+    #[test]
+    fn claim_swap() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                AtomicSwap::claim_swap(Origin::signed(2), b"not the secret".to_vec()),
+                "no swap for this proof"
+            );
+            assert_noop!(
+                AtomicSwap::claim_swap(Origin::signed(3), SECRET.to_vec()),
+                "no swap for this proof"
+            );
+            assert_noop!(
+                AtomicSwap::claim_swap(Origin::signed(2), vec![0; 33]),
+                "proof is longer than the limit"
+            );
+            System::set_block_number(10);
+            assert_ok!(AtomicSwap::claim_swap(Origin::signed(2), SECRET.to_vec()));
+            assert_eq!(Balances::free_balance(1), 60);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(2), 140);
+            assert_eq!(AtomicSwap::pending_swap((2, blake2_256(SECRET))), None);
+        });
+    }
+
+    #[test]
+    fn cancel_expired_swap() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(10);
+            assert_noop!(
+                AtomicSwap::cancel_swap(Origin::signed(1), 2, blake2_256(SECRET)),
+                "swap has not expired yet"
+            );
+            System::set_block_number(11);
+            assert_noop!(
+                AtomicSwap::claim_swap(Origin::signed(2), SECRET.to_vec()),
+                "swap has expired"
+            );
+            assert_noop!(
+                AtomicSwap::cancel_swap(Origin::signed(2), 2, blake2_256(SECRET)),
+                "only the source may cancel a swap"
+            );
+            assert_ok!(AtomicSwap::cancel_swap(
+                Origin::signed(1),
+                2,
+                blake2_256(SECRET)
+            ));
+            assert_eq!(Balances::free_balance(1), 100);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_noop!(
+                AtomicSwap::cancel_swap(Origin::signed(1), 2, blake2_256(SECRET)),
+                "no swap for this target and proof"
+            );
+            // the expired secret may be reused for a new swap
+            assert_ok!(AtomicSwap::create_swap(
+                Origin::signed(1),
+                2,
+                blake2_256(SECRET),
+                40,
+                10
+            ));
+        });
+    }
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod atomic_swap;
+
+pub use crate::atomic_swap::{Call, Event, HashedProof, Module, PendingSwap, RawEvent, Trait};
@@ -0,0 +1,192 @@
+use sr_primitives::traits::{SaturatedConversion, Saturating, Zero};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    traits::{Currency, EnsureOrigin, Imbalance},
+    StorageValue,
+};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Reward minted per block on chains that don't set it at genesis, one token at 12 decimals.
+pub const DEFAULT_REWARD: u64 = 1_000_000_000_000;
+
+pub trait Trait: authorship::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency rewards are minted in.
+    type Currency: Currency<Self::AccountId>;
+    /// Changes the reward.
+    type RewardOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // change the reward minted to the author of each block, 0 stops rewards
+        fn set_reward(origin, reward: BalanceOf<T>) -> Result {
+            T::RewardOrigin::ensure_origin(origin)?;
+            <Reward<T>>::put(reward);
+            Self::deposit_event(RawEvent::RewardSet(reward));
+            Ok(())
+        }
+
+        fn on_finalize() {
+            let reward = Self::reward();
+            if reward.is_zero() {
+                return;
+            }
+            let author = <authorship::Module<T>>::author();
+            let minted = T::Currency::deposit_creating(&author, reward).peek();
+            <TotalRewarded<T>>::mutate(|total| *total = total.saturating_add(minted));
+            Self::deposit_event(RawEvent::Rewarded(author, minted));
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as BlockRewards {
+        // Amount minted to the author of each block.
+        Reward get(reward) config(): BalanceOf<T> = BalanceOf::<T>::saturated_from(DEFAULT_REWARD);
+        // Amount minted to block authors since genesis.
+        TotalRewarded get(total_rewarded): BalanceOf<T>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // author, amount
+        Rewarded(AccountId, Balance),
+        // reward
+        RewardSet(Balance),
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup, OnFinalize},
+    };
+    use sr_primitives::{ConsensusEngineId, Perbill};
+    use support::traits::FindAuthor;
+    use support::{assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    // every block is authored by 7
+    pub struct Author7;
+    impl FindAuthor<u64> for Author7 {
+        fn find_author<'a, I>(_digests: I) -> Option<u64>
+        where
+            I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+        {
+            Some(7)
+        }
+    }
+    parameter_types! {
+        pub const UncleGenerations: u64 = 0;
+    }
+    impl authorship::Trait for Test {
+        type FindAuthor = Author7;
+        type UncleGenerations = UncleGenerations;
+        type FilterUncle = ();
+        type EventHandler = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type RewardOrigin = system::EnsureRoot<u64>;
+    }
+    type Balances = balances::Module<Test>;
+    type BlockRewards = Module<Test>;
+
+    // blocks are rewarded with 10
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> { reward: 10 }
+            .build_storage()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn rewards_the_author() {
+        with_externalities(&mut new_test_ext(), || {
+            BlockRewards::on_finalize(1);
+            BlockRewards::on_finalize(2);
+            assert_eq!(Balances::free_balance(7), 20);
+            assert_eq!(Balances::total_issuance(), 20);
+            assert_eq!(BlockRewards::total_rewarded(), 20);
+        });
+    }
+
+    #[test]
+    fn set_reward() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(BlockRewards::set_reward(Origin::signed(7), 100).is_err());
+            assert_ok!(BlockRewards::set_reward(Origin::ROOT, 0));
+            BlockRewards::on_finalize(1);
+            assert_eq!(Balances::free_balance(7), 0);
+            assert_eq!(BlockRewards::total_rewarded(), 0);
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod block_rewards;
+
+#[cfg(feature = "std")]
+pub use crate::block_rewards::GenesisConfig;
+
+pub use crate::block_rewards::{Call, Event, Module, RawEvent, Trait, DEFAULT_REWARD};
@@ -0,0 +1,119 @@
+use rstd::marker::PhantomData;
+use support::{decl_module, decl_storage, traits::Get};
+
+/// Target block time of chains that don't set one at genesis.
+pub const DEFAULT_MILLISECS_PER_BLOCK: u64 = 6000;
+
+pub trait Trait: system::Trait {}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as BlockTime {
+        // Target time between blocks in milliseconds. Block production reads the slot duration
+        // once, at genesis, so this is only ever set by the chainspec.
+        MillisecsPerBlock get(millisecs_per_block) config(): u64 = DEFAULT_MILLISECS_PER_BLOCK;
+    }
+}
+
+/// The target block time, for use as babe's `ExpectedBlockTime`.
+pub struct ExpectedBlockTime<T>(PhantomData<T>);
+
+impl<T: Trait> Get<u64> for ExpectedBlockTime<T> {
+    fn get() -> u64 {
+        <Module<T>>::millisecs_per_block()
+    }
+}
+
+/// Half the target block time, for use as the timestamp module's `MinimumPeriod`. Babe slots last
+/// twice the minimum period, so slots last the target block time.
+pub struct MinimumPeriod<T>(PhantomData<T>);
+
+impl<T: Trait> Get<u64> for MinimumPeriod<T> {
+    fn get() -> u64 {
+        <Module<T>>::millisecs_per_block() / 2
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl Trait for Test {}
+
+    fn ext(millisecs_per_block: Option<u64>) -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut config = GenesisConfig::default();
+        if let Some(millisecs_per_block) = millisecs_per_block {
+            config.millisecs_per_block = millisecs_per_block;
+        }
+        config.build_storage::<Test>().unwrap().into()
+    }
+
+    #[test]
+    fn default_block_time() {
+        with_externalities(&mut ext(None), || {
+            assert_eq!(
+                <Module<Test>>::millisecs_per_block(),
+                DEFAULT_MILLISECS_PER_BLOCK
+            );
+            assert_eq!(
+                ExpectedBlockTime::<Test>::get(),
+                DEFAULT_MILLISECS_PER_BLOCK
+            );
+            assert_eq!(
+                MinimumPeriod::<Test>::get(),
+                DEFAULT_MILLISECS_PER_BLOCK / 2
+            );
+        });
+    }
+
+    #[test]
+    fn block_time_from_genesis() {
+        with_externalities(&mut ext(Some(1000)), || {
+            assert_eq!(ExpectedBlockTime::<Test>::get(), 1000);
+            assert_eq!(MinimumPeriod::<Test>::get(), 500);
+        });
+    }
+}
@@ -0,0 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod block_time;
+
+#[cfg(feature = "std")]
+pub use crate::block_time::GenesisConfig;
+
+pub use crate::block_time::{
+    ExpectedBlockTime, MinimumPeriod, Module, Trait, DEFAULT_MILLISECS_PER_BLOCK,
+};
@@ -0,0 +1,321 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::{
+    traits::{AccountIdConversion, SaturatedConversion},
+    ModuleId,
+};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, EnsureOrigin, Get, ReservableCurrency},
+    StorageMap, StorageValue,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Derives the accounts holding the funds of approved bounties.
+pub const MODULE_ID: ModuleId = ModuleId(*b"py/bntys");
+/// Deposit of a bounty proposal on chains that don't set it at genesis, one token at 12 decimals.
+pub const DEFAULT_BOUNTY_DEPOSIT: u64 = 1_000_000_000_000;
+
+pub type BountyIndex = u32;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Account bounties are funded from, e.g. the treasury pot.
+    type Pot: Get<Self::AccountId>;
+    /// Approves proposed bounties, funding them from the pot.
+    type ApproveOrigin: EnsureOrigin<Self::Origin>;
+    /// Rejects proposed bounties and closes approved ones.
+    type RejectOrigin: EnsureOrigin<Self::Origin>;
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub enum BountyStatus<AccountId> {
+    // waiting for approval, the proposer's deposit reserved
+    Proposed,
+    // funded, awarded by the curator once the work is done
+    Active { curator: AccountId },
+}
+
+// `value` paid from the pot for the work in `description`
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Bounty<AccountId, Balance> {
+    pub proposer: AccountId,
+    pub value: Balance,
+    pub deposit: Balance,
+    pub description: Vec<u8>,
+    pub status: BountyStatus<AccountId>,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // propose a bounty, reserving the bounty deposit of the origin until it is approved
+        fn propose_bounty(origin, value: BalanceOf<T>, description: Vec<u8>) -> Result {
+            let proposer = ensure_signed(origin)?;
+            ensure!(
+                value >= T::Currency::minimum_balance(),
+                "bounty value is below the existential deposit"
+            );
+            let deposit = Self::bounty_deposit();
+            T::Currency::reserve(&proposer, deposit)?;
+            let id = Self::bounty_count();
+            BountyCount::put(id + 1);
+            let bounty = Bounty {
+                proposer,
+                value,
+                deposit,
+                description,
+                status: BountyStatus::Proposed,
+            };
+            <Bounties<T>>::insert(id, bounty);
+            Self::deposit_event(RawEvent::BountyProposed(id));
+            Ok(())
+        }
+
+        // fund a proposed bounty from the pot and return the proposer's deposit
+        fn approve_bounty(origin, id: BountyIndex, curator: T::AccountId) -> Result {
+            T::ApproveOrigin::ensure_origin(origin)?;
+            let mut bounty = Self::bounty(id).ok_or("bounty does not exist")?;
+            ensure!(bounty.status == BountyStatus::Proposed, "bounty is not proposed");
+            T::Currency::transfer(&T::Pot::get(), &Self::bounty_account(id), bounty.value)?;
+            T::Currency::unreserve(&bounty.proposer, bounty.deposit);
+            bounty.status = BountyStatus::Active { curator: curator.clone() };
+            <Bounties<T>>::insert(id, bounty);
+            Self::deposit_event(RawEvent::BountyApproved(id, curator));
+            Ok(())
+        }
+
+        // drop a proposed bounty, paying the proposer's deposit into the pot
+        fn reject_bounty(origin, id: BountyIndex) -> Result {
+            T::RejectOrigin::ensure_origin(origin)?;
+            let bounty = Self::bounty(id).ok_or("bounty does not exist")?;
+            ensure!(bounty.status == BountyStatus::Proposed, "bounty is not proposed");
+            T::Currency::repatriate_reserved(&bounty.proposer, &T::Pot::get(), bounty.deposit)?;
+            <Bounties<T>>::remove(id);
+            Self::deposit_event(RawEvent::BountyRejected(id, bounty.deposit));
+            Ok(())
+        }
+
+        // pay the funds of an approved bounty of the origin's curation to `beneficiary`
+        fn award_bounty(origin, id: BountyIndex, beneficiary: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            let bounty = Self::bounty(id).ok_or("bounty does not exist")?;
+            match bounty.status {
+                BountyStatus::Active { curator } => {
+                    ensure!(curator == who, "only the curator may do this")
+                }
+                BountyStatus::Proposed => return Err("bounty is not active"),
+            }
+            let account = Self::bounty_account(id);
+            let payout = T::Currency::free_balance(&account);
+            T::Currency::transfer(&account, &beneficiary, payout)?;
+            <Bounties<T>>::remove(id);
+            Self::deposit_event(RawEvent::BountyAwarded(id, beneficiary, payout));
+            Ok(())
+        }
+
+        // cancel an approved bounty, returning its funds to the pot
+        fn close_bounty(origin, id: BountyIndex) -> Result {
+            T::RejectOrigin::ensure_origin(origin)?;
+            let bounty = Self::bounty(id).ok_or("bounty does not exist")?;
+            ensure!(bounty.status != BountyStatus::Proposed, "bounty is not active");
+            let account = Self::bounty_account(id);
+            T::Currency::transfer(&account, &T::Pot::get(), T::Currency::free_balance(&account))?;
+            <Bounties<T>>::remove(id);
+            Self::deposit_event(RawEvent::BountyClosed(id));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Bounties {
+        // proposed and approved bounties
+        Bounties get(bounty): map BountyIndex => Option<Bounty<T::AccountId, BalanceOf<T>>>;
+        // number of bounties ever proposed, the index of the next one
+        BountyCount get(bounty_count): BountyIndex;
+        // reserved from the proposer of a bounty until it is approved or rejected
+        BountyDeposit get(bounty_deposit) config(): BalanceOf<T> =
+            BalanceOf::<T>::saturated_from(DEFAULT_BOUNTY_DEPOSIT);
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // bounty
+        BountyProposed(BountyIndex),
+        // bounty, curator
+        BountyApproved(BountyIndex, AccountId),
+        // bounty, slashed deposit
+        BountyRejected(BountyIndex, Balance),
+        // bounty, beneficiary, payout
+        BountyAwarded(BountyIndex, AccountId, Balance),
+        // bounty
+        BountyClosed(BountyIndex),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Account holding the funds of an approved bounty.
+    pub fn bounty_account(id: BountyIndex) -> T::AccountId {
+        MODULE_ID.into_sub_account(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const Pot: u64 = 100;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type Pot = Pot;
+        type ApproveOrigin = system::EnsureRoot<u64>;
+        type RejectOrigin = system::EnsureRoot<u64>;
+    }
+    type Balances = balances::Module<Test>;
+    type Bounties = Module<Test>;
+
+    // the pot holds 100, 1 holds 10 and proposals reserve 3
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = balances::GenesisConfig::<Test> {
+            balances: vec![(100, 100), (1, 10)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap();
+        GenesisConfig::<Test> { bounty_deposit: 3 }
+            .assimilate_storage(&mut storage)
+            .unwrap();
+        storage.into()
+    }
+
+    #[test]
+    fn approve_and_award() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Bounties::propose_bounty(
+                Origin::signed(1),
+                50,
+                b"docs".to_vec()
+            ));
+            assert_eq!(Balances::reserved_balance(1), 3);
+            assert!(Bounties::approve_bounty(Origin::signed(1), 0, 2).is_err());
+            assert_noop!(
+                Bounties::award_bounty(Origin::signed(2), 0, 7),
+                "bounty is not active"
+            );
+            assert_ok!(Bounties::approve_bounty(Origin::ROOT, 0, 2));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(100), 50);
+            assert_eq!(Balances::free_balance(Bounties::bounty_account(0)), 50);
+            assert_noop!(
+                Bounties::approve_bounty(Origin::ROOT, 0, 2),
+                "bounty is not proposed"
+            );
+
+            assert_noop!(
+                Bounties::award_bounty(Origin::signed(1), 0, 7),
+                "only the curator may do this"
+            );
+            assert_ok!(Bounties::award_bounty(Origin::signed(2), 0, 7));
+            assert_eq!(Balances::free_balance(7), 50);
+            assert_eq!(Bounties::bounty(0), None);
+        });
+    }
+
+    #[test]
+    fn reject_and_close() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Bounties::propose_bounty(Origin::signed(1), 50, vec![]));
+            assert_ok!(Bounties::reject_bounty(Origin::ROOT, 0));
+            assert_eq!(Balances::free_balance(1), 7);
+            assert_eq!(Balances::free_balance(100), 103);
+
+            assert_ok!(Bounties::propose_bounty(Origin::signed(1), 50, vec![]));
+            assert_eq!(Bounties::bounty_count(), 2);
+            assert_noop!(
+                Bounties::close_bounty(Origin::ROOT, 1),
+                "bounty is not active"
+            );
+            assert_ok!(Bounties::approve_bounty(Origin::ROOT, 1, 2));
+            assert_ok!(Bounties::close_bounty(Origin::ROOT, 1));
+            assert_eq!(Balances::free_balance(100), 103);
+            assert_eq!(Bounties::bounty(1), None);
+        });
+    }
+}
@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod bounties;
+
+#[cfg(feature = "std")]
+pub use crate::bounties::GenesisConfig;
+
+pub use crate::bounties::{
+    Bounty, BountyIndex, BountyStatus, Call, Event, Module, RawEvent, Trait,
+    DEFAULT_BOUNTY_DEPOSIT, MODULE_ID,
+};
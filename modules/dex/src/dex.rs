@@ -0,0 +1,512 @@
+use codec::{Decode, Encode};
+use primitives::U256;
+use rstd::{convert::TryFrom, prelude::*};
+use sr_primitives::{
+    traits::{AccountIdConversion, CheckedAdd, UniqueSaturatedInto, Zero},
+    ModuleId, Permill,
+};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Get, StorageMap,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <T as assets::Trait>::Balance;
+
+/// Derives the account holding the reserves of every pool.
+pub const MODULE_ID: ModuleId = ModuleId(*b"py/dexpl");
+
+pub trait Trait: assets::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Share of a swap's input left in the pool, paying liquidity providers.
+    type SwapFee: Get<Permill>;
+}
+
+/// Reserves of a pool, in order of asset id, and the liquidity shares issued against them.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Pool<Balance> {
+    pub reserves: (Balance, Balance),
+    pub shares: Balance,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // open a pool of two assets with a deposit of both, which sets the initial price; the
+        // origin gets as many shares as it deposits of the asset with the lower id
+        fn create_pool(
+            origin,
+            asset_a: u32,
+            asset_b: u32,
+            amount_a: BalanceOf<T>,
+            amount_b: BalanceOf<T>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let shares = Self::do_create_pool(&who, asset_a, asset_b, amount_a, amount_b)?;
+            let (first, second) = pool_key(asset_a, asset_b);
+            Self::deposit_event(RawEvent::PoolCreated(who, first, second, shares));
+            Ok(())
+        }
+
+        // deposit `amount_a` of `asset_a` and as much of `asset_b` as keeps the pool's price,
+        // failing if that is more than `max_amount_b`
+        fn add_liquidity(
+            origin,
+            asset_a: u32,
+            asset_b: u32,
+            amount_a: BalanceOf<T>,
+            max_amount_b: BalanceOf<T>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let key = pool_key(asset_a, asset_b);
+            let mut pool = Self::pool(key).ok_or("pool does not exist")?;
+            let (reserve_a, reserve_b) = orient(pool.reserves, asset_a, asset_b);
+            // rounded up so that new shares are never worth more than they were paid
+            let amount_b = mul_div::<T>(amount_a, reserve_b, reserve_a, true)?;
+            ensure!(amount_b <= max_amount_b, "price moved beyond the maximum");
+            let shares = mul_div::<T>(amount_a, pool.shares, reserve_a, false)?;
+            ensure!(!shares.is_zero(), "deposit is too small to issue shares");
+            Self::deposit((asset_a, amount_a), (asset_b, amount_b), &who)?;
+            pool.reserves = orient((reserve_a + amount_a, reserve_b + amount_b), asset_a, asset_b);
+            pool.shares += shares;
+            <Pools<T>>::insert(key, pool);
+            <SharesOf<T>>::mutate((key, who.clone()), |held| *held += shares);
+            Self::deposit_event(RawEvent::LiquidityAdded(who, key.0, key.1, shares));
+            Ok(())
+        }
+
+        // redeem shares of a pool for their part of both reserves
+        fn remove_liquidity(origin, asset_a: u32, asset_b: u32, shares: BalanceOf<T>) -> Result {
+            let who = ensure_signed(origin)?;
+            let key = pool_key(asset_a, asset_b);
+            let mut pool = Self::pool(key).ok_or("pool does not exist")?;
+            let held = Self::shares_of((key, who.clone()));
+            ensure!(!shares.is_zero() && shares <= held, "not enough shares");
+            let (reserve_a, reserve_b) = orient(pool.reserves, asset_a, asset_b);
+            let amount_a = mul_div::<T>(shares, reserve_a, pool.shares, false)?;
+            let amount_b = mul_div::<T>(shares, reserve_b, pool.shares, false)?;
+            // the pools account holds both amounts, so neither move fails
+            <assets::Module<T>>::do_move_balance(asset_a, &Self::account_id(), &who, amount_a)?;
+            <assets::Module<T>>::do_move_balance(asset_b, &Self::account_id(), &who, amount_b)?;
+            pool.shares -= shares;
+            if pool.shares.is_zero() {
+                <Pools<T>>::remove(key);
+            } else {
+                pool.reserves =
+                    orient((reserve_a - amount_a, reserve_b - amount_b), asset_a, asset_b);
+                <Pools<T>>::insert(key, pool);
+            }
+            if shares == held {
+                <SharesOf<T>>::remove((key, who.clone()));
+            } else {
+                <SharesOf<T>>::insert((key, who.clone()), held - shares);
+            }
+            Self::deposit_event(RawEvent::LiquidityRemoved(who, key.0, key.1, shares));
+            Ok(())
+        }
+
+        // sell `amount_in` of `asset_in` to the pool for `asset_out`, failing if it pays less
+        // than `min_amount_out`
+        fn swap(
+            origin,
+            asset_in: u32,
+            asset_out: u32,
+            amount_in: BalanceOf<T>,
+            min_amount_out: BalanceOf<T>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let key = pool_key(asset_in, asset_out);
+            let mut pool = Self::pool(key).ok_or("pool does not exist")?;
+            let (reserve_in, reserve_out) = orient(pool.reserves, asset_in, asset_out);
+            let amount_out = Self::swap_output(amount_in, reserve_in, reserve_out)?;
+            ensure!(!amount_out.is_zero(), "swap is too small to pay anything");
+            ensure!(amount_out >= min_amount_out, "price moved beyond the minimum");
+            <assets::Module<T>>::ensure_movable(asset_in, &who)?;
+            ensure!(!<assets::Module<T>>::is_asset_frozen(asset_out), "Asset is frozen.");
+            <assets::Module<T>>::do_move_balance(asset_in, &who, &Self::account_id(), amount_in)?;
+            // the pools account holds the reserve paid out of, so this doesn't fail
+            <assets::Module<T>>::do_move_balance(asset_out, &Self::account_id(), &who, amount_out)?;
+            pool.reserves =
+                orient((reserve_in + amount_in, reserve_out - amount_out), asset_in, asset_out);
+            <Pools<T>>::insert(key, pool);
+            Self::deposit_event(RawEvent::Swapped(who, asset_in, amount_in, asset_out, amount_out));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Dex {
+        // pools by pair of asset ids, lower id first
+        Pools get(pool): map (u32, u32) => Option<Pool<BalanceOf<T>>>;
+        // liquidity shares of an account in a pool
+        SharesOf get(shares_of): map ((u32, u32), T::AccountId) => BalanceOf<T>;
+    }
+    add_extra_genesis {
+        // (asset a, asset b, provider, amount a, amount b) of each pool, created with assets
+        // the provider holds at genesis
+        config(pools): Vec<(u32, u32, T::AccountId, BalanceOf<T>, BalanceOf<T>)>;
+        build(|config: &GenesisConfig<T>| {
+            for (asset_a, asset_b, provider, amount_a, amount_b) in &config.pools {
+                <Module<T>>::do_create_pool(provider, *asset_a, *asset_b, *amount_a, *amount_b)
+                    .expect("genesis pool is invalid");
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // provider, asset ids of the pool, shares issued
+        PoolCreated(AccountId, u32, u32, Balance),
+        // provider, asset ids of the pool, shares issued
+        LiquidityAdded(AccountId, u32, u32, Balance),
+        // provider, asset ids of the pool, shares redeemed
+        LiquidityRemoved(AccountId, u32, u32, Balance),
+        // trader, asset in, amount in, asset out, amount out
+        Swapped(AccountId, u32, Balance, u32, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Account holding the reserves of every pool.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    /// What a pool with reserves `reserve_in` and `reserve_out` pays for `amount_in`. Once the
+    /// fee is taken from the input, the product of the reserves stays the same.
+    pub fn swap_output(
+        amount_in: BalanceOf<T>,
+        reserve_in: BalanceOf<T>,
+        reserve_out: BalanceOf<T>,
+    ) -> rstd::result::Result<BalanceOf<T>, &'static str> {
+        let amount_in = amount_in - T::SwapFee::get() * amount_in;
+        let reserve_in = reserve_in
+            .checked_add(&amount_in)
+            .ok_or("Arithmetic overflow.")?;
+        mul_div::<T>(amount_in, reserve_out, reserve_in, false)
+    }
+
+    fn do_create_pool(
+        who: &T::AccountId,
+        asset_a: u32,
+        asset_b: u32,
+        amount_a: BalanceOf<T>,
+        amount_b: BalanceOf<T>,
+    ) -> rstd::result::Result<BalanceOf<T>, &'static str> {
+        ensure!(asset_a != asset_b, "a pool needs two different assets");
+        ensure!(
+            <assets::Module<T>>::asset_details(asset_a).is_some()
+                && <assets::Module<T>>::asset_details(asset_b).is_some(),
+            "asset does not exist"
+        );
+        let key = pool_key(asset_a, asset_b);
+        ensure!(!<Pools<T>>::exists(key), "pool already exists");
+        ensure!(
+            !amount_a.is_zero() && !amount_b.is_zero(),
+            "pools start with some of both assets"
+        );
+        Self::deposit((asset_a, amount_a), (asset_b, amount_b), who)?;
+        let reserves = orient((amount_a, amount_b), asset_a, asset_b);
+        let shares = reserves.0;
+        <Pools<T>>::insert(key, Pool { reserves, shares });
+        <SharesOf<T>>::insert((key, who.clone()), shares);
+        Ok(shares)
+    }
+
+    // move both assets into the pools account, checking both balances and freezes before moving
+    // either
+    fn deposit(
+        (asset_a, amount_a): (u32, BalanceOf<T>),
+        (asset_b, amount_b): (u32, BalanceOf<T>),
+        who: &T::AccountId,
+    ) -> Result {
+        <assets::Module<T>>::ensure_movable(asset_a, who)?;
+        <assets::Module<T>>::ensure_movable(asset_b, who)?;
+        ensure!(
+            <assets::Module<T>>::balance_of((asset_a, who.clone())) >= amount_a
+                && <assets::Module<T>>::balance_of((asset_b, who.clone())) >= amount_b,
+            "Not enough balance."
+        );
+        <assets::Module<T>>::do_move_balance(asset_a, who, &Self::account_id(), amount_a)?;
+        <assets::Module<T>>::do_move_balance(asset_b, who, &Self::account_id(), amount_b)
+    }
+}
+
+/// Key of the pool of two assets, the lower asset id first.
+pub fn pool_key(asset_a: u32, asset_b: u32) -> (u32, u32) {
+    if asset_a < asset_b {
+        (asset_a, asset_b)
+    } else {
+        (asset_b, asset_a)
+    }
+}
+
+// swap a pair between key order and the order of `asset_a, asset_b`, either way
+fn orient<B>(pair: (B, B), asset_a: u32, asset_b: u32) -> (B, B) {
+    if asset_a < asset_b {
+        pair
+    } else {
+        (pair.1, pair.0)
+    }
+}
+
+// `a * b / c` without overflowing in between
+fn mul_div<T: Trait>(
+    a: BalanceOf<T>,
+    b: BalanceOf<T>,
+    c: BalanceOf<T>,
+    round_up: bool,
+) -> rstd::result::Result<BalanceOf<T>, &'static str> {
+    let wide = |x: BalanceOf<T>| U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(x));
+    let (product, c) = (wide(a) * wide(b), wide(c));
+    ensure!(!c.is_zero(), "pool is empty");
+    let mut quotient = product / c;
+    if round_up && !(product % c).is_zero() {
+        quotient += U256::one();
+    }
+    ensure!(
+        quotient <= U256::from(u128::max_value()),
+        "Arithmetic overflow."
+    );
+    BalanceOf::<T>::try_from(quotient.as_u128()).map_err(|_| "Arithmetic overflow.")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl assets::Trait for Test {
+        type Event = ();
+        type Balance = u128;
+        type AdminOrigin = system::EnsureRoot<u64>;
+    }
+    parameter_types! {
+        pub const SwapFee: Permill = Permill::from_percent(1);
+    }
+    impl Trait for Test {
+        type Event = ();
+        type SwapFee = SwapFee;
+    }
+    type Assets = assets::Module<Test>;
+    type Dex = Module<Test>;
+
+    // 1 pooled 1000 of assets 0 and 1 at genesis, asset 2 has no pool
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = assets::GenesisConfig::<Test> {
+            assets: vec![
+                (0, b"USDX".to_vec(), 6, 1, vec![(1, 10_000), (2, 1_000)]),
+                (1, b"GLD".to_vec(), 0, 1, vec![(1, 10_000), (2, 1_000)]),
+                (2, b"SLV".to_vec(), 0, 1, vec![(1, 10_000)]),
+            ],
+        }
+        .build_storage()
+        .unwrap();
+        GenesisConfig::<Test> {
+            pools: vec![(1, 0, 1, 1_000, 1_000)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        storage.into()
+    }
+
+    #[test]
+    fn genesis_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                Dex::pool((0, 1)),
+                Some(Pool {
+                    reserves: (1_000, 1_000),
+                    shares: 1_000,
+                })
+            );
+            assert_eq!(Dex::shares_of(((0, 1), 1)), 1_000);
+            assert_eq!(Assets::balance_of((0, 1)), 9_000);
+            assert_eq!(Assets::balance_of((1, Dex::account_id())), 1_000);
+        });
+    }
+
+    #[test]
+    fn create_pool() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Dex::create_pool(Origin::signed(1), 0, 0, 10, 10),
+                "a pool needs two different assets"
+            );
+            assert_noop!(
+                Dex::create_pool(Origin::signed(1), 0, 3, 10, 10),
+                "asset does not exist"
+            );
+            assert_noop!(
+                Dex::create_pool(Origin::signed(1), 0, 1, 10, 10),
+                "pool already exists"
+            );
+            assert_noop!(
+                Dex::create_pool(Origin::signed(1), 2, 0, 10, 0),
+                "pools start with some of both assets"
+            );
+            // 2 holds some of asset 0 but none of asset 2, neither moves
+            assert_noop!(
+                Dex::create_pool(Origin::signed(2), 2, 0, 10, 10),
+                "Not enough balance."
+            );
+            assert_ok!(Dex::create_pool(Origin::signed(1), 2, 0, 400, 100));
+            assert_eq!(
+                Dex::pool((0, 2)),
+                Some(Pool {
+                    reserves: (100, 400),
+                    shares: 100,
+                })
+            );
+            assert_eq!(Dex::shares_of(((0, 2), 1)), 100);
+            assert_eq!(Assets::balance_of((2, 1)), 9_600);
+        });
+    }
+
+    #[test]
+    fn add_and_remove_liquidity() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Dex::add_liquidity(Origin::signed(2), 0, 2, 100, 100),
+                "pool does not exist"
+            );
+            assert_noop!(
+                Dex::add_liquidity(Origin::signed(2), 1, 0, 100, 99),
+                "price moved beyond the maximum"
+            );
+            assert_ok!(Dex::add_liquidity(Origin::signed(2), 1, 0, 100, 100));
+            assert_eq!(Dex::shares_of(((0, 1), 2)), 100);
+            assert_eq!(Dex::pool((0, 1)).unwrap().shares, 1_100);
+            assert_eq!(Assets::balance_of((0, 2)), 900);
+
+            assert_noop!(
+                Dex::remove_liquidity(Origin::signed(2), 0, 1, 101),
+                "not enough shares"
+            );
+            assert_ok!(Dex::remove_liquidity(Origin::signed(2), 0, 1, 100));
+            assert_eq!(Dex::shares_of(((0, 1), 2)), 0);
+            assert_eq!(Assets::balance_of((0, 2)), 1_000);
+            assert_eq!(Assets::balance_of((1, 2)), 1_000);
+
+            // the last shares empty the pool
+            assert_ok!(Dex::remove_liquidity(Origin::signed(1), 0, 1, 1_000));
+            assert_eq!(Dex::pool((0, 1)), None);
+            assert_eq!(Assets::balance_of((0, Dex::account_id())), 0);
+            assert_eq!(Assets::balance_of((0, 1)), 10_000);
+        });
+    }
+
+    #[test]
+    fn swap() {
+        with_externalities(&mut new_test_ext(), || {
+            // 99 after the fee buys 99 * 1000 / 1099 of asset 1
+            assert_eq!(Dex::swap_output(100, 1_000, 1_000), Ok(90));
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 0, 1, 100, 91),
+                "price moved beyond the minimum"
+            );
+            assert_ok!(Dex::swap(Origin::signed(2), 0, 1, 100, 90));
+            assert_eq!(Assets::balance_of((0, 2)), 900);
+            assert_eq!(Assets::balance_of((1, 2)), 1_090);
+            assert_eq!(Dex::pool((0, 1)).unwrap().reserves, (1_100, 910));
+
+            // the fee stays in the pool
+            assert_ok!(Dex::swap(Origin::signed(2), 1, 0, 90, 0));
+            let reserves = Dex::pool((0, 1)).unwrap().reserves;
+            assert!(reserves.0 * reserves.1 > 1_000 * 1_000);
+            assert_eq!(reserves.1, 1_000);
+
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 0, 1, 0, 0),
+                "swap is too small to pay anything"
+            );
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 0, 1, 10_000, 0),
+                "Not enough balance."
+            );
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 0, 2, 10, 0),
+                "pool does not exist"
+            );
+        });
+    }
+
+    #[test]
+    fn frozen_assets() {
+        with_externalities(&mut new_test_ext(), || {
+            // 2 may not deposit asset 1, and asset 0 doesn't move either
+            assert_ok!(Assets::freeze(Origin::ROOT, 1, 2));
+            assert_noop!(
+                Dex::add_liquidity(Origin::signed(2), 0, 1, 100, 100),
+                "Account is frozen for this asset."
+            );
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 1, 0, 100, 0),
+                "Account is frozen for this asset."
+            );
+            // but may still buy it
+            assert_ok!(Dex::swap(Origin::signed(2), 0, 1, 100, 0));
+            assert_ok!(Assets::thaw(Origin::ROOT, 1, 2));
+
+            // nothing trades for a frozen asset
+            assert_ok!(Dex::add_liquidity(Origin::signed(2), 0, 1, 100, 1_000));
+            assert_ok!(Assets::freeze_asset(Origin::ROOT, 1));
+            assert_noop!(
+                Dex::swap(Origin::signed(2), 0, 1, 100, 0),
+                "Asset is frozen."
+            );
+            assert_noop!(
+                Dex::add_liquidity(Origin::signed(2), 0, 1, 100, 1_000),
+                "Asset is frozen."
+            );
+            let shares = Dex::shares_of(((0, 1), 2));
+            assert_ok!(Dex::remove_liquidity(Origin::signed(2), 0, 1, shares));
+            assert_eq!(Dex::shares_of(((0, 1), 2)), 0);
+        });
+    }
+}
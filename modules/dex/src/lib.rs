@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod dex;
+
+#[cfg(feature = "std")]
+pub use crate::dex::GenesisConfig;
+
+pub use crate::dex::{pool_key, Call, Event, Module, Pool, RawEvent, Trait, MODULE_ID};
@@ -1,12 +1,14 @@
+use crate::weights;
 use codec::{Codec, Decode, Encode};
 use core::convert::TryInto;
 use rstd::prelude::*;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sr_primitives::traits::{CheckedAdd, CheckedSub, Member, SimpleArithmetic};
+use sr_primitives::weights::SimpleDispatchInfo;
 use support::{
-    decl_event, decl_module, decl_storage, dispatch::Result, ensure, Parameter, StorageMap,
-    StorageValue,
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::EnsureOrigin,
+    Parameter, StorageMap, StorageValue,
 };
 use system::{self, ensure_root, ensure_signed};
 
@@ -15,6 +17,8 @@ use system::{self, ensure_root, ensure_signed};
 pub trait Trait: system::Trait {
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
     type TokenBalance: Parameter + Member + SimpleArithmetic + Codec + Default + Copy;
+    // freezes and thaws tokens and accounts, and forces transfers
+    type AdminOrigin: EnsureOrigin<Self::Origin>;
 }
 
 // struct to store the token details
@@ -41,30 +45,21 @@ decl_module! {
         /// Only root can call this function
         fn init(origin, beneficiary: T::AccountId, name: Vec<u8>, ticker: Vec<u8>, total_supply: T::TokenBalance) -> Result {
             ensure_root(origin)?;
+            Self::_init(beneficiary, name, ticker, total_supply)
+        }
 
-            // checking max size for name and ticker
-            // byte arrays (vecs) with no max size should be avoided
-            ensure!(name.len() <= 64, "token name cannot exceed 64 bytes");
-            ensure!(ticker.len() <= 32, "token ticker cannot exceed 32 bytes");
-
-            let token_id = Self::token_id();
-            let next_token_id = token_id.checked_add(1).ok_or("overflow in calculating next token id")?;
-            TokenId::put(next_token_id);
-
-            let token = Erc20Token {
-                name,
-                ticker,
-                total_supply,
-            };
-
-            <Tokens<T>>::insert(token_id, token);
-            <BalanceOf<T>>::insert((token_id, beneficiary), total_supply);
-
-            Ok(())
+        /// like init, but anyone can call this function
+        /// the caller becomes the owner of the token and receives the total supply
+        /// no more of the token can ever be created
+        #[weight = SimpleDispatchInfo::FixedNormal(weights::CREATE)]
+        fn create(origin, name: Vec<u8>, ticker: Vec<u8>, total_supply: T::TokenBalance) -> Result {
+            let owner = ensure_signed(origin)?;
+            Self::_init(owner, name, ticker, total_supply)
         }
 
         // transfer tokens from one account to another
         // origin is assumed as sender
+        #[weight = SimpleDispatchInfo::FixedNormal(weights::TRANSFER)]
         fn transfer(_origin, token_id: u32, to: T::AccountId, value: T::TokenBalance) -> Result {
             let sender = ensure_signed(_origin)?;
             Self::_transfer(token_id, sender, to, value)
@@ -72,6 +67,7 @@ decl_module! {
 
         // approve token transfer from one account to another
         // once this is done, transfer_from can be called with corresponding values
+        #[weight = SimpleDispatchInfo::FixedNormal(weights::APPROVE)]
         fn approve(_origin, token_id: u32, spender: T::AccountId, value: T::TokenBalance) -> Result {
             let sender = ensure_signed(_origin)?;
             ensure!(<BalanceOf<T>>::exists((token_id, sender.clone())), "Account does not own this token");
@@ -88,6 +84,7 @@ decl_module! {
         // the ERC20 standard transfer_from function
         // implemented in the open-zeppelin way - increase/decrease allownace
         // if approved, transfer from an account to another account without owner's signature
+        #[weight = SimpleDispatchInfo::FixedNormal(weights::TRANSFER_FROM)]
         pub fn transfer_from(_origin, token_id: u32, from: T::AccountId, to: T::AccountId, value: T::TokenBalance) -> Result {
             ensure!(<Allowance<T>>::exists((token_id, from.clone(), to.clone())), "Allowance does not exist.");
             let allowance = Self::allowance((token_id, from.clone(), to.clone()));
@@ -104,11 +101,63 @@ decl_module! {
         // permanently discard, throw away tokens
         pub fn burn(origin, token_id: u32, value: T::TokenBalance) -> Result {
             let account = ensure_signed(origin)?;
+            Self::ensure_not_frozen(token_id, &account)?;
             let key = (token_id, account);
             let bal = <BalanceOf<T>>::get(&key).checked_sub(&value).ok_or("Not enough balance.")?;
             <BalanceOf<T>>::insert(key, bal);
             Ok(())
         }
+
+        // stop every holder of a token from transferring or burning it
+        // only the admin origin can call this function
+        fn freeze_token(origin, token_id: u32) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(<Tokens<T>>::exists(token_id), "Token does not exist.");
+            FrozenTokens::insert(token_id, true);
+            Self::deposit_event(RawEvent::TokenFrozen(token_id));
+            Ok(())
+        }
+
+        // only the admin origin can call this function
+        fn thaw_token(origin, token_id: u32) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            FrozenTokens::remove(token_id);
+            Self::deposit_event(RawEvent::TokenThawed(token_id));
+            Ok(())
+        }
+
+        // stop an account from transferring or burning a token, it can still receive it
+        // only the admin origin can call this function
+        fn freeze(origin, token_id: u32, who: T::AccountId) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(<Tokens<T>>::exists(token_id), "Token does not exist.");
+            <FrozenAccounts<T>>::insert((token_id, who.clone()), true);
+            Self::deposit_event(RawEvent::Frozen(token_id, who));
+            Ok(())
+        }
+
+        // only the admin origin can call this function
+        fn thaw(origin, token_id: u32, who: T::AccountId) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            <FrozenAccounts<T>>::remove((token_id, who.clone()));
+            Self::deposit_event(RawEvent::Thawed(token_id, who));
+            Ok(())
+        }
+
+        // transfer tokens from one account to another, frozen or not
+        // only the admin origin can call this function
+        fn force_transfer(
+            origin,
+            token_id: u32,
+            from: T::AccountId,
+            to: T::AccountId,
+            value: T::TokenBalance
+        ) -> Result {
+            T::AdminOrigin::ensure_origin(origin)?;
+            Self::_move(token_id, from.clone(), to.clone(), value)?;
+            Self::deposit_event(RawEvent::ForceTransfer(token_id, from, to, value));
+            Ok(())
+        }
     }
 }
 
@@ -154,6 +203,10 @@ decl_storage! {
             : map (u32, T::AccountId) => T::TokenBalance;
         // allowance for an account and token
         Allowance get(allowance): map (u32, T::AccountId, T::AccountId) => T::TokenBalance;
+        // tokens no account can transfer
+        FrozenTokens get(is_token_frozen): map u32 => bool;
+        // accounts which cannot transfer a token
+        FrozenAccounts get(is_frozen): map (u32, T::AccountId) => bool;
     }
 
     add_extra_genesis {
@@ -174,6 +227,19 @@ decl_event!(
         // event when an approval is made
         // tokenid, owner, spender, value
         Approval(u32, AccountId, AccountId, Balance),
+        // event when a token is created
+        // tokenid, owner, total supply
+        Created(u32, AccountId, Balance),
+        // tokenid
+        TokenFrozen(u32),
+        // tokenid
+        TokenThawed(u32),
+        // tokenid, account
+        Frozen(u32, AccountId),
+        // tokenid, account
+        Thawed(u32, AccountId),
+        // tokenid, from, to, value
+        ForceTransfer(u32, AccountId, AccountId, Balance),
     }
 );
 
@@ -181,6 +247,38 @@ decl_event!(
 // utility and private functions
 // if marked public, accessible by other modules
 impl<T: Trait> Module<T> {
+    // creates a token, owned by beneficiary
+    // internal
+    fn _init(
+        beneficiary: T::AccountId,
+        name: Vec<u8>,
+        ticker: Vec<u8>,
+        total_supply: T::TokenBalance,
+    ) -> Result {
+        // checking max size for name and ticker
+        // byte arrays (vecs) with no max size should be avoided
+        ensure!(name.len() <= 64, "token name cannot exceed 64 bytes");
+        ensure!(ticker.len() <= 32, "token ticker cannot exceed 32 bytes");
+
+        let token_id = Self::token_id();
+        let next_token_id = token_id
+            .checked_add(1)
+            .ok_or("overflow in calculating next token id")?;
+        TokenId::put(next_token_id);
+
+        let token = Erc20Token {
+            name,
+            ticker,
+            total_supply,
+        };
+
+        <Tokens<T>>::insert(token_id, token);
+        <BalanceOf<T>>::insert((token_id, beneficiary.clone()), total_supply);
+
+        Self::deposit_event(RawEvent::Created(token_id, beneficiary, total_supply));
+        Ok(())
+    }
+
     // the ERC20 standard transfer function
     // internal
     fn _transfer(
@@ -188,6 +286,31 @@ impl<T: Trait> Module<T> {
         from: T::AccountId,
         to: T::AccountId,
         value: T::TokenBalance,
+    ) -> Result {
+        Self::ensure_not_frozen(token_id, &from)?;
+        Self::_move(token_id, from.clone(), to.clone(), value)?;
+        Self::deposit_event(RawEvent::Transfer(token_id, from, to, value));
+        Ok(())
+    }
+
+    // fails if the token or the account are frozen
+    // internal
+    fn ensure_not_frozen(token_id: u32, who: &T::AccountId) -> Result {
+        ensure!(!Self::is_token_frozen(token_id), "Token is frozen.");
+        ensure!(
+            !Self::is_frozen((token_id, who.clone())),
+            "Account is frozen for this token."
+        );
+        Ok(())
+    }
+
+    // moves tokens between accounts, whether they are frozen or not
+    // internal
+    fn _move(
+        token_id: u32,
+        from: T::AccountId,
+        to: T::AccountId,
+        value: T::TokenBalance,
     ) -> Result {
         // reduce sender's balance
         <BalanceOf<T>>::insert(
@@ -207,8 +330,6 @@ impl<T: Trait> Module<T> {
                      catostrophic error.",
                 ),
         );
-
-        Self::deposit_event(RawEvent::Transfer(token_id, from, to, value));
         Ok(())
     }
 }
@@ -263,6 +384,7 @@ mod test {
     impl Trait for Test {
         type Event = ();
         type TokenBalance = u128;
+        type AdminOrigin = system::EnsureRoot<u64>;
     }
     type TemplateModule = Module<Test>;
 
@@ -650,4 +772,71 @@ mod test {
     fn events() {
         unimplemented!();
     }
+
+    #[test]
+    fn create() {
+        with_externalities(&mut new_test_ext(), || {
+            TemplateModule::create(Origin::signed(B), b"Bee".to_vec(), b"BEE".to_vec(), 10)
+                .unwrap();
+            TemplateModule::create(Origin::signed(A), b"Ant".to_vec(), b"ANT".to_vec(), 5).unwrap();
+            assert_eq!(TemplateModule::balance_of((0, B)), 10);
+            assert_eq!(TemplateModule::balance_of((1, A)), 5);
+            assert_eq!(TemplateModule::token_details(1).ticker, b"ANT".to_vec());
+            assert_eq!(TemplateModule::token_id(), 2);
+            TemplateModule::create(Origin::ROOT, b"Root".to_vec(), b"ROOT".to_vec(), 1)
+                .unwrap_err();
+            TemplateModule::create(Origin::signed(A), vec![0; 65], b"LONG".to_vec(), 1)
+                .unwrap_err();
+            TemplateModule::create(Origin::signed(A), b"Long".to_vec(), vec![0; 33], 1)
+                .unwrap_err();
+            assert_eq!(TemplateModule::token_id(), 2);
+
+            // approve and transfer_from work as for tokens created by root
+            TemplateModule::approve(Origin::signed(B), 0, A, 4).unwrap();
+            TemplateModule::transfer_from(Origin::signed(A), 0, B, A, 3).unwrap();
+            assert_eq!(TemplateModule::balance_of((0, A)), 3);
+            assert_eq!(TemplateModule::balance_of((0, B)), 7);
+            assert_eq!(TemplateModule::allowance((0, B, A)), 1);
+        });
+    }
+
+    #[test]
+    fn freeze_account() {
+        with_externalities(&mut new_test_ext(), || {
+            TemplateModule::init(Origin::ROOT, A, b"Trash".to_vec(), b"TRS".to_vec(), 10).unwrap();
+            TemplateModule::freeze(Origin::signed(A), 0, A).unwrap_err();
+            TemplateModule::freeze(Origin::ROOT, 1, A).unwrap_err();
+            TemplateModule::freeze(Origin::ROOT, 0, A).unwrap();
+
+            // A can neither transfer nor burn, but can still receive
+            TemplateModule::transfer(Origin::signed(A), 0, B, 1).unwrap_err();
+            TemplateModule::burn(Origin::signed(A), 0, 1).unwrap_err();
+            TemplateModule::approve(Origin::signed(A), 0, B, 1).unwrap();
+            TemplateModule::transfer_from(Origin::signed(B), 0, A, B, 1).unwrap_err();
+            TemplateModule::force_transfer(Origin::ROOT, 0, A, B, 2).unwrap();
+            TemplateModule::transfer(Origin::signed(B), 0, A, 1).unwrap();
+            assert_eq!(TemplateModule::balance_of((0, A)), 9);
+
+            TemplateModule::thaw(Origin::ROOT, 0, A).unwrap();
+            TemplateModule::transfer(Origin::signed(A), 0, B, 1).unwrap();
+            assert_eq!(TemplateModule::balance_of((0, B)), 2);
+        });
+    }
+
+    #[test]
+    fn freeze_token() {
+        with_externalities(&mut new_test_ext(), || {
+            TemplateModule::init(Origin::ROOT, A, b"Trash".to_vec(), b"TRS".to_vec(), 10).unwrap();
+            TemplateModule::init(Origin::ROOT, A, b"Junk".to_vec(), b"JNK".to_vec(), 10).unwrap();
+            TemplateModule::freeze_token(Origin::ROOT, 0).unwrap();
+            TemplateModule::transfer(Origin::signed(A), 0, B, 1).unwrap_err();
+            TemplateModule::transfer(Origin::signed(A), 1, B, 1).unwrap();
+            TemplateModule::force_transfer(Origin::signed(A), 0, A, C, 1).unwrap_err();
+            TemplateModule::force_transfer(Origin::ROOT, 0, A, C, 1).unwrap();
+            TemplateModule::force_transfer(Origin::ROOT, 0, A, C, 10).unwrap_err();
+            assert_eq!(TemplateModule::balance_of((0, C)), 1);
+            TemplateModule::thaw_token(Origin::ROOT, 0).unwrap();
+            TemplateModule::transfer(Origin::signed(C), 0, B, 1).unwrap();
+        });
+    }
 }
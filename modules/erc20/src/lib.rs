@@ -1,8 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod erc20;
+mod weights;
 
 #[cfg(feature = "std")]
 pub use crate::erc20::GenesisConfig;
 
-pub use crate::erc20::{Erc20Token, Event, Module, Trait, __InherentHiddenInstance};
+pub use crate::erc20::{
+    Call, Erc20Token, Event, Module, RawEvent, Trait, __InherentHiddenInstance,
+};
@@ -0,0 +1,292 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::traits::Saturating;
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    StorageMap, StorageValue,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Reserved from the payer, on top of the amount, until the escrow is closed.
+    type EscrowDeposit: Get<BalanceOf<Self>>;
+}
+
+// `amount` reserved from `payer` until it is released to `payee` or refunded, which `payer` may
+// do alone from `expiry`
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Escrow<AccountId, Balance, BlockNumber> {
+    pub payer: AccountId,
+    pub payee: AccountId,
+    pub arbiter: Option<AccountId>,
+    pub amount: Balance,
+    pub deposit: Balance,
+    pub expiry: BlockNumber,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // reserve `amount` of the origin for `payee`, refundable to the origin after `duration`
+        // blocks unless released before
+        fn create(
+            origin,
+            payee: T::AccountId,
+            arbiter: Option<T::AccountId>,
+            amount: BalanceOf<T>,
+            duration: T::BlockNumber
+        ) -> Result {
+            let payer = ensure_signed(origin)?;
+            ensure!(payer != payee, "payer and payee must differ");
+            let deposit = T::EscrowDeposit::get();
+            T::Currency::reserve(&payer, amount.saturating_add(deposit))?;
+            let id = Self::next_escrow_id();
+            NextEscrowId::put(id + 1);
+            let expiry = <system::Module<T>>::block_number().saturating_add(duration);
+            let escrow = Escrow {
+                payer: payer.clone(),
+                payee: payee.clone(),
+                arbiter,
+                amount,
+                deposit,
+                expiry,
+            };
+            <Escrows<T>>::insert(id, escrow);
+            Self::deposit_event(RawEvent::Created(id, payer, payee, amount));
+            Ok(())
+        }
+
+        // pay the escrowed amount to the payee, as the payer or the arbiter
+        fn release(origin, id: u64) -> Result {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(id).ok_or("escrow does not exist")?;
+            ensure!(
+                who == escrow.payer || Some(&who) == escrow.arbiter.as_ref(),
+                "only the payer or the arbiter may release an escrow"
+            );
+            T::Currency::repatriate_reserved(&escrow.payer, &escrow.payee, escrow.amount)?;
+            T::Currency::unreserve(&escrow.payer, escrow.deposit);
+            <Escrows<T>>::remove(id);
+            Self::deposit_event(RawEvent::Released(id));
+            Ok(())
+        }
+
+        // return the escrowed amount to the payer, as the payee or the arbiter at any time, or as
+        // the payer once the escrow expired
+        fn refund(origin, id: u64) -> Result {
+            let who = ensure_signed(origin)?;
+            let escrow = Self::escrow(id).ok_or("escrow does not exist")?;
+            let expired = <system::Module<T>>::block_number() >= escrow.expiry;
+            ensure!(
+                who == escrow.payee
+                    || Some(&who) == escrow.arbiter.as_ref()
+                    || (who == escrow.payer && expired),
+                "only the payee or the arbiter may refund an escrow before it expires"
+            );
+            T::Currency::unreserve(&escrow.payer, escrow.amount.saturating_add(escrow.deposit));
+            <Escrows<T>>::remove(id);
+            Self::deposit_event(RawEvent::Refunded(id));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Escrow {
+        // open escrows by id
+        Escrows get(escrow):
+            map u64 => Option<Escrow<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+        // id of the next escrow created
+        NextEscrowId get(next_escrow_id): u64;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // id, payer, payee, amount
+        Created(u64, AccountId, AccountId, Balance),
+        // id
+        Released(u64),
+        // id
+        Refunded(u64),
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const EscrowDeposit: u64 = 5;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type EscrowDeposit = EscrowDeposit;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Escrow = Module<Test>;
+
+    // escrow 0 holds 50 of 1 for 2, arbitrated by 3, until block 11
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut ext: runtime_io::TestExternalities<Blake2Hasher> =
+            balances::GenesisConfig::<Test> {
+                balances: vec![(1, 100), (2, 100)],
+                vesting: vec![],
+            }
+            .build_storage()
+            .unwrap()
+            .into();
+        with_externalities(&mut ext, || {
+            System::set_block_number(1);
+            assert_ok!(Escrow::create(Origin::signed(1), 2, Some(3), 50, 10));
+        });
+        ext
+    }
+
+    #[test]
+    fn create() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                Escrow::escrow(0),
+                Some(super::Escrow {
+                    payer: 1,
+                    payee: 2,
+                    arbiter: Some(3),
+                    amount: 50,
+                    deposit: 5,
+                    expiry: 11,
+                })
+            );
+            assert_eq!(Balances::reserved_balance(1), 55);
+            assert_noop!(
+                Escrow::create(Origin::signed(1), 1, None, 10, 10),
+                "payer and payee must differ"
+            );
+            // 45 is free, the deposit doesn't fit
+            assert!(Escrow::create(Origin::signed(1), 2, None, 41, 10).is_err());
+            assert_ok!(Escrow::create(Origin::signed(1), 2, None, 40, 10));
+            assert_eq!(Escrow::next_escrow_id(), 2);
+        });
+    }
+
+    #[test]
+    fn release() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Escrow::release(Origin::signed(2), 0),
+                "only the payer or the arbiter may release an escrow"
+            );
+            assert_ok!(Escrow::release(Origin::signed(3), 0));
+            assert_eq!(Balances::free_balance(1), 50);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(2), 150);
+            assert_noop!(
+                Escrow::release(Origin::signed(1), 0),
+                "escrow does not exist"
+            );
+        });
+    }
+
+    #[test]
+    fn refund() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Escrow::refund(Origin::signed(1), 0),
+                "only the payee or the arbiter may refund an escrow before it expires"
+            );
+            assert_ok!(Escrow::refund(Origin::signed(2), 0));
+            assert_eq!(Balances::free_balance(1), 100);
+            assert_eq!(Balances::reserved_balance(1), 0);
+        });
+    }
+
+    #[test]
+    fn refund_expired() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(11);
+            assert_noop!(
+                Escrow::refund(Origin::signed(4), 0),
+                "only the payee or the arbiter may refund an escrow before it expires"
+            );
+            assert_ok!(Escrow::refund(Origin::signed(1), 0));
+            assert_eq!(Balances::free_balance(1), 100);
+            assert_eq!(Escrow::escrow(0), None);
+        });
+    }
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod escrow;
+
+pub use crate::escrow::{Call, Escrow, Event, Module, RawEvent, Trait};
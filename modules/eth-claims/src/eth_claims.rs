@@ -0,0 +1,346 @@
+use codec::{Decode, Encode};
+use primitives::H160;
+use rstd::prelude::*;
+use runtime_io::{keccak_256, secp256k1_ecdsa_recover};
+use sr_primitives::{
+    traits::{Saturating, Zero},
+    transaction_validity::{
+        InvalidTransaction, TransactionLongevity, TransactionValidity, ValidTransaction,
+    },
+};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, traits::Currency, traits::Get,
+    StorageMap, StorageValue,
+};
+use system::{self, ensure_none};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Last 20 bytes of the keccak hash of an ethereum public key.
+pub type EthereumAddress = H160;
+
+/// Signature by an ethereum key, as r, s and the recovery id v. Wallets show it as 65 bytes of
+/// hex in that order.
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct EcdsaSignature(pub [u8; 32], pub [u8; 32], pub i8);
+
+impl EcdsaSignature {
+    /// The 65 bytes r, s, v.
+    pub fn to_blob(&self) -> [u8; 65] {
+        let mut blob = [0; 65];
+        blob[..32].copy_from_slice(&self.0[..]);
+        blob[32..64].copy_from_slice(&self.1[..]);
+        blob[64] = self.2 as u8;
+        blob
+    }
+
+    pub fn from_blob(blob: &[u8; 65]) -> Self {
+        let mut r = [0; 32];
+        r.copy_from_slice(&blob[..32]);
+        let mut s = [0; 32];
+        s.copy_from_slice(&blob[32..64]);
+        EcdsaSignature(r, s, blob[64] as i8)
+    }
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency claims are minted in.
+    type Currency: Currency<Self::AccountId>;
+    /// Start of the message holders sign, which ends with the hex encoded account to pay.
+    type Prefix: Get<&'static [u8]>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        const Prefix: &[u8] = T::Prefix::get();
+
+        fn deposit_event() = default;
+
+        // mint the snapshot balance of the ethereum address that signed `dest` with
+        // `ethereum_signature` to `dest`; unsigned so that empty accounts can claim
+        fn claim(origin, dest: T::AccountId, ethereum_signature: EcdsaSignature) -> Result {
+            ensure_none(origin)?;
+            let signer = Self::eth_recover(&ethereum_signature, &dest)
+                .ok_or("invalid ethereum signature")?;
+            let amount = <Claims<T>>::take(&signer).ok_or("ethereum address has no claim")?;
+            <Total<T>>::mutate(|total| *total = total.saturating_sub(amount));
+            drop(T::Currency::deposit_creating(&dest, amount));
+            Self::deposit_event(RawEvent::Claimed(dest, signer, amount));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as EthClaims {
+        // Unclaimed snapshot balance of an ethereum address.
+        Claims get(claims) build(|config: &GenesisConfig<T>| config.claims.clone()):
+            map EthereumAddress => Option<BalanceOf<T>>;
+        // Sum of the unclaimed balances.
+        Total get(total) build(|config: &GenesisConfig<T>| {
+            config.claims.iter().fold(Zero::zero(), |total: BalanceOf<T>, (_, amount)| {
+                total + *amount
+            })
+        }): BalanceOf<T>;
+    }
+    add_extra_genesis {
+        // (address, balance) of each holder in the snapshot, each address listed once
+        config(claims): Vec<(EthereumAddress, BalanceOf<T>)>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // account paid, ethereum address, amount
+        Claimed(AccountId, EthereumAddress, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// The message an ethereum wallet signs for a claim paying `dest`: the prefix then the hex
+    /// encoded account, in the "\x19Ethereum Signed Message:\n" envelope of `personal_sign`.
+    pub fn ethereum_signable_message(dest: &T::AccountId) -> Vec<u8> {
+        let prefix = T::Prefix::get();
+        let account = dest.using_encoded(to_ascii_hex);
+        let mut length = prefix.len() + account.len();
+        let mut digits = Vec::new();
+        while length > 0 {
+            digits.push(b'0' + (length % 10) as u8);
+            length /= 10;
+        }
+        let mut message = b"\x19Ethereum Signed Message:\n".to_vec();
+        message.extend(digits.into_iter().rev());
+        message.extend_from_slice(prefix);
+        message.extend_from_slice(&account);
+        message
+    }
+
+    /// Address of the key that signed the claim message for `dest`.
+    pub fn eth_recover(signature: &EcdsaSignature, dest: &T::AccountId) -> Option<EthereumAddress> {
+        let message = keccak_256(&Self::ethereum_signable_message(dest));
+        let public = secp256k1_ecdsa_recover(&signature.to_blob(), &message).ok()?;
+        Some(EthereumAddress::from_slice(&keccak_256(&public[..])[12..]))
+    }
+}
+
+/// Lowercase hex of `data`, without a 0x prefix.
+fn to_ascii_hex(data: &[u8]) -> Vec<u8> {
+    let digit = |nibble: u8| {
+        if nibble < 10 {
+            b'0' + nibble
+        } else {
+            b'a' + nibble - 10
+        }
+    };
+    let mut hex = Vec::with_capacity(data.len() * 2);
+    for byte in data {
+        hex.push(digit(byte >> 4));
+        hex.push(digit(byte & 0xf));
+    }
+    hex
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+        if let Call::claim(dest, signature) = call {
+            let signer = match Self::eth_recover(signature, dest) {
+                Some(signer) => signer,
+                None => return InvalidTransaction::BadProof.into(),
+            };
+            if !<Claims<T>>::exists(&signer) {
+                return InvalidTransaction::Stale.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(b"eth_claims", signer).encode()],
+                longevity: TransactionLongevity::max_value(),
+                propagate: true,
+            })
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::unsigned::ValidateUnsigned;
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const Prefix: &'static [u8] = b"Pay TEST to the account:";
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type Prefix = Prefix;
+    }
+    type Balances = balances::Module<Test>;
+    type EthClaims = Module<Test>;
+
+    fn alice() -> secp256k1::SecretKey {
+        secp256k1::SecretKey::parse(&keccak_256(b"Alice")).unwrap()
+    }
+
+    fn bob() -> secp256k1::SecretKey {
+        secp256k1::SecretKey::parse(&keccak_256(b"Bob")).unwrap()
+    }
+
+    fn eth(secret: &secp256k1::SecretKey) -> EthereumAddress {
+        let public = secp256k1::PublicKey::from_secret_key(secret).serialize();
+        EthereumAddress::from_slice(&keccak_256(&public[1..65])[12..])
+    }
+
+    fn sig(secret: &secp256k1::SecretKey, dest: u64) -> EcdsaSignature {
+        let message = keccak_256(&EthClaims::ethereum_signable_message(&dest));
+        let (signature, recovery_id) =
+            secp256k1::sign(&secp256k1::Message::parse(&message), secret).unwrap();
+        let mut blob = [0; 65];
+        blob[..64].copy_from_slice(&signature.serialize()[..]);
+        blob[64] = recovery_id.serialize();
+        EcdsaSignature::from_blob(&blob)
+    }
+
+    // alice's ethereum address holds 100 in the snapshot
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> {
+            claims: vec![(eth(&alice()), 100)],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn signable_message() {
+        assert_eq!(to_ascii_hex(&[0x01, 0xab, 0xf0]), b"01abf0".to_vec());
+        // 24 bytes of prefix and 16 hex digits of account
+        assert_eq!(
+            EthClaims::ethereum_signable_message(&1),
+            b"\x19Ethereum Signed Message:\n40Pay TEST to the account:0100000000000000".to_vec()
+        );
+        let signature = sig(&alice(), 1);
+        assert_eq!(EcdsaSignature::from_blob(&signature.to_blob()), signature);
+    }
+
+    #[test]
+    fn claim() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(EthClaims::total(), 100);
+            assert_eq!(
+                EthClaims::eth_recover(&sig(&alice(), 1), &1),
+                Some(eth(&alice()))
+            );
+            assert!(EthClaims::claim(Origin::signed(1), 1, sig(&alice(), 1)).is_err());
+            // a signature for another account recovers another address
+            assert_noop!(
+                EthClaims::claim(Origin::NONE, 2, sig(&alice(), 1)),
+                "ethereum address has no claim"
+            );
+            assert_noop!(
+                EthClaims::claim(Origin::NONE, 1, sig(&bob(), 1)),
+                "ethereum address has no claim"
+            );
+            assert_ok!(EthClaims::claim(Origin::NONE, 1, sig(&alice(), 1)));
+            assert_eq!(Balances::free_balance(1), 100);
+            assert_eq!(Balances::total_issuance(), 100);
+            assert_eq!(EthClaims::total(), 0);
+            assert_eq!(EthClaims::claims(eth(&alice())), None);
+            assert_noop!(
+                EthClaims::claim(Origin::NONE, 1, sig(&alice(), 1)),
+                "ethereum address has no claim"
+            );
+        });
+    }
+
+    #[test]
+    fn validate_unsigned() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                EthClaims::validate_unsigned(&Call::claim(1, sig(&alice(), 1)))
+                    .unwrap()
+                    .provides,
+                vec![(b"eth_claims", eth(&alice())).encode()]
+            );
+            assert_eq!(
+                EthClaims::validate_unsigned(&Call::claim(1, sig(&bob(), 1))),
+                InvalidTransaction::Stale.into()
+            );
+            assert_eq!(
+                EthClaims::validate_unsigned(&Call::claim(1, EcdsaSignature([0; 32], [0; 32], 0))),
+                InvalidTransaction::BadProof.into()
+            );
+        });
+    }
+}
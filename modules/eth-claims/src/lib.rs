@@ -0,0 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod eth_claims;
+
+#[cfg(feature = "std")]
+pub use crate::eth_claims::GenesisConfig;
+
+pub use crate::eth_claims::{
+    Call, EcdsaSignature, EthereumAddress, Event, Module, RawEvent, Trait,
+};
@@ -0,0 +1,248 @@
+use crate::weights;
+use codec::Encode;
+use rstd::prelude::*;
+use sr_primitives::{
+    traits::{AccountIdConversion, SaturatedConversion, Saturating},
+    transaction_validity::{
+        InvalidTransaction, TransactionLongevity, TransactionValidity, ValidTransaction,
+    },
+    weights::SimpleDispatchInfo,
+    ModuleId,
+};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Currency, StorageMap,
+};
+use system::{self, ensure_none};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Derives the faucet account, which is endowed at genesis.
+pub const MODULE_ID: ModuleId = ModuleId(*b"py/fauct");
+/// Amount of a drip on chains that don't set it at genesis, ten tokens at 12 decimals.
+pub const DEFAULT_DRIP_AMOUNT: u64 = 10_000_000_000_000;
+/// Blocks an account waits between drips on chains that don't set it at genesis, a day of 6
+/// second blocks.
+pub const DEFAULT_COOLDOWN: u32 = 14_400;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency the faucet drips.
+    type Currency: Currency<Self::AccountId>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // pay `dest` a drip from the faucet account, once per cooldown; unsigned so that empty
+        // accounts can ask
+        #[weight = SimpleDispatchInfo::FixedNormal(weights::DRIP)]
+        fn drip(origin, dest: T::AccountId) -> Result {
+            ensure_none(origin)?;
+            ensure!(Self::can_drip(&dest), "account must wait for its cooldown to pass");
+            let amount = Self::drip_amount();
+            T::Currency::transfer(&Self::account_id(), &dest, amount)?;
+            <LastDrip<T>>::insert(&dest, <system::Module<T>>::block_number());
+            Self::deposit_event(RawEvent::Dripped(dest, amount));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Faucet {
+        // Amount paid by a drip.
+        DripAmount get(drip_amount) config(): BalanceOf<T> =
+            BalanceOf::<T>::saturated_from(DEFAULT_DRIP_AMOUNT);
+        // Blocks an account waits between drips.
+        Cooldown get(cooldown) config(): T::BlockNumber = DEFAULT_COOLDOWN.into();
+        // Block of the last drip to an account.
+        LastDrip get(last_drip): map T::AccountId => Option<T::BlockNumber>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // recipient, amount
+        Dripped(AccountId, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Account the faucet drips from.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    fn can_drip(dest: &T::AccountId) -> bool {
+        Self::last_drip(dest).map_or(true, |last| {
+            <system::Module<T>>::block_number() >= last.saturating_add(Self::cooldown())
+        })
+    }
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+        if let Call::drip(dest) = call {
+            if !Self::can_drip(dest) {
+                return InvalidTransaction::Stale.into();
+            }
+            if T::Currency::free_balance(&Self::account_id()) < Self::drip_amount() {
+                return InvalidTransaction::Payment.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(b"faucet", dest).encode()],
+                longevity: Self::cooldown().saturated_into::<TransactionLongevity>(),
+                propagate: true,
+            })
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::unsigned::ValidateUnsigned;
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Faucet = Module<Test>;
+
+    // the faucet holds 35 and drips 10 every 5 blocks
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = balances::GenesisConfig::<Test> {
+            balances: vec![(Faucet::account_id(), 35)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap();
+        GenesisConfig::<Test> {
+            drip_amount: 10,
+            cooldown: 5,
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        storage.into()
+    }
+
+    #[test]
+    fn drip() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert!(Faucet::drip(Origin::signed(1), 1).is_err());
+            assert_ok!(Faucet::drip(Origin::NONE, 1));
+            assert_eq!(Balances::free_balance(1), 10);
+            assert_eq!(Faucet::last_drip(1), Some(1));
+            assert_noop!(
+                Faucet::drip(Origin::NONE, 1),
+                "account must wait for its cooldown to pass"
+            );
+            assert_ok!(Faucet::drip(Origin::NONE, 2));
+            System::set_block_number(6);
+            assert_ok!(Faucet::drip(Origin::NONE, 1));
+            assert_eq!(Balances::free_balance(1), 20);
+            // the faucet ran dry
+            assert!(Faucet::drip(Origin::NONE, 3).is_err());
+        });
+    }
+
+    #[test]
+    fn validate_unsigned() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            let valid = Faucet::validate_unsigned(&Call::drip(1)).unwrap();
+            assert_eq!(valid.longevity, 5);
+            assert_ne!(
+                valid.provides,
+                Faucet::validate_unsigned(&Call::drip(2)).unwrap().provides
+            );
+            assert_ok!(Faucet::drip(Origin::NONE, 1));
+            assert_eq!(
+                Faucet::validate_unsigned(&Call::drip(1)),
+                InvalidTransaction::Stale.into()
+            );
+            assert_ok!(Faucet::drip(Origin::NONE, 2));
+            assert_ok!(Faucet::drip(Origin::NONE, 3));
+            assert_eq!(
+                Faucet::validate_unsigned(&Call::drip(4)),
+                InvalidTransaction::Payment.into()
+            );
+        });
+    }
+}
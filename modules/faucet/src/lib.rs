@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod faucet;
+mod weights;
+
+#[cfg(feature = "std")]
+pub use crate::faucet::GenesisConfig;
+
+pub use crate::faucet::{
+    Call, Event, Module, RawEvent, Trait, DEFAULT_COOLDOWN, DEFAULT_DRIP_AMOUNT, MODULE_ID,
+};
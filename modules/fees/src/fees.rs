@@ -0,0 +1,126 @@
+use rstd::marker::PhantomData;
+use rstd::prelude::*;
+use sr_primitives::{
+    traits::{Convert, Saturating, SimpleArithmetic},
+    weights::Weight,
+};
+use support::{decl_module, traits::Get, Parameter};
+
+pub trait Trait: system::Trait {
+    /// Balance in which transaction fees are paid.
+    type Balance: Parameter + SimpleArithmetic + Default + Copy + 'static;
+    /// Coefficients of the polynomial turning the weight of a transaction into its fee, lowest
+    /// degree first.
+    type WeightFeeCoefficients: Get<&'static [Self::Balance]>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        /// Coefficients of the polynomial turning the weight of a transaction into its fee,
+        /// lowest degree first. The base and length fees of the balances module come on top.
+        const WeightFeeCoefficients: Vec<T::Balance> = T::WeightFeeCoefficients::get().to_vec();
+    }
+}
+
+/// The polynomial with `coefficients`, lowest degree first, at `weight`. Saturates rather than
+/// overflows.
+pub fn weight_to_fee<Balance: SimpleArithmetic + Copy>(
+    coefficients: &[Balance],
+    weight: Weight,
+) -> Balance {
+    let weight = Balance::from(weight);
+    let mut power = Balance::one();
+    let mut fee = Balance::zero();
+    for coefficient in coefficients {
+        fee = fee.saturating_add(coefficient.saturating_mul(power));
+        power = power.saturating_mul(weight);
+    }
+    fee
+}
+
+/// For use as the balances module's `WeightToFee`.
+pub struct WeightToFee<T>(PhantomData<T>);
+
+impl<T: Trait> Convert<Weight, T::Balance> for WeightToFee<T> {
+    fn convert(weight: Weight) -> T::Balance {
+        weight_to_fee(T::WeightFeeCoefficients::get(), weight)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::H256;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const WeightFeeCoefficients: &'static [u128] = &[5, 3, 2];
+    }
+    impl Trait for Test {
+        type Balance = u128;
+        type WeightFeeCoefficients = WeightFeeCoefficients;
+    }
+
+    #[test]
+    fn polynomial() {
+        assert_eq!(WeightToFee::<Test>::convert(0), 5);
+        assert_eq!(WeightToFee::<Test>::convert(1), 10);
+        assert_eq!(WeightToFee::<Test>::convert(10), 5 + 30 + 200);
+        assert_eq!(weight_to_fee::<u128>(&[], 10), 0);
+        assert_eq!(weight_to_fee::<u128>(&[0, 7], 10), 70);
+    }
+
+    #[test]
+    fn saturates() {
+        assert_eq!(
+            weight_to_fee::<u64>(&[1, 1, 1, 1, 1], u32::max_value()),
+            u64::max_value()
+        );
+        assert_eq!(
+            weight_to_fee::<u64>(&[u64::max_value(), 1], 1),
+            u64::max_value()
+        );
+        // a zero coefficient after a saturated power adds nothing
+        assert_eq!(
+            weight_to_fee::<u64>(&[0, 0, 0, 0, 1], 1 << 16),
+            u64::max_value()
+        );
+        assert_eq!(weight_to_fee::<u64>(&[0, 0, 0, 0, 0], 1 << 16), 0);
+    }
+}
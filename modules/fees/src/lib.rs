@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod fees;
+
+pub use crate::fees::{weight_to_fee, Module, Trait, WeightToFee};
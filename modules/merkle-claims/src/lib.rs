@@ -0,0 +1,12 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod merkle;
+mod merkle_claims;
+
+#[cfg(feature = "std")]
+pub use crate::merkle::MerkleTree;
+#[cfg(feature = "std")]
+pub use crate::merkle_claims::GenesisConfig;
+
+pub use crate::merkle::{leaf, parent, root_of, Node};
+pub use crate::merkle_claims::{Call, Event, Module, RawEvent, Trait};
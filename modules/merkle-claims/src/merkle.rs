@@ -0,0 +1,103 @@
+use codec::Encode;
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+
+/// A node of an allocation tree, leaves and parents alike.
+pub type Node = [u8; 32];
+
+/// Leaf committing to the allocation of `amount` to `who`.
+pub fn leaf<AccountId: Encode, Balance: Encode>(who: &AccountId, amount: &Balance) -> Node {
+    (who, amount).using_encoded(blake2_256)
+}
+
+/// Parent of two nodes. The pair is sorted before hashing so that a proof doesn't need to say
+/// which side each sibling is on.
+pub fn parent(a: &Node, b: &Node) -> Node {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    (first, second).using_encoded(blake2_256)
+}
+
+/// Root of the tree `leaf` belongs to, given the siblings on the path from `leaf` up to the root.
+pub fn root_of(leaf: Node, proof: &[Node]) -> Node {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| parent(&node, sibling))
+}
+
+/// A binary merkle tree over a list of leaves. A node without a sibling is promoted to the next
+/// level unchanged, so proofs of leaves near the end of an odd level are shorter. The tree of no
+/// leaves has an all zero root, which no proof reaches.
+#[cfg(feature = "std")]
+pub struct MerkleTree {
+    // leaves first, root last
+    levels: Vec<Vec<Node>>,
+}
+
+#[cfg(feature = "std")]
+impl MerkleTree {
+    pub fn new(leaves: Vec<Node>) -> Self {
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| {
+                    if pair.len() == 2 {
+                        parent(&pair[0], &pair[1])
+                    } else {
+                        pair[0]
+                    }
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> Node {
+        self.levels[self.levels.len() - 1]
+            .first()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Siblings on the path from the leaf at `index` to the root, for `root_of`.
+    pub fn proof(&self, index: usize) -> Vec<Node> {
+        let mut index = index;
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn leaves(count: u64) -> Vec<Node> {
+        (0..count).map(|who| leaf(&who, &(who * 10))).collect()
+    }
+
+    #[test]
+    fn proofs_reach_the_root() {
+        for count in 1..12 {
+            let tree = MerkleTree::new(leaves(count));
+            for (index, leaf) in leaves(count).into_iter().enumerate() {
+                assert_eq!(root_of(leaf, &tree.proof(index)), tree.root());
+            }
+        }
+    }
+
+    #[test]
+    fn proofs_are_specific() {
+        let tree = MerkleTree::new(leaves(5));
+        assert_ne!(root_of(leaf(&1u64, &11u64), &tree.proof(1)), tree.root());
+        assert_ne!(root_of(leaves(5)[1], &tree.proof(2)), tree.root());
+        assert_eq!(MerkleTree::new(vec![]).root(), [0; 32]);
+        assert_eq!(MerkleTree::new(leaves(1)).root(), leaves(1)[0]);
+    }
+}
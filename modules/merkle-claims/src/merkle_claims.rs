@@ -0,0 +1,230 @@
+use crate::merkle::{leaf, root_of, Node};
+use codec::Encode;
+use rstd::prelude::*;
+use sr_primitives::transaction_validity::{
+    InvalidTransaction, TransactionLongevity, TransactionValidity, ValidTransaction,
+};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Currency, StorageMap,
+};
+use system::{self, ensure_none};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency allocations are minted in.
+    type Currency: Currency<Self::AccountId>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // mint the `amount` allocated to `dest`, proven by the siblings on the path from its leaf
+        // to the genesis root; unsigned so that empty accounts can claim
+        fn claim(origin, dest: T::AccountId, amount: BalanceOf<T>, proof: Vec<Node>) -> Result {
+            ensure_none(origin)?;
+            ensure!(!Self::claimed(&dest), "allocation already claimed");
+            ensure!(Self::is_allocated(&dest, amount, &proof), "proof does not match the root");
+            <Claimed<T>>::insert(&dest, true);
+            drop(T::Currency::deposit_creating(&dest, amount));
+            Self::deposit_event(RawEvent::Claimed(dest, amount));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as MerkleClaims {
+        // Root of the tree of (account, amount) allocations. Left at zero there is nothing to
+        // claim.
+        Root get(root) config(): Node;
+        // Whether an account has claimed its allocation.
+        Claimed get(claimed): map T::AccountId => bool;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // account, amount
+        Claimed(AccountId, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Whether `proof` shows the tree under the genesis root allocates `amount` to `who`.
+    pub fn is_allocated(who: &T::AccountId, amount: BalanceOf<T>, proof: &[Node]) -> bool {
+        root_of(leaf(who, &amount), proof) == Self::root()
+    }
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+        if let Call::claim(dest, amount, proof) = call {
+            if Self::claimed(dest) {
+                return InvalidTransaction::Stale.into();
+            }
+            if !Self::is_allocated(dest, *amount, proof) {
+                return InvalidTransaction::BadProof.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(b"claims", dest).encode()],
+                longevity: TransactionLongevity::max_value(),
+                propagate: true,
+            })
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::merkle::MerkleTree;
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::unsigned::ValidateUnsigned;
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+    }
+    type Balances = balances::Module<Test>;
+    type Claims = Module<Test>;
+
+    // accounts 1 to 3 are allocated 10 times their id
+    fn tree() -> MerkleTree {
+        MerkleTree::new((1..4u64).map(|who| leaf(&who, &(who * 10))).collect())
+    }
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+        GenesisConfig {
+            root: tree().root(),
+        }
+        .assimilate_storage::<Test>(&mut storage)
+        .unwrap();
+        storage.into()
+    }
+
+    #[test]
+    fn claim() {
+        with_externalities(&mut new_test_ext(), || {
+            let tree = tree();
+            assert!(Claims::claim(Origin::signed(1), 1, 10, tree.proof(0)).is_err());
+            assert_noop!(
+                Claims::claim(Origin::NONE, 1, 11, tree.proof(0)),
+                "proof does not match the root"
+            );
+            assert_noop!(
+                Claims::claim(Origin::NONE, 2, 10, tree.proof(0)),
+                "proof does not match the root"
+            );
+            assert_ok!(Claims::claim(Origin::NONE, 1, 10, tree.proof(0)));
+            assert_eq!(Balances::free_balance(1), 10);
+            assert_eq!(Balances::total_issuance(), 10);
+            assert!(Claims::claimed(1));
+            assert_noop!(
+                Claims::claim(Origin::NONE, 1, 10, tree.proof(0)),
+                "allocation already claimed"
+            );
+            assert_ok!(Claims::claim(Origin::NONE, 3, 30, tree.proof(2)));
+            assert_eq!(Balances::free_balance(3), 30);
+        });
+    }
+
+    #[test]
+    fn validate_unsigned() {
+        with_externalities(&mut new_test_ext(), || {
+            let tree = tree();
+            let valid = Claims::validate_unsigned(&Call::claim(2, 20, tree.proof(1))).unwrap();
+            assert_ne!(
+                valid.provides,
+                Claims::validate_unsigned(&Call::claim(1, 10, tree.proof(0)))
+                    .unwrap()
+                    .provides
+            );
+            assert_eq!(
+                Claims::validate_unsigned(&Call::claim(2, 21, tree.proof(1))),
+                InvalidTransaction::BadProof.into()
+            );
+            assert_ok!(Claims::claim(Origin::NONE, 2, 20, tree.proof(1)));
+            assert_eq!(
+                Claims::validate_unsigned(&Call::claim(2, 20, tree.proof(1))),
+                InvalidTransaction::Stale.into()
+            );
+        });
+    }
+}
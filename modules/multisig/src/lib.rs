@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod multisig;
+
+pub use crate::multisig::{multi_account_id, Event, Module, Multisig, RawEvent, Trait};
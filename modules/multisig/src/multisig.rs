@@ -0,0 +1,441 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::{Dispatchable, Result},
+    ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    Parameter, StorageMap,
+};
+use system::{self, ensure_signed, RawOrigin};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Calls a multisig account can make.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Reserved from the first approver of a call until the call is executed or cancelled.
+    type MultisigDeposit: Get<BalanceOf<Self>>;
+    /// Largest number of signatories of a multisig account.
+    type MaxSignatories: Get<u16>;
+}
+
+/// Account controlled by `threshold` of `signatories`, which must be sorted and distinct. This is
+/// the derivation of the substrate utility module, so addresses computed off chain match.
+pub fn multi_account_id<AccountId: Encode + Decode + Default>(
+    signatories: &[AccountId],
+    threshold: u16,
+) -> AccountId {
+    let entropy = (b"modlpy/utilisuba", signatories, threshold).using_encoded(blake2_256);
+    AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+}
+
+// approvals of a call that has not reached its threshold yet
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Multisig<AccountId, Balance> {
+    pub depositor: AccountId,
+    pub deposit: Balance,
+    pub approvals: Vec<AccountId>,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // approve a call of the multisig account of the origin and `other_signatories`, the call
+        // is dispatched from the multisig account by the approval that reaches `threshold`
+        fn as_multi(
+            origin,
+            threshold: u16,
+            other_signatories: Vec<T::AccountId>,
+            call: Box<<T as Trait>::Call>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let id = Self::multisig_account(&who, threshold, other_signatories)?;
+            let call_hash = call.using_encoded(blake2_256);
+            let multisig = Self::pending(&who, &id, call_hash)?;
+            if Self::approvals(&multisig) + 1 < usize::from(threshold) {
+                return Self::approve(who, id, call_hash, multisig);
+            }
+            if let Some(multisig) = multisig {
+                T::Currency::unreserve(&multisig.depositor, multisig.deposit);
+                <Multisigs<T>>::remove((id.clone(), call_hash));
+            }
+            let result = call.dispatch(RawOrigin::Signed(id.clone()).into());
+            Self::deposit_event(RawEvent::MultisigExecuted(who, id, call_hash, result.is_ok()));
+            Ok(())
+        }
+
+        // approve a call by its hash without supplying it, the final approval must use `as_multi`
+        fn approve_as_multi(
+            origin,
+            threshold: u16,
+            other_signatories: Vec<T::AccountId>,
+            call_hash: [u8; 32]
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let id = Self::multisig_account(&who, threshold, other_signatories)?;
+            let multisig = Self::pending(&who, &id, call_hash)?;
+            ensure!(
+                Self::approvals(&multisig) + 1 < usize::from(threshold),
+                "the final approval must include the call"
+            );
+            Self::approve(who, id, call_hash, multisig)
+        }
+
+        // drop the approvals of a call, returning the deposit; only the first approver may cancel
+        fn cancel_as_multi(
+            origin,
+            threshold: u16,
+            other_signatories: Vec<T::AccountId>,
+            call_hash: [u8; 32]
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let id = Self::multisig_account(&who, threshold, other_signatories)?;
+            let multisig = Self::multisigs((id.clone(), call_hash)).ok_or("call has no approvals")?;
+            ensure!(multisig.depositor == who, "only the first approver may cancel a call");
+            T::Currency::unreserve(&who, multisig.deposit);
+            <Multisigs<T>>::remove((id.clone(), call_hash));
+            Self::deposit_event(RawEvent::MultisigCancelled(who, id, call_hash));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Multisig {
+        // pending approvals by multisig account and call hash
+        Multisigs get(multisigs):
+            map (T::AccountId, [u8; 32]) => Option<Multisig<T::AccountId, BalanceOf<T>>>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        // approver, multisig account, call hash; the approver reserved the deposit
+        NewMultisig(AccountId, AccountId, [u8; 32]),
+        // approver, multisig account, call hash
+        MultisigApproval(AccountId, AccountId, [u8; 32]),
+        // final approver, multisig account, call hash, whether the call succeeded
+        MultisigExecuted(AccountId, AccountId, [u8; 32], bool),
+        // first approver, multisig account, call hash
+        MultisigCancelled(AccountId, AccountId, [u8; 32]),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn multisig_account(
+        who: &T::AccountId,
+        threshold: u16,
+        mut signatories: Vec<T::AccountId>,
+    ) -> rstd::result::Result<T::AccountId, &'static str> {
+        signatories.push(who.clone());
+        signatories.sort();
+        ensure!(
+            signatories.windows(2).all(|pair| pair[0] != pair[1]),
+            "signatories must be distinct"
+        );
+        ensure!(
+            signatories.len() <= usize::from(T::MaxSignatories::get()),
+            "too many signatories"
+        );
+        ensure!(
+            threshold > 0 && usize::from(threshold) <= signatories.len(),
+            "multisig threshold must be between 1 and the number of signatories"
+        );
+        Ok(multi_account_id(&signatories, threshold))
+    }
+
+    // approvals of the call so far, unless `who` already approved it
+    fn pending(
+        who: &T::AccountId,
+        id: &T::AccountId,
+        call_hash: [u8; 32],
+    ) -> rstd::result::Result<Option<Multisig<T::AccountId, BalanceOf<T>>>, &'static str> {
+        let multisig = Self::multisigs((id.clone(), call_hash));
+        if let Some(multisig) = &multisig {
+            ensure!(
+                !multisig.approvals.contains(who),
+                "account already approved this call"
+            );
+        }
+        Ok(multisig)
+    }
+
+    fn approvals(multisig: &Option<Multisig<T::AccountId, BalanceOf<T>>>) -> usize {
+        multisig
+            .as_ref()
+            .map_or(0, |multisig| multisig.approvals.len())
+    }
+
+    fn approve(
+        who: T::AccountId,
+        id: T::AccountId,
+        call_hash: [u8; 32],
+        multisig: Option<Multisig<T::AccountId, BalanceOf<T>>>,
+    ) -> Result {
+        let multisig = match multisig {
+            Some(mut multisig) => {
+                multisig.approvals.push(who.clone());
+                Self::deposit_event(RawEvent::MultisigApproval(who, id.clone(), call_hash));
+                multisig
+            }
+            None => {
+                let deposit = T::MultisigDeposit::get();
+                T::Currency::reserve(&who, deposit)?;
+                Self::deposit_event(RawEvent::NewMultisig(who.clone(), id.clone(), call_hash));
+                Multisig {
+                    depositor: who.clone(),
+                    deposit,
+                    approvals: vec![who],
+                }
+            }
+        };
+        <Multisigs<T>>::insert((id, call_hash), multisig);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const MultisigDeposit: u64 = 2;
+        pub const MaxSignatories: u16 = 3;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Call = Call;
+        type Currency = Balances;
+        type MultisigDeposit = MultisigDeposit;
+        type MaxSignatories = MaxSignatories;
+    }
+    type Balances = balances::Module<Test>;
+    type Multisig = Module<Test>;
+
+    // the 2 of 3 multisig account of 1, 2 and 3
+    fn multisig() -> u64 {
+        multi_account_id(&[1, 2, 3], 2)
+    }
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        balances::GenesisConfig::<Test> {
+            balances: vec![(1, 10), (2, 10), (3, 10), (multisig(), 100)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn transfer(to: u64, value: u64) -> Box<Call> {
+        Box::new(Call::Balances(balances::Call::transfer(to, value)))
+    }
+
+    #[test]
+    fn multisig_account() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                Multisig::multisig_account(&2, 2, vec![3, 1]),
+                Ok(multisig())
+            );
+            assert_eq!(
+                Multisig::multisig_account(&3, 2, vec![1, 2]),
+                Ok(multisig())
+            );
+            assert_ne!(
+                Multisig::multisig_account(&1, 3, vec![2, 3]),
+                Ok(multisig())
+            );
+            assert_ne!(Multisig::multisig_account(&1, 2, vec![2]), Ok(multisig()));
+            assert!(Multisig::multisig_account(&1, 2, vec![1, 2]).is_err());
+            assert!(Multisig::multisig_account(&1, 0, vec![2, 3]).is_err());
+            assert!(Multisig::multisig_account(&1, 4, vec![2, 3]).is_err());
+            assert!(Multisig::multisig_account(&1, 1, vec![2, 3, 4]).is_err());
+        });
+    }
+
+    #[test]
+    fn executes_at_threshold() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(1),
+                2,
+                vec![2, 3],
+                transfer(4, 50)
+            ));
+            assert_eq!(Balances::free_balance(&4), 0);
+            assert_eq!(Balances::reserved_balance(&1), 2);
+            assert_noop!(
+                Multisig::as_multi(Origin::signed(1), 2, vec![3, 2], transfer(4, 50)),
+                "account already approved this call"
+            );
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(3),
+                2,
+                vec![1, 2],
+                transfer(4, 50)
+            ));
+            assert_eq!(Balances::free_balance(&4), 50);
+            assert_eq!(Balances::free_balance(&multisig()), 50);
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert_eq!(Balances::free_balance(&1), 10);
+            let call_hash = transfer(4, 50).using_encoded(blake2_256);
+            assert_eq!(Multisig::multisigs((multisig(), call_hash)), None);
+        });
+    }
+
+    #[test]
+    fn approve_by_hash() {
+        with_externalities(&mut new_test_ext(), || {
+            let call_hash = transfer(4, 50).using_encoded(blake2_256);
+            assert_ok!(Multisig::approve_as_multi(
+                Origin::signed(2),
+                2,
+                vec![1, 3],
+                call_hash
+            ));
+            assert_noop!(
+                Multisig::approve_as_multi(Origin::signed(3), 2, vec![1, 2], call_hash),
+                "the final approval must include the call"
+            );
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(3),
+                2,
+                vec![1, 2],
+                transfer(4, 50)
+            ));
+            assert_eq!(Balances::free_balance(&4), 50);
+            assert_eq!(Balances::reserved_balance(&2), 0);
+        });
+    }
+
+    #[test]
+    fn cancel() {
+        with_externalities(&mut new_test_ext(), || {
+            let call_hash = transfer(4, 50).using_encoded(blake2_256);
+            assert_noop!(
+                Multisig::cancel_as_multi(Origin::signed(1), 2, vec![2, 3], call_hash),
+                "call has no approvals"
+            );
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(1),
+                2,
+                vec![2, 3],
+                transfer(4, 50)
+            ));
+            assert_noop!(
+                Multisig::cancel_as_multi(Origin::signed(2), 2, vec![1, 3], call_hash),
+                "only the first approver may cancel a call"
+            );
+            assert_ok!(Multisig::cancel_as_multi(
+                Origin::signed(1),
+                2,
+                vec![2, 3],
+                call_hash
+            ));
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert_eq!(Multisig::multisigs((multisig(), call_hash)), None);
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(2),
+                2,
+                vec![1, 3],
+                transfer(4, 50)
+            ));
+            assert_eq!(Balances::free_balance(&4), 0);
+        });
+    }
+
+    #[test]
+    fn threshold_of_one() {
+        with_externalities(&mut new_test_ext(), || {
+            let id = multi_account_id(&[1, 2], 1);
+            let _ = Balances::deposit_creating(&id, 10);
+            assert_ok!(Multisig::as_multi(
+                Origin::signed(2),
+                1,
+                vec![1],
+                transfer(4, 5)
+            ));
+            assert_eq!(Balances::free_balance(&4), 5);
+            assert_eq!(Balances::reserved_balance(&2), 0);
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod nft;
+
+#[cfg(feature = "std")]
+pub use crate::nft::GenesisConfig;
+
+pub use crate::nft::{Call, Class, Event, Instance, Module, RawEvent, Trait};
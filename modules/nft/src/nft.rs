@@ -0,0 +1,332 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Get, StorageMap,
+    StorageValue,
+};
+use system::{self, ensure_signed};
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Largest size in bytes of the metadata of a class or an instance.
+    type MetadataLimit: Get<u32>;
+}
+
+// a collection of non fungible instances, which only its owner mints
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Class<AccountId> {
+    pub owner: AccountId,
+    pub instances: u32,
+    pub metadata: Vec<u8>,
+}
+
+// a token of a class, e.g. the uri of a picture in its metadata
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Instance<AccountId> {
+    pub owner: AccountId,
+    pub metadata: Vec<u8>,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // create a class owned by the origin, with the next free class id
+        fn create_class(origin, metadata: Vec<u8>) -> Result {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_metadata(&metadata)?;
+            let id = Self::next_class_id();
+            NextClassId::put(id.checked_add(1).ok_or("class ids are exhausted")?);
+            <Classes<T>>::insert(id, Class { owner: owner.clone(), instances: 0, metadata });
+            Self::deposit_event(RawEvent::ClassCreated(id, owner));
+            Ok(())
+        }
+
+        // replace the metadata of a class of the origin
+        fn set_class_metadata(origin, class: u32, metadata: Vec<u8>) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::ensure_metadata(&metadata)?;
+            let mut details = Self::class(class).ok_or("class does not exist")?;
+            ensure!(details.owner == who, "only the class owner may do this");
+            details.metadata = metadata;
+            <Classes<T>>::insert(class, details);
+            Self::deposit_event(RawEvent::ClassMetadataSet(class));
+            Ok(())
+        }
+
+        // mint `instance` of a class of the origin for `owner`
+        fn mint(
+            origin,
+            class: u32,
+            instance: u32,
+            owner: T::AccountId,
+            metadata: Vec<u8>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::ensure_metadata(&metadata)?;
+            let mut details = Self::class(class).ok_or("class does not exist")?;
+            ensure!(details.owner == who, "only the class owner may do this");
+            ensure!(
+                !<Instances<T>>::exists((class, instance)),
+                "instance already exists"
+            );
+            details.instances = details.instances.checked_add(1).ok_or("class is full")?;
+            <Classes<T>>::insert(class, details);
+            <Instances<T>>::insert((class, instance), Instance { owner: owner.clone(), metadata });
+            Self::deposit_event(RawEvent::Minted(class, instance, owner));
+            Ok(())
+        }
+
+        // give an instance of the origin to `dest`
+        fn transfer(origin, class: u32, instance: u32, dest: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut details = Self::instance((class, instance)).ok_or("instance does not exist")?;
+            ensure!(details.owner == who, "only the instance owner may do this");
+            details.owner = dest.clone();
+            <Instances<T>>::insert((class, instance), details);
+            Self::deposit_event(RawEvent::Transferred(class, instance, who, dest));
+            Ok(())
+        }
+
+        // destroy an instance, as its owner or the owner of its class
+        fn burn(origin, class: u32, instance: u32) -> Result {
+            let who = ensure_signed(origin)?;
+            let details = Self::instance((class, instance)).ok_or("instance does not exist")?;
+            let mut class_details = Self::class(class).ok_or("class does not exist")?;
+            ensure!(
+                details.owner == who || class_details.owner == who,
+                "only the instance or class owner may burn an instance"
+            );
+            class_details.instances -= 1;
+            <Classes<T>>::insert(class, class_details);
+            <Instances<T>>::remove((class, instance));
+            Self::deposit_event(RawEvent::Burned(class, instance));
+            Ok(())
+        }
+
+        // replace the metadata of an instance of a class of the origin
+        fn set_metadata(origin, class: u32, instance: u32, metadata: Vec<u8>) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::ensure_metadata(&metadata)?;
+            let class_details = Self::class(class).ok_or("class does not exist")?;
+            ensure!(class_details.owner == who, "only the class owner may do this");
+            let mut details = Self::instance((class, instance)).ok_or("instance does not exist")?;
+            details.metadata = metadata;
+            <Instances<T>>::insert((class, instance), details);
+            Self::deposit_event(RawEvent::MetadataSet(class, instance));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Nft {
+        // classes by id
+        Classes get(class): map u32 => Option<Class<T::AccountId>>;
+        // id of the next class created, above the ids of every genesis class
+        NextClassId get(next_class_id): u32;
+        // instances by class id and instance id
+        Instances get(instance): map (u32, u32) => Option<Instance<T::AccountId>>;
+    }
+    add_extra_genesis {
+        // (id, owner, metadata) of each class, without instances
+        config(classes): Vec<(u32, T::AccountId, Vec<u8>)>;
+        build(|config: &GenesisConfig<T>| {
+            for (id, owner, metadata) in &config.classes {
+                assert!(!<Classes<T>>::exists(id), "class id is used twice");
+                let class = Class {
+                    owner: owner.clone(),
+                    instances: 0,
+                    metadata: metadata.clone(),
+                };
+                <Classes<T>>::insert(id, class);
+                if *id >= NextClassId::get() {
+                    NextClassId::put(id.checked_add(1).expect("class ids are exhausted"));
+                }
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        // class, owner
+        ClassCreated(u32, AccountId),
+        // class
+        ClassMetadataSet(u32),
+        // class, instance, owner
+        Minted(u32, u32, AccountId),
+        // class, instance, from, to
+        Transferred(u32, u32, AccountId, AccountId),
+        // class, instance
+        Burned(u32, u32),
+        // class, instance
+        MetadataSet(u32, u32),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn ensure_metadata(metadata: &[u8]) -> Result {
+        ensure!(
+            metadata.len() <= T::MetadataLimit::get() as usize,
+            "metadata is longer than the limit"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const MetadataLimit: u32 = 16;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type MetadataLimit = MetadataLimit;
+    }
+    type Nft = Module<Test>;
+
+    // class 0 of 1 and class 5 of 2 exist at genesis
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> {
+            classes: vec![(5, 2, b"punks".to_vec()), (0, 1, vec![])],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn genesis_classes() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(
+                Nft::class(5),
+                Some(Class {
+                    owner: 2,
+                    instances: 0,
+                    metadata: b"punks".to_vec(),
+                })
+            );
+            assert_eq!(Nft::class(0).unwrap().owner, 1);
+            assert_eq!(Nft::next_class_id(), 6);
+            assert_ok!(Nft::create_class(Origin::signed(3), vec![]));
+            assert_eq!(Nft::class(6).unwrap().owner, 3);
+            assert_noop!(
+                Nft::create_class(Origin::signed(3), vec![0; 17]),
+                "metadata is longer than the limit"
+            );
+        });
+    }
+
+    #[test]
+    fn mint_and_transfer() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Nft::mint(Origin::signed(2), 0, 7, 3, vec![]),
+                "only the class owner may do this"
+            );
+            assert_ok!(Nft::mint(Origin::signed(1), 0, 7, 3, b"uri".to_vec()));
+            assert_noop!(
+                Nft::mint(Origin::signed(1), 0, 7, 4, vec![]),
+                "instance already exists"
+            );
+            assert_eq!(Nft::class(0).unwrap().instances, 1);
+            assert_noop!(
+                Nft::transfer(Origin::signed(1), 0, 7, 4),
+                "only the instance owner may do this"
+            );
+            assert_ok!(Nft::transfer(Origin::signed(3), 0, 7, 4));
+            assert_eq!(
+                Nft::instance((0, 7)),
+                Some(Instance {
+                    owner: 4,
+                    metadata: b"uri".to_vec(),
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn metadata() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Nft::mint(Origin::signed(1), 0, 7, 3, vec![]));
+            assert_noop!(
+                Nft::set_metadata(Origin::signed(3), 0, 7, b"uri".to_vec()),
+                "only the class owner may do this"
+            );
+            assert_ok!(Nft::set_metadata(Origin::signed(1), 0, 7, b"uri".to_vec()));
+            assert_eq!(Nft::instance((0, 7)).unwrap().metadata, b"uri".to_vec());
+            assert_ok!(Nft::set_class_metadata(
+                Origin::signed(1),
+                0,
+                b"apes".to_vec()
+            ));
+            assert_eq!(Nft::class(0).unwrap().metadata, b"apes".to_vec());
+        });
+    }
+
+    #[test]
+    fn burn() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Nft::mint(Origin::signed(1), 0, 7, 3, vec![]));
+            assert_ok!(Nft::mint(Origin::signed(1), 0, 8, 3, vec![]));
+            assert_noop!(
+                Nft::burn(Origin::signed(2), 0, 7),
+                "only the instance or class owner may burn an instance"
+            );
+            assert_ok!(Nft::burn(Origin::signed(3), 0, 7));
+            assert_ok!(Nft::burn(Origin::signed(1), 0, 8));
+            assert_eq!(Nft::instance((0, 7)), None);
+            assert_eq!(Nft::class(0).unwrap().instances, 0);
+            assert_noop!(
+                Nft::burn(Origin::signed(1), 0, 8),
+                "instance does not exist"
+            );
+        });
+    }
+}
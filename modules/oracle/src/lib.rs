@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod oracle;
+
+#[cfg(feature = "std")]
+pub use crate::oracle::GenesisConfig;
+
+pub use crate::oracle::{
+    median, parse_price, sr25519, Call, Event, Feed, Module, Price, RawEvent, Trait, KEY_TYPE,
+    PRICE_DECIMALS,
+};
@@ -0,0 +1,499 @@
+use app_crypto::RuntimeAppPublic;
+use codec::{Decode, Encode};
+use primitives::crypto::KeyTypeId;
+use primitives::offchain::Duration;
+use rstd::prelude::*;
+use sr_primitives::offchain::http;
+use sr_primitives::traits::{Member, SaturatedConversion, Saturating, Zero};
+use sr_primitives::transaction_validity::{
+    InvalidTransaction, TransactionLongevity, TransactionValidity, ValidTransaction,
+};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure, print,
+    traits::{EnsureOrigin, Get},
+    Parameter, StorageMap, StorageValue,
+};
+use system::{self, ensure_none, offchain::SubmitUnsignedTransaction};
+
+/// Key type of the feeder keys offchain workers sign prices with, inserted in the keystore of
+/// their node.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orcl");
+/// Decimals of a price, 1_000_000 being one unit of the quote currency.
+pub const PRICE_DECIMALS: u32 = 6;
+/// Milliseconds the offchain worker waits for the endpoint to answer.
+const FETCH_TIMEOUT: u64 = 3_000;
+
+/// Price with `PRICE_DECIMALS` decimals.
+pub type Price = u64;
+
+pub mod sr25519 {
+    mod app_sr25519 {
+        use super::super::KEY_TYPE;
+        use app_crypto::{app_crypto, sr25519};
+        app_crypto!(sr25519, KEY_TYPE);
+    }
+
+    /// Key of a feeder.
+    pub type FeederId = app_sr25519::Public;
+    /// Signature of a feed by its feeder.
+    pub type FeederSignature = app_sr25519::Signature;
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Key feeders sign their prices with.
+    type FeederId: Member + Parameter + RuntimeAppPublic + Default + Ord;
+    /// Runtime call the offchain worker submits.
+    type Call: From<Call<Self>>;
+    /// Submits the prices fetched by the offchain worker as unsigned transactions.
+    type SubmitTransaction: SubmitUnsignedTransaction<Self, <Self as Trait>::Call>;
+    /// Adds and removes feeders and sets the endpoint.
+    type OracleOrigin: EnsureOrigin<Self::Origin>;
+    /// Blocks between two prices fed by the offchain worker of a node, not zero.
+    type FeedInterval: Get<Self::BlockNumber>;
+    /// Blocks a price counts towards the median for.
+    type ValidityPeriod: Get<Self::BlockNumber>;
+}
+
+// a price fetched by the offchain worker of `feeder` at `block_number`
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Feed<FeederId, BlockNumber> {
+    pub price: Price,
+    pub block_number: BlockNumber,
+    pub feeder: FeederId,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // record the price of a feeder, submitted by the offchain worker of its node; the
+        // signature is checked when the transaction is validated
+        fn feed(
+            origin,
+            feed: Feed<T::FeederId, T::BlockNumber>,
+            _signature: <T::FeederId as RuntimeAppPublic>::Signature
+        ) -> Result {
+            ensure_none(origin)?;
+            ensure!(Self::is_feeder(&feed.feeder), "feeder is not whitelisted");
+            ensure!(Self::is_fresh(&feed), "a newer price of this feeder was fed");
+            <Prices<T>>::insert(&feed.feeder, (feed.price, feed.block_number));
+            Self::update_median();
+            Self::deposit_event(RawEvent::Fed(feed.feeder, feed.price));
+            Ok(())
+        }
+
+        // whitelist a feeder
+        fn add_feeder(origin, feeder: T::FeederId) -> Result {
+            T::OracleOrigin::ensure_origin(origin)?;
+            ensure!(!Self::is_feeder(&feeder), "feeder is already whitelisted");
+            <Feeders<T>>::mutate(|feeders| feeders.push(feeder.clone()));
+            Self::deposit_event(RawEvent::FeederAdded(feeder));
+            Ok(())
+        }
+
+        // stop whitelisting a feeder and drop its price from the median
+        fn remove_feeder(origin, feeder: T::FeederId) -> Result {
+            T::OracleOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_feeder(&feeder), "feeder is not whitelisted");
+            <Feeders<T>>::mutate(|feeders| feeders.retain(|f| f != &feeder));
+            <Prices<T>>::remove(&feeder);
+            Self::update_median();
+            Self::deposit_event(RawEvent::FeederRemoved(feeder));
+            Ok(())
+        }
+
+        // set the http url offchain workers fetch the price from
+        fn set_endpoint(origin, endpoint: Vec<u8>) -> Result {
+            T::OracleOrigin::ensure_origin(origin)?;
+            Endpoint::put(&endpoint);
+            Self::deposit_event(RawEvent::EndpointSet(endpoint));
+            Ok(())
+        }
+
+        // feed the price at the endpoint with each feeder key in the keystore of the node, once
+        // per feed interval
+        fn offchain_worker(now: T::BlockNumber) {
+            if (now % T::FeedInterval::get()).is_zero() {
+                Self::feed_local_keys(now);
+            }
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Oracle {
+        // keys whose prices are accepted
+        Feeders get(feeders) config(): Vec<T::FeederId>;
+        // last price of each feeder and the block its offchain worker fetched it at
+        Prices get(price_of): map T::FeederId => Option<(Price, T::BlockNumber)>;
+        // median of the prices fetched within the validity period, as of the last feed
+        Median get(median): Option<Price>;
+        // http url answering with the price, as the first number of the response body
+        Endpoint get(endpoint) config(): Vec<u8>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        FeederId = <T as Trait>::FeederId,
+    {
+        // feeder, price
+        Fed(FeederId, Price),
+        // feeder
+        FeederAdded(FeederId),
+        // feeder
+        FeederRemoved(FeederId),
+        // endpoint
+        EndpointSet(Vec<u8>),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn is_feeder(feeder: &T::FeederId) -> bool {
+        Self::feeders().contains(feeder)
+    }
+
+    // whether `feed` is newer than the last price of its feeder
+    fn is_fresh(feed: &Feed<T::FeederId, T::BlockNumber>) -> bool {
+        Self::price_of(&feed.feeder).map_or(true, |(_, at)| feed.block_number > at)
+    }
+
+    fn update_median() {
+        let now = <system::Module<T>>::block_number();
+        let mut prices: Vec<Price> = Self::feeders()
+            .iter()
+            .filter_map(|feeder| Self::price_of(feeder))
+            .filter(|(_, at)| now.saturating_sub(*at) < T::ValidityPeriod::get())
+            .map(|(price, _)| price)
+            .collect();
+        match median(&mut prices) {
+            Some(price) => Median::put(price),
+            None => Median::kill(),
+        }
+    }
+
+    fn feed_local_keys(now: T::BlockNumber) {
+        let feeders = Self::feeders();
+        let keys: Vec<T::FeederId> = T::FeederId::all()
+            .into_iter()
+            .filter(|key| feeders.contains(key))
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+        let price = match Self::fetch_price() {
+            Ok(price) => price,
+            Err(e) => {
+                print(e);
+                return;
+            }
+        };
+        for feeder in keys {
+            let feed = Feed {
+                price,
+                block_number: now,
+                feeder,
+            };
+            let signature = match feed.feeder.sign(&feed.encode()) {
+                Some(signature) => signature,
+                None => {
+                    print("oracle: failed to sign a price");
+                    continue;
+                }
+            };
+            if T::SubmitTransaction::submit_unsigned(Call::feed(feed, signature)).is_err() {
+                print("oracle: failed to submit a price");
+            }
+        }
+    }
+
+    fn fetch_price() -> rstd::result::Result<Price, &'static str> {
+        let endpoint = Self::endpoint();
+        let url = rstd::str::from_utf8(&endpoint).map_err(|_| "oracle: endpoint is not utf8")?;
+        let deadline = runtime_io::timestamp().add(Duration::from_millis(FETCH_TIMEOUT));
+        let response = http::Request::get(url)
+            .deadline(deadline)
+            .send()
+            .map_err(|_| "oracle: failed to send the request")?
+            .try_wait(deadline)
+            .map_err(|_| "oracle: the endpoint timed out")?
+            .map_err(|_| "oracle: the request failed")?;
+        ensure!(
+            response.code == 200,
+            "oracle: the endpoint answered with an error"
+        );
+        parse_price(&response.body().collect::<Vec<u8>>()).ok_or("oracle: no price in the answer")
+    }
+}
+
+/// Median of `prices`, the mean of the two middle ones for an even count.
+pub fn median(prices: &mut [Price]) -> Option<Price> {
+    prices.sort_unstable();
+    let middle = prices.len() / 2;
+    match prices.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(prices[middle]),
+        _ => Some(((u128::from(prices[middle - 1]) + u128::from(prices[middle])) / 2) as Price),
+    }
+}
+
+/// First number of `body`, e.g. `{"USD":4.2}`, as a price rounded down to `PRICE_DECIMALS`
+/// decimals. Negative numbers are not prices.
+pub fn parse_price(body: &[u8]) -> Option<Price> {
+    let start = body.iter().position(u8::is_ascii_digit)?;
+    if start > 0 && body[start - 1] == b'-' {
+        return None;
+    }
+    let mut price: Price = 0;
+    let mut decimals: Option<u32> = None;
+    for &byte in &body[start..] {
+        match byte {
+            b'0'..=b'9' if decimals != Some(PRICE_DECIMALS) => {
+                price = price
+                    .checked_mul(10)?
+                    .checked_add(Price::from(byte - b'0'))?;
+                decimals = decimals.map(|decimals| decimals + 1);
+            }
+            b'0'..=b'9' => {}
+            b'.' if decimals.is_none() => decimals = Some(0),
+            _ => break,
+        }
+    }
+    price.checked_mul(10u64.pow(PRICE_DECIMALS - decimals.unwrap_or(0)))
+}
+
+impl<T: Trait> support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+        if let Call::feed(feed, signature) = call {
+            if !Self::is_feeder(&feed.feeder) {
+                return InvalidTransaction::BadProof.into();
+            }
+            if !Self::is_fresh(feed) {
+                return InvalidTransaction::Stale.into();
+            }
+            if feed.block_number > <system::Module<T>>::block_number() {
+                return InvalidTransaction::Future.into();
+            }
+            if !feed.using_encoded(|encoded| feed.feeder.verify(&encoded, signature)) {
+                return InvalidTransaction::BadProof.into();
+            }
+            Ok(ValidTransaction {
+                priority: 0,
+                requires: vec![],
+                provides: vec![(b"oracle", &feed.feeder, feed.block_number).encode()],
+                longevity: T::FeedInterval::get().saturated_into::<TransactionLongevity>(),
+                propagate: true,
+            })
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::{Header, TestXt, UintAuthorityId},
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::unsigned::ValidateUnsigned;
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            oracle::Oracle,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = Call;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const FeedInterval: u64 = 2;
+        pub const ValidityPeriod: u64 = 10;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type FeederId = UintAuthorityId;
+        type Call = Call;
+        type SubmitTransaction = system::offchain::TransactionSubmitter<(), Call, TestXt<Call, ()>>;
+        type OracleOrigin = system::EnsureRoot<u64>;
+        type FeedInterval = FeedInterval;
+        type ValidityPeriod = ValidityPeriod;
+    }
+    type System = system::Module<Test>;
+    type Oracle = Module<Test>;
+
+    // 1, 2 and 3 are feeders
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut ext: runtime_io::TestExternalities<Blake2Hasher> = GenesisConfig::<Test> {
+            feeders: vec![1.into(), 2.into(), 3.into()],
+            endpoint: b"https://example.com/price".to_vec(),
+        }
+        .build_storage()
+        .unwrap()
+        .into();
+        with_externalities(&mut ext, || System::set_block_number(1));
+        ext
+    }
+
+    fn signed_feed(
+        feeder: u64,
+        price: Price,
+    ) -> (
+        Feed<UintAuthorityId, u64>,
+        <UintAuthorityId as RuntimeAppPublic>::Signature,
+    ) {
+        let feed = Feed {
+            price,
+            block_number: System::block_number(),
+            feeder: feeder.into(),
+        };
+        let signature = feed.feeder.sign(&feed.encode()).unwrap();
+        (feed, signature)
+    }
+
+    fn feed(feeder: u64, price: Price) -> Result {
+        let (feed, signature) = signed_feed(feeder, price);
+        Oracle::feed(Origin::NONE, feed, signature)
+    }
+
+    #[test]
+    fn validate_feed() {
+        with_externalities(&mut new_test_ext(), || {
+            let (feed, signature) = signed_feed(1, 5);
+            assert!(Oracle::validate_unsigned(&oracle::Call::feed(feed, signature)).is_ok());
+
+            let (mut forged, signature) = signed_feed(1, 5);
+            forged.price = 6;
+            assert_eq!(
+                Oracle::validate_unsigned(&oracle::Call::feed(forged, signature)),
+                InvalidTransaction::BadProof.into()
+            );
+            let (feed, signature) = signed_feed(4, 5);
+            assert_eq!(
+                Oracle::validate_unsigned(&oracle::Call::feed(feed, signature)),
+                InvalidTransaction::BadProof.into()
+            );
+
+            let (feed, signature) = signed_feed(1, 5);
+            assert_ok!(Oracle::feed(Origin::NONE, feed.clone(), signature.clone()));
+            assert_eq!(
+                Oracle::validate_unsigned(&oracle::Call::feed(feed, signature)),
+                InvalidTransaction::Stale.into()
+            );
+        });
+    }
+
+    #[test]
+    fn median_of_fresh_prices() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(feed(1, 10));
+            assert_eq!(Oracle::median(), Some(10));
+            assert_ok!(feed(2, 30));
+            assert_eq!(Oracle::median(), Some(20));
+            assert_ok!(feed(3, 1000));
+            assert_eq!(Oracle::median(), Some(30));
+            assert_noop!(feed(4, 10), "feeder is not whitelisted");
+            assert_noop!(feed(1, 10), "a newer price of this feeder was fed");
+            let (signed, signature) = signed_feed(1, 10);
+            assert!(Oracle::feed(Origin::signed(1), signed, signature).is_err());
+
+            // the prices of 1 and 2 expire
+            System::set_block_number(11);
+            assert_ok!(feed(3, 40));
+            assert_eq!(Oracle::median(), Some(40));
+        });
+    }
+
+    #[test]
+    fn feeders() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(feed(1, 10));
+            assert_ok!(feed(2, 30));
+            assert!(Oracle::remove_feeder(Origin::signed(1), 2.into()).is_err());
+            assert_ok!(Oracle::remove_feeder(Origin::ROOT, 2.into()));
+            assert_eq!(Oracle::median(), Some(10));
+            assert_eq!(Oracle::price_of(UintAuthorityId(2)), None);
+            assert_noop!(feed(2, 30), "feeder is not whitelisted");
+
+            assert_ok!(Oracle::add_feeder(Origin::ROOT, 4.into()));
+            assert_noop!(
+                Oracle::add_feeder(Origin::ROOT, 4.into()),
+                "feeder is already whitelisted"
+            );
+            assert_ok!(feed(4, 20));
+            assert_eq!(Oracle::median(), Some(15));
+
+            assert_ok!(Oracle::set_endpoint(
+                Origin::ROOT,
+                b"http://localhost".to_vec()
+            ));
+            assert_eq!(Oracle::endpoint(), b"http://localhost".to_vec());
+        });
+    }
+
+    #[test]
+    fn parse_prices() {
+        assert_eq!(parse_price(b"4.2"), Some(4_200_000));
+        assert_eq!(parse_price(b"{\"USD\":12.3456789}"), Some(12_345_678));
+        assert_eq!(parse_price(b"{\"USD\":7}\n"), Some(7_000_000));
+        assert_eq!(parse_price(b"0.000001"), Some(1));
+        assert_eq!(parse_price(b"{\"USD\":-1.5}"), None);
+        assert_eq!(parse_price(b"{\"USD\":null}"), None);
+        assert_eq!(parse_price(b"99999999999999999999"), None);
+    }
+
+    #[test]
+    fn median_of_even_count() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [3, 1]), Some(2));
+        assert_eq!(
+            median(&mut [Price::max_value(), Price::max_value()]),
+            Some(Price::max_value())
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Runtime API for querying the orderbook module's open orders off chain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use rstd::prelude::*;
+
+pub use orderbook::{Order, Side};
+
+client::decl_runtime_apis! {
+    pub trait OrderbookApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Open orders of the market selling `base` for `quote`, with their ids, oldest first.
+        fn open_orders(base: u32, quote: u32) -> Vec<(u64, Order<AccountId, Balance>)>;
+    }
+}
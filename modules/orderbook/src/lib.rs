@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod orderbook;
+
+pub use crate::orderbook::{Call, Event, Module, Order, RawEvent, Side, Trait, MODULE_ID};
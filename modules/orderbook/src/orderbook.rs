@@ -0,0 +1,439 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::{
+    traits::{AccountIdConversion, CheckedMul, Zero},
+    ModuleId,
+};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::Get, StorageMap,
+    StorageValue,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <T as assets::Trait>::Balance;
+
+/// Derives the account holding the assets locked by open orders.
+pub const MODULE_ID: ModuleId = ModuleId(*b"py/ordbk");
+
+pub trait Trait: assets::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Largest number of open orders in a market, which bounds the work of matching it.
+    type MaxOrdersPerMarket: Get<u32>;
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// An open limit order in the market of `market.0` (the base) for `market.1` (the quote). Sells
+/// lock the base amount, buys lock `amount * price` of the quote.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Order<AccountId, Balance> {
+    pub owner: AccountId,
+    pub market: (u32, u32),
+    pub side: Side,
+    /// Units of the quote paid per unit of the base.
+    pub price: Balance,
+    /// Units of the base left to trade.
+    pub amount: Balance,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // offer to buy or sell `amount` of `base` at `price` units of `quote` each, locking what
+        // the order pays until it is filled or canceled; orders are matched at the end of the
+        // block
+        fn place_order(
+            origin,
+            base: u32,
+            quote: u32,
+            side: Side,
+            price: BalanceOf<T>,
+            amount: BalanceOf<T>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(base != quote, "a market needs two different assets");
+            ensure!(
+                <assets::Module<T>>::asset_details(base).is_some()
+                    && <assets::Module<T>>::asset_details(quote).is_some(),
+                "asset does not exist"
+            );
+            ensure!(!price.is_zero() && !amount.is_zero(), "orders need a price and an amount");
+            let market = (base, quote);
+            let mut ids = Self::market_orders(market);
+            ensure!(
+                ids.len() < T::MaxOrdersPerMarket::get() as usize,
+                "market has too many open orders"
+            );
+            let order = Order { owner: who.clone(), market, side, price, amount };
+            let (asset, locked) = Self::locked(&order).ok_or("Arithmetic overflow.")?;
+            <assets::Module<T>>::move_balance(asset, &who, &Self::account_id(), locked)?;
+            let id = Self::next_order_id();
+            NextOrderId::put(id + 1);
+            <Orders<T>>::insert(id, order);
+            ids.push(id);
+            <MarketOrders>::insert(market, ids);
+            PendingMarkets::mutate(|markets| {
+                if !markets.contains(&market) {
+                    markets.push(market);
+                }
+            });
+            Self::deposit_event(RawEvent::Placed(id, who, base, quote));
+            Ok(())
+        }
+
+        // cancel an order of the origin, returning what it still locks
+        fn cancel_order(origin, id: u64) -> Result {
+            let who = ensure_signed(origin)?;
+            let order = Self::order(id).ok_or("order does not exist")?;
+            ensure!(order.owner == who, "only the owner may cancel an order");
+            let (asset, locked) = Self::locked(&order).ok_or("Arithmetic overflow.")?;
+            // the orders account holds what the order locks, so this doesn't fail
+            <assets::Module<T>>::do_move_balance(asset, &Self::account_id(), &who, locked)?;
+            Self::remove_order(id, order.market);
+            Self::deposit_event(RawEvent::Canceled(id));
+            Ok(())
+        }
+
+        fn on_finalize() {
+            for market in PendingMarkets::take() {
+                Self::match_market(market);
+            }
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Orderbook {
+        // open orders by id
+        Orders get(order): map u64 => Option<Order<T::AccountId, BalanceOf<T>>>;
+        // ids of the open orders of a market, oldest first
+        MarketOrders get(market_orders): map (u32, u32) => Vec<u64>;
+        // id of the next order placed
+        NextOrderId get(next_order_id): u64;
+        // markets with orders placed in this block, matched at its end
+        PendingMarkets get(pending_markets): Vec<(u32, u32)>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // order id, owner, base, quote
+        Placed(u64, AccountId, u32, u32),
+        // order id
+        Canceled(u64),
+        // buy order id, sell order id, price, amount of the base traded
+        Matched(u64, u64, Balance, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Account holding the assets locked by open orders.
+    pub fn account_id() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    /// Open orders of the market selling `base` for `quote`, with their ids, oldest first.
+    pub fn open_orders(base: u32, quote: u32) -> Vec<(u64, Order<T::AccountId, BalanceOf<T>>)> {
+        Self::market_orders((base, quote))
+            .into_iter()
+            .filter_map(|id| Self::order(id).map(|order| (id, order)))
+            .collect()
+    }
+
+    // the asset an order locks and how much of it, None if that overflows
+    fn locked(order: &Order<T::AccountId, BalanceOf<T>>) -> Option<(u32, BalanceOf<T>)> {
+        match order.side {
+            Side::Buy => Some((order.market.1, order.amount.checked_mul(&order.price)?)),
+            Side::Sell => Some((order.market.0, order.amount)),
+        }
+    }
+
+    fn remove_order(id: u64, market: (u32, u32)) {
+        <Orders<T>>::remove(id);
+        <MarketOrders>::mutate(market, |ids| ids.retain(|open| *open != id));
+    }
+
+    // Fill the highest buy against the lowest sell while the buy pays at least the sell's price,
+    // orders of the same price filling oldest first. Trades are at the price of the older of the
+    // two orders, buyers getting back what they locked above it. Markets of a frozen asset stay
+    // pending until it thaws.
+    fn match_market(market: (u32, u32)) {
+        let frozen = |asset| <assets::Module<T>>::is_asset_frozen(asset);
+        if frozen(market.0) || frozen(market.1) {
+            PendingMarkets::mutate(|markets| markets.push(market));
+            return;
+        }
+        let (mut bids, mut asks): (Vec<_>, Vec<_>) = Self::open_orders(market.0, market.1)
+            .into_iter()
+            .partition(|(_, order)| order.side == Side::Buy);
+        bids.sort_by(|(a_id, a), (b_id, b)| b.price.cmp(&a.price).then(a_id.cmp(b_id)));
+        asks.sort_by(|(a_id, a), (b_id, b)| a.price.cmp(&b.price).then(a_id.cmp(b_id)));
+        let (mut b, mut a) = (0, 0);
+        let account = Self::account_id();
+        while b < bids.len() && a < asks.len() && bids[b].1.price >= asks[a].1.price {
+            let (bid_id, ask_id) = (bids[b].0, asks[a].0);
+            let (bid_price, ask_price) = (bids[b].1.price, asks[a].1.price);
+            let price = if bid_id < ask_id {
+                bid_price
+            } else {
+                ask_price
+            };
+            let fill = rstd::cmp::min(bids[b].1.amount, asks[a].1.amount);
+            let (buyer, seller) = (&bids[b].1.owner, &asks[a].1.owner);
+            // the locked assets always cover a fill, and owners frozen since placing their orders
+            // still receive, so these moves don't fail
+            let _ = <assets::Module<T>>::do_move_balance(market.0, &account, buyer, fill);
+            let _ = <assets::Module<T>>::do_move_balance(market.1, &account, seller, fill * price);
+            let _ = <assets::Module<T>>::do_move_balance(
+                market.1,
+                &account,
+                buyer,
+                fill * (bid_price - price),
+            );
+            bids[b].1.amount -= fill;
+            asks[a].1.amount -= fill;
+            Self::deposit_event(RawEvent::Matched(bid_id, ask_id, price, fill));
+            if bids[b].1.amount.is_zero() {
+                Self::remove_order(bid_id, market);
+                b += 1;
+            }
+            if asks[a].1.amount.is_zero() {
+                Self::remove_order(ask_id, market);
+                a += 1;
+            }
+        }
+        // orders partially filled by the last trade
+        for (id, order) in bids
+            .into_iter()
+            .nth(b)
+            .into_iter()
+            .chain(asks.into_iter().nth(a))
+        {
+            <Orders<T>>::insert(id, order);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup, OnFinalize},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl assets::Trait for Test {
+        type Event = ();
+        type Balance = u128;
+        type AdminOrigin = system::EnsureRoot<u64>;
+    }
+    parameter_types! {
+        pub const MaxOrdersPerMarket: u32 = 4;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type MaxOrdersPerMarket = MaxOrdersPerMarket;
+    }
+    type Assets = assets::Module<Test>;
+    type Orderbook = Module<Test>;
+
+    const GLD: u32 = 0;
+    const USDX: u32 = 1;
+
+    // 1 holds 100 GLD to sell, 2 holds 10000 USDX to buy with
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        assets::GenesisConfig::<Test> {
+            assets: vec![
+                (GLD, b"GLD".to_vec(), 0, 1, vec![(1, 100)]),
+                (USDX, b"USDX".to_vec(), 2, 2, vec![(2, 10_000)]),
+            ],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn place(who: u64, side: Side, price: u128, amount: u128) -> Result {
+        Orderbook::place_order(Origin::signed(who), GLD, USDX, side, price, amount)
+    }
+
+    #[test]
+    fn place_and_cancel() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Orderbook::place_order(Origin::signed(1), GLD, GLD, Side::Sell, 1, 1),
+                "a market needs two different assets"
+            );
+            assert_noop!(
+                Orderbook::place_order(Origin::signed(1), GLD, 2, Side::Sell, 1, 1),
+                "asset does not exist"
+            );
+            assert_noop!(
+                place(1, Side::Sell, 0, 1),
+                "orders need a price and an amount"
+            );
+            assert_noop!(place(2, Side::Buy, 101, 100), "Not enough balance.");
+            assert_ok!(place(1, Side::Sell, 50, 10));
+            assert_ok!(place(2, Side::Buy, 40, 5));
+            assert_eq!(Assets::balance_of((GLD, 1)), 90);
+            assert_eq!(Assets::balance_of((USDX, 2)), 9_800);
+            assert_eq!(
+                Orderbook::open_orders(GLD, USDX),
+                vec![
+                    (
+                        0,
+                        Order {
+                            owner: 1,
+                            market: (GLD, USDX),
+                            side: Side::Sell,
+                            price: 50,
+                            amount: 10,
+                        }
+                    ),
+                    (
+                        1,
+                        Order {
+                            owner: 2,
+                            market: (GLD, USDX),
+                            side: Side::Buy,
+                            price: 40,
+                            amount: 5,
+                        }
+                    ),
+                ]
+            );
+
+            assert_noop!(
+                Orderbook::cancel_order(Origin::signed(1), 1),
+                "only the owner may cancel an order"
+            );
+            assert_ok!(Orderbook::cancel_order(Origin::signed(2), 1));
+            assert_eq!(Assets::balance_of((USDX, 2)), 10_000);
+            assert_eq!(Orderbook::market_orders((GLD, USDX)), vec![0]);
+            assert_noop!(
+                Orderbook::cancel_order(Origin::signed(2), 1),
+                "order does not exist"
+            );
+
+            // the market is full
+            assert_ok!(place(1, Side::Sell, 50, 1));
+            assert_ok!(place(1, Side::Sell, 50, 1));
+            assert_ok!(place(1, Side::Sell, 50, 1));
+            assert_noop!(
+                place(1, Side::Sell, 50, 1),
+                "market has too many open orders"
+            );
+        });
+    }
+
+    #[test]
+    fn match_at_maker_price() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(place(1, Side::Sell, 50, 10));
+            assert_ok!(place(2, Side::Buy, 60, 4));
+            Orderbook::on_finalize(1);
+            // 4 GLD for 50 each, 10 of each 60 locked are returned
+            assert_eq!(Assets::balance_of((GLD, 2)), 4);
+            assert_eq!(Assets::balance_of((USDX, 1)), 200);
+            assert_eq!(Assets::balance_of((USDX, 2)), 9_800);
+            assert_eq!(Orderbook::order(1), None);
+            assert_eq!(Orderbook::order(0).unwrap().amount, 6);
+            assert_eq!(Orderbook::pending_markets(), vec![]);
+
+            // an older buy sets the price
+            assert_ok!(place(2, Side::Buy, 70, 6));
+            assert_ok!(place(1, Side::Sell, 45, 6));
+            Orderbook::on_finalize(2);
+            assert_eq!(Assets::balance_of((GLD, 2)), 10);
+            assert_eq!(Assets::balance_of((USDX, 1)), 200 + 6 * 70);
+            assert_eq!(Orderbook::market_orders((GLD, USDX)), vec![0]);
+        });
+    }
+
+    #[test]
+    fn match_best_price_first() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(place(1, Side::Sell, 55, 5));
+            assert_ok!(place(1, Side::Sell, 50, 5));
+            assert_ok!(place(1, Side::Sell, 50, 5));
+            assert_ok!(place(2, Side::Buy, 52, 8));
+            Orderbook::on_finalize(1);
+            // both sells at 50 fill, the older first, and the one at 55 doesn't
+            assert_eq!(Orderbook::order(1), None);
+            assert_eq!(Orderbook::order(2).unwrap().amount, 2);
+            assert_eq!(Orderbook::order(0).unwrap().amount, 5);
+            assert_eq!(Assets::balance_of((GLD, 2)), 8);
+            assert_eq!(Assets::balance_of((USDX, 2)), 10_000 - 8 * 50);
+            assert_eq!(Orderbook::market_orders((GLD, USDX)), vec![0, 2]);
+        });
+    }
+
+    #[test]
+    fn frozen_markets() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(place(1, Side::Sell, 50, 10));
+            assert_ok!(place(2, Side::Buy, 50, 4));
+            assert_ok!(Assets::freeze_asset(Origin::ROOT, GLD));
+            Orderbook::on_finalize(1);
+            assert_eq!(Orderbook::market_orders((GLD, USDX)), vec![0, 1]);
+            assert_eq!(Orderbook::pending_markets(), vec![(GLD, USDX)]);
+            assert_eq!(Assets::balance_of((GLD, 2)), 0);
+
+            // owners frozen since placing their orders still trade and cancel them
+            assert_ok!(Assets::thaw_asset(Origin::ROOT, GLD));
+            assert_ok!(Assets::freeze(Origin::ROOT, GLD, 1));
+            assert_ok!(Assets::freeze(Origin::ROOT, USDX, 2));
+            Orderbook::on_finalize(2);
+            assert_eq!(Assets::balance_of((GLD, 2)), 4);
+            assert_eq!(Assets::balance_of((USDX, 1)), 200);
+            assert_eq!(Orderbook::pending_markets(), vec![]);
+            assert_ok!(Orderbook::cancel_order(Origin::signed(1), 0));
+            assert_eq!(Assets::balance_of((GLD, 1)), 96);
+        });
+    }
+}
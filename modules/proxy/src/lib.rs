@@ -0,0 +1,7 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod proxy;
+
+pub use crate::proxy::{
+    Announcement, Event, Module, ProxyDefinition, ProxyFilter, RawEvent, Trait,
+};
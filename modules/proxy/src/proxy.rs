@@ -0,0 +1,599 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use sr_primitives::traits::{Member, SaturatedConversion, Saturating, Zero};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::{Dispatchable, Result},
+    ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    Parameter, StorageMap,
+};
+use system::{self, ensure_signed, RawOrigin};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Decides which calls a kind of proxy may make.
+pub trait ProxyFilter<Call> {
+    fn filter(&self, call: &Call) -> bool;
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Calls a proxy can make.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Kinds of proxy, each allowed a subset of calls.
+    type ProxyType: Parameter + Member + Ord + Default + Copy + ProxyFilter<<Self as Trait>::Call>;
+    /// Reserved from an account that has any proxies.
+    type ProxyDepositBase: Get<BalanceOf<Self>>;
+    /// Reserved from an account for each of its proxies.
+    type ProxyDepositFactor: Get<BalanceOf<Self>>;
+    /// Largest number of proxies of an account.
+    type MaxProxies: Get<u16>;
+    /// Reserved from a proxy that has any pending announcements.
+    type AnnouncementDepositBase: Get<BalanceOf<Self>>;
+    /// Reserved from a proxy for each of its pending announcements.
+    type AnnouncementDepositFactor: Get<BalanceOf<Self>>;
+    /// Largest number of pending announcements of a proxy.
+    type MaxPending: Get<u32>;
+}
+
+// an account allowed to make calls for another, after announcing them `delay` blocks ahead
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct ProxyDefinition<AccountId, ProxyType, BlockNumber> {
+    pub delegate: AccountId,
+    pub proxy_type: ProxyType,
+    pub delay: BlockNumber,
+}
+
+// a call a proxy intends to make for `real`, announced at `height`
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Announcement<AccountId, BlockNumber> {
+    pub real: AccountId,
+    pub call_hash: [u8; 32],
+    pub height: BlockNumber,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // let `delegate` make calls allowed by `proxy_type` for the origin, announcing them
+        // `delay` blocks ahead so that they can be rejected
+        fn add_proxy(
+            origin,
+            delegate: T::AccountId,
+            proxy_type: T::ProxyType,
+            delay: T::BlockNumber
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(who != delegate, "an account cannot be its own proxy");
+            let (mut proxies, deposit) = Self::proxies(&who);
+            let proxy = ProxyDefinition { delegate: delegate.clone(), proxy_type, delay };
+            ensure!(!proxies.contains(&proxy), "proxy was already added");
+            ensure!(proxies.len() < usize::from(T::MaxProxies::get()), "too many proxies");
+            proxies.push(proxy);
+            let new_deposit = Self::deposit(
+                proxies.len(),
+                T::ProxyDepositBase::get(),
+                T::ProxyDepositFactor::get(),
+            );
+            Self::rereserve(&who, deposit, new_deposit)?;
+            <Proxies<T>>::insert(&who, (proxies, new_deposit));
+            Self::deposit_event(RawEvent::ProxyAdded(who, delegate, proxy_type, delay));
+            Ok(())
+        }
+
+        fn remove_proxy(
+            origin,
+            delegate: T::AccountId,
+            proxy_type: T::ProxyType,
+            delay: T::BlockNumber
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let (mut proxies, deposit) = Self::proxies(&who);
+            let proxy = ProxyDefinition { delegate: delegate.clone(), proxy_type, delay };
+            let index = proxies.iter().position(|p| *p == proxy).ok_or("proxy not found")?;
+            proxies.remove(index);
+            let new_deposit = Self::deposit(
+                proxies.len(),
+                T::ProxyDepositBase::get(),
+                T::ProxyDepositFactor::get(),
+            );
+            Self::rereserve(&who, deposit, new_deposit)?;
+            if proxies.is_empty() {
+                <Proxies<T>>::remove(&who);
+            } else {
+                <Proxies<T>>::insert(&who, (proxies, new_deposit));
+            }
+            Self::deposit_event(RawEvent::ProxyRemoved(who, delegate, proxy_type, delay));
+            Ok(())
+        }
+
+        // remove every proxy of the origin
+        fn remove_proxies(origin) -> Result {
+            let who = ensure_signed(origin)?;
+            let (_, deposit) = <Proxies<T>>::take(&who);
+            T::Currency::unreserve(&who, deposit);
+            Ok(())
+        }
+
+        // as a proxy of `real`, announce a call that is made with `proxy_announced` once the
+        // proxy's delay has passed
+        fn announce(origin, real: T::AccountId, call_hash: [u8; 32]) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::proxies(&real).0.iter().any(|p| p.delegate == who),
+                "not a proxy of the account"
+            );
+            let (mut pending, deposit) = Self::announcements(&who);
+            ensure!(pending.len() < T::MaxPending::get() as usize, "too many announcements");
+            pending.push(Announcement {
+                real: real.clone(),
+                call_hash,
+                height: <system::Module<T>>::block_number(),
+            });
+            let new_deposit = Self::deposit(
+                pending.len(),
+                T::AnnouncementDepositBase::get(),
+                T::AnnouncementDepositFactor::get(),
+            );
+            Self::rereserve(&who, deposit, new_deposit)?;
+            <Announcements<T>>::insert(&who, (pending, new_deposit));
+            Self::deposit_event(RawEvent::Announced(real, who, call_hash));
+            Ok(())
+        }
+
+        // withdraw an announcement of the origin
+        fn remove_announcement(origin, real: T::AccountId, call_hash: [u8; 32]) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::remove_announcements(&who, |a| a.real == real && a.call_hash == call_hash)
+        }
+
+        // as the account a call was announced for, drop the announcement of `delegate`
+        fn reject_announcement(origin, delegate: T::AccountId, call_hash: [u8; 32]) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::remove_announcements(&delegate, |a| a.real == who && a.call_hash == call_hash)
+        }
+
+        // as a proxy of `real` without a delay, dispatch a call from `real`; `force_proxy_type`
+        // picks which of the origin's proxy types to use
+        fn proxy(
+            origin,
+            real: T::AccountId,
+            force_proxy_type: Option<T::ProxyType>,
+            call: Box<<T as Trait>::Call>
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            let proxy = Self::find_proxy(&real, &who, force_proxy_type)?;
+            ensure!(proxy.delay.is_zero(), "proxy must announce the call first");
+            Self::do_proxy(proxy, real, *call)
+        }
+
+        // dispatch a call that `delegate` announced for `real` at least its delay ago, any
+        // account may submit it
+        fn proxy_announced(
+            origin,
+            delegate: T::AccountId,
+            real: T::AccountId,
+            force_proxy_type: Option<T::ProxyType>,
+            call: Box<<T as Trait>::Call>
+        ) -> Result {
+            ensure_signed(origin)?;
+            let proxy = Self::find_proxy(&real, &delegate, force_proxy_type)?;
+            let call_hash = call.using_encoded(blake2_256);
+            let now = <system::Module<T>>::block_number();
+            Self::remove_announcements(&delegate, |a| {
+                a.real == real
+                    && a.call_hash == call_hash
+                    && now.saturating_sub(a.height) >= proxy.delay
+            })
+            .map_err(|_| "call was not announced at least the proxy's delay ago")?;
+            Self::do_proxy(proxy, real, *call)
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Proxy {
+        // proxies of an account and the deposit reserved for them
+        Proxies get(proxies): map T::AccountId =>
+            (Vec<ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>>, BalanceOf<T>);
+        // pending announcements of a proxy and the deposit reserved for them
+        Announcements get(announcements): map T::AccountId =>
+            (Vec<Announcement<T::AccountId, T::BlockNumber>>, BalanceOf<T>);
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        ProxyType = <T as Trait>::ProxyType,
+        BlockNumber = <T as system::Trait>::BlockNumber,
+    {
+        // real, delegate, proxy type, delay
+        ProxyAdded(AccountId, AccountId, ProxyType, BlockNumber),
+        // real, delegate, proxy type, delay
+        ProxyRemoved(AccountId, AccountId, ProxyType, BlockNumber),
+        // real, delegate, call hash
+        Announced(AccountId, AccountId, [u8; 32]),
+        // whether the proxied call succeeded
+        ProxyExecuted(bool),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn deposit(count: usize, base: BalanceOf<T>, factor: BalanceOf<T>) -> BalanceOf<T> {
+        if count == 0 {
+            return Zero::zero();
+        }
+        base.saturating_add(factor.saturating_mul((count as u64).saturated_into()))
+    }
+
+    fn rereserve(who: &T::AccountId, old: BalanceOf<T>, new: BalanceOf<T>) -> Result {
+        if new > old {
+            T::Currency::reserve(who, new - old)?;
+        } else {
+            T::Currency::unreserve(who, old - new);
+        }
+        Ok(())
+    }
+
+    fn find_proxy(
+        real: &T::AccountId,
+        delegate: &T::AccountId,
+        force_proxy_type: Option<T::ProxyType>,
+    ) -> rstd::result::Result<
+        ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>,
+        &'static str,
+    > {
+        Self::proxies(real)
+            .0
+            .into_iter()
+            .find(|p| {
+                p.delegate == *delegate && force_proxy_type.map_or(true, |t| t == p.proxy_type)
+            })
+            .ok_or("not a proxy of the account")
+    }
+
+    // remove the announcements of `delegate` matching `remove`, failing if there are none
+    fn remove_announcements(
+        delegate: &T::AccountId,
+        remove: impl Fn(&Announcement<T::AccountId, T::BlockNumber>) -> bool,
+    ) -> Result {
+        let (mut pending, deposit) = Self::announcements(delegate);
+        let count = pending.len();
+        pending.retain(|a| !remove(a));
+        ensure!(pending.len() < count, "announcement not found");
+        let new_deposit = Self::deposit(
+            pending.len(),
+            T::AnnouncementDepositBase::get(),
+            T::AnnouncementDepositFactor::get(),
+        );
+        Self::rereserve(delegate, deposit, new_deposit)?;
+        if pending.is_empty() {
+            <Announcements<T>>::remove(delegate);
+        } else {
+            <Announcements<T>>::insert(delegate, (pending, new_deposit));
+        }
+        Ok(())
+    }
+
+    fn do_proxy(
+        proxy: ProxyDefinition<T::AccountId, T::ProxyType, T::BlockNumber>,
+        real: T::AccountId,
+        call: <T as Trait>::Call,
+    ) -> Result {
+        ensure!(
+            proxy.proxy_type.filter(&call),
+            "proxy type does not allow this call"
+        );
+        let result = call.dispatch(RawOrigin::Signed(real).into());
+        Self::deposit_event(RawEvent::ProxyExecuted(result.is_ok()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+
+    #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+    pub enum ProxyType {
+        Any,
+        NonTransfer,
+    }
+    impl Default for ProxyType {
+        fn default() -> Self {
+            ProxyType::Any
+        }
+    }
+    impl ProxyFilter<Call> for ProxyType {
+        fn filter(&self, call: &Call) -> bool {
+            match (self, call) {
+                (ProxyType::NonTransfer, Call::Balances(balances::Call::transfer(..))) => false,
+                _ => true,
+            }
+        }
+    }
+
+    parameter_types! {
+        pub const ProxyDepositBase: u64 = 2;
+        pub const ProxyDepositFactor: u64 = 1;
+        pub const MaxProxies: u16 = 2;
+        pub const AnnouncementDepositBase: u64 = 2;
+        pub const AnnouncementDepositFactor: u64 = 1;
+        pub const MaxPending: u32 = 2;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Call = Call;
+        type Currency = Balances;
+        type ProxyType = ProxyType;
+        type ProxyDepositBase = ProxyDepositBase;
+        type ProxyDepositFactor = ProxyDepositFactor;
+        type MaxProxies = MaxProxies;
+        type AnnouncementDepositBase = AnnouncementDepositBase;
+        type AnnouncementDepositFactor = AnnouncementDepositFactor;
+        type MaxPending = MaxPending;
+    }
+    type Balances = balances::Module<Test>;
+    type Proxy = Module<Test>;
+    type System = system::Module<Test>;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        balances::GenesisConfig::<Test> {
+            balances: vec![(1, 100), (2, 10), (3, 10)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn transfer(to: u64, value: u64) -> Box<Call> {
+        Box::new(Call::Balances(balances::Call::transfer(to, value)))
+    }
+
+    fn hash(call: &Call) -> [u8; 32] {
+        call.using_encoded(blake2_256)
+    }
+
+    #[test]
+    fn add_and_remove_proxies() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+            assert_eq!(Balances::reserved_balance(&1), 3);
+            assert_noop!(
+                Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0),
+                "proxy was already added"
+            );
+            assert_ok!(Proxy::add_proxy(
+                Origin::signed(1),
+                2,
+                ProxyType::NonTransfer,
+                0
+            ));
+            assert_eq!(Balances::reserved_balance(&1), 4);
+            assert_noop!(
+                Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any, 0),
+                "too many proxies"
+            );
+            assert_noop!(
+                Proxy::add_proxy(Origin::signed(1), 1, ProxyType::Any, 0),
+                "an account cannot be its own proxy"
+            );
+            assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+            assert_eq!(Balances::reserved_balance(&1), 3);
+            assert_noop!(
+                Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any, 0),
+                "proxy not found"
+            );
+            assert_ok!(Proxy::remove_proxies(Origin::signed(1)));
+            assert_eq!(Balances::reserved_balance(&1), 0);
+            assert!(Proxy::proxies(&1).0.is_empty());
+        });
+    }
+
+    #[test]
+    fn proxy_types() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Proxy::add_proxy(
+                Origin::signed(1),
+                2,
+                ProxyType::NonTransfer,
+                0
+            ));
+            assert_noop!(
+                Proxy::proxy(Origin::signed(2), 1, None, transfer(3, 10)),
+                "proxy type does not allow this call"
+            );
+            assert_noop!(
+                Proxy::proxy(Origin::signed(3), 1, None, transfer(3, 10)),
+                "not a proxy of the account"
+            );
+            assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 0));
+            assert_noop!(
+                Proxy::proxy(
+                    Origin::signed(2),
+                    1,
+                    Some(ProxyType::NonTransfer),
+                    transfer(3, 10)
+                ),
+                "proxy type does not allow this call"
+            );
+            assert_ok!(Proxy::proxy(
+                Origin::signed(2),
+                1,
+                Some(ProxyType::Any),
+                transfer(3, 10)
+            ));
+            assert_eq!(Balances::free_balance(&3), 20);
+        });
+    }
+
+    #[test]
+    fn announcement_delay() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 2));
+            assert_noop!(
+                Proxy::proxy(Origin::signed(2), 1, None, transfer(3, 10)),
+                "proxy must announce the call first"
+            );
+            assert_noop!(
+                Proxy::announce(Origin::signed(3), 1, hash(&transfer(3, 10))),
+                "not a proxy of the account"
+            );
+            assert_ok!(Proxy::announce(
+                Origin::signed(2),
+                1,
+                hash(&transfer(3, 10))
+            ));
+            assert_eq!(Balances::reserved_balance(&2), 3);
+
+            System::set_block_number(2);
+            assert_noop!(
+                Proxy::proxy_announced(Origin::signed(3), 2, 1, None, transfer(3, 10)),
+                "call was not announced at least the proxy's delay ago"
+            );
+            assert_noop!(
+                Proxy::proxy_announced(Origin::signed(3), 2, 1, None, transfer(3, 11)),
+                "call was not announced at least the proxy's delay ago"
+            );
+
+            System::set_block_number(3);
+            assert_ok!(Proxy::proxy_announced(
+                Origin::signed(3),
+                2,
+                1,
+                None,
+                transfer(3, 10)
+            ));
+            assert_eq!(Balances::free_balance(&3), 20);
+            assert_eq!(Balances::reserved_balance(&2), 0);
+            assert!(Proxy::announcements(&2).0.is_empty());
+            assert_noop!(
+                Proxy::proxy_announced(Origin::signed(3), 2, 1, None, transfer(3, 10)),
+                "call was not announced at least the proxy's delay ago"
+            );
+        });
+    }
+
+    #[test]
+    fn reject_announcement() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any, 2));
+            assert_ok!(Proxy::announce(
+                Origin::signed(2),
+                1,
+                hash(&transfer(3, 10))
+            ));
+            assert_ok!(Proxy::announce(
+                Origin::signed(2),
+                1,
+                hash(&transfer(3, 20))
+            ));
+            assert_eq!(Balances::reserved_balance(&2), 4);
+            assert_noop!(
+                Proxy::announce(Origin::signed(2), 1, hash(&transfer(3, 30))),
+                "too many announcements"
+            );
+            assert_noop!(
+                Proxy::reject_announcement(Origin::signed(3), 2, hash(&transfer(3, 10))),
+                "announcement not found"
+            );
+            assert_ok!(Proxy::reject_announcement(
+                Origin::signed(1),
+                2,
+                hash(&transfer(3, 10))
+            ));
+            assert_ok!(Proxy::remove_announcement(
+                Origin::signed(2),
+                1,
+                hash(&transfer(3, 20))
+            ));
+            assert_eq!(Balances::reserved_balance(&2), 0);
+
+            System::set_block_number(5);
+            assert_noop!(
+                Proxy::proxy_announced(Origin::signed(3), 2, 1, None, transfer(3, 10)),
+                "call was not announced at least the proxy's delay ago"
+            );
+        });
+    }
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod rate_limit;
+
+pub use crate::rate_limit::{CheckRateLimit, Module, Trait};
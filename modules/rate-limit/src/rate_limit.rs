@@ -0,0 +1,199 @@
+use codec::{Decode, Encode};
+use rstd::{fmt, marker::PhantomData, prelude::*};
+use sr_primitives::traits::SignedExtension;
+use sr_primitives::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+};
+use sr_primitives::weights::DispatchInfo;
+use support::{decl_module, decl_storage, traits::Get, StorageMap, StorageValue};
+
+pub trait Trait: system::Trait {
+    /// Most extrinsics an account may sign in a block.
+    type MaxExtrinsicsPerBlock: Get<u32>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        /// Most extrinsics an account may sign in a block.
+        const MaxExtrinsicsPerBlock: u32 = T::MaxExtrinsicsPerBlock::get();
+
+        fn on_finalize() {
+            for who in <Senders<T>>::take() {
+                <Counts<T>>::remove(who);
+            }
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as RateLimit {
+        // extrinsics each account signed in the current block
+        Counts get(count_of): map T::AccountId => u32;
+        // accounts with a count, cleared at the end of the block
+        Senders: Vec<T::AccountId>;
+    }
+}
+
+/// Rejects the extrinsics of an account beyond `MaxExtrinsicsPerBlock` in a block, keeping fee
+/// free chains from being spammed by a single account. The rejected extrinsics exhaust the
+/// resources of the block, so they stay in the transaction pool for a later block.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckRateLimit<T: Trait + Send + Sync>(PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckRateLimit<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Trait + Send + Sync> Default for CheckRateLimit<T> {
+    fn default() -> Self {
+        CheckRateLimit(PhantomData)
+    }
+}
+
+impl<T: Trait + Send + Sync> fmt::Debug for CheckRateLimit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CheckRateLimit")
+    }
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckRateLimit<T> {
+    type AccountId = T::AccountId;
+    type Call = <T as system::Trait>::Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> rstd::result::Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        if <Module<T>>::count_of(who) >= T::MaxExtrinsicsPerBlock::get() {
+            return InvalidTransaction::ExhaustsResources.into();
+        }
+        Ok(ValidTransaction::default())
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: DispatchInfo,
+        len: usize,
+    ) -> rstd::result::Result<(), TransactionValidityError> {
+        self.validate(who, call, info, len)?;
+        let count = <Counts<T>>::get(who);
+        if count == 0 {
+            <Senders<T>>::mutate(|senders| senders.push(who.clone()));
+        }
+        <Counts<T>>::insert(who, count + 1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup, OnFinalize},
+    };
+    use support::{impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const MaxExtrinsicsPerBlock: u32 = 3;
+    }
+    impl Trait for Test {
+        type MaxExtrinsicsPerBlock = MaxExtrinsicsPerBlock;
+    }
+    type RateLimit = Module<Test>;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap()
+            .into()
+    }
+
+    fn validate(who: u64) -> TransactionValidity {
+        CheckRateLimit::<Test>::new().validate(&who, &(), Default::default(), 0)
+    }
+
+    fn pre_dispatch(who: u64) -> rstd::result::Result<(), TransactionValidityError> {
+        CheckRateLimit::<Test>::new().pre_dispatch(&who, &(), Default::default(), 0)
+    }
+
+    #[test]
+    fn rejects_extrinsics_beyond_the_limit() {
+        with_externalities(&mut new_test_ext(), || {
+            for _ in 0..3 {
+                assert!(validate(1).is_ok());
+                assert!(pre_dispatch(1).is_ok());
+            }
+            assert_eq!(RateLimit::count_of(1), 3);
+            assert_eq!(validate(1), InvalidTransaction::ExhaustsResources.into());
+            assert_eq!(
+                pre_dispatch(1),
+                Err(InvalidTransaction::ExhaustsResources.into())
+            );
+            assert_eq!(RateLimit::count_of(1), 3);
+            assert!(validate(2).is_ok());
+        });
+    }
+
+    #[test]
+    fn counts_reset_every_block() {
+        with_externalities(&mut new_test_ext(), || {
+            for _ in 0..3 {
+                assert!(pre_dispatch(1).is_ok());
+            }
+            assert!(pre_dispatch(2).is_ok());
+            RateLimit::on_finalize(1);
+            assert_eq!(RateLimit::count_of(1), 0);
+            assert_eq!(RateLimit::count_of(2), 0);
+            assert!(validate(1).is_ok());
+        });
+    }
+}
@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod recovery;
+
+pub use crate::recovery::{ActiveRecovery, Call, Event, Module, RawEvent, RecoveryConfig, Trait};
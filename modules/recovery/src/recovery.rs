@@ -0,0 +1,491 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::traits::Saturating;
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::{Dispatchable, Result},
+    ensure,
+    traits::{Currency, Get, ReservableCurrency},
+    Parameter, StorageMap,
+};
+use system::{self, ensure_root, ensure_signed, RawOrigin};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Calls a rescuer can make for the account it recovered.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// Reserved from an account while it is recoverable, on top of the deposit per friend.
+    type ConfigDepositBase: Get<BalanceOf<Self>>;
+    /// Reserved per friend from an account while it is recoverable.
+    type FriendDepositFactor: Get<BalanceOf<Self>>;
+    /// Largest number of friends of a recoverable account.
+    type MaxFriends: Get<u16>;
+    /// Reserved from a rescuer until the lost account closes the recovery.
+    type RecoveryDeposit: Get<BalanceOf<Self>>;
+}
+
+// `threshold` of `friends` may recover an account, `delay_period` blocks after a recovery of it
+// was initiated
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct RecoveryConfig<BlockNumber, Balance, AccountId> {
+    pub delay_period: BlockNumber,
+    pub deposit: Balance,
+    pub friends: Vec<AccountId>,
+    pub threshold: u16,
+}
+
+// a rescuer's attempt to recover an account, and the friends who vouched for it so far
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct ActiveRecovery<BlockNumber, Balance, AccountId> {
+    pub created: BlockNumber,
+    pub deposit: Balance,
+    pub friends: Vec<AccountId>,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // dispatch a call from an account the origin recovered
+        fn as_recovered(origin, account: T::AccountId, call: Box<<T as Trait>::Call>) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::proxy(&who).as_ref() == Some(&account),
+                "origin has not recovered this account"
+            );
+            call.dispatch(RawOrigin::Signed(account).into())
+        }
+
+        // let `rescuer` act for `lost` without going through the friends
+        fn set_recovered(origin, lost: T::AccountId, rescuer: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            <Proxy<T>>::insert(&rescuer, &lost);
+            Self::deposit_event(RawEvent::AccountRecovered(lost, rescuer));
+            Ok(())
+        }
+
+        // make the origin recoverable by `threshold` of `friends`, which must be sorted and
+        // distinct, `delay_period` blocks after a recovery is initiated
+        fn create_recovery(
+            origin,
+            friends: Vec<T::AccountId>,
+            threshold: u16,
+            delay_period: T::BlockNumber
+        ) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(!<Recoverable<T>>::exists(&who), "account is already recoverable");
+            ensure!(
+                friends.windows(2).all(|pair| pair[0] < pair[1]),
+                "friends must be sorted and distinct"
+            );
+            ensure!(friends.len() <= usize::from(T::MaxFriends::get()), "too many friends");
+            ensure!(
+                threshold > 0 && usize::from(threshold) <= friends.len(),
+                "recovery threshold must be between 1 and the number of friends"
+            );
+            let friend_deposit =
+                T::FriendDepositFactor::get().saturating_mul((friends.len() as u32).into());
+            let deposit = T::ConfigDepositBase::get().saturating_add(friend_deposit);
+            T::Currency::reserve(&who, deposit)?;
+            let config = RecoveryConfig { delay_period, deposit, friends, threshold };
+            <Recoverable<T>>::insert(&who, config);
+            Self::deposit_event(RawEvent::RecoveryCreated(who));
+            Ok(())
+        }
+
+        // start recovering `account` for the origin, reserving the recovery deposit
+        fn initiate_recovery(origin, account: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(<Recoverable<T>>::exists(&account), "account is not recoverable");
+            ensure!(
+                !<ActiveRecoveries<T>>::exists((account.clone(), who.clone())),
+                "recovery already initiated"
+            );
+            let deposit = T::RecoveryDeposit::get();
+            T::Currency::reserve(&who, deposit)?;
+            let recovery = ActiveRecovery {
+                created: <system::Module<T>>::block_number(),
+                deposit,
+                friends: vec![],
+            };
+            <ActiveRecoveries<T>>::insert((account.clone(), who.clone()), recovery);
+            <ActiveRecoveryCount<T>>::mutate(&account, |count| *count += 1);
+            Self::deposit_event(RawEvent::RecoveryInitiated(account, who));
+            Ok(())
+        }
+
+        // vouch, as a friend of `lost`, for `rescuer` recovering it
+        fn vouch_recovery(origin, lost: T::AccountId, rescuer: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            let config = Self::recovery_config(&lost).ok_or("account is not recoverable")?;
+            ensure!(
+                config.friends.binary_search(&who).is_ok(),
+                "origin is not a friend of the account"
+            );
+            let key = (lost.clone(), rescuer.clone());
+            let mut recovery = Self::active_recovery(&key).ok_or("recovery was not initiated")?;
+            match recovery.friends.binary_search(&who) {
+                Ok(_) => return Err("friend already vouched"),
+                Err(index) => recovery.friends.insert(index, who.clone()),
+            }
+            <ActiveRecoveries<T>>::insert(key, recovery);
+            Self::deposit_event(RawEvent::RecoveryVouched(lost, rescuer, who));
+            Ok(())
+        }
+
+        // take over `account` once enough friends vouched and the delay passed
+        fn claim_recovery(origin, account: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            let config = Self::recovery_config(&account).ok_or("account is not recoverable")?;
+            let recovery = Self::active_recovery((account.clone(), who.clone()))
+                .ok_or("recovery was not initiated")?;
+            let claimable = recovery.created.saturating_add(config.delay_period);
+            ensure!(
+                <system::Module<T>>::block_number() >= claimable,
+                "recovery delay has not passed"
+            );
+            ensure!(
+                recovery.friends.len() >= usize::from(config.threshold),
+                "not enough friends vouched"
+            );
+            ensure!(!<Proxy<T>>::exists(&who), "origin already recovered an account");
+            <Proxy<T>>::insert(&who, &account);
+            Self::deposit_event(RawEvent::AccountRecovered(account, who));
+            Ok(())
+        }
+
+        // end a recovery of the origin, which receives the rescuer's deposit
+        fn close_recovery(origin, rescuer: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            let recovery = <ActiveRecoveries<T>>::take((who.clone(), rescuer.clone()))
+                .ok_or("recovery was not initiated")?;
+            T::Currency::repatriate_reserved(&rescuer, &who, recovery.deposit)?;
+            <ActiveRecoveryCount<T>>::mutate(&who, |count| *count -= 1);
+            Self::deposit_event(RawEvent::RecoveryClosed(who, rescuer));
+            Ok(())
+        }
+
+        // make the origin unrecoverable, returning its deposit; its recoveries must be closed
+        fn remove_recovery(origin) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::active_recovery_count(&who) == 0, "account has active recoveries");
+            let config = <Recoverable<T>>::take(&who).ok_or("account is not recoverable")?;
+            T::Currency::unreserve(&who, config.deposit);
+            Self::deposit_event(RawEvent::RecoveryRemoved(who));
+            Ok(())
+        }
+
+        // stop acting for an account the origin recovered
+        fn cancel_recovered(origin, account: T::AccountId) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                Self::proxy(&who).as_ref() == Some(&account),
+                "origin has not recovered this account"
+            );
+            <Proxy<T>>::remove(&who);
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Recovery {
+        // who may recover an account and how
+        Recoverable get(recovery_config):
+            map T::AccountId => Option<RecoveryConfig<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+        // recoveries by lost account and rescuer
+        ActiveRecoveries get(active_recovery):
+            map (T::AccountId, T::AccountId)
+                => Option<ActiveRecovery<T::BlockNumber, BalanceOf<T>, T::AccountId>>;
+        // number of active recoveries of an account
+        ActiveRecoveryCount get(active_recovery_count): map T::AccountId => u32;
+        // account a rescuer recovered
+        Proxy get(proxy): map T::AccountId => Option<T::AccountId>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        // account that became recoverable
+        RecoveryCreated(AccountId),
+        // lost account, rescuer
+        RecoveryInitiated(AccountId, AccountId),
+        // lost account, rescuer, friend
+        RecoveryVouched(AccountId, AccountId, AccountId),
+        // lost account, rescuer; the rescuer's deposit went to the lost account
+        RecoveryClosed(AccountId, AccountId),
+        // lost account, rescuer that may now act for it
+        AccountRecovered(AccountId, AccountId),
+        // account that is no longer recoverable
+        RecoveryRemoved(AccountId),
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const ConfigDepositBase: u64 = 10;
+        pub const FriendDepositFactor: u64 = 1;
+        pub const MaxFriends: u16 = 3;
+        pub const RecoveryDeposit: u64 = 10;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Call = Call;
+        type Currency = Balances;
+        type ConfigDepositBase = ConfigDepositBase;
+        type FriendDepositFactor = FriendDepositFactor;
+        type MaxFriends = MaxFriends;
+        type RecoveryDeposit = RecoveryDeposit;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Recovery = Module<Test>;
+
+    // 1 is recoverable by 2 of 2, 3 and 4 after 10 blocks, 5 is the rescuer
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        balances::GenesisConfig::<Test> {
+            balances: vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn transfer(to: u64, value: u64) -> Box<Call> {
+        Box::new(Call::Balances(balances::Call::transfer(to, value)))
+    }
+
+    #[test]
+    fn recover() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Recovery::create_recovery(
+                Origin::signed(1),
+                vec![2, 3, 4],
+                2,
+                10
+            ));
+            assert_eq!(Balances::reserved_balance(1), 13);
+            assert_ok!(Recovery::initiate_recovery(Origin::signed(5), 1));
+            assert_eq!(Balances::reserved_balance(5), 10);
+            assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 1, 5));
+            assert_noop!(
+                Recovery::claim_recovery(Origin::signed(5), 1),
+                "recovery delay has not passed"
+            );
+            System::set_block_number(10);
+            assert_noop!(
+                Recovery::claim_recovery(Origin::signed(5), 1),
+                "not enough friends vouched"
+            );
+            assert_ok!(Recovery::vouch_recovery(Origin::signed(4), 1, 5));
+            assert_eq!(
+                Recovery::active_recovery((1, 5)).unwrap().friends,
+                vec![2, 4]
+            );
+            assert_noop!(
+                Recovery::as_recovered(Origin::signed(5), 1, transfer(5, 10)),
+                "origin has not recovered this account"
+            );
+            assert_ok!(Recovery::claim_recovery(Origin::signed(5), 1));
+            assert_eq!(Recovery::proxy(5), Some(1));
+
+            assert_ok!(Recovery::as_recovered(
+                Origin::signed(5),
+                1,
+                transfer(5, 10)
+            ));
+            assert_eq!(Balances::free_balance(5), 100);
+            assert_eq!(Balances::free_balance(1), 77);
+
+            // the lost account takes the rescuer's deposit and gets its own back
+            assert_noop!(
+                Recovery::remove_recovery(Origin::signed(1)),
+                "account has active recoveries"
+            );
+            assert_ok!(Recovery::close_recovery(Origin::signed(1), 5));
+            assert_eq!(Balances::reserved_balance(5), 0);
+            assert_eq!(Balances::free_balance(1), 87);
+            assert_ok!(Recovery::remove_recovery(Origin::signed(1)));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(1), 100);
+            assert_eq!(Recovery::recovery_config(1), None);
+            // the rescuer keeps acting for the account until it cancels
+            assert_eq!(Recovery::proxy(5), Some(1));
+            assert_ok!(Recovery::cancel_recovered(Origin::signed(5), 1));
+            assert_eq!(Recovery::proxy(5), None);
+        });
+    }
+
+    #[test]
+    fn create_recovery() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Recovery::create_recovery(Origin::signed(1), vec![3, 2], 1, 0),
+                "friends must be sorted and distinct"
+            );
+            assert_noop!(
+                Recovery::create_recovery(Origin::signed(1), vec![2, 2], 1, 0),
+                "friends must be sorted and distinct"
+            );
+            assert_noop!(
+                Recovery::create_recovery(Origin::signed(1), vec![2, 3, 4, 5], 1, 0),
+                "too many friends"
+            );
+            for threshold in &[0, 3] {
+                assert_noop!(
+                    Recovery::create_recovery(Origin::signed(1), vec![2, 3], *threshold, 0),
+                    "recovery threshold must be between 1 and the number of friends"
+                );
+            }
+            assert_ok!(Recovery::create_recovery(
+                Origin::signed(1),
+                vec![2, 3],
+                1,
+                0
+            ));
+            assert_noop!(
+                Recovery::create_recovery(Origin::signed(1), vec![2, 3], 1, 0),
+                "account is already recoverable"
+            );
+            assert_eq!(Balances::reserved_balance(1), 12);
+        });
+    }
+
+    #[test]
+    fn vouch_recovery() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Recovery::initiate_recovery(Origin::signed(5), 1),
+                "account is not recoverable"
+            );
+            assert_ok!(Recovery::create_recovery(
+                Origin::signed(1),
+                vec![2, 3, 4],
+                2,
+                0
+            ));
+            assert_noop!(
+                Recovery::vouch_recovery(Origin::signed(2), 1, 5),
+                "recovery was not initiated"
+            );
+            assert_ok!(Recovery::initiate_recovery(Origin::signed(5), 1));
+            assert_noop!(
+                Recovery::initiate_recovery(Origin::signed(5), 1),
+                "recovery already initiated"
+            );
+            assert_noop!(
+                Recovery::vouch_recovery(Origin::signed(5), 1, 5),
+                "origin is not a friend of the account"
+            );
+            assert_ok!(Recovery::vouch_recovery(Origin::signed(3), 1, 5));
+            assert_noop!(
+                Recovery::vouch_recovery(Origin::signed(3), 1, 5),
+                "friend already vouched"
+            );
+            // a second rescuer needs vouches of its own
+            assert_ok!(Recovery::initiate_recovery(Origin::signed(4), 1));
+            assert_eq!(Recovery::active_recovery_count(1), 2);
+            assert_ok!(Recovery::vouch_recovery(Origin::signed(2), 1, 5));
+            assert_noop!(
+                Recovery::claim_recovery(Origin::signed(4), 1),
+                "not enough friends vouched"
+            );
+            assert_ok!(Recovery::claim_recovery(Origin::signed(5), 1));
+        });
+    }
+
+    #[test]
+    fn set_recovered() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(Recovery::set_recovered(Origin::signed(1), 1, 5).is_err());
+            assert_ok!(Recovery::set_recovered(Origin::ROOT, 1, 5));
+            assert_ok!(Recovery::as_recovered(
+                Origin::signed(5),
+                1,
+                transfer(5, 100)
+            ));
+            assert_eq!(Balances::free_balance(1), 0);
+            assert_eq!(Balances::free_balance(5), 200);
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod registrars;
+
+#[cfg(feature = "std")]
+pub use crate::registrars::GenesisConfig;
+
+pub use crate::registrars::{field_bits, Module, Trait, IDENTITY_FIELDS};
@@ -0,0 +1,184 @@
+use codec::{Decode, Encode};
+use identity::IdentityFields;
+use rstd::prelude::*;
+use support::{decl_module, decl_storage, dispatch::Dispatchable, traits::Currency};
+use system::RawOrigin;
+
+type BalanceOf<T> =
+    <<T as identity::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Identity fields a registrar can judge, by name, with their bit in `IdentityFields`.
+pub const IDENTITY_FIELDS: &[(&str, u64)] = &[
+    ("display", 0b1),
+    ("legal", 0b10),
+    ("web", 0b100),
+    ("riot", 0b1000),
+    ("email", 0b1_0000),
+    ("pgp_fingerprint", 0b10_0000),
+    ("image", 0b100_0000),
+];
+
+/// The `IdentityFields` bitmask of the named fields, or the first name that is not a field.
+pub fn field_bits<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<u64, &'a str> {
+    names.into_iter().try_fold(0, |bits, name| {
+        IDENTITY_FIELDS
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(_, bit)| bits | bit)
+            .ok_or(name)
+    })
+}
+
+/// Registrars are added through the identity module's calls, so the runtime's `RegistrarOrigin`
+/// must accept root.
+pub trait Trait: identity::Trait {}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Registrars {}
+    add_extra_genesis {
+        // (account, fee, fields) of each registrar, in order of registrar index
+        config(registrars): Vec<(T::AccountId, BalanceOf<T>, u64)>;
+        build(|config: &GenesisConfig<T>| {
+            for (index, (account, fee, fields)) in config.registrars.iter().enumerate() {
+                let index = index as u32;
+                let fields = IdentityFields::decode(&mut &fields.encode()[..])
+                    .expect("registrar fields must be identity fields");
+                let signed = || RawOrigin::Signed(account.clone()).into();
+                identity::Call::<T>::add_registrar(account.clone())
+                    .dispatch(RawOrigin::Root.into())
+                    .expect("registrar origin must accept root");
+                identity::Call::<T>::set_fee(index, *fee)
+                    .dispatch(signed())
+                    .expect("registrar was just added");
+                identity::Call::<T>::set_fields(index, fields)
+                    .dispatch(signed())
+                    .expect("registrar was just added");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const BasicDeposit: u64 = 10;
+        pub const FieldDeposit: u64 = 10;
+        pub const SubAccountDeposit: u64 = 10;
+        pub const MaximumSubAccounts: u32 = 2;
+    }
+    impl identity::Trait for Test {
+        type Event = ();
+        type Currency = balances::Module<Test>;
+        type Slashed = ();
+        type BasicDeposit = BasicDeposit;
+        type FieldDeposit = FieldDeposit;
+        type SubAccountDeposit = SubAccountDeposit;
+        type MaximumSubAccounts = MaximumSubAccounts;
+        type RegistrarOrigin = system::EnsureRoot<u64>;
+        type ForceOrigin = system::EnsureRoot<u64>;
+    }
+    impl Trait for Test {}
+    type Identity = identity::Module<Test>;
+
+    fn fields(bits: u64) -> IdentityFields {
+        IdentityFields::decode(&mut &bits.encode()[..]).unwrap()
+    }
+
+    #[test]
+    fn genesis_registrars() {
+        let config = GenesisConfig::<Test> {
+            registrars: vec![(3, 100, 0b1), (1, 0, 0b1_0001)],
+        };
+        let mut ext: runtime_io::TestExternalities<Blake2Hasher> =
+            config.build_storage().unwrap().into();
+        with_externalities(&mut ext, || {
+            let registrars = Identity::registrars();
+            assert_eq!(registrars.len(), 2);
+            let first = registrars[0].clone().unwrap();
+            assert_eq!((first.account, first.fee), (3, 100));
+            assert_eq!(first.fields, fields(0b1));
+            let second = registrars[1].clone().unwrap();
+            assert_eq!((second.account, second.fee), (1, 0));
+            assert_eq!(second.fields, fields(0b1_0001));
+        });
+    }
+
+    #[test]
+    fn parse_fields() {
+        assert_eq!(field_bits(vec![]), Ok(0));
+        assert_eq!(field_bits(vec!["display", "email"]), Ok(0b1_0001));
+        assert_eq!(field_bits(vec!["display", "display"]), Ok(0b1));
+        assert_eq!(field_bits(vec!["web", "phone"]), Err("phone"));
+        for (_, bit) in IDENTITY_FIELDS {
+            fields(*bit);
+        }
+    }
+}
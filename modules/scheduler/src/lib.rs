@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod scheduler;
+
+#[cfg(feature = "std")]
+pub use crate::scheduler::GenesisConfig;
+
+pub use crate::scheduler::{Event, Module, RawEvent, Scheduled, Trait};
@@ -0,0 +1,357 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::traits::{One, Zero};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::{Dispatchable, Result},
+    ensure,
+    traits::{EnsureOrigin, Get},
+    Parameter, StorageMap,
+};
+use system::{self, RawOrigin};
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Calls that can be scheduled. They are dispatched as root.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin>;
+    /// Who may schedule and cancel calls.
+    type ScheduleOrigin: EnsureOrigin<Self::Origin>;
+    /// Largest number of calls scheduled for a single block.
+    type MaxScheduledPerBlock: Get<u32>;
+}
+
+// a call to dispatch, `count` times in all every `period` blocks if periodic
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct Scheduled<Call, BlockNumber> {
+    pub maybe_periodic: Option<(BlockNumber, u32)>,
+    pub call: Call,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // dispatch `call` as root at block `when`, and if `maybe_periodic` is (period, count),
+        // every `period` blocks after until it has been dispatched `count` times
+        fn schedule(
+            origin,
+            when: T::BlockNumber,
+            maybe_periodic: Option<(T::BlockNumber, u32)>,
+            call: Box<<T as Trait>::Call>
+        ) -> Result {
+            T::ScheduleOrigin::ensure_origin(origin)?;
+            ensure!(
+                when > <system::Module<T>>::block_number(),
+                "calls must be scheduled for a future block"
+            );
+            if let Some((period, count)) = maybe_periodic {
+                ensure!(
+                    !period.is_zero() && count > 0,
+                    "periodic calls need a period and a count of at least 1"
+                );
+            }
+            let index = Self::insert(when, Scheduled { maybe_periodic, call: *call })?;
+            Self::deposit_event(RawEvent::Scheduled(when, index));
+            Ok(())
+        }
+
+        // cancel the call at `index` in the agenda of block `when`
+        fn cancel(origin, when: T::BlockNumber, index: u32) -> Result {
+            T::ScheduleOrigin::ensure_origin(origin)?;
+            Self::cancel_scheduled(when, index)
+        }
+
+        fn on_initialize(now: T::BlockNumber) {
+            for (index, scheduled) in <Agenda<T>>::take(now).into_iter().enumerate() {
+                let Scheduled { maybe_periodic, call } = match scheduled {
+                    Some(scheduled) => scheduled,
+                    None => continue,
+                };
+                let next = maybe_periodic
+                    .filter(|(_, count)| *count > 1)
+                    .map(|(period, count)| (period, count - 1));
+                if let Some((period, _)) = next {
+                    // a full agenda drops the repetition rather than the call
+                    let _ = Self::insert(
+                        now + period,
+                        Scheduled { maybe_periodic: next, call: call.clone() },
+                    );
+                }
+                let result = call.dispatch(RawOrigin::Root.into());
+                Self::deposit_event(RawEvent::Dispatched(now, index as u32, result.is_ok()));
+            }
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Scheduler {
+        // calls to dispatch at the start of a block, canceled ones are None so that indices stay
+        // stable
+        Agenda get(agenda): map T::BlockNumber =>
+            Vec<Option<Scheduled<<T as Trait>::Call, T::BlockNumber>>>;
+    }
+    add_extra_genesis {
+        // (block, periodic, encoded call) of calls scheduled at genesis
+        config(scheduled): Vec<(T::BlockNumber, Option<(T::BlockNumber, u32)>, Vec<u8>)>;
+        build(|config: &GenesisConfig<T>| {
+            for (when, maybe_periodic, call) in &config.scheduled {
+                assert!(*when >= One::one(), "calls must be scheduled after genesis");
+                let call = Decode::decode(&mut &call[..]).expect("scheduled call must decode");
+                Module::<T>::insert(*when, Scheduled { maybe_periodic: *maybe_periodic, call })
+                    .expect("too many calls scheduled for a block at genesis");
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        BlockNumber = <T as system::Trait>::BlockNumber,
+    {
+        // block, index in its agenda
+        Scheduled(BlockNumber, u32),
+        // block, index in its agenda
+        Canceled(BlockNumber, u32),
+        // block, index in its agenda, whether the call succeeded
+        Dispatched(BlockNumber, u32, bool),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Dispatch `scheduled` as root at block `when`, returning its index in the agenda of the
+    /// block.
+    pub fn insert(
+        when: T::BlockNumber,
+        scheduled: Scheduled<<T as Trait>::Call, T::BlockNumber>,
+    ) -> rstd::result::Result<u32, &'static str> {
+        let mut agenda = Self::agenda(when);
+        ensure!(
+            agenda.len() < T::MaxScheduledPerBlock::get() as usize,
+            "too many calls scheduled for this block"
+        );
+        agenda.push(Some(scheduled));
+        let index = agenda.len() as u32 - 1;
+        <Agenda<T>>::insert(when, agenda);
+        Ok(index)
+    }
+
+    /// Cancel the call at `index` in the agenda of block `when`.
+    pub fn cancel_scheduled(when: T::BlockNumber, index: u32) -> Result {
+        let mut agenda = Self::agenda(when);
+        let scheduled = agenda
+            .get_mut(index as usize)
+            .ok_or("no call at this index")?;
+        ensure!(scheduled.take().is_some(), "no call at this index");
+        <Agenda<T>>::insert(when, agenda);
+        Self::deposit_event(RawEvent::Canceled(when, index));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup, OnInitialize},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const MaxScheduledPerBlock: u32 = 2;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Call = Call;
+        type ScheduleOrigin = system::EnsureRoot<u64>;
+        type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    }
+    type Balances = balances::Module<Test>;
+    type Scheduler = Module<Test>;
+    type System = system::Module<Test>;
+
+    fn ext(config: GenesisConfig<Test>) -> runtime_io::TestExternalities<Blake2Hasher> {
+        config.build_storage().unwrap().into()
+    }
+
+    fn set_balance(who: u64, free: u64) -> Call {
+        Call::Balances(balances::Call::set_balance(who, free, 0))
+    }
+
+    fn run_to_block(n: u64) {
+        while System::block_number() < n {
+            System::set_block_number(System::block_number() + 1);
+            Scheduler::on_initialize(System::block_number());
+        }
+    }
+
+    #[test]
+    fn schedule() {
+        with_externalities(&mut ext(Default::default()), || {
+            System::set_block_number(1);
+            assert!(
+                Scheduler::schedule(Origin::signed(1), 3, None, Box::new(set_balance(1, 10)))
+                    .is_err()
+            );
+            assert_noop!(
+                Scheduler::schedule(Origin::ROOT, 1, None, Box::new(set_balance(1, 10))),
+                "calls must be scheduled for a future block"
+            );
+            assert_ok!(Scheduler::schedule(
+                Origin::ROOT,
+                3,
+                None,
+                Box::new(set_balance(1, 10))
+            ));
+            run_to_block(2);
+            assert_eq!(Balances::free_balance(&1), 0);
+            run_to_block(3);
+            assert_eq!(Balances::free_balance(&1), 10);
+            assert!(Scheduler::agenda(3).is_empty());
+        });
+    }
+
+    #[test]
+    fn periodic() {
+        with_externalities(&mut ext(Default::default()), || {
+            System::set_block_number(1);
+            assert_noop!(
+                Scheduler::schedule(Origin::ROOT, 2, Some((0, 2)), Box::new(set_balance(1, 10))),
+                "periodic calls need a period and a count of at least 1"
+            );
+            assert_ok!(Scheduler::schedule(
+                Origin::ROOT,
+                2,
+                Some((3, 2)),
+                Box::new(set_balance(1, 10))
+            ));
+            run_to_block(2);
+            assert_eq!(Balances::free_balance(&1), 10);
+            assert_ok!(set_balance(1, 0).dispatch(Origin::ROOT));
+            run_to_block(5);
+            assert_eq!(Balances::free_balance(&1), 10);
+            assert_ok!(set_balance(1, 0).dispatch(Origin::ROOT));
+            run_to_block(20);
+            assert_eq!(Balances::free_balance(&1), 0);
+        });
+    }
+
+    #[test]
+    fn cancel() {
+        with_externalities(&mut ext(Default::default()), || {
+            System::set_block_number(1);
+            assert_ok!(Scheduler::schedule(
+                Origin::ROOT,
+                3,
+                None,
+                Box::new(set_balance(1, 10))
+            ));
+            assert_ok!(Scheduler::schedule(
+                Origin::ROOT,
+                3,
+                None,
+                Box::new(set_balance(2, 10))
+            ));
+            assert_noop!(
+                Scheduler::schedule(Origin::ROOT, 3, None, Box::new(set_balance(3, 10))),
+                "too many calls scheduled for this block"
+            );
+            assert_ok!(Scheduler::cancel(Origin::ROOT, 3, 0));
+            assert_noop!(
+                Scheduler::cancel(Origin::ROOT, 3, 0),
+                "no call at this index"
+            );
+            assert_noop!(
+                Scheduler::cancel(Origin::ROOT, 3, 2),
+                "no call at this index"
+            );
+            run_to_block(3);
+            assert_eq!(Balances::free_balance(&1), 0);
+            assert_eq!(Balances::free_balance(&2), 10);
+        });
+    }
+
+    #[test]
+    fn scheduled_at_genesis() {
+        let config = GenesisConfig::<Test> {
+            scheduled: vec![
+                (2, None, set_balance(1, 10).encode()),
+                (2, Some((2, 2)), set_balance(2, 10).encode()),
+            ],
+        };
+        with_externalities(&mut ext(config), || {
+            assert_eq!(Scheduler::agenda(2).len(), 2);
+            run_to_block(2);
+            assert_eq!(Balances::free_balance(&1), 10);
+            assert_eq!(Balances::free_balance(&2), 10);
+            assert_eq!(Scheduler::agenda(4).len(), 1);
+        });
+    }
+}
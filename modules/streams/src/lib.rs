@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod streams;
+
+pub use crate::streams::{Call, Event, Module, RawEvent, Stream, Trait};
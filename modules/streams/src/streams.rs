@@ -0,0 +1,293 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::traits::{SaturatedConversion, Saturating, Zero};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, ReservableCurrency},
+    StorageMap, StorageValue,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    type Currency: ReservableCurrency<Self::AccountId>;
+}
+
+// `deposit` reserved from `sender` and paid to `recipient` at `rate_per_block` from `start`, of
+// which `withdrawn` was claimed already
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct Stream<AccountId, Balance, BlockNumber> {
+    pub sender: AccountId,
+    pub recipient: AccountId,
+    pub rate_per_block: Balance,
+    pub deposit: Balance,
+    pub start: BlockNumber,
+    pub withdrawn: Balance,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // reserve `deposit` of the origin and pay it to `recipient`, `rate_per_block` every block
+        // from the next one until it runs out
+        fn open(
+            origin,
+            recipient: T::AccountId,
+            rate_per_block: BalanceOf<T>,
+            deposit: BalanceOf<T>
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+            ensure!(sender != recipient, "sender and recipient must differ");
+            ensure!(
+                !rate_per_block.is_zero() && rate_per_block <= deposit,
+                "rate must be positive and at most the deposit"
+            );
+            T::Currency::reserve(&sender, deposit)?;
+            let id = Self::next_stream_id();
+            NextStreamId::put(id + 1);
+            let stream = Stream {
+                sender: sender.clone(),
+                recipient: recipient.clone(),
+                rate_per_block,
+                deposit,
+                start: <system::Module<T>>::block_number(),
+                withdrawn: Zero::zero(),
+            };
+            <Streams<T>>::insert(id, stream);
+            Self::deposit_event(
+                RawEvent::Opened(id, sender, recipient, rate_per_block, deposit)
+            );
+            Ok(())
+        }
+
+        // pay the recipient of a stream what it earned since its last claim
+        fn claim(origin, id: u64) -> Result {
+            let who = ensure_signed(origin)?;
+            let mut stream = Self::stream(id).ok_or("stream does not exist")?;
+            ensure!(who == stream.recipient, "only the recipient may claim a stream");
+            let amount = Self::claimable(&stream);
+            T::Currency::repatriate_reserved(&stream.sender, &stream.recipient, amount)?;
+            stream.withdrawn = stream.withdrawn.saturating_add(amount);
+            if stream.withdrawn == stream.deposit {
+                <Streams<T>>::remove(id);
+            } else {
+                <Streams<T>>::insert(id, stream);
+            }
+            Self::deposit_event(RawEvent::Claimed(id, amount));
+            Ok(())
+        }
+
+        // close a stream as its sender or recipient, paying the recipient what it earned and
+        // returning the rest of the deposit to the sender
+        fn cancel(origin, id: u64) -> Result {
+            let who = ensure_signed(origin)?;
+            let stream = Self::stream(id).ok_or("stream does not exist")?;
+            ensure!(
+                who == stream.sender || who == stream.recipient,
+                "only the sender or the recipient may cancel a stream"
+            );
+            let amount = Self::claimable(&stream);
+            T::Currency::repatriate_reserved(&stream.sender, &stream.recipient, amount)?;
+            let refund = stream.deposit - stream.withdrawn - amount;
+            T::Currency::unreserve(&stream.sender, refund);
+            <Streams<T>>::remove(id);
+            Self::deposit_event(RawEvent::Canceled(id, amount, refund));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Streams {
+        // open streams by id
+        Streams get(stream):
+            map u64 => Option<Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+        // id of the next stream opened
+        NextStreamId get(next_stream_id): u64;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // id, sender, recipient, rate per block, deposit
+        Opened(u64, AccountId, AccountId, Balance, Balance),
+        // id, amount paid
+        Claimed(u64, Balance),
+        // id, amount paid, amount returned to the sender
+        Canceled(u64, Balance, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// What the recipient of a stream earned and has not claimed yet.
+    pub fn claimable(stream: &Stream<T::AccountId, BalanceOf<T>, T::BlockNumber>) -> BalanceOf<T> {
+        let blocks = <system::Module<T>>::block_number().saturating_sub(stream.start);
+        let blocks = BalanceOf::<T>::saturated_from(blocks.saturated_into::<u64>());
+        let earned = rstd::cmp::min(stream.rate_per_block.saturating_mul(blocks), stream.deposit);
+        earned - stream.withdrawn
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Streams = Module<Test>;
+
+    // stream 0 pays 2 three per block from block 1, out of a deposit of 10 from 1
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut ext: runtime_io::TestExternalities<Blake2Hasher> =
+            balances::GenesisConfig::<Test> {
+                balances: vec![(1, 100)],
+                vesting: vec![],
+            }
+            .build_storage()
+            .unwrap()
+            .into();
+        with_externalities(&mut ext, || {
+            System::set_block_number(1);
+            assert_ok!(Streams::open(Origin::signed(1), 2, 3, 10));
+        });
+        ext
+    }
+
+    #[test]
+    fn open() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(Balances::reserved_balance(1), 10);
+            assert_eq!(Streams::stream(0).unwrap().start, 1);
+            assert_noop!(
+                Streams::open(Origin::signed(1), 1, 3, 10),
+                "sender and recipient must differ"
+            );
+            assert_noop!(
+                Streams::open(Origin::signed(1), 2, 0, 10),
+                "rate must be positive and at most the deposit"
+            );
+            assert_noop!(
+                Streams::open(Origin::signed(1), 2, 11, 10),
+                "rate must be positive and at most the deposit"
+            );
+            assert!(Streams::open(Origin::signed(1), 2, 1, 91).is_err());
+        });
+    }
+
+    #[test]
+    fn claim() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                Streams::claim(Origin::signed(1), 0),
+                "only the recipient may claim a stream"
+            );
+            System::set_block_number(3);
+            assert_ok!(Streams::claim(Origin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 6);
+            assert_eq!(Balances::reserved_balance(1), 4);
+            assert_ok!(Streams::claim(Origin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 6);
+
+            // the last block pays what is left of the deposit
+            System::set_block_number(100);
+            assert_eq!(Streams::claimable(&Streams::stream(0).unwrap()), 4);
+            assert_ok!(Streams::claim(Origin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 10);
+            assert_eq!(Balances::free_balance(1), 90);
+            assert_eq!(Streams::stream(0), None);
+        });
+    }
+
+    #[test]
+    fn cancel() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(2);
+            assert_ok!(Streams::claim(Origin::signed(2), 0));
+            System::set_block_number(3);
+            assert_noop!(
+                Streams::cancel(Origin::signed(3), 0),
+                "only the sender or the recipient may cancel a stream"
+            );
+            assert_ok!(Streams::cancel(Origin::signed(1), 0));
+            assert_eq!(Balances::free_balance(2), 6);
+            assert_eq!(Balances::free_balance(1), 94);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Streams::stream(0), None);
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod timelock;
+
+#[cfg(feature = "std")]
+pub use crate::timelock::GenesisConfig;
+
+pub use crate::timelock::{Call, Event, Module, RawEvent, Trait, DEFAULT_DELAY};
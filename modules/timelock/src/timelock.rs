@@ -0,0 +1,291 @@
+use rstd::prelude::*;
+use scheduler::Scheduled;
+use sr_primitives::traits::{Hash, One, Saturating};
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, storage::unhashed,
+    traits::EnsureOrigin, StorageMap, StorageValue,
+};
+use system::{self, ensure_root, ensure_signed};
+
+/// Blocks between the announcement of a call and its dispatch on chains that don't set it at
+/// genesis, a day of 6 second blocks.
+pub const DEFAULT_DELAY: u32 = 14_400;
+
+/// Name the sudo module stores its key under, hashed into the storage key.
+const SUDO_KEY: &[u8] = b"Sudo Key";
+
+pub trait Trait: scheduler::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Cancels announced calls in an emergency.
+    type CancelOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // have the scheduler dispatch `call` as root once the delay passed; only the admin may
+        // announce calls
+        fn announce(origin, call: Box<<T as scheduler::Trait>::Call>) -> Result {
+            let who = ensure_signed(origin)?;
+            ensure!(who == Self::admin(), "only the admin may announce calls");
+            let hash = T::Hashing::hash_of(&call);
+            let now = <system::Module<T>>::block_number();
+            // a call scheduled for the current block would never be dispatched
+            let when = now.saturating_add(Self::delay().max(One::one()));
+            let index = <scheduler::Module<T>>::insert(
+                when,
+                Scheduled { maybe_periodic: None, call: *call },
+            )?;
+            <Announcements<T>>::mutate(when, |announced| announced.push(index));
+            Self::deposit_event(RawEvent::Announced(when, index, hash));
+            Ok(())
+        }
+
+        // drop an announced call before it is dispatched
+        fn cancel(origin, when: T::BlockNumber, index: u32) -> Result {
+            T::CancelOrigin::ensure_origin(origin)?;
+            ensure!(
+                Self::announcements(when).contains(&index),
+                "no call was announced at this index"
+            );
+            <scheduler::Module<T>>::cancel_scheduled(when, index)?;
+            <Announcements<T>>::mutate(when, |announced| announced.retain(|i| *i != index));
+            Self::deposit_event(RawEvent::Canceled(when, index));
+            Ok(())
+        }
+
+        // hand the admin key to `new`, itself an announced call
+        fn set_admin(origin, new: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            <Admin<T>>::put(&new);
+            Self::deposit_event(RawEvent::AdminSet(new));
+            Ok(())
+        }
+
+        // clear the sudo key, leaving root to referenda and the calls the admin announces; the
+        // sudo key retires itself by dispatching this through sudo
+        fn retire_sudo(origin) -> Result {
+            ensure_root(origin)?;
+            ensure!(!Self::sudo_retired(), "sudo is already retired");
+            unhashed::kill(&runtime_io::twox_128(SUDO_KEY));
+            Self::deposit_event(RawEvent::SudoRetired);
+            Ok(())
+        }
+
+        fn on_finalize(now: T::BlockNumber) {
+            // the scheduler dispatched the calls announced for this block when it started
+            <Announcements<T>>::remove(now);
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Timelock {
+        // account announcing calls
+        Admin get(admin) config(): T::AccountId;
+        // blocks between the announcement of a call and its dispatch
+        Delay get(delay) config(): T::BlockNumber = DEFAULT_DELAY.into();
+        // indices in the scheduler agenda of a block of the calls announced for it
+        Announcements get(announcements): map T::BlockNumber => Vec<u32>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        BlockNumber = <T as system::Trait>::BlockNumber,
+        Hash = <T as system::Trait>::Hash,
+    {
+        // block, index in its agenda, call hash
+        Announced(BlockNumber, u32, Hash),
+        // block, index in its agenda
+        Canceled(BlockNumber, u32),
+        // admin
+        AdminSet(AccountId),
+        SudoRetired,
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Whether the sudo key was cleared, so that no account can dispatch calls as root.
+    pub fn sudo_retired() -> bool {
+        !unhashed::exists(&runtime_io::twox_128(SUDO_KEY))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup, OnFinalize, OnInitialize},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const MaxScheduledPerBlock: u32 = 2;
+    }
+    impl scheduler::Trait for Test {
+        type Event = ();
+        type Call = Call;
+        type ScheduleOrigin = system::EnsureRoot<u64>;
+        type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type CancelOrigin = system::EnsureRoot<u64>;
+    }
+    type Balances = balances::Module<Test>;
+    type Scheduler = scheduler::Module<Test>;
+    type System = system::Module<Test>;
+    type Timelock = Module<Test>;
+
+    // 1 is the admin and calls are dispatched 3 blocks after their announcement
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> { admin: 1, delay: 3 }
+            .build_storage()
+            .unwrap()
+            .into()
+    }
+
+    fn set_balance(who: u64, free: u64) -> Box<Call> {
+        Box::new(Call::Balances(balances::Call::set_balance(who, free, 0)))
+    }
+
+    fn run_to_block(n: u64) {
+        while System::block_number() < n {
+            Timelock::on_finalize(System::block_number());
+            System::set_block_number(System::block_number() + 1);
+            Scheduler::on_initialize(System::block_number());
+        }
+    }
+
+    #[test]
+    fn dispatches_after_the_delay() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_noop!(
+                Timelock::announce(Origin::signed(2), set_balance(2, 10)),
+                "only the admin may announce calls"
+            );
+            assert_ok!(Timelock::announce(Origin::signed(1), set_balance(2, 10)));
+            assert_eq!(Timelock::announcements(4), vec![0]);
+            run_to_block(3);
+            assert_eq!(Balances::free_balance(&2), 0);
+            run_to_block(4);
+            assert_eq!(Balances::free_balance(&2), 10);
+            run_to_block(5);
+            assert!(Timelock::announcements(4).is_empty());
+        });
+    }
+
+    #[test]
+    fn cancel() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(Timelock::announce(Origin::signed(1), set_balance(2, 10)));
+            assert!(Timelock::cancel(Origin::signed(1), 4, 0).is_err());
+            assert_noop!(
+                Timelock::cancel(Origin::ROOT, 4, 1),
+                "no call was announced at this index"
+            );
+            assert_ok!(Timelock::cancel(Origin::ROOT, 4, 0));
+            run_to_block(4);
+            assert_eq!(Balances::free_balance(&2), 0);
+        });
+    }
+
+    #[test]
+    fn set_admin() {
+        with_externalities(&mut new_test_ext(), || {
+            // the admin only sets the admin through an announced call, dispatched as root
+            assert!(Timelock::set_admin(Origin::signed(1), 2).is_err());
+            assert_ok!(Timelock::set_admin(Origin::ROOT, 2));
+            assert_eq!(Timelock::admin(), 2);
+            assert_ok!(Timelock::announce(Origin::signed(2), set_balance(2, 10)));
+        });
+    }
+
+    #[test]
+    fn retire_sudo() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(Timelock::sudo_retired());
+            unhashed::put(&runtime_io::twox_128(SUDO_KEY), &1u64);
+            assert!(!Timelock::sudo_retired());
+            assert!(Timelock::retire_sudo(Origin::signed(1)).is_err());
+            assert_ok!(Timelock::retire_sudo(Origin::ROOT));
+            assert!(Timelock::sudo_retired());
+            assert_noop!(
+                Timelock::retire_sudo(Origin::ROOT),
+                "sudo is already retired"
+            );
+        });
+    }
+}
@@ -0,0 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod tips;
+
+#[cfg(feature = "std")]
+pub use crate::tips::GenesisConfig;
+
+pub use crate::tips::{
+    Call, Event, Module, OpenTip, RawEvent, Tippers, Trait, DEFAULT_TIP_COUNTDOWN,
+};
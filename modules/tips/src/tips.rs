@@ -0,0 +1,292 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use sr_primitives::traits::{Hash, Saturating};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    ensure,
+    traits::{Currency, Get},
+    StorageMap,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Blocks between enough tippers tipping and a tip closing on chains that don't set it at
+/// genesis, a day of 6 second blocks.
+pub const DEFAULT_TIP_COUNTDOWN: u32 = 14_400;
+
+/// Accounts allowed to open and tip tips, e.g. the members of the council.
+pub trait Tippers<AccountId> {
+    fn tippers() -> Vec<AccountId>;
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency tips are paid in.
+    type Currency: Currency<Self::AccountId>;
+    /// Account tips are paid from, e.g. the treasury pot.
+    type Pot: Get<Self::AccountId>;
+    type Tippers: Tippers<Self::AccountId>;
+}
+
+// a tip for `who`, paid the median of the tips of tippers from the pot once half the tippers
+// tipped and the countdown passed
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+pub struct OpenTip<AccountId, Balance, BlockNumber> {
+    pub reason: Vec<u8>,
+    pub who: AccountId,
+    pub closes: Option<BlockNumber>,
+    pub tips: Vec<(AccountId, Balance)>,
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // open a tip for `who`, e.g. for a contribution described by `reason`, and tip it
+        fn tip_new(origin, reason: Vec<u8>, who: T::AccountId, tip_value: BalanceOf<T>) -> Result {
+            let tipper = ensure_signed(origin)?;
+            Self::ensure_tipper(&tipper)?;
+            let hash = T::Hashing::hash_of(&(&reason, &who));
+            ensure!(!<Tips<T>>::exists(hash), "tip already exists");
+            let mut tip = OpenTip { reason, who: who.clone(), closes: None, tips: vec![] };
+            Self::insert_tip(hash, &mut tip, tipper, tip_value);
+            <Tips<T>>::insert(hash, tip);
+            Self::deposit_event(RawEvent::NewTip(hash, who));
+            Ok(())
+        }
+
+        // tip an open tip, replacing an earlier tip of the origin
+        fn tip(origin, hash: T::Hash, tip_value: BalanceOf<T>) -> Result {
+            let tipper = ensure_signed(origin)?;
+            Self::ensure_tipper(&tipper)?;
+            let mut tip = Self::tips(hash).ok_or("tip does not exist")?;
+            Self::insert_tip(hash, &mut tip, tipper, tip_value);
+            <Tips<T>>::insert(hash, tip);
+            Ok(())
+        }
+
+        // pay a tip the median of the tips of current tippers once its countdown passed
+        fn close_tip(origin, hash: T::Hash) -> Result {
+            ensure_signed(origin)?;
+            let tip = Self::tips(hash).ok_or("tip does not exist")?;
+            let closes = tip.closes.ok_or("tip has too few tippers")?;
+            ensure!(
+                <system::Module<T>>::block_number() >= closes,
+                "tip countdown has not passed"
+            );
+            let tippers = T::Tippers::tippers();
+            let mut values: Vec<_> = tip
+                .tips
+                .into_iter()
+                .filter(|(tipper, _)| tippers.contains(tipper))
+                .map(|(_, value)| value)
+                .collect();
+            values.sort();
+            let payout = values.get(values.len() / 2).copied().unwrap_or_default();
+            T::Currency::transfer(&T::Pot::get(), &tip.who, payout)?;
+            <Tips<T>>::remove(hash);
+            Self::deposit_event(RawEvent::TipClosed(hash, tip.who, payout));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Tips {
+        // open tips by the hash of their reason and recipient
+        Tips get(tips): map T::Hash => Option<OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+        // blocks between half the tippers tipping and a tip closing
+        TipCountdown get(tip_countdown) config(): T::BlockNumber = DEFAULT_TIP_COUNTDOWN.into();
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+        Hash = <T as system::Trait>::Hash,
+    {
+        // tip, recipient
+        NewTip(Hash, AccountId),
+        // tip
+        TipClosing(Hash),
+        // tip, recipient, payout
+        TipClosed(Hash, AccountId, Balance),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn ensure_tipper(who: &T::AccountId) -> Result {
+        ensure!(T::Tippers::tippers().contains(who), "only tippers may tip");
+        Ok(())
+    }
+
+    // record the tip of `tipper`, starting the countdown once half the tippers tipped
+    fn insert_tip(
+        hash: T::Hash,
+        tip: &mut OpenTip<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+        tipper: T::AccountId,
+        value: BalanceOf<T>,
+    ) {
+        match tip.tips.iter_mut().find(|(who, _)| *who == tipper) {
+            Some(existing) => existing.1 = value,
+            None => tip.tips.push((tipper, value)),
+        }
+        let threshold = (T::Tippers::tippers().len() + 1) / 2;
+        if tip.closes.is_none() && tip.tips.len() >= threshold {
+            let now = <system::Module<T>>::block_number();
+            tip.closes = Some(now.saturating_add(Self::tip_countdown()));
+            Self::deposit_event(RawEvent::TipClosing(hash));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    parameter_types! {
+        pub const Pot: u64 = 100;
+    }
+    pub struct OneTwoThree;
+    impl Tippers<u64> for OneTwoThree {
+        fn tippers() -> Vec<u64> {
+            vec![1, 2, 3]
+        }
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+        type Pot = Pot;
+        type Tippers = OneTwoThree;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Tips = Module<Test>;
+
+    // the pot holds 100, tippers are 1, 2 and 3 and tips close 5 blocks after two tipped
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = balances::GenesisConfig::<Test> {
+            balances: vec![(100, 100)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap();
+        GenesisConfig::<Test> { tip_countdown: 5 }
+            .assimilate_storage(&mut storage)
+            .unwrap();
+        storage.into()
+    }
+
+    fn hash() -> H256 {
+        BlakeTwo256::hash_of(&(&b"docs".to_vec(), &7u64))
+    }
+
+    #[test]
+    fn pays_the_median_tip() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_noop!(
+                Tips::tip_new(Origin::signed(7), b"docs".to_vec(), 7, 10),
+                "only tippers may tip"
+            );
+            assert_ok!(Tips::tip_new(Origin::signed(1), b"docs".to_vec(), 7, 10));
+            assert_noop!(
+                Tips::tip_new(Origin::signed(2), b"docs".to_vec(), 7, 10),
+                "tip already exists"
+            );
+            assert_noop!(
+                Tips::close_tip(Origin::signed(7), hash()),
+                "tip has too few tippers"
+            );
+            assert_ok!(Tips::tip(Origin::signed(2), hash(), 30));
+            assert_eq!(Tips::tips(hash()).unwrap().closes, Some(6));
+            assert_ok!(Tips::tip(Origin::signed(3), hash(), 20));
+            System::set_block_number(5);
+            assert_noop!(
+                Tips::close_tip(Origin::signed(7), hash()),
+                "tip countdown has not passed"
+            );
+            System::set_block_number(6);
+            assert_ok!(Tips::close_tip(Origin::signed(7), hash()));
+            assert_eq!(Balances::free_balance(7), 20);
+            assert_eq!(Balances::free_balance(100), 80);
+            assert_eq!(Tips::tips(hash()), None);
+        });
+    }
+
+    #[test]
+    fn retips_replace_earlier_tips() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Tips::tip_new(Origin::signed(1), b"docs".to_vec(), 7, 10));
+            assert_ok!(Tips::tip(Origin::signed(1), hash(), 50));
+            assert_eq!(Tips::tips(hash()).unwrap().tips, vec![(1, 50)]);
+            assert_eq!(Tips::tips(hash()).unwrap().closes, None);
+        });
+    }
+}
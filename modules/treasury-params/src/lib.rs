@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod treasury_params;
+
+#[cfg(feature = "std")]
+pub use crate::treasury_params::GenesisConfig;
+
+pub use crate::treasury_params::{
+    Module, ProposalBond, ProposalBondMinimum, SpendPeriod, Trait, DEFAULT_PROPOSAL_BOND_MINIMUM,
+    DEFAULT_SPEND_PERIOD,
+};
@@ -0,0 +1,153 @@
+use rstd::marker::PhantomData;
+use sr_primitives::{
+    traits::{SaturatedConversion, SimpleArithmetic},
+    Permill,
+};
+use support::{decl_module, decl_storage, traits::Get, Parameter};
+
+/// Smallest proposal bond of chains that don't set one at genesis, one token at 12 decimals.
+pub const DEFAULT_PROPOSAL_BOND_MINIMUM: u64 = 1_000_000_000_000;
+/// Blocks between treasury spends of chains that don't set it at genesis, a day of 6 second
+/// blocks.
+pub const DEFAULT_SPEND_PERIOD: u32 = 14_400;
+
+pub trait Trait: system::Trait {
+    /// Balance of the currency held by the treasury.
+    type Balance: Parameter + SimpleArithmetic + Default + Copy;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as TreasuryParams {
+        // Share of a spending proposal's value its proposer bonds.
+        Bond get(proposal_bond) config(): Permill = Permill::from_percent(5);
+        // Smallest bond of a spending proposal.
+        BondMinimum get(proposal_bond_minimum) config(): T::Balance =
+            T::Balance::saturated_from(DEFAULT_PROPOSAL_BOND_MINIMUM);
+        // Blocks between treasury spends.
+        Period get(spend_period) config(): T::BlockNumber = DEFAULT_SPEND_PERIOD.into();
+        // Share of transaction fees paid to the treasury, the rest is burnt.
+        FeeShare get(fees_to_treasury) config(): Permill = Permill::from_percent(20);
+    }
+}
+
+/// For use as the treasury module's `ProposalBond`.
+pub struct ProposalBond<T>(PhantomData<T>);
+
+impl<T: Trait> Get<Permill> for ProposalBond<T> {
+    fn get() -> Permill {
+        <Module<T>>::proposal_bond()
+    }
+}
+
+/// For use as the treasury module's `ProposalBondMinimum`.
+pub struct ProposalBondMinimum<T>(PhantomData<T>);
+
+impl<T: Trait> Get<T::Balance> for ProposalBondMinimum<T> {
+    fn get() -> T::Balance {
+        <Module<T>>::proposal_bond_minimum()
+    }
+}
+
+/// For use as the treasury module's `SpendPeriod`.
+pub struct SpendPeriod<T>(PhantomData<T>);
+
+impl<T: Trait> Get<T::BlockNumber> for SpendPeriod<T> {
+    fn get() -> T::BlockNumber {
+        <Module<T>>::spend_period()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl Trait for Test {
+        type Balance = u128;
+    }
+
+    fn ext(config: GenesisConfig<Test>) -> runtime_io::TestExternalities<Blake2Hasher> {
+        config.build_storage().unwrap().into()
+    }
+
+    #[test]
+    fn default_params() {
+        with_externalities(&mut ext(Default::default()), || {
+            assert_eq!(ProposalBond::<Test>::get(), Permill::from_percent(5));
+            assert_eq!(
+                ProposalBondMinimum::<Test>::get(),
+                u128::from(DEFAULT_PROPOSAL_BOND_MINIMUM)
+            );
+            assert_eq!(SpendPeriod::<Test>::get(), u64::from(DEFAULT_SPEND_PERIOD));
+            assert_eq!(
+                <Module<Test>>::fees_to_treasury(),
+                Permill::from_percent(20)
+            );
+        });
+    }
+
+    #[test]
+    fn params_from_genesis() {
+        let config = GenesisConfig::<Test> {
+            proposal_bond: Permill::from_percent(1),
+            spend_period: 10,
+            fees_to_treasury: Permill::from_percent(100),
+            ..Default::default()
+        };
+        with_externalities(&mut ext(config), || {
+            assert_eq!(ProposalBond::<Test>::get(), Permill::from_percent(1));
+            assert_eq!(
+                ProposalBondMinimum::<Test>::get(),
+                u128::from(DEFAULT_PROPOSAL_BOND_MINIMUM)
+            );
+            assert_eq!(SpendPeriod::<Test>::get(), 10);
+            assert_eq!(
+                <Module<Test>>::fees_to_treasury(),
+                Permill::from_percent(100)
+            );
+        });
+    }
+}
@@ -0,0 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod tx_pause;
+
+#[cfg(feature = "std")]
+pub use crate::tx_pause::GenesisConfig;
+
+pub use crate::tx_pause::{
+    Call, CallIndex, CheckPaused, DispatchedCalls, Event, IsUnpausable, Module, Trait, PAUSED,
+};
@@ -0,0 +1,381 @@
+use codec::{Decode, Encode};
+use rstd::{fmt, marker::PhantomData, prelude::*};
+use sr_primitives::traits::SignedExtension;
+use sr_primitives::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+};
+use sr_primitives::weights::DispatchInfo;
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, ensure, traits::EnsureOrigin,
+    StorageMap,
+};
+
+/// Code of the `InvalidTransaction::Custom` error rejecting a paused call.
+pub const PAUSED: u8 = 2;
+
+/// Index of a module in the call enum of the runtime, in `construct_runtime!` order counting only
+/// modules with calls, and index of a call in that module. These are the first two bytes of an
+/// encoded call.
+pub type CallIndex = (u8, u8);
+
+/// Recognizes the calls `CheckPaused` never rejects, e.g. those of the origin unpausing calls.
+pub trait IsUnpausable<Call> {
+    fn is_unpausable(call: &Call) -> bool;
+}
+
+/// Finds the calls a call dispatches, so that `CheckPaused` checks those too.
+pub trait DispatchedCalls<Call, AccountId> {
+    /// The calls `call`, signed by `who`, dispatches, looking through calls dispatching others,
+    /// e.g. batches or proxy calls. `call` itself if it dispatches no others.
+    fn dispatched_calls<'a>(who: &AccountId, call: &'a Call) -> Vec<&'a Call>;
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+    /// Pauses and unpauses calls.
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+    /// Calls let through while paused, so that a pause can always be lifted.
+    type Unpausable: IsUnpausable<<Self as system::Trait>::Call>;
+    /// Looks through calls dispatching others, to the calls they dispatch.
+    type Dispatched: DispatchedCalls<<Self as system::Trait>::Call, Self::AccountId>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // reject a call, e.g. transfers during an incident, until it is unpaused
+        fn pause(origin, call: CallIndex) -> Result {
+            T::PauseOrigin::ensure_origin(origin)?;
+            ensure!(!Self::is_paused(call), "call is already paused");
+            Paused::insert(call, true);
+            Self::deposit_event(Event::CallPaused(call));
+            Ok(())
+        }
+
+        // accept a paused call again
+        fn unpause(origin, call: CallIndex) -> Result {
+            T::PauseOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_paused(call), "call is not paused");
+            Paused::remove(call);
+            Self::deposit_event(Event::CallUnpaused(call));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as TxPause {
+        // calls rejected by `CheckPaused`
+        Paused get(is_paused): map CallIndex => bool;
+    }
+    add_extra_genesis {
+        config(paused): Vec<CallIndex>;
+        build(|config: &GenesisConfig| {
+            for call in &config.paused {
+                Paused::insert(call, true);
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event {
+        // call
+        CallPaused(CallIndex),
+        // call
+        CallUnpaused(CallIndex),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    /// Index of a call of the runtime, as the storage of paused calls keys it.
+    pub fn call_index<C: Encode>(call: &C) -> CallIndex {
+        call.using_encoded(|encoded| CallIndex::decode(&mut &encoded[..]).unwrap_or_default())
+    }
+}
+
+/// Rejects signed extrinsics making a paused call, or dispatching one, e.g. in a batch, before
+/// they get into the transaction pool.
+///
+/// Later substrate versions filter calls at dispatch with the `BaseCallFilter` of the system
+/// module. Substrate 870b976 has no call filter, so this signed extension stands in for one. Unlike
+/// a call filter it only sees signed extrinsics: unsigned extrinsics, and calls dispatched by root,
+/// e.g. through sudo, the scheduler or governance, still make paused calls.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckPaused<T: Trait + Send + Sync>(PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckPaused<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Trait + Send + Sync> Default for CheckPaused<T> {
+    fn default() -> Self {
+        CheckPaused(PhantomData)
+    }
+}
+
+impl<T: Trait + Send + Sync> fmt::Debug for CheckPaused<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CheckPaused")
+    }
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckPaused<T>
+where
+    <T as system::Trait>::Call: Encode,
+{
+    type AccountId = T::AccountId;
+    type Call = <T as system::Trait>::Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> rstd::result::Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        let paused = |call: &Self::Call| {
+            <Module<T>>::is_paused(<Module<T>>::call_index(call))
+                && !T::Unpausable::is_unpausable(call)
+        };
+        if paused(call)
+            || T::Dispatched::dispatched_calls(who, call)
+                .into_iter()
+                .any(paused)
+        {
+            return InvalidTransaction::Custom(PAUSED).into();
+        }
+        Ok(ValidTransaction::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::dispatch::Dispatchable;
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+            system::System,
+            utility::Utility,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = Call;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    pub struct SystemCalls;
+    impl IsUnpausable<Call> for SystemCalls {
+        fn is_unpausable(call: &Call) -> bool {
+            match call {
+                Call::System(_) => true,
+                _ => false,
+            }
+        }
+    }
+    impl utility::Trait for Test {
+        type Event = ();
+        type Call = Call;
+    }
+    pub struct Batches;
+    impl DispatchedCalls<Call, u64> for Batches {
+        fn dispatched_calls<'a>(who: &u64, call: &'a Call) -> Vec<&'a Call> {
+            match call {
+                Call::Utility(utility::Call::batch(calls)) => calls
+                    .iter()
+                    .flat_map(|call| Self::dispatched_calls(who, call))
+                    .collect(),
+                _ => vec![call],
+            }
+        }
+    }
+    impl Trait for Test {
+        type Event = ();
+        type PauseOrigin = system::EnsureRoot<u64>;
+        type Unpausable = SystemCalls;
+        type Dispatched = Batches;
+    }
+    type Balances = balances::Module<Test>;
+    type TxPause = Module<Test>;
+
+    // balances transfers are paused
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig {
+            paused: vec![(0, 0)],
+        }
+        .build_storage::<Test>()
+        .unwrap()
+        .into()
+    }
+
+    fn validate(call: &Call) -> TransactionValidity {
+        CheckPaused::<Test>::new().validate(&1, call, Default::default(), 0)
+    }
+
+    fn transfer() -> Call {
+        Call::Balances(balances::Call::transfer(3, 10))
+    }
+
+    fn remark() -> Call {
+        Call::System(system::Call::remark(vec![]))
+    }
+
+    #[test]
+    fn rejects_paused_calls() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(TxPause::call_index(&transfer()), (0, 0));
+            assert_eq!(
+                validate(&transfer()),
+                InvalidTransaction::Custom(PAUSED).into()
+            );
+            assert!(CheckPaused::<Test>::new()
+                .pre_dispatch(&1, &transfer(), Default::default(), 0)
+                .is_err());
+            assert!(validate(&Call::Balances(balances::Call::set_balance(3, 10, 0))).is_ok());
+
+            assert_ok!(TxPause::unpause(Origin::ROOT, (0, 0)));
+            assert!(validate(&transfer()).is_ok());
+        });
+    }
+
+    #[test]
+    fn rejects_batches_of_paused_calls() {
+        with_externalities(&mut new_test_ext(), || {
+            let batch = |calls| Call::Utility(utility::Call::batch(calls));
+            assert_eq!(
+                validate(&batch(vec![remark(), transfer()])),
+                InvalidTransaction::Custom(PAUSED).into()
+            );
+            assert_eq!(
+                validate(&batch(vec![batch(vec![transfer()])])),
+                InvalidTransaction::Custom(PAUSED).into()
+            );
+            assert!(validate(&batch(vec![remark()])).is_ok());
+            assert_ok!(TxPause::unpause(Origin::ROOT, (0, 0)));
+            assert!(validate(&batch(vec![transfer()])).is_ok());
+
+            assert_ok!(TxPause::pause(
+                Origin::ROOT,
+                TxPause::call_index(&batch(vec![]))
+            ));
+            assert_eq!(
+                validate(&batch(vec![remark()])),
+                InvalidTransaction::Custom(PAUSED).into()
+            );
+        });
+    }
+
+    #[test]
+    fn paused_calls_dispatch_from_root() {
+        with_externalities(&mut new_test_ext(), || {
+            // only signed extrinsics are checked, calls dispatched otherwise, e.g. by root, the
+            // scheduler or governance, or in unsigned extrinsics, still dispatch
+            let set_balance = Call::Balances(balances::Call::set_balance(3, 10, 0));
+            assert_ok!(TxPause::pause(
+                Origin::ROOT,
+                TxPause::call_index(&set_balance)
+            ));
+            assert_eq!(
+                validate(&set_balance),
+                InvalidTransaction::Custom(PAUSED).into()
+            );
+            assert_ok!(set_balance.dispatch(Origin::ROOT));
+            assert_eq!(Balances::free_balance(3), 10);
+        });
+    }
+
+    #[test]
+    fn unpausable_calls() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(TxPause::pause(Origin::ROOT, TxPause::call_index(&remark())));
+            assert!(validate(&remark()).is_ok());
+        });
+    }
+
+    #[test]
+    fn pause() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(TxPause::pause(Origin::signed(1), (0, 1)).is_err());
+            assert_ok!(TxPause::pause(Origin::ROOT, (0, 1)));
+            assert!(TxPause::is_paused((0, 1)));
+            assert_noop!(
+                TxPause::pause(Origin::ROOT, (0, 1)),
+                "call is already paused"
+            );
+            assert!(TxPause::unpause(Origin::signed(1), (0, 1)).is_err());
+            assert_ok!(TxPause::unpause(Origin::ROOT, (0, 1)));
+            assert_noop!(TxPause::unpause(Origin::ROOT, (0, 1)), "call is not paused");
+        });
+    }
+}
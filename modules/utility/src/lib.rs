@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod utility;
+
+pub use crate::utility::{derivative_account_id, Event, Module, RawEvent, Trait};
@@ -0,0 +1,204 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+use runtime_io::blake2_256;
+use support::{
+    decl_event, decl_module,
+    dispatch::{Dispatchable, Result},
+    Parameter,
+};
+use system::{self, ensure_signed, RawOrigin};
+
+pub trait Trait: system::Trait {
+    type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+    /// Calls that can be batched or made from a derivative account.
+    type Call: Parameter + Dispatchable<Origin = Self::Origin>;
+}
+
+/// Derivative account number `index` of `who`. This is the derivation of the substrate utility
+/// module's sub-accounts, so addresses computed off chain match.
+pub fn derivative_account_id<AccountId: Encode + Decode + Default>(
+    who: &AccountId,
+    index: u16,
+) -> AccountId {
+    let entropy = (b"modlpy/utilisuba", who, index).using_encoded(blake2_256);
+    AccountId::decode(&mut &entropy[..]).unwrap_or_default()
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // dispatch calls in order with the origin, stopping at the first that fails; calls
+        // before it are not reverted
+        fn batch(origin, calls: Vec<<T as Trait>::Call>) -> Result {
+            for (index, call) in calls.into_iter().enumerate() {
+                if let Err(e) = call.dispatch(origin.clone()) {
+                    let error = e.as_bytes().to_vec();
+                    Self::deposit_event(Event::BatchInterrupted(index as u32, error));
+                    return Ok(());
+                }
+            }
+            Self::deposit_event(Event::BatchCompleted);
+            Ok(())
+        }
+
+        // dispatch a call from derivative account number `index` of the origin
+        fn as_derivative(origin, index: u16, call: Box<<T as Trait>::Call>) -> Result {
+            let who = ensure_signed(origin)?;
+            let derivative = derivative_account_id(&who, index);
+            call.dispatch(RawOrigin::Signed(derivative).into())
+        }
+    }
+}
+
+decl_event!(
+    pub enum Event {
+        // index of the call that failed and its error
+        BatchInterrupted(u32, Vec<u8>),
+        BatchCompleted,
+    }
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{
+        assert_noop, assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types,
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Call = Call;
+    }
+    type Balances = balances::Module<Test>;
+    type Utility = Module<Test>;
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        balances::GenesisConfig::<Test> {
+            balances: vec![(1, 100), (derivative_account_id(&1, 0), 10)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn transfer(to: u64, value: u64) -> Call {
+        Call::Balances(balances::Call::transfer(to, value))
+    }
+
+    #[test]
+    fn batch() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Utility::batch(
+                Origin::signed(1),
+                vec![transfer(2, 10), transfer(3, 20), transfer(4, 30)]
+            ));
+            assert_eq!(Balances::free_balance(&1), 40);
+            assert_eq!(Balances::free_balance(&2), 10);
+            assert_eq!(Balances::free_balance(&3), 20);
+            assert_eq!(Balances::free_balance(&4), 30);
+        });
+    }
+
+    #[test]
+    fn batch_stops_at_failure() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(Utility::batch(
+                Origin::signed(1),
+                vec![transfer(2, 60), transfer(3, 60), transfer(4, 10)]
+            ));
+            assert_eq!(Balances::free_balance(&1), 40);
+            assert_eq!(Balances::free_balance(&2), 60);
+            assert_eq!(Balances::free_balance(&3), 0);
+            assert_eq!(Balances::free_balance(&4), 0);
+        });
+    }
+
+    #[test]
+    fn as_derivative() {
+        with_externalities(&mut new_test_ext(), || {
+            let derivative = derivative_account_id(&1, 0);
+            assert_ne!(derivative, derivative_account_id(&1, 1));
+            assert_ne!(derivative, derivative_account_id(&2, 0));
+            assert_ok!(Utility::as_derivative(
+                Origin::signed(1),
+                0,
+                Box::new(transfer(2, 10))
+            ));
+            assert_eq!(Balances::free_balance(&derivative), 0);
+            assert_eq!(Balances::free_balance(&2), 10);
+            assert_eq!(Balances::free_balance(&1), 100);
+            assert!(
+                Utility::as_derivative(Origin::signed(1), 1, Box::new(transfer(2, 10))).is_err()
+            );
+        });
+    }
+}
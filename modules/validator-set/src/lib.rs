@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod validator_set;
+
+#[cfg(feature = "std")]
+pub use crate::validator_set::GenesisConfig;
+
+pub use crate::validator_set::{Event, Module, RawEvent, Trait};
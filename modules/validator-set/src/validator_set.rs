@@ -0,0 +1,214 @@
+use rstd::prelude::*;
+use session::{OnSessionEnding, SessionIndex};
+use support::{decl_event, decl_module, decl_storage, dispatch::Result, ensure, StorageValue};
+use system::ensure_root;
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Source of elected validators, e.g. staking. Root's changes take precedence over the next
+    /// election.
+    type Elected: OnSessionEnding<Self::AccountId>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        /// Make `validator` an authority, starting two sessions from now. The validator should
+        /// register its session keys with `session.set_keys` before then.
+        ///
+        /// Only root can call this function
+        fn add_validator(origin, validator: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            let mut validators = Self::validators();
+            ensure!(!validators.contains(&validator), "account is already a validator");
+            validators.push(validator.clone());
+            <Validators<T>>::put(validators);
+            Changed::put(true);
+            Self::deposit_event(RawEvent::ValidatorAdded(validator));
+            Ok(())
+        }
+
+        /// Stop `validator` from being an authority, starting two sessions from now.
+        ///
+        /// Only root can call this function
+        fn remove_validator(origin, validator: T::AccountId) -> Result {
+            ensure_root(origin)?;
+            let mut validators = Self::validators();
+            let index = validators
+                .iter()
+                .position(|v| *v == validator)
+                .ok_or("account is not a validator")?;
+            ensure!(validators.len() > 1, "cannot remove the last validator");
+            validators.remove(index);
+            <Validators<T>>::put(validators);
+            Changed::put(true);
+            Self::deposit_event(RawEvent::ValidatorRemoved(validator));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as ValidatorSet {
+        // accounts of the authorities chosen by root, they take effect once queued by the session
+        // module
+        Validators get(validators) config(): Vec<T::AccountId>;
+        // whether validators changed since they were last handed to the session module
+        Changed: bool;
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        // a validator will join the authority set
+        ValidatorAdded(AccountId),
+        // a validator will leave the authority set
+        ValidatorRemoved(AccountId),
+    }
+);
+
+impl<T: Trait> OnSessionEnding<T::AccountId> for Module<T> {
+    fn on_session_ending(ending: SessionIndex, start: SessionIndex) -> Option<Vec<T::AccountId>> {
+        let elected = T::Elected::on_session_ending(ending, start);
+        if Changed::take() {
+            Some(Self::validators())
+        } else {
+            if let Some(elected) = &elected {
+                <Validators<T>>::put(elected);
+            }
+            elected
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Elected = Elect;
+    }
+
+    // elects account 3 at the end of session 5
+    pub struct Elect;
+    impl OnSessionEnding<u64> for Elect {
+        fn on_session_ending(ending: SessionIndex, _start: SessionIndex) -> Option<Vec<u64>> {
+            if ending == 5 {
+                Some(vec![3])
+            } else {
+                None
+            }
+        }
+    }
+    type ValidatorSet = Module<Test>;
+
+    fn new_test_ext(validators: Vec<u64>) -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> { validators }
+            .build_storage()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn genesis_validators() {
+        with_externalities(&mut new_test_ext(vec![1, 2]), || {
+            assert_eq!(ValidatorSet::validators(), vec![1, 2]);
+            assert_eq!(ValidatorSet::on_session_ending(0, 1), None);
+        });
+    }
+
+    #[test]
+    fn add_validator() {
+        with_externalities(&mut new_test_ext(vec![1]), || {
+            assert_ok!(ValidatorSet::add_validator(Origin::ROOT, 2));
+            assert_eq!(ValidatorSet::validators(), vec![1, 2]);
+            assert_eq!(ValidatorSet::on_session_ending(0, 1), Some(vec![1, 2]));
+            // handed over once
+            assert_eq!(ValidatorSet::on_session_ending(1, 2), None);
+            assert_noop!(
+                ValidatorSet::add_validator(Origin::ROOT, 2),
+                "account is already a validator"
+            );
+            assert_noop!(
+                ValidatorSet::add_validator(Origin::signed(1), 3),
+                "RequireRootOrigin"
+            );
+        });
+    }
+
+    #[test]
+    fn remove_validator() {
+        with_externalities(&mut new_test_ext(vec![1, 2]), || {
+            assert_noop!(
+                ValidatorSet::remove_validator(Origin::ROOT, 3),
+                "account is not a validator"
+            );
+            assert_noop!(
+                ValidatorSet::remove_validator(Origin::signed(1), 2),
+                "RequireRootOrigin"
+            );
+            assert_ok!(ValidatorSet::remove_validator(Origin::ROOT, 1));
+            assert_eq!(ValidatorSet::on_session_ending(0, 1), Some(vec![2]));
+            assert_noop!(
+                ValidatorSet::remove_validator(Origin::ROOT, 2),
+                "cannot remove the last validator"
+            );
+        });
+    }
+
+    #[test]
+    fn elected_validators() {
+        with_externalities(&mut new_test_ext(vec![1]), || {
+            assert_eq!(ValidatorSet::on_session_ending(5, 6), Some(vec![3]));
+            assert_eq!(ValidatorSet::validators(), vec![3]);
+            // root takes precedence over the election
+            assert_ok!(ValidatorSet::add_validator(Origin::ROOT, 4));
+            assert_eq!(ValidatorSet::on_session_ending(5, 6), Some(vec![3, 4]));
+        });
+    }
+}
@@ -0,0 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod vesting;
+
+#[cfg(feature = "std")]
+pub use crate::vesting::GenesisConfig;
+
+pub use crate::vesting::{Event, Module, RawEvent, Trait, VestingSchedule, VESTING_ID};
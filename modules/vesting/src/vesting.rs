@@ -0,0 +1,296 @@
+use codec::{Decode, Encode};
+use rstd::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sr_primitives::traits::{Bounded, SaturatedConversion, Saturating, Zero};
+use support::{
+    decl_event, decl_module, decl_storage,
+    dispatch::Result,
+    traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReasons},
+    StorageMap,
+};
+use system::{self, ensure_signed};
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Lock under which the unvested part of a balance is held.
+pub const VESTING_ID: LockIdentifier = *b"vesting ";
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Currency in which the vested balances are held.
+    type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+}
+
+// `amount` vests linearly over `duration` blocks from `start_block`, but none of it unlocks
+// before `cliff` blocks have passed
+#[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    pub start_block: BlockNumber,
+    pub cliff: BlockNumber,
+    pub duration: BlockNumber,
+    pub amount: Balance,
+}
+
+impl<Balance, BlockNumber> VestingSchedule<Balance, BlockNumber>
+where
+    Balance: Copy + Saturating + Zero + rstd::ops::Div<Output = Balance> + From<u32>,
+    BlockNumber: Copy + Saturating + Zero + PartialOrd + SaturatedConversion,
+{
+    /// Part of `amount` that is still locked at block `now`.
+    pub fn locked_at(&self, now: BlockNumber) -> Balance {
+        let elapsed = now.saturating_sub(self.start_block);
+        if now < self.start_block || elapsed < self.cliff {
+            return self.amount;
+        }
+        if elapsed >= self.duration {
+            return Zero::zero();
+        }
+        let elapsed: u32 = elapsed.saturated_into();
+        let duration: u32 = self.duration.saturated_into();
+        let vested = self.amount.saturating_mul(elapsed.into()) / duration.into();
+        self.amount.saturating_sub(vested)
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // unlock whatever has vested of the origin's balance
+        fn vest(origin) -> Result {
+            let who = ensure_signed(origin)?;
+            Self::update_lock(who)
+        }
+
+        // unlock whatever has vested of another account's balance
+        fn vest_other(origin, target: T::AccountId) -> Result {
+            ensure_signed(origin)?;
+            Self::update_lock(target)
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Vesting {
+        // vesting schedule of an account
+        Vesting get(vesting):
+            map T::AccountId => Option<VestingSchedule<BalanceOf<T>, T::BlockNumber>>;
+    }
+    add_extra_genesis {
+        // (account, schedule) of each account vesting from genesis, the account must hold at
+        // least the amount
+        config(vesting): Vec<(T::AccountId, VestingSchedule<BalanceOf<T>, T::BlockNumber>)>;
+        build(|config: &GenesisConfig<T>| {
+            for (who, schedule) in &config.vesting {
+                assert!(!<Vesting<T>>::exists(who), "account has more than one vesting schedule");
+                assert!(!schedule.duration.is_zero(), "vesting duration must be at least a block");
+                assert!(
+                    schedule.cliff <= schedule.duration,
+                    "vesting cliff must not exceed the duration"
+                );
+                assert!(
+                    T::Currency::free_balance(who) >= schedule.amount,
+                    "vesting account must hold the amount"
+                );
+                T::Currency::set_lock(
+                    VESTING_ID,
+                    who,
+                    schedule.locked_at(Zero::zero()),
+                    T::BlockNumber::max_value(),
+                    WithdrawReasons::all(),
+                );
+                <Vesting<T>>::insert(who, schedule);
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+        Balance = BalanceOf<T>,
+    {
+        // account, amount still locked
+        VestingUpdated(AccountId, Balance),
+        // the account's balance has fully vested
+        VestingCompleted(AccountId),
+    }
+);
+
+impl<T: Trait> Module<T> {
+    fn update_lock(who: T::AccountId) -> Result {
+        let schedule = Self::vesting(&who).ok_or("account is not vesting")?;
+        let locked = schedule.locked_at(<system::Module<T>>::block_number());
+        if locked.is_zero() {
+            T::Currency::remove_lock(VESTING_ID, &who);
+            <Vesting<T>>::remove(&who);
+            Self::deposit_event(RawEvent::VestingCompleted(who));
+        } else {
+            T::Currency::set_lock(
+                VESTING_ID,
+                &who,
+                locked,
+                T::BlockNumber::max_value(),
+                WithdrawReasons::all(),
+            );
+            Self::deposit_event(RawEvent::VestingUpdated(who, locked));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = ();
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    impl Trait for Test {
+        type Event = ();
+        type Currency = Balances;
+    }
+    type Balances = balances::Module<Test>;
+    type System = system::Module<Test>;
+    type Vesting = Module<Test>;
+
+    fn schedule(
+        start_block: u64,
+        cliff: u64,
+        duration: u64,
+        amount: u64,
+    ) -> VestingSchedule<u64, u64> {
+        VestingSchedule {
+            start_block,
+            cliff,
+            duration,
+            amount,
+        }
+    }
+
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        let mut storage = balances::GenesisConfig::<Test> {
+            balances: vec![(1, 100), (2, 100)],
+            vesting: vec![],
+        }
+        .build_storage()
+        .unwrap();
+        GenesisConfig::<Test> {
+            vesting: vec![(1, schedule(10, 20, 100, 80))],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+        storage.into()
+    }
+
+    #[test]
+    fn locked_at() {
+        let schedule = schedule(10, 20, 100, 1000);
+        assert_eq!(schedule.locked_at(0), 1000);
+        assert_eq!(schedule.locked_at(29), 1000);
+        assert_eq!(schedule.locked_at(30), 800);
+        assert_eq!(schedule.locked_at(60), 500);
+        assert_eq!(schedule.locked_at(109), 10);
+        assert_eq!(schedule.locked_at(110), 0);
+        assert_eq!(schedule.locked_at(1000), 0);
+        let no_cliff = VestingSchedule {
+            cliff: 0,
+            ..schedule
+        };
+        assert_eq!(no_cliff.locked_at(10), 1000);
+        assert_eq!(no_cliff.locked_at(11), 990);
+    }
+
+    #[test]
+    fn locked_from_genesis() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(Vesting::vesting(&1), Some(schedule(10, 20, 100, 80)));
+            assert_eq!(Vesting::vesting(&2), None);
+            assert_noop!(
+                <Balances as Currency<_>>::transfer(&1, &2, 21),
+                "account liquidity restrictions prevent withdrawal"
+            );
+            assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 20));
+        });
+    }
+
+    #[test]
+    fn vest_after_cliff() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(29);
+            assert_ok!(Vesting::vest(Origin::signed(1)));
+            assert!(<Balances as Currency<_>>::transfer(&1, &2, 21).is_err());
+            System::set_block_number(60);
+            assert_ok!(Vesting::vest_other(Origin::signed(2), 1));
+            assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 60));
+            assert!(<Balances as Currency<_>>::transfer(&1, &2, 1).is_err());
+            System::set_block_number(110);
+            assert_ok!(Vesting::vest(Origin::signed(1)));
+            assert_eq!(Vesting::vesting(&1), None);
+            assert_ok!(<Balances as Currency<_>>::transfer(&1, &2, 40));
+            assert_noop!(Vesting::vest(Origin::signed(1)), "account is not vesting");
+        });
+    }
+}
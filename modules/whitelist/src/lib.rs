@@ -0,0 +1,10 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod whitelist;
+
+#[cfg(feature = "std")]
+pub use crate::whitelist::GenesisConfig;
+
+pub use crate::whitelist::{
+    Call, CheckWhitelist, Event, Module, RawEvent, Trait, TransferSources, NOT_WHITELISTED,
+};
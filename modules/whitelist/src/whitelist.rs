@@ -0,0 +1,298 @@
+use codec::{Decode, Encode};
+use rstd::{fmt, marker::PhantomData, prelude::*};
+use sr_primitives::traits::SignedExtension;
+use sr_primitives::transaction_validity::{
+    InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+};
+use sr_primitives::weights::DispatchInfo;
+use support::{
+    decl_event, decl_module, decl_storage, dispatch::Result, traits::EnsureOrigin, StorageMap,
+    StorageValue,
+};
+
+/// Code of the `InvalidTransaction::Custom` error rejecting a transfer from an account off the
+/// whitelist.
+pub const NOT_WHITELISTED: u8 = 1;
+
+/// Finds the accounts whose funds a call moves, which must be whitelisted while the whitelist is
+/// enforced.
+pub trait TransferSources<Call, AccountId> {
+    /// The accounts whose funds `call`, signed by `who`, moves: `who` for a transfer, and for a
+    /// call dispatching others on behalf of other accounts, e.g. a proxy call, the accounts those
+    /// are dispatched from. Empty for calls moving no funds.
+    fn transfer_sources(who: &AccountId, call: &Call) -> Vec<AccountId>;
+}
+
+pub trait Trait: system::Trait {
+    type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// Adds and removes accounts and switches enforcement.
+    type WhitelistOrigin: EnsureOrigin<Self::Origin>;
+    /// Calls checked by `CheckWhitelist`, and whose funds they move.
+    type Transfers: TransferSources<<Self as system::Trait>::Call, Self::AccountId>;
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+
+        // whitelist an account, e.g. once its identity was attested off chain
+        fn add(origin, who: T::AccountId) -> Result {
+            T::WhitelistOrigin::ensure_origin(origin)?;
+            <Whitelisted<T>>::insert(&who, true);
+            Self::deposit_event(RawEvent::Added(who));
+            Ok(())
+        }
+
+        // stop whitelisting an account
+        fn remove(origin, who: T::AccountId) -> Result {
+            T::WhitelistOrigin::ensure_origin(origin)?;
+            <Whitelisted<T>>::remove(&who);
+            Self::deposit_event(RawEvent::Removed(who));
+            Ok(())
+        }
+
+        // start or stop rejecting transfers from accounts off the whitelist
+        fn set_enforced(origin, enforced: bool) -> Result {
+            T::WhitelistOrigin::ensure_origin(origin)?;
+            Enforced::put(enforced);
+            Self::deposit_event(RawEvent::EnforcedSet(enforced));
+            Ok(())
+        }
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Whitelist {
+        // accounts allowed to transfer while the whitelist is enforced
+        Whitelisted get(is_whitelisted): map T::AccountId => bool;
+        // whether `CheckWhitelist` rejects transfers from accounts off the whitelist
+        Enforced get(enforced) config(): bool;
+    }
+    add_extra_genesis {
+        config(whitelist): Vec<T::AccountId>;
+        build(|config: &GenesisConfig<T>| {
+            for who in &config.whitelist {
+                <Whitelisted<T>>::insert(who, true);
+            }
+        });
+    }
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as system::Trait>::AccountId,
+    {
+        // account
+        Added(AccountId),
+        // account
+        Removed(AccountId),
+        // enforced
+        EnforcedSet(bool),
+    }
+);
+
+/// Rejects transfers of funds of accounts off the whitelist while it is enforced, before they get
+/// into the transaction pool, whether signed by the account or dispatched on its behalf.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct CheckWhitelist<T: Trait + Send + Sync>(PhantomData<T>);
+
+impl<T: Trait + Send + Sync> CheckWhitelist<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Trait + Send + Sync> Default for CheckWhitelist<T> {
+    fn default() -> Self {
+        CheckWhitelist(PhantomData)
+    }
+}
+
+impl<T: Trait + Send + Sync> fmt::Debug for CheckWhitelist<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CheckWhitelist")
+    }
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for CheckWhitelist<T> {
+    type AccountId = T::AccountId;
+    type Call = <T as system::Trait>::Call;
+    type AdditionalSigned = ();
+    type Pre = ();
+
+    fn additional_signed(&self) -> rstd::result::Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: DispatchInfo,
+        _len: usize,
+    ) -> TransactionValidity {
+        if <Module<T>>::enforced()
+            && T::Transfers::transfer_sources(who, call)
+                .iter()
+                .any(|source| !<Module<T>>::is_whitelisted(source))
+        {
+            return InvalidTransaction::Custom(NOT_WHITELISTED).into();
+        }
+        Ok(ValidTransaction::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use primitives::{Blake2Hasher, H256};
+    use runtime_io::with_externalities;
+    use sr_primitives::weights::Weight;
+    use sr_primitives::Perbill;
+    use sr_primitives::{
+        testing::Header,
+        traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    };
+    use support::{assert_ok, impl_outer_dispatch, impl_outer_origin, parameter_types};
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    impl_outer_dispatch! {
+        pub enum Call for Test where origin: Origin {
+            balances::Balances,
+            system::System,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub const MaximumBlockWeight: Weight = 1024;
+        pub const MaximumBlockLength: u32 = 2 * 1024;
+        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    }
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Call = Call;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type WeightMultiplierUpdate = ();
+        type Event = ();
+        type BlockHashCount = BlockHashCount;
+        type MaximumBlockWeight = MaximumBlockWeight;
+        type MaximumBlockLength = MaximumBlockLength;
+        type AvailableBlockRatio = AvailableBlockRatio;
+        type Version = ();
+    }
+    parameter_types! {
+        pub const ExistentialDeposit: u64 = 0;
+        pub const TransferFee: u64 = 0;
+        pub const CreationFee: u64 = 0;
+        pub const TransactionBaseFee: u64 = 0;
+        pub const TransactionByteFee: u64 = 0;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type Event = ();
+        type TransactionPayment = ();
+        type TransferPayment = ();
+        type DustRemoval = ();
+        type ExistentialDeposit = ExistentialDeposit;
+        type TransferFee = TransferFee;
+        type CreationFee = CreationFee;
+        type TransactionBaseFee = TransactionBaseFee;
+        type TransactionByteFee = TransactionByteFee;
+        type WeightToFee = ConvertInto;
+    }
+    // balances calls move the signer's funds, and `force_transfer` those of its source
+    pub struct BalanceCalls;
+    impl TransferSources<Call, u64> for BalanceCalls {
+        fn transfer_sources(who: &u64, call: &Call) -> Vec<u64> {
+            match call {
+                Call::Balances(balances::Call::force_transfer(source, _, _)) => vec![*source],
+                Call::Balances(_) => vec![*who],
+                _ => vec![],
+            }
+        }
+    }
+    impl Trait for Test {
+        type Event = ();
+        type WhitelistOrigin = system::EnsureRoot<u64>;
+        type Transfers = BalanceCalls;
+    }
+    type System = system::Module<Test>;
+    type Whitelist = Module<Test>;
+
+    // 1 is whitelisted and the whitelist is enforced
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        GenesisConfig::<Test> {
+            enforced: true,
+            whitelist: vec![1],
+        }
+        .build_storage()
+        .unwrap()
+        .into()
+    }
+
+    fn validate(who: u64, call: &Call) -> TransactionValidity {
+        CheckWhitelist::<Test>::new().validate(&who, call, Default::default(), 0)
+    }
+
+    fn transfer() -> Call {
+        Call::Balances(balances::Call::transfer(3, 10))
+    }
+
+    #[test]
+    fn rejects_transfers_off_the_whitelist() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(validate(1, &transfer()).is_ok());
+            assert_eq!(
+                validate(2, &transfer()),
+                InvalidTransaction::Custom(NOT_WHITELISTED).into()
+            );
+            assert!(validate(2, &Call::System(system::Call::remark(vec![]))).is_ok());
+            assert!(CheckWhitelist::<Test>::new()
+                .pre_dispatch(&2, &transfer(), Default::default(), 0)
+                .is_err());
+
+            assert_ok!(Whitelist::add(Origin::ROOT, 2));
+            assert!(validate(2, &transfer()).is_ok());
+            assert_ok!(Whitelist::remove(Origin::ROOT, 1));
+            assert!(validate(1, &transfer()).is_err());
+        });
+    }
+
+    #[test]
+    fn checks_every_source() {
+        with_externalities(&mut new_test_ext(), || {
+            let force_transfer =
+                |source| Call::Balances(balances::Call::force_transfer(source, 3, 10));
+            assert!(validate(2, &force_transfer(1)).is_ok());
+            assert_eq!(
+                validate(1, &force_transfer(2)),
+                InvalidTransaction::Custom(NOT_WHITELISTED).into()
+            );
+        });
+    }
+
+    #[test]
+    fn set_enforced() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(Whitelist::set_enforced(Origin::signed(1), false).is_err());
+            assert_ok!(Whitelist::set_enforced(Origin::ROOT, false));
+            assert!(validate(2, &transfer()).is_ok());
+        });
+    }
+}
@@ -10,39 +10,343 @@ mod runtime;
 // The following exports only exists when compiling with feature = "std".
 #[cfg(feature = "std")]
 pub use runtime::{
-    native_version, BabeConfig, BalancesConfig, Erc20Config, GenesisConfig, GrandpaConfig,
-    IndicesConfig, SudoConfig, SystemConfig, WASM_BINARY,
+    native_version, AssetsConfig, AuthorityDiscoveryConfig, BabeConfig, BalancesConfig,
+    BlockRewardsConfig, BlockTimeConfig, BountiesConfig, ContractsConfig, CouncilConfig,
+    DemocracyConfig, DexConfig, Erc20Config, EthClaimsConfig, FaucetConfig, GenesisConfig,
+    GrandpaConfig, ImOnlineConfig, ImOnlineId, IndicesConfig, MerkleClaimsConfig, NftConfig,
+    OracleConfig, OracleId, RegistrarsConfig, SchedulerConfig, SessionConfig, StakerStatus,
+    StakingConfig, SudoConfig, SystemConfig, TimelockConfig, TipsConfig, TreasuryParamsConfig,
+    TxPauseConfig, ValidatorSetConfig, VestingConfig, WhitelistConfig, WASM_BINARY,
 };
 
 // The following is only made public only when compiling with feature = "std".
 #[cfg(feature = "std")]
-pub use runtime::{api, opaque, AccountId, RuntimeApi};
+pub use runtime::{
+    api, opaque, AccountId, Balance, BlockNumber, Call, Event, Executive, Index, Origin, Runtime,
+    RuntimeApi, SignedExtra, UncheckedExtrinsic, EXISTENTIAL_DEPOSIT, VERSION,
+};
 
 #[cfg(test)]
 mod tests {
-    use super::GenesisConfig;
+    use super::runtime::{
+        Babe, Balances, Call, MaxExtrinsicsPerBlock, Origin, ProxyType, Runtime, SignedExtra,
+        Treasury, TxPause, UncheckedExtrinsic, Whitelist, CENTS, DOLLARS,
+    };
+    use super::{AccountId, BalancesConfig, GenesisConfig};
 
-    use primitives::Blake2Hasher;
+    use codec::Encode;
+    use primitives::{sr25519, Blake2Hasher};
+    use proxy::ProxyFilter;
     use runtime_io::with_externalities;
+    use sr_primitives::generic::Era;
+    use sr_primitives::traits::SignedExtension;
+    use sr_primitives::transaction_validity::InvalidTransaction;
+    use sr_primitives::weights::GetDispatchInfo;
     use sr_primitives::BuildStorage as _;
 
+    fn alice() -> AccountId {
+        sr25519::Public::from_raw([1; 32])
+    }
+
     // This function basically just builds a genesis storage key/value store according to
     // our desired mockup.
     fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
         GenesisConfig {
             babe: None,
-            balances: None,
+            balances: Some(BalancesConfig {
+                balances: vec![(alice(), DOLLARS)],
+                vesting: vec![],
+            }),
             grandpa: None,
             indices: None,
             sudo: None,
             system: None,
             erc20: None,
+            block_time: None,
+            session: None,
+            validator_set: None,
+            staking: None,
+            im_online: None,
+            authority_discovery: None,
+            assets: None,
+            contracts: None,
+            democracy: None,
+            collective_Instance1: None,
+            treasury_params: None,
+            registrars: None,
+            scheduler: None,
+            vesting: None,
+            faucet: None,
+            merkle_claims: None,
+            eth_claims: None,
+            dex: None,
+            nft: None,
+            whitelist: None,
+            tx_pause: None,
+            oracle: None,
+            block_rewards: None,
+            tips: None,
+            bounties: None,
+            timelock: None,
         }
         .build_storage()
         .unwrap()
         .into()
     }
 
+    #[test]
+    fn default_slot_duration() {
+        // chains that don't set a block time at genesis, see OVERVIEW.md
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(Babe::slot_duration(), 6000);
+        });
+    }
+
+    #[test]
+    fn transfer_fee_band() {
+        with_externalities(&mut new_test_ext(), || {
+            let bob = sr25519::Public::from_raw([2; 32]);
+            let call = Call::Balances(balances::Call::transfer(bob.into(), DOLLARS / 2));
+            let extra: SignedExtra = (
+                system::CheckVersion::new(),
+                system::CheckGenesis::new(),
+                system::CheckEra::from(Era::Immortal),
+                system::CheckNonce::from(0),
+                system::CheckWeight::new(),
+                balances::TakeFees::from(0),
+                whitelist::CheckWhitelist::new(),
+                tx_pause::CheckPaused::new(),
+                rate_limit::CheckRateLimit::new(),
+            );
+            let signature = sr25519::Signature::from_raw([0; 64]).into();
+            let len =
+                UncheckedExtrinsic::new_signed(call.clone(), alice().into(), signature, extra)
+                    .encode()
+                    .len();
+            let treasury = Balances::free_balance(Treasury::account_id());
+            let issuance = Balances::total_issuance();
+            assert!(balances::TakeFees::<Runtime>::from(0)
+                .pre_dispatch(&alice(), &call, call.get_dispatch_info(), len)
+                .is_ok());
+
+            let fee = DOLLARS - Balances::free_balance(alice());
+            assert!(2 * CENTS <= fee && fee <= 5 * CENTS, "transfer fee {}", fee);
+            // the treasury gets 20% by default and the rest is burnt
+            let to_treasury = Balances::free_balance(Treasury::account_id()) - treasury;
+            assert_eq!(to_treasury, fee / 5);
+            assert_eq!(Balances::total_issuance(), issuance - (fee - to_treasury));
+        });
+    }
+
+    #[test]
+    fn non_transfer_proxies() {
+        let bob = sr25519::Public::from_raw([2; 32]);
+        let allowed = [
+            Call::System(system::Call::remark(vec![])),
+            Call::Staking(staking::Call::chill()),
+            Call::Identity(identity::Call::clear_identity()),
+            Call::Vesting(vesting::Call::vest()),
+        ];
+        let denied = [
+            Call::Balances(balances::Call::transfer(bob.into(), DOLLARS)),
+            Call::Escrow(escrow::Call::release(0)),
+            Call::Streams(streams::Call::claim(0)),
+            Call::AtomicSwap(atomic_swap::Call::claim_swap(vec![])),
+            Call::Dex(dex::Call::remove_liquidity(0, 1, DOLLARS)),
+            Call::Orderbook(orderbook::Call::cancel_order(0)),
+            Call::Nft(nft::Call::transfer(0, 0, bob)),
+            Call::Utility(utility::Call::batch(vec![])),
+        ];
+        for call in &allowed {
+            assert!(ProxyType::NonTransfer.filter(call), "{:?}", call);
+        }
+        for call in &denied {
+            assert!(!ProxyType::NonTransfer.filter(call), "{:?}", call);
+        }
+    }
+
+    #[test]
+    fn whitelist_gates_transfers() {
+        with_externalities(&mut new_test_ext(), || {
+            let bob = sr25519::Public::from_raw([2; 32]);
+            let transfer = Call::Balances(balances::Call::transfer(bob.into(), DOLLARS / 2));
+            let remark = Call::System(system::Call::remark(vec![]));
+            let valid = |call: &Call| {
+                whitelist::CheckWhitelist::<Runtime>::new()
+                    .validate(&alice(), call, call.get_dispatch_info(), 0)
+                    .is_ok()
+            };
+            assert!(valid(&transfer));
+            assert!(Whitelist::set_enforced(Origin::ROOT, true).is_ok());
+            assert!(!valid(&transfer));
+            assert!(valid(&remark));
+            assert!(Whitelist::add(Origin::ROOT, alice()).is_ok());
+            assert!(valid(&transfer));
+        });
+    }
+
+    #[test]
+    fn whitelist_gates_escrows_streams_and_swaps() {
+        with_externalities(&mut new_test_ext(), || {
+            let calls = [
+                Call::Escrow(escrow::Call::release(0)),
+                Call::Streams(streams::Call::claim(0)),
+                Call::AtomicSwap(atomic_swap::Call::claim_swap(vec![])),
+            ];
+            let valid = |call: &Call| {
+                whitelist::CheckWhitelist::<Runtime>::new()
+                    .validate(&alice(), call, call.get_dispatch_info(), 0)
+                    .is_ok()
+            };
+            assert!(Whitelist::set_enforced(Origin::ROOT, true).is_ok());
+            for call in &calls {
+                assert!(!valid(call), "{:?}", call);
+            }
+            assert!(Whitelist::add(Origin::ROOT, alice()).is_ok());
+            for call in &calls {
+                assert!(valid(call), "{:?}", call);
+            }
+        });
+    }
+
+    #[test]
+    fn whitelist_gates_the_accounts_wrapped_transfers_move() {
+        with_externalities(&mut new_test_ext(), || {
+            let bob = sr25519::Public::from_raw([2; 32]);
+            let transfer = Call::Balances(balances::Call::transfer(alice().into(), DOLLARS / 2));
+            let valid = |call: &Call| {
+                whitelist::CheckWhitelist::<Runtime>::new()
+                    .validate(&alice(), call, call.get_dispatch_info(), 0)
+                    .is_ok()
+            };
+            let multisig = multisig::multi_account_id(&[alice(), bob], 2);
+            let derivative = utility::derivative_account_id(&alice(), 0);
+            // each dispatches the transfer from bob's, the multisig's or the derivative's account,
+            // none of which alice's whitelisting covers
+            let bypasses = [
+                (
+                    bob,
+                    Call::Proxy(proxy::Call::proxy(bob, None, Box::new(transfer.clone()))),
+                ),
+                (
+                    bob,
+                    Call::Recovery(recovery::Call::as_recovered(
+                        bob,
+                        Box::new(transfer.clone()),
+                    )),
+                ),
+                (
+                    multisig,
+                    Call::Multisig(multisig::Call::as_multi(
+                        2,
+                        vec![bob],
+                        Box::new(transfer.clone()),
+                    )),
+                ),
+                (
+                    derivative,
+                    Call::Utility(utility::Call::as_derivative(0, Box::new(transfer.clone()))),
+                ),
+                (
+                    bob,
+                    Call::Utility(utility::Call::batch(vec![Call::Proxy(proxy::Call::proxy(
+                        bob,
+                        None,
+                        Box::new(transfer.clone()),
+                    ))])),
+                ),
+            ];
+            assert!(Whitelist::set_enforced(Origin::ROOT, true).is_ok());
+            assert!(Whitelist::add(Origin::ROOT, alice()).is_ok());
+            assert!(valid(&transfer));
+            assert!(valid(&Call::Utility(utility::Call::batch(vec![
+                transfer.clone()
+            ]))));
+            for (source, call) in &bypasses {
+                assert!(!valid(call), "{:?}", call);
+                assert!(Whitelist::add(Origin::ROOT, *source).is_ok());
+                assert!(valid(call), "{:?}", call);
+                assert!(Whitelist::remove(Origin::ROOT, *source).is_ok());
+            }
+        });
+    }
+
+    #[test]
+    fn pause_transfers() {
+        with_externalities(&mut new_test_ext(), || {
+            let bob = sr25519::Public::from_raw([2; 32]);
+            let transfer = Call::Balances(balances::Call::transfer(bob.into(), DOLLARS / 2));
+            let unpause = Call::TxPause(tx_pause::Call::unpause((5, 0)));
+            let valid = |call: &Call| {
+                tx_pause::CheckPaused::<Runtime>::new()
+                    .validate(&alice(), call, call.get_dispatch_info(), 0)
+                    .is_ok()
+            };
+            // the index OVERVIEW.md gives for transfers
+            assert_eq!(TxPause::call_index(&transfer), (5, 0));
+            assert!(TxPause::pause(Origin::ROOT, (5, 0)).is_ok());
+            assert!(!valid(&transfer));
+            assert!(TxPause::pause(Origin::ROOT, TxPause::call_index(&unpause)).is_ok());
+            assert!(valid(&unpause));
+        });
+    }
+
+    #[test]
+    fn pause_rejects_wrapped_transfers() {
+        with_externalities(&mut new_test_ext(), || {
+            let bob = sr25519::Public::from_raw([2; 32]);
+            let transfer = Call::Balances(balances::Call::transfer(bob.into(), DOLLARS / 2));
+            let wrappers = [
+                Call::Utility(utility::Call::batch(vec![transfer.clone()])),
+                Call::Proxy(proxy::Call::proxy(bob, None, Box::new(transfer.clone()))),
+                Call::Multisig(multisig::Call::as_multi(
+                    2,
+                    vec![bob],
+                    Box::new(transfer.clone()),
+                )),
+                Call::Recovery(recovery::Call::as_recovered(
+                    bob,
+                    Box::new(transfer.clone()),
+                )),
+            ];
+            let valid = |call: &Call| {
+                tx_pause::CheckPaused::<Runtime>::new()
+                    .validate(&alice(), call, call.get_dispatch_info(), 0)
+                    .is_ok()
+            };
+            for call in &wrappers {
+                assert!(valid(call), "{:?}", call);
+            }
+            assert!(TxPause::pause(Origin::ROOT, (5, 0)).is_ok());
+            for call in &wrappers {
+                assert!(!valid(call), "{:?}", call);
+            }
+            let remark = Call::System(system::Call::remark(vec![]));
+            assert!(valid(&Call::Utility(utility::Call::batch(vec![remark]))));
+            // sudo is never rejected, and the calls it dispatches as root are not checked
+            assert!(valid(&Call::Sudo(sudo::Call::sudo(Box::new(transfer)))));
+        });
+    }
+
+    #[test]
+    fn rate_limit_per_account() {
+        with_externalities(&mut new_test_ext(), || {
+            let call = Call::System(system::Call::remark(vec![]));
+            let info = call.get_dispatch_info();
+            let limit = rate_limit::CheckRateLimit::<Runtime>::new;
+            for _ in 0..MaxExtrinsicsPerBlock::get() {
+                assert!(limit().validate(&alice(), &call, info, 0).is_ok());
+                assert!(limit().pre_dispatch(&alice(), &call, info, 0).is_ok());
+            }
+            assert_eq!(
+                limit().validate(&alice(), &call, info, 0),
+                InvalidTransaction::ExhaustsResources.into()
+            );
+            let bob = sr25519::Public::from_raw([2; 32]);
+            assert!(limit().validate(&bob, &call, info, 0).is_ok());
+        });
+    }
+
     #[test]
     #[ignore] // not yet implemented
     fn it_works_for_default_value() {
@@ -15,28 +15,38 @@ use client::{
     block_builder::api::{self as block_builder_api, CheckInherentsResult, InherentData},
     impl_runtime_apis, runtime_api as client_api,
 };
+use codec::{Decode, Encode};
+use contracts_rpc_runtime_api::ContractExecResult;
 use grandpa::fg_primitives::{self, ScheduledChange};
 use grandpa::{AuthorityId as GrandpaId, AuthorityWeight as GrandpaWeight};
+use primitives::u32_trait::{_1, _2, _3, _4, _5};
 use primitives::{crypto::key_types, OpaqueMetadata};
 use rstd::prelude::*;
 
+use sr_primitives::curve::PiecewiseLinear;
 use sr_primitives::traits::{
-    BlakeTwo256, Block as BlockT, ConvertInto, DigestFor, NumberFor, StaticLookup, Verify,
+    BlakeTwo256, Block as BlockT, Convert, ConvertInto, DigestFor, NumberFor, StaticLookup, Verify,
 };
 use sr_primitives::weights::Weight;
-use sr_primitives::Perbill;
 use sr_primitives::{
     create_runtime_str, generic, impl_opaque_keys, transaction_validity::TransactionValidity,
     AnySignature, ApplyResult,
 };
+use sr_primitives::{Perbill, Permill};
+use support::traits::{Currency, EnsureOrigin, Get, Imbalance, OnUnbalanced};
 use support::{construct_runtime, parameter_types};
+use system::offchain::TransactionSubmitter;
+
+pub use im_online::sr25519::AuthorityId as ImOnlineId;
+pub use oracle::sr25519::FeederId as OracleId;
+pub use staking::StakerStatus;
 use version::RuntimeVersion;
 
 #[cfg(feature = "std")]
 use version::NativeVersion;
 
 /// An index to a block.
-type BlockNumber = u32;
+pub type BlockNumber = u32;
 
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
 type Signature = AnySignature;
@@ -46,10 +56,10 @@ type Signature = AnySignature;
 pub type AccountId = <Signature as Verify>::Signer;
 
 /// Balance of an account.
-type Balance = u128;
+pub type Balance = u128;
 
 /// Index of a transaction in the chain.
-type Index = u32;
+pub type Index = u32;
 
 /// A hash of some data used by the chain.
 type Hash = primitives::H256;
@@ -70,7 +80,7 @@ pub mod opaque {
     /// Opaque block identifier type.
     pub type BlockId = generic::BlockId<Block>;
 
-    pub type SessionHandlers = (Grandpa, Babe);
+    pub type SessionHandlers = (Grandpa, Babe, ImOnline, AuthorityDiscovery);
 
     impl_opaque_keys! {
         pub struct SessionKeys {
@@ -78,6 +88,8 @@ pub mod opaque {
             pub grandpa: GrandpaId,
             #[id(key_types::BABE)]
             pub babe: BabeId,
+            #[id(key_types::IM_ONLINE)]
+            pub im_online: ImOnlineId,
         }
     }
 }
@@ -106,12 +118,16 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 /// `SLOT_DURATION` instead (like the timestamp module for calculating the
 /// minimum period).
 /// <https://research.web3.foundation/en/latest/polkadot/BABE/Babe/#6-practical-results>
-const MILLISECS_PER_BLOCK: u64 = 6000;
+/// Chains may set their own block time at genesis, see the block-time module. Durations expressed
+/// in blocks assume the default.
+const MILLISECS_PER_BLOCK: u64 = block_time::DEFAULT_MILLISECS_PER_BLOCK;
 
 const EPOCH_DURATION_IN_BLOCKS: u32 = 10 * MINUTES;
 
 // These time units are defined in number of blocks.
 const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
+const HOURS: BlockNumber = MINUTES * 60;
+const DAYS: BlockNumber = HOURS * 24;
 
 // 1 in 4 blocks (on average, not counting collisions) will be primary babe blocks.
 const PRIMARY_PROBABILITY: (u64, u64) = (1, 4);
@@ -179,12 +195,11 @@ impl system::Trait for Runtime {
 
 parameter_types! {
     pub const EpochDuration: u64 = EPOCH_DURATION_IN_BLOCKS as u64;
-    pub const ExpectedBlockTime: u64 = MILLISECS_PER_BLOCK;
 }
 
 impl babe::Trait for Runtime {
     type EpochDuration = EpochDuration;
-    type ExpectedBlockTime = ExpectedBlockTime;
+    type ExpectedBlockTime = block_time::ExpectedBlockTime<Runtime>;
 }
 
 impl grandpa::Trait for Runtime {
@@ -203,36 +218,43 @@ impl indices::Trait for Runtime {
     type Event = Event;
 }
 
-parameter_types! {
-    pub const MinimumPeriod: u64 = 5000;
-}
-
 impl timestamp::Trait for Runtime {
     /// A timestamp: milliseconds since the unix epoch.
     type Moment = u64;
     type OnTimestampSet = Babe;
-    type MinimumPeriod = MinimumPeriod;
+    type MinimumPeriod = block_time::MinimumPeriod<Runtime>;
 }
 
+/// Minimum balance an account must hold to exist.
+pub const EXISTENTIAL_DEPOSIT: Balance = 500;
+
+// Amounts of the native token, which has 12 decimals.
+pub const DOLLARS: Balance = 1_000_000_000_000;
+pub const CENTS: Balance = DOLLARS / 100;
+pub const MILLICENTS: Balance = CENTS / 1_000;
+
 parameter_types! {
-    pub const ExistentialDeposit: u128 = 500;
+    pub const ExistentialDeposit: u128 = EXISTENTIAL_DEPOSIT;
     pub const TransferFee: u128 = 0;
     pub const CreationFee: u128 = 0;
-    pub const TransactionBaseFee: u128 = 0;
-    pub const TransactionByteFee: u128 = 1;
+    // a transfer is about 150 bytes, so pays some 2.5 cents before its weight
+    pub const TransactionBaseFee: Balance = CENTS;
+    pub const TransactionByteFee: Balance = 10 * MILLICENTS;
+    // linear in weight, a block full of normal transactions pays 0.75 cents for its weight
+    pub const WeightFeeCoefficients: &'static [Balance] = &[0, 10_000];
 }
 
 impl balances::Trait for Runtime {
     /// The type for recording an account's balance.
     type Balance = Balance;
     /// What to do if an account's free balance gets zeroed.
-    type OnFreeBalanceZero = ();
+    type OnFreeBalanceZero = ((Staking, Contracts), Session);
     /// What to do if a new account is created.
     type OnNewAccount = Indices;
     /// The ubiquitous event type.
     type Event = Event;
 
-    type TransactionPayment = ();
+    type TransactionPayment = DealWithFees;
     type DustRemoval = ();
     type TransferPayment = ();
     type ExistentialDeposit = ExistentialDeposit;
@@ -240,7 +262,32 @@ impl balances::Trait for Runtime {
     type CreationFee = CreationFee;
     type TransactionBaseFee = TransactionBaseFee;
     type TransactionByteFee = TransactionByteFee;
-    type WeightToFee = ConvertInto;
+    type WeightToFee = fees::WeightToFee<Runtime>;
+}
+
+impl fees::Trait for Runtime {
+    type Balance = Balance;
+    type WeightFeeCoefficients = WeightFeeCoefficients;
+}
+
+impl faucet::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+}
+
+impl merkle_claims::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+}
+
+parameter_types! {
+    pub const EthClaimsPrefix: &'static [u8] = b"Pay WARMUP to the warmup account:";
+}
+
+impl eth_claims::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type Prefix = EthClaimsPrefix;
 }
 
 impl sudo::Trait for Runtime {
@@ -251,12 +298,648 @@ impl sudo::Trait for Runtime {
 impl erc20::Trait for Runtime {
     type Event = Event;
     type TokenBalance = u128;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
 }
 
 impl voting::Trait for Runtime {
     type Event = Event;
 }
 
+impl block_time::Trait for Runtime {}
+
+parameter_types! {
+    pub const ContractTransferFee: Balance = CENTS;
+    pub const ContractCreationFee: Balance = CENTS;
+    pub const ContractTransactionBaseFee: Balance = CENTS;
+    pub const ContractTransactionByteFee: Balance = 10 * MILLICENTS;
+    pub const ContractFee: Balance = CENTS;
+    pub const TombstoneDeposit: Balance = DOLLARS;
+    pub const RentByteFee: Balance = DOLLARS;
+    pub const RentDepositOffset: Balance = 1000 * DOLLARS;
+    pub const SurchargeReward: Balance = 150 * DOLLARS;
+}
+
+impl contracts::Trait for Runtime {
+    type Currency = Balances;
+    type Time = Timestamp;
+    type Call = Call;
+    type Event = Event;
+    type DetermineContractAddress = contracts::SimpleAddressDeterminator<Runtime>;
+    type ComputeDispatchFee = contracts::DefaultDispatchFeeComputor<Runtime>;
+    type TrieIdGenerator = contracts::TrieIdFromParentCounter<Runtime>;
+    type GasPayment = ();
+    type RentPayment = ();
+    type SignedClaimHandicap = contracts::DefaultSignedClaimHandicap;
+    type TombstoneDeposit = TombstoneDeposit;
+    type StorageSizeOffset = contracts::DefaultStorageSizeOffset;
+    type RentByteFee = RentByteFee;
+    type RentDepositOffset = RentDepositOffset;
+    type SurchargeReward = SurchargeReward;
+    type TransferFee = ContractTransferFee;
+    type CreationFee = ContractCreationFee;
+    type TransactionBaseFee = ContractTransactionBaseFee;
+    type TransactionByteFee = ContractTransactionByteFee;
+    type ContractFee = ContractFee;
+    type CallBaseFee = contracts::DefaultCallBaseFee;
+    type InstantiateBaseFee = contracts::DefaultInstantiateBaseFee;
+    type MaxDepth = contracts::DefaultMaxDepth;
+    type MaxValueSize = contracts::DefaultMaxValueSize;
+    type BlockGasLimit = contracts::DefaultBlockGasLimit;
+}
+
+// Governance periods are short, so that proposals can be rehearsed on testnets.
+parameter_types! {
+    pub const LaunchPeriod: BlockNumber = DAYS;
+    pub const VotingPeriod: BlockNumber = DAYS;
+    pub const EmergencyVotingPeriod: BlockNumber = HOURS;
+    pub const MinimumDeposit: Balance = 100 * DOLLARS;
+    pub const EnactmentPeriod: BlockNumber = DAYS;
+    pub const CooloffPeriod: BlockNumber = DAYS;
+}
+
+impl democracy::Trait for Runtime {
+    type Proposal = Call;
+    type Event = Event;
+    type Currency = Balances;
+    type EnactmentPeriod = EnactmentPeriod;
+    type LaunchPeriod = LaunchPeriod;
+    type VotingPeriod = VotingPeriod;
+    type EmergencyVotingPeriod = EmergencyVotingPeriod;
+    type MinimumDeposit = MinimumDeposit;
+    /// A straight majority of the council can decide what their next motion is.
+    type ExternalOrigin = collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+    /// A super-majority can have the next scheduled referendum be a straight majority-carries vote.
+    type ExternalMajorityOrigin =
+        collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
+    /// A unanimous council can have the next scheduled referendum be a straight default-carries
+    /// (NTB) vote.
+    type ExternalDefaultOrigin =
+        collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+    /// Two thirds of the council can have an ExternalMajority/ExternalDefault vote tabled
+    /// immediately and with a shorter voting/enactment period.
+    type FastTrackOrigin =
+        collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+    /// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
+    type CancellationOrigin =
+        collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+    /// Any single council member may veto a coming council proposal, however they can only do it
+    /// once and it lasts only for the cooloff period.
+    type VetoOrigin = collective::EnsureMember<AccountId, CouncilCollective>;
+    type CooloffPeriod = CooloffPeriod;
+}
+
+type CouncilCollective = collective::Instance1;
+impl collective::Trait<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+}
+
+type MoreThanHalfCouncil =
+    collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+
+/// Root, e.g. the sudo key, or a majority of the council.
+pub struct EnsureRootOrHalfCouncil;
+
+impl EnsureOrigin<Origin> for EnsureRootOrHalfCouncil {
+    type Success = ();
+
+    fn try_origin(o: Origin) -> rstd::result::Result<(), Origin> {
+        system::EnsureRoot::<AccountId>::try_origin(o).or_else(MoreThanHalfCouncil::try_origin)
+    }
+}
+
+impl treasury_params::Trait for Runtime {
+    type Balance = Balance;
+}
+
+parameter_types! {
+    pub const Burn: Permill = Permill::from_percent(50);
+}
+
+impl treasury::Trait for Runtime {
+    type Currency = Balances;
+    type ApproveOrigin = collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilCollective>;
+    type RejectOrigin = collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+    type Event = Event;
+    type MintedForSpending = ();
+    type ProposalRejection = Treasury;
+    /// Set at genesis, see the treasury-params module.
+    type ProposalBond = treasury_params::ProposalBond<Runtime>;
+    type ProposalBondMinimum = treasury_params::ProposalBondMinimum<Runtime>;
+    type SpendPeriod = treasury_params::SpendPeriod<Runtime>;
+    type Burn = Burn;
+}
+
+/// The treasury pot, which pays tips and funds bounties.
+pub struct TreasuryPot;
+
+impl Get<AccountId> for TreasuryPot {
+    fn get() -> AccountId {
+        Treasury::account_id()
+    }
+}
+
+/// Council members open and tip tips.
+pub struct CouncilTippers;
+
+impl tips::Tippers<AccountId> for CouncilTippers {
+    fn tippers() -> Vec<AccountId> {
+        Council::members()
+    }
+}
+
+impl tips::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type Pot = TreasuryPot;
+    type Tippers = CouncilTippers;
+}
+
+impl bounties::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type Pot = TreasuryPot;
+    type ApproveOrigin = collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilCollective>;
+    type RejectOrigin = collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>;
+}
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Pays the genesis configured share of transaction fees to the treasury and burns the rest. A
+/// share of 100% turns the burn off.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+    fn on_unbalanced(fees: NegativeImbalance) {
+        let to_treasury = TreasuryParams::fees_to_treasury() * fees.peek();
+        let (to_treasury, _burnt) = fees.split(to_treasury);
+        Treasury::on_unbalanced(to_treasury);
+    }
+}
+
+parameter_types! {
+    pub const BasicDeposit: Balance = 10 * DOLLARS;
+    pub const FieldDeposit: Balance = 250 * CENTS;
+    pub const SubAccountDeposit: Balance = 2 * DOLLARS;
+    pub const MaximumSubAccounts: u32 = 100;
+}
+
+impl identity::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type Slashed = Treasury;
+    type BasicDeposit = BasicDeposit;
+    type FieldDeposit = FieldDeposit;
+    type SubAccountDeposit = SubAccountDeposit;
+    type MaximumSubAccounts = MaximumSubAccounts;
+    /// Genesis registrars are added as root, see the registrars module.
+    type RegistrarOrigin = system::EnsureRoot<AccountId>;
+    type ForceOrigin = system::EnsureRoot<AccountId>;
+}
+
+impl registrars::Trait for Runtime {}
+
+parameter_types! {
+    pub const MultisigDeposit: Balance = DOLLARS;
+    pub const MaxSignatories: u16 = 100;
+}
+
+impl multisig::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type MultisigDeposit = MultisigDeposit;
+    type MaxSignatories = MaxSignatories;
+}
+
+/// What a proxy may do for the account that added it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug)]
+pub enum ProxyType {
+    Any,
+    /// Staking, session, governance, identity and vesting calls, which don't move funds, e.g. to
+    /// stake from a cold stash.
+    NonTransfer,
+    /// Democracy, council, treasury, tips and bounties calls.
+    Governance,
+}
+
+impl Default for ProxyType {
+    fn default() -> Self {
+        ProxyType::Any
+    }
+}
+
+impl proxy::ProxyFilter<Call> for ProxyType {
+    fn filter(&self, call: &Call) -> bool {
+        match self {
+            ProxyType::Any => true,
+            // calls of modules added later are denied until they are listed here; proxy,
+            // multisig, utility and recovery calls are left out as they could wrap any other call
+            ProxyType::NonTransfer => match call {
+                Call::System(_)
+                | Call::Session(_)
+                | Call::Staking(_)
+                | Call::ImOnline(_)
+                | Call::Democracy(_)
+                | Call::Council(_)
+                | Call::Treasury(_)
+                | Call::Identity(_)
+                | Call::Vesting(_)
+                | Call::Tips(_)
+                | Call::Bounties(_) => true,
+                _ => false,
+            },
+            ProxyType::Governance => match call {
+                Call::Democracy(_)
+                | Call::Council(_)
+                | Call::Treasury(_)
+                | Call::Tips(_)
+                | Call::Bounties(_) => true,
+                _ => false,
+            },
+        }
+    }
+}
+
+parameter_types! {
+    // a proxy is an account id, a proxy type and a block number
+    pub const ProxyDepositBase: Balance = DOLLARS;
+    pub const ProxyDepositFactor: Balance = 40 * CENTS;
+    pub const MaxProxies: u16 = 32;
+    // an announcement is an account id, a call hash and a block number
+    pub const AnnouncementDepositBase: Balance = DOLLARS;
+    pub const AnnouncementDepositFactor: Balance = 70 * CENTS;
+    pub const MaxPending: u32 = 32;
+}
+
+impl proxy::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = MaxProxies;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
+    type MaxPending = MaxPending;
+}
+
+impl utility::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+}
+
+parameter_types! {
+    pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl scheduler::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    /// Sudo and passed referenda dispatch as root, so both can schedule calls.
+    type ScheduleOrigin = system::EnsureRoot<AccountId>;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+}
+
+impl timelock::Trait for Runtime {
+    type Event = Event;
+    /// The council cancels calls the admin announced in an emergency.
+    type CancelOrigin = MoreThanHalfCouncil;
+}
+
+impl vesting::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+}
+
+parameter_types! {
+    // a recovery config is a delay, a threshold and a deposit, with an account id per friend
+    pub const ConfigDepositBase: Balance = 5 * DOLLARS;
+    pub const FriendDepositFactor: Balance = 50 * CENTS;
+    pub const MaxFriends: u16 = 9;
+    // repaid to the lost account if it closes the recovery, so a thief pays for trying
+    pub const RecoveryDeposit: Balance = 5 * DOLLARS;
+}
+
+impl recovery::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ConfigDepositBase = ConfigDepositBase;
+    type FriendDepositFactor = FriendDepositFactor;
+    type MaxFriends = MaxFriends;
+    type RecoveryDeposit = RecoveryDeposit;
+}
+
+impl assets::Trait for Runtime {
+    type Event = Event;
+    type Balance = Balance;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
+}
+
+parameter_types! {
+    pub const SwapFee: Permill = Permill::from_parts(3_000);
+}
+
+// genesis pools are created from genesis asset balances, so Dex is declared after Assets in
+// construct_runtime!
+impl dex::Trait for Runtime {
+    type Event = Event;
+    type SwapFee = SwapFee;
+}
+
+parameter_types! {
+    pub const MaxOrdersPerMarket: u32 = 1_000;
+}
+
+impl orderbook::Trait for Runtime {
+    type Event = Event;
+    type MaxOrdersPerMarket = MaxOrdersPerMarket;
+}
+
+parameter_types! {
+    pub const ProofLimit: u32 = 1024;
+}
+
+impl atomic_swap::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type ProofLimit = ProofLimit;
+}
+
+parameter_types! {
+    pub const EscrowDeposit: Balance = DOLLARS;
+}
+
+impl escrow::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type EscrowDeposit = EscrowDeposit;
+}
+
+impl streams::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+}
+
+parameter_types! {
+    pub const MetadataLimit: u32 = 1024;
+}
+
+impl nft::Trait for Runtime {
+    type Event = Event;
+    type MetadataLimit = MetadataLimit;
+}
+
+/// The calls `call`, signed by `who`, dispatches, each with the account it is dispatched from.
+/// Utility, proxy, multisig and recovery calls are looked through, down to the calls they
+/// dispatch; other calls dispatch themselves, from `who`.
+pub fn dispatched_calls<'a>(who: &AccountId, call: &'a Call) -> Vec<(AccountId, &'a Call)> {
+    let mut calls = Vec::new();
+    push_dispatched_calls(who.clone(), call, &mut calls);
+    calls
+}
+
+fn push_dispatched_calls<'a>(
+    who: AccountId,
+    call: &'a Call,
+    calls: &mut Vec<(AccountId, &'a Call)>,
+) {
+    match call {
+        Call::Utility(utility::Call::batch(inner)) => {
+            for call in inner {
+                push_dispatched_calls(who.clone(), call, calls);
+            }
+        }
+        Call::Utility(utility::Call::as_derivative(index, inner)) => {
+            push_dispatched_calls(utility::derivative_account_id(&who, *index), inner, calls)
+        }
+        Call::Proxy(proxy::Call::proxy(real, _, inner))
+        | Call::Proxy(proxy::Call::proxy_announced(_, real, _, inner))
+        | Call::Recovery(recovery::Call::as_recovered(real, inner)) => {
+            push_dispatched_calls(real.clone(), inner, calls)
+        }
+        Call::Multisig(multisig::Call::as_multi(threshold, others, inner)) => {
+            let mut signatories = others.clone();
+            signatories.push(who);
+            signatories.sort();
+            let multisig = multisig::multi_account_id(&signatories, *threshold);
+            push_dispatched_calls(multisig, inner, calls)
+        }
+        _ => calls.push((who, call)),
+    }
+}
+
+/// Calls which move native tokens, rejected while the whitelist is enforced if they move those of
+/// an account off the whitelist.
+pub struct NativeTransfers;
+
+impl whitelist::TransferSources<Call, AccountId> for NativeTransfers {
+    fn transfer_sources(who: &AccountId, call: &Call) -> Vec<AccountId> {
+        dispatched_calls(who, call)
+            .into_iter()
+            .filter(|(_, call)| match call {
+                // contract calls carry a value, and escrows, streams and swaps lock and pay out
+                // native funds; modules added later that move native funds belong here too
+                Call::Balances(_)
+                | Call::Contracts(_)
+                | Call::Escrow(_)
+                | Call::Streams(_)
+                | Call::AtomicSwap(_) => true,
+                _ => false,
+            })
+            .map(|(source, _)| source)
+            .collect()
+    }
+}
+
+impl whitelist::Trait for Runtime {
+    type Event = Event;
+    type WhitelistOrigin = system::EnsureRoot<AccountId>;
+    type Transfers = NativeTransfers;
+}
+
+/// Calls accepted while paused, so that the council and the admin keys can always unpause calls.
+pub struct GovernanceCalls;
+
+impl tx_pause::IsUnpausable<Call> for GovernanceCalls {
+    fn is_unpausable(call: &Call) -> bool {
+        match call {
+            Call::Sudo(_) | Call::Timelock(_) | Call::Council(_) | Call::TxPause(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Looks through utility, proxy, multisig and recovery calls for `CheckPaused`, as
+/// `dispatched_calls` does.
+pub struct InnerCalls;
+
+impl tx_pause::DispatchedCalls<Call, AccountId> for InnerCalls {
+    fn dispatched_calls<'a>(who: &AccountId, call: &'a Call) -> Vec<&'a Call> {
+        dispatched_calls(who, call)
+            .into_iter()
+            .map(|(_, call)| call)
+            .collect()
+    }
+}
+
+impl tx_pause::Trait for Runtime {
+    type Event = Event;
+    type PauseOrigin = EnsureRootOrHalfCouncil;
+    type Unpausable = GovernanceCalls;
+    type Dispatched = InnerCalls;
+}
+
+parameter_types! {
+    pub const FeedInterval: BlockNumber = MINUTES;
+    pub const PriceValidity: BlockNumber = 10 * MINUTES;
+}
+
+impl oracle::Trait for Runtime {
+    type Event = Event;
+    type FeederId = OracleId;
+    type Call = Call;
+    /// Prices are sent as unsigned transactions by the offchain worker.
+    type SubmitTransaction = TransactionSubmitter<OracleId, Runtime, UncheckedExtrinsic>;
+    type OracleOrigin = EnsureRootOrHalfCouncil;
+    type FeedInterval = FeedInterval;
+    type ValidityPeriod = PriceValidity;
+}
+
+parameter_types! {
+    pub const MaxExtrinsicsPerBlock: u32 = 16;
+}
+
+impl rate_limit::Trait for Runtime {
+    type MaxExtrinsicsPerBlock = MaxExtrinsicsPerBlock;
+}
+
+parameter_types! {
+    pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl authorship::Trait for Runtime {
+    /// Block authors are the accounts of the babe authorities claiming the slots.
+    type FindAuthor = session::FindAccountFromAuthorIndex<Self, Babe>;
+    type UncleGenerations = UncleGenerations;
+    type FilterUncle = ();
+    /// Authoring blocks earns era reward points.
+    type EventHandler = Staking;
+}
+
+impl block_rewards::Trait for Runtime {
+    type Event = Event;
+    type Currency = Balances;
+    type RewardOrigin = EnsureRootOrHalfCouncil;
+}
+
+parameter_types! {
+    pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl session::Trait for Runtime {
+    /// Staking elects validators at the end of each era, the sudo key may override them.
+    type OnSessionEnding = ValidatorSet;
+    type SessionHandler = opaque::SessionHandlers;
+    /// Sessions end with babe epochs.
+    type ShouldEndSession = Babe;
+    type Event = Event;
+    type Keys = opaque::SessionKeys;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = ConvertInto;
+    type SelectInitialValidators = ();
+    type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+}
+
+impl session::historical::Trait for Runtime {
+    type FullIdentification = staking::Exposure<AccountId, Balance>;
+    type FullIdentificationOf = staking::ExposureOf<Runtime>;
+}
+
+impl im_online::Trait for Runtime {
+    type AuthorityId = ImOnlineId;
+    type Call = Call;
+    type Event = Event;
+    /// Heartbeats are sent as unsigned transactions by the offchain worker.
+    type SubmitTransaction = TransactionSubmitter<ImOnlineId, Runtime, UncheckedExtrinsic>;
+    type ReportUnresponsiveness = Offences;
+}
+
+/// Validators sign their addresses in the DHT with their im-online key.
+impl authority_discovery::Trait for Runtime {
+    type AuthorityId = ImOnlineId;
+}
+
+impl offences::Trait for Runtime {
+    type Event = Event;
+    type IdentificationTuple = session::historical::IdentificationTuple<Self>;
+    /// Offenders are slashed and chilled by staking.
+    type OnOffenceHandler = Staking;
+}
+
+impl validator_set::Trait for Runtime {
+    type Event = Event;
+    type Elected = Staking;
+}
+
+srml_staking_reward_curve::build! {
+    const REWARD_CURVE: PiecewiseLinear<'static> = curve!(
+        min_inflation: 0_025_000,
+        max_inflation: 0_100_000,
+        ideal_stake: 0_500_000,
+        falloff: 0_050_000,
+        max_piece_count: 40,
+        test_precision: 0_005_000,
+    );
+}
+
+parameter_types! {
+    /// An era lasts an hour.
+    pub const SessionsPerEra: sr_staking_primitives::SessionIndex = 6;
+    /// Unbonded funds stay locked for a day.
+    pub const BondingDuration: staking::EraIndex = 24;
+    pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
+}
+
+/// Converts balances to the u64 vote weights used by phragmen, and back, scaling by the total
+/// issuance so that large balances don't saturate.
+pub struct CurrencyToVoteHandler;
+
+impl CurrencyToVoteHandler {
+    fn factor() -> Balance {
+        (Balances::total_issuance() / u64::max_value() as Balance).max(1)
+    }
+}
+
+impl Convert<Balance, u64> for CurrencyToVoteHandler {
+    fn convert(x: Balance) -> u64 {
+        (x / Self::factor()) as u64
+    }
+}
+
+impl Convert<u128, Balance> for CurrencyToVoteHandler {
+    fn convert(x: u128) -> Balance {
+        x * Self::factor()
+    }
+}
+
+impl staking::Trait for Runtime {
+    type Currency = Balances;
+    type Time = Timestamp;
+    type CurrencyToVote = CurrencyToVoteHandler;
+    type RewardRemainder = Treasury;
+    type Event = Event;
+    type Slash = Treasury;
+    type Reward = ();
+    type SessionsPerEra = SessionsPerEra;
+    type BondingDuration = BondingDuration;
+    type SessionInterface = Self;
+    type RewardCurve = RewardCurve;
+}
+
 construct_runtime!(
     pub enum Runtime where
         Block = Block,
@@ -272,6 +955,46 @@ construct_runtime!(
         Sudo: sudo,
         Erc20: erc20::{Module, Call, Storage, Config<T>, Event<T>},
         Voting: voting::{Module, Call, Storage, Event<T>},
+        BlockTime: block_time::{Module, Storage, Config},
+        Session: session::{Module, Call, Storage, Event, Config<T>},
+        ValidatorSet: validator_set::{Module, Call, Storage, Event<T>, Config<T>},
+        Staking: staking::{default, Config<T>},
+        ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
+        Offences: offences::{Module, Call, Storage, Event},
+        AuthorityDiscovery: authority_discovery::{Module, Call, Config<T>},
+        Assets: assets::{Module, Call, Storage, Event<T>, Config<T>},
+        Contracts: contracts::{Module, Call, Storage, Event<T>, Config<T>},
+        Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
+        Council: collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+        Treasury: treasury::{Module, Call, Storage, Event<T>},
+        TreasuryParams: treasury_params::{Module, Storage, Config<T>},
+        Identity: identity::{Module, Call, Storage, Event<T>},
+        Registrars: registrars::{Module, Config<T>},
+        Multisig: multisig::{Module, Call, Storage, Event<T>},
+        Proxy: proxy::{Module, Call, Storage, Event<T>},
+        Utility: utility::{Module, Call, Event},
+        Scheduler: scheduler::{Module, Call, Storage, Config<T>, Event<T>},
+        Vesting: vesting::{Module, Call, Storage, Event<T>, Config<T>},
+        Recovery: recovery::{Module, Call, Storage, Event<T>},
+        Fees: fees::{Module},
+        Faucet: faucet::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+        MerkleClaims: merkle_claims::{Module, Call, Storage, Event<T>, Config, ValidateUnsigned},
+        EthClaims: eth_claims::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+        Dex: dex::{Module, Call, Storage, Event<T>, Config<T>},
+        Orderbook: orderbook::{Module, Call, Storage, Event<T>},
+        AtomicSwap: atomic_swap::{Module, Call, Storage, Event<T>},
+        Escrow: escrow::{Module, Call, Storage, Event<T>},
+        Streams: streams::{Module, Call, Storage, Event<T>},
+        Nft: nft::{Module, Call, Storage, Event<T>, Config<T>},
+        Whitelist: whitelist::{Module, Call, Storage, Event<T>, Config<T>},
+        TxPause: tx_pause::{Module, Call, Storage, Event, Config},
+        Oracle: oracle::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+        RateLimit: rate_limit::{Module, Storage},
+        Authorship: authorship::{Module, Call, Storage},
+        BlockRewards: block_rewards::{Module, Call, Storage, Event<T>, Config<T>},
+        Tips: tips::{Module, Call, Storage, Event<T>, Config<T>},
+        Bounties: bounties::{Module, Call, Storage, Event<T>, Config<T>},
+        Timelock: timelock::{Module, Call, Storage, Event<T>, Config<T>},
     }
 );
 
@@ -282,18 +1005,21 @@ type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
 type Block = generic::Block<Header, UncheckedExtrinsic>;
 /// The SignedExtension to the basic transaction logic.
-type SignedExtra = (
+pub type SignedExtra = (
     system::CheckVersion<Runtime>,
     system::CheckGenesis<Runtime>,
     system::CheckEra<Runtime>,
     system::CheckNonce<Runtime>,
     system::CheckWeight<Runtime>,
     balances::TakeFees<Runtime>,
+    whitelist::CheckWhitelist<Runtime>,
+    tx_pause::CheckPaused<Runtime>,
+    rate_limit::CheckRateLimit<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
-type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-type Executive =
+pub type Executive =
     executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Runtime, AllModules>;
 
 impl_runtime_apis! {
@@ -351,6 +1077,54 @@ impl_runtime_apis! {
         }
     }
 
+    impl authority_discovery_primitives::AuthorityDiscoveryApi<Block, ImOnlineId> for Runtime {
+        fn authorities() -> Vec<ImOnlineId> {
+            AuthorityDiscovery::authorities()
+        }
+
+        fn sign(payload: Vec<u8>, authority_id: ImOnlineId) -> Option<Vec<u8>> {
+            AuthorityDiscovery::sign(payload, authority_id)
+        }
+
+        fn verify(payload: Vec<u8>, signature: Vec<u8>, public_key: ImOnlineId) -> bool {
+            AuthorityDiscovery::verify(payload, signature, public_key)
+        }
+    }
+
+    impl contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance> for Runtime {
+        fn call(
+            origin: AccountId,
+            dest: AccountId,
+            value: Balance,
+            gas_limit: u64,
+            input_data: Vec<u8>,
+        ) -> ContractExecResult {
+            match Contracts::bare_call(origin, dest, value, gas_limit, input_data) {
+                Ok(v) => ContractExecResult::Success {
+                    status: v.status,
+                    data: v.data,
+                },
+                Err(_) => ContractExecResult::Error,
+            }
+        }
+    }
+
+    impl orderbook_runtime_api::OrderbookApi<Block, AccountId, Balance> for Runtime {
+        fn open_orders(base: u32, quote: u32) -> Vec<(u64, orderbook::Order<AccountId, Balance>)> {
+            Orderbook::open_orders(base, quote)
+        }
+    }
+
+    impl assets_runtime_api::TokenApi<Block, AccountId, Balance> for Runtime {
+        fn balance(asset_id: u32, who: AccountId) -> Balance {
+            Assets::balance_of((asset_id, who))
+        }
+
+        fn total_issuance(asset_id: u32) -> Option<Balance> {
+            Assets::asset_details(asset_id).map(|details| details.total_supply)
+        }
+    }
+
     impl fg_primitives::GrandpaApi<Block> for Runtime {
         fn grandpa_pending_change(digest: &DigestFor<Block>)
             -> Option<ScheduledChange<NumberFor<Block>>>
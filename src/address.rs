@@ -0,0 +1,168 @@
+//! Converting an account between the forms operators meet it in: ss58 addresses of any network,
+//! hex public keys, and the accounts derived from it by the multisig and utility pallets.
+
+use crate::chain_spec::multisig_account;
+use crate::ss58::{self, GENERIC_PREFIX, SS58_PREFIX};
+use node_template_runtime::AccountId;
+use std::fmt;
+
+/// Parse an ss58 address of any prefix, returning the prefix too, or a 0x prefixed hex public key.
+pub fn parse_account(s: &str) -> Result<(Option<u8>, AccountId), String> {
+    if s.starts_with("0x") {
+        let raw = hex::decode(&s[2..]).map_err(|e| format!("invalid hex {:?}: {}", s, e))?;
+        if raw.len() != 32 {
+            return Err(format!("{} is not a 32 byte public key", s));
+        }
+        let mut public = [0u8; 32];
+        public.copy_from_slice(&raw);
+        Ok((None, AccountId::from_raw(public)))
+    } else {
+        let (prefix, account) = ss58::decode(s)?;
+        Ok((Some(prefix), account))
+    }
+}
+
+/// `parse_account` without the prefix, for accounts given on the command line.
+pub fn account_of(s: &str) -> Result<AccountId, String> {
+    parse_account(s).map(|(_, account)| account)
+}
+
+/// The forms of an account, with the accounts derived from it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AddressInfo {
+    /// Prefix of the address the account was given as, if it was.
+    pub input_prefix: Option<u8>,
+    pub account: AccountId,
+    /// Prefixes to show the address with, besides the project and generic ones.
+    pub prefixes: Vec<u8>,
+    /// Derivative accounts of the utility pallet, by index.
+    pub derivatives: Vec<(u16, AccountId)>,
+    /// Multisig account of the account with other signatories: the threshold, the number of
+    /// signatories, and the account.
+    pub multisig: Option<(u16, usize, AccountId)>,
+}
+
+impl AddressInfo {
+    pub fn new(address: &str) -> Result<Self, String> {
+        let (input_prefix, account) = parse_account(address)?;
+        Ok(AddressInfo {
+            input_prefix,
+            account,
+            prefixes: input_prefix.into_iter().collect(),
+            derivatives: Vec::new(),
+            multisig: None,
+        })
+    }
+
+    /// Also show the address with `prefix`.
+    pub fn prefix(mut self, prefix: u8) -> Self {
+        self.prefixes.push(prefix);
+        self
+    }
+
+    /// Also derive the first `count` derivative accounts.
+    pub fn derivatives(mut self, count: u16) -> Self {
+        self.derivatives = (0..count)
+            .map(|index| (index, utility::derivative_account_id(&self.account, index)))
+            .collect();
+        self
+    }
+
+    /// Also derive the account `threshold` of this account and `others` control together.
+    pub fn multisig(mut self, others: &[AccountId], threshold: u16) -> Result<Self, String> {
+        let mut signatories = others.to_vec();
+        signatories.push(self.account.clone());
+        let count = signatories.len();
+        let multisig = multisig_account(&signatories, threshold)?;
+        self.multisig = Some((threshold, count, multisig));
+        Ok(self)
+    }
+}
+
+impl fmt::Display for AddressInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(prefix) = self.input_prefix {
+            writeln!(f, "input prefix: {}", prefix)?;
+        }
+        writeln!(f, "public key: 0x{}", hex::encode(&self.account))?;
+        let mut prefixes = vec![SS58_PREFIX, GENERIC_PREFIX];
+        for prefix in &self.prefixes {
+            if !prefixes.contains(prefix) {
+                prefixes.push(*prefix);
+            }
+        }
+        for prefix in prefixes {
+            writeln!(
+                f,
+                "ss58 ({}): {}",
+                prefix,
+                ss58::encode(prefix, &self.account)
+            )?;
+        }
+        for (index, derivative) in &self.derivatives {
+            writeln!(f, "derivative {}: {}", index, ss58::to_ss58(derivative))?;
+        }
+        if let Some((threshold, count, multisig)) = &self.multisig {
+            writeln!(
+                f,
+                "multisig {} of {}: {}",
+                threshold,
+                count,
+                ss58::to_ss58(multisig)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+
+    #[test]
+    fn t_parse_account() {
+        let alice = Keyring::Alice.account();
+        let hex_key = format!("0x{}", hex::encode(&alice));
+        assert_eq!(parse_account(&hex_key), Ok((None, alice.clone())));
+        assert_eq!(
+            parse_account(&alice.to_string()),
+            Ok((Some(42), alice.clone()))
+        );
+        let kusama = ss58::encode(2, &alice);
+        assert_eq!(parse_account(&kusama), Ok((Some(2), alice.clone())));
+        parse_account("0x1234").unwrap_err();
+        parse_account("alice").unwrap_err();
+    }
+
+    #[test]
+    fn t_address_info() {
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let info = AddressInfo::new(&ss58::encode(2, &alice))
+            .unwrap()
+            .prefix(0)
+            .derivatives(2)
+            .multisig(&[bob.clone()], 2)
+            .unwrap();
+        assert_eq!(
+            info.derivatives[1],
+            (1, utility::derivative_account_id(&alice, 1))
+        );
+        let multisig = multisig_account(&[alice.clone(), bob.clone()], 2).unwrap();
+        assert_eq!(info.multisig, Some((2, 2, multisig.clone())));
+
+        let shown = info.to_string();
+        assert!(shown.starts_with("input prefix: 2\n"));
+        assert!(shown.contains(&format!("ss58 (22): {}", ss58::to_ss58(&alice))));
+        assert!(shown.contains(&format!("ss58 (42): {}", alice)));
+        assert!(shown.contains(&format!("ss58 (2): {}", ss58::encode(2, &alice))));
+        assert!(shown.contains(&format!("ss58 (0): {}", ss58::encode(0, &alice))));
+        assert!(shown.contains(&format!("multisig 2 of 2: {}", ss58::to_ss58(&multisig))));
+
+        AddressInfo::new(&alice.to_string())
+            .unwrap()
+            .multisig(&[bob], 3)
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,254 @@
+//! Timing the calls of the runtime's own pallets, to weigh them.
+//!
+//! Each benchmark prepares its state on top of the genesis state of a chainspec, then dispatches
+//! its call natively, fees and signature checks aside, on fresh state each repetition. Nodes
+//! execute the runtime as wasm, so the median time is multiplied by a wasm factor, then converts
+//! to weight by the share of a block's execution time it takes: the runtime's
+//! `MaximumBlockWeight` is the weight of `block_execution_time` of the chainspec's block time.
+//! `weights_file` prints a pallet's weights as the `weights.rs` its calls are annotated from.
+
+use crate::serializable_genesis::ChainSpec;
+use node_template_runtime::{AccountId, Call, GenesisConfig, Origin, Runtime};
+use sr_primitives::traits::Dispatchable;
+use sr_primitives::weights::Weight;
+use sr_primitives::BuildStorage;
+use std::fmt;
+use std::time::{Duration, Instant};
+use substrate_primitives::Blake2Hasher;
+use support::traits::Get;
+
+/// How many times slower a call executes as wasm, as nodes execute it, than natively. The nodes
+/// of substrate 870b976 interpret wasm, which is about an order of magnitude slower than native
+/// code; this is an estimate rather than a measurement, so benchmarks for weights that are
+/// committed pass the factor measured on hardware like the validators' with `--wasm-factor`.
+pub const DEFAULT_WASM_FACTOR: u32 = 10;
+
+/// Execution time `MaximumBlockWeight` stands for in blocks of `millisecs_per_block`: a third of
+/// the block time, leaving the rest for the block to propagate and be imported by the other
+/// validators.
+pub fn block_execution_time(millisecs_per_block: u64) -> Duration {
+    Duration::from_millis(millisecs_per_block) / 3
+}
+
+type Erc20 = erc20::Module<Runtime>;
+
+/// A call of a pallet, with the state it runs on.
+struct Benchmark {
+    pallet: &'static str,
+    /// Name of the weight constant of the call.
+    name: &'static str,
+    /// Prepares the state, and returns the call with its origin.
+    setup: fn() -> Result<(Call, Origin), String>,
+}
+
+fn benchmarks() -> Vec<Benchmark> {
+    vec![
+        Benchmark {
+            pallet: "erc20",
+            name: "CREATE",
+            setup: || Ok((create_call(), Origin::signed(account(1)))),
+        },
+        Benchmark {
+            pallet: "erc20",
+            name: "TRANSFER",
+            setup: || {
+                let token = create_token(account(1))?;
+                let call = erc20::Call::transfer(token, account(2), 1_000);
+                Ok((Call::Erc20(call), Origin::signed(account(1))))
+            },
+        },
+        Benchmark {
+            pallet: "erc20",
+            name: "APPROVE",
+            setup: || {
+                let token = create_token(account(1))?;
+                let call = erc20::Call::approve(token, account(2), 1_000);
+                Ok((Call::Erc20(call), Origin::signed(account(1))))
+            },
+        },
+        Benchmark {
+            pallet: "erc20",
+            name: "TRANSFER_FROM",
+            setup: || {
+                let token = create_token(account(1))?;
+                let approve = erc20::Call::approve(token, account(2), 1_000);
+                Call::Erc20(approve).dispatch(Origin::signed(account(1)))?;
+                let call = erc20::Call::transfer_from(token, account(1), account(2), 1_000);
+                Ok((Call::Erc20(call), Origin::signed(account(2))))
+            },
+        },
+        Benchmark {
+            pallet: "faucet",
+            name: "DRIP",
+            setup: || {
+                let call = faucet::Call::drip(account(1));
+                Ok((Call::Faucet(call), system::RawOrigin::None.into()))
+            },
+        },
+    ]
+}
+
+fn account(index: u8) -> AccountId {
+    AccountId::from_raw([index; 32])
+}
+
+fn create_call() -> Call {
+    Call::Erc20(erc20::Call::create(
+        b"BENCH".to_vec(),
+        b"BENCH".to_vec(),
+        1_000_000,
+    ))
+}
+
+/// Have `owner` create a token, returning its id.
+fn create_token(owner: AccountId) -> Result<u32, String> {
+    create_call().dispatch(Origin::signed(owner))?;
+    Ok(Erc20::token_id() - 1)
+}
+
+/// Weight of a call taking `time` to execute in blocks executing for `execution_time`, at least 1.
+pub fn weight_of(time: Duration, execution_time: Duration) -> Weight {
+    let max = <Runtime as system::Trait>::MaximumBlockWeight::get() as u128;
+    let weight = max * time.as_nanos() / execution_time.as_nanos();
+    (weight as Weight).max(1)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Measurement {
+    pub pallet: &'static str,
+    pub name: &'static str,
+    /// Median native execution time.
+    pub time: Duration,
+    pub weight: Weight,
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}::{}: {:?}, weight {}",
+            self.pallet, self.name, self.time, self.weight
+        )
+    }
+}
+
+/// Run the benchmarks of `pallet`, or of all pallets, `repeat` times each on the state of `spec`,
+/// weighing calls as taking `wasm_factor` times their native time.
+pub fn run_benchmarks(
+    spec: ChainSpec<GenesisConfig>,
+    pallet: Option<&str>,
+    repeat: usize,
+    wasm_factor: u32,
+) -> Result<Vec<Measurement>, String> {
+    if repeat == 0 {
+        return Err("repeat at least once".into());
+    }
+    if wasm_factor == 0 {
+        return Err("the wasm factor should be at least 1".into());
+    }
+    let storage = spec.build_storage()?;
+    let mut ext: sr_io::TestExternalities<Blake2Hasher> = storage.clone().into();
+    // babe slots last the block time the chainspec sets
+    let execution_time = block_execution_time(sr_io::with_externalities(
+        &mut ext,
+        babe::Module::<Runtime>::slot_duration,
+    ));
+    let selected: Vec<Benchmark> = benchmarks()
+        .into_iter()
+        .filter(|b| pallet.map_or(true, |pallet| b.pallet == pallet))
+        .collect();
+    if selected.is_empty() {
+        return Err(format!(
+            "no benchmarks for pallet {:?}",
+            pallet.unwrap_or_default()
+        ));
+    }
+    selected
+        .into_iter()
+        .map(|benchmark| {
+            let mut times = (0..repeat)
+                .map(|_| {
+                    let mut ext: sr_io::TestExternalities<Blake2Hasher> = storage.clone().into();
+                    sr_io::with_externalities(&mut ext, || {
+                        system::Module::<Runtime>::set_block_number(1);
+                        let (call, origin) = (benchmark.setup)()?;
+                        let start = Instant::now();
+                        call.dispatch(origin)?;
+                        Ok(start.elapsed())
+                    })
+                })
+                .collect::<Result<Vec<Duration>, String>>()
+                .map_err(|e| format!("{}::{}: {}", benchmark.pallet, benchmark.name, e))?;
+            times.sort();
+            let time = times[times.len() / 2];
+            Ok(Measurement {
+                pallet: benchmark.pallet,
+                name: benchmark.name,
+                time,
+                weight: weight_of(time * wasm_factor, execution_time),
+            })
+        })
+        .collect()
+}
+
+/// The `weights.rs` of a pallet, from its measurements.
+pub fn weights_file(pallet: &str, measurements: &[Measurement]) -> String {
+    let mut file = format!(
+        "//! Weights of the calls of the {} pallet, generated by\n\
+         //! `chaingen benchmark --pallet {}`. Regenerate them after changing a call.\n\n\
+         use sr_primitives::weights::Weight;\n",
+        pallet, pallet
+    );
+    for measurement in measurements.iter().filter(|m| m.pallet == pallet) {
+        file.push_str(&format!(
+            "\n/// Median of {:?}.\npub const {}: Weight = {};\n",
+            measurement.time, measurement.name, measurement.weight
+        ));
+    }
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{Chain, TestnetOptions};
+
+    fn ved() -> ChainSpec<GenesisConfig> {
+        Chain::Ved {
+            options: TestnetOptions::default(),
+        }
+        .generate()
+        .unwrap()
+    }
+
+    #[test]
+    fn t_run_benchmarks() {
+        let measurements = run_benchmarks(ved(), None, 3, DEFAULT_WASM_FACTOR).unwrap();
+        assert_eq!(measurements.len(), benchmarks().len());
+        assert!(measurements.iter().all(|m| m.weight >= 1));
+
+        let faucet = run_benchmarks(ved(), Some("faucet"), 1, 1).unwrap();
+        assert_eq!(faucet.len(), 1);
+        let file = weights_file("faucet", &faucet);
+        assert!(file.contains(&format!("pub const DRIP: Weight = {};", faucet[0].weight)));
+
+        run_benchmarks(ved(), Some("nonexistent"), 1, 1).unwrap_err();
+        run_benchmarks(ved(), None, 0, 1).unwrap_err();
+        run_benchmarks(ved(), None, 1, 0).unwrap_err();
+    }
+
+    #[test]
+    fn t_weight_of() {
+        let max = <Runtime as system::Trait>::MaximumBlockWeight::get();
+        let execution_time = block_execution_time(6000);
+        assert_eq!(execution_time, Duration::from_secs(2));
+        assert_eq!(weight_of(execution_time, execution_time), max);
+        assert_eq!(weight_of(execution_time / 4, execution_time), max / 4);
+        assert_eq!(weight_of(Duration::from_nanos(1), execution_time), 1);
+        // a call takes twice the share of a block half as long
+        assert_eq!(
+            weight_of(execution_time / 4, block_execution_time(3000)),
+            max / 2
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Exporting the blocks of a chain from a running node, e.g. to move a testnet's history to
+//! another machine or to keep a regression fixture.
+//!
+//! The binary format is the node's own: the number of blocks as a little endian u64, followed by
+//! the scale encoded blocks with their justifications. `substrate import-blocks --chain <spec>`
+//! imports it into the database of a node. The json format is an array of the blocks as the
+//! `chain_getBlock` rpc returns them, for reading.
+
+use crate::rpc::Rpc;
+use codec::Encode;
+use node_template_runtime::opaque::{Block, Header};
+use node_template_runtime::BlockNumber;
+use serde_json::json;
+use sr_primitives::generic;
+use sr_primitives::traits::Header as _;
+use std::str::FromStr;
+
+pub type SignedBlock = generic::SignedBlock<Block>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Binary,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "binary" => Ok(Format::Binary),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {:?}, expected binary or json", s)),
+        }
+    }
+}
+
+/// Blocks `from` through `to` of the best chain of the node at `rpc`, through its best block if
+/// `to` is not given.
+pub fn fetch_blocks(
+    rpc: &Rpc,
+    from: BlockNumber,
+    to: Option<BlockNumber>,
+) -> Result<Vec<SignedBlock>, String> {
+    let to = match to {
+        Some(to) => to,
+        None => {
+            let best: Header = serde_json::from_value(rpc.call("chain_getHeader", json!([]))?)
+                .map_err(|e| format!("invalid header: {}", e))?;
+            *best.number()
+        }
+    };
+    if from > to {
+        return Err(format!("no blocks from {} to {}", from, to));
+    }
+    (from..=to)
+        .map(|number| {
+            let hash = rpc.call("chain_getBlockHash", json!([number]))?;
+            if hash.is_null() {
+                return Err(format!("the node has no block {}", number));
+            }
+            serde_json::from_value(rpc.call("chain_getBlock", json!([hash]))?)
+                .map_err(|e| format!("invalid block {}: {}", number, e))
+        })
+        .collect()
+}
+
+pub fn encode_blocks(blocks: &[SignedBlock], format: Format) -> Vec<u8> {
+    match format {
+        Format::Binary => {
+            let mut encoded = (blocks.len() as u64).encode();
+            for block in blocks {
+                block.encode_to(&mut encoded);
+            }
+            encoded
+        }
+        Format::Json => serde_json::to_vec_pretty(blocks).expect("blocks serialize"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Decode;
+    use sr_primitives::OpaqueExtrinsic;
+
+    fn block(number: BlockNumber) -> SignedBlock {
+        SignedBlock {
+            block: Block {
+                header: Header::new(
+                    number,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                ),
+                extrinsics: vec![OpaqueExtrinsic(vec![1, 2, 3])],
+            },
+            justification: Some(vec![4]),
+        }
+    }
+
+    #[test]
+    fn t_encode_blocks() {
+        let blocks = vec![block(1), block(2)];
+
+        let binary = encode_blocks(&blocks, Format::Binary);
+        let mut input = &binary[..];
+        assert_eq!(u64::decode(&mut input), Ok(2));
+        assert_eq!(SignedBlock::decode(&mut input), Ok(blocks[0].clone()));
+        assert_eq!(SignedBlock::decode(&mut input), Ok(blocks[1].clone()));
+        assert!(input.is_empty());
+
+        let json = encode_blocks(&blocks, Format::Json);
+        let decoded: Vec<SignedBlock> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(decoded, blocks);
+        let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(json[1]["block"]["header"]["number"], "0x2");
+    }
+
+    #[test]
+    fn t_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        "scale".parse::<Format>().unwrap_err();
+    }
+}
@@ -0,0 +1,118 @@
+//! Keys of the validators of a private network, derived from one mnemonic.
+//!
+//! Each bundle holds an account and its session keys, all derived along "//<prefix>/<index>" with
+//! `keyring::authority_keys_from_mnemonic`. The addresses go into the `[[authorities]]` of a
+//! genesis config file, and the keystore entries are the parameters of the `author_insertKey` rpc
+//! of the validator's node. Keystore entries hold the secret session keys of the validator, so
+//! bundles are secret, but not the mnemonic the keys of other validators derive from.
+
+use crate::keyring::authority_keys_from_mnemonic;
+use crate::keystore::KeyType;
+use crate::ss58::to_ss58;
+use node_template_runtime::AccountId;
+use serde::Serialize;
+use substrate_primitives::Public;
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct KeyBundle {
+    pub account: String,
+    pub grandpa: String,
+    pub babe: String,
+    pub im_online: String,
+    pub keystore: Vec<KeystoreEntry>,
+}
+
+/// A key to insert into the keystore of a node.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct KeystoreEntry {
+    pub key_type: String,
+    pub suri: String,
+    pub public: String,
+}
+
+/// Bundles "//<prefix>/0" through "//<prefix>/<n - 1>" derived from `mnemonic`.
+pub fn key_bundles(mnemonic: &str, prefix: &str, n: usize) -> Result<Vec<KeyBundle>, String> {
+    (0..n)
+        .map(|i| {
+            let seed = format!("{}/{}", prefix, i);
+            let (account, keys) = authority_keys_from_mnemonic(mnemonic, &seed)?;
+            let entry = |key_type: KeyType, public: &[u8]| -> Result<_, String> {
+                Ok(KeystoreEntry {
+                    key_type: key_type.id().to_owned(),
+                    suri: key_type.scheme().secret(mnemonic, &format!("//{}", seed))?,
+                    public: format!("0x{}", hex::encode(public)),
+                })
+            };
+            // addresses encode the public key of either scheme the same way
+            let address = |public: &[u8]| to_ss58(&AccountId::from_slice(public));
+            Ok(KeyBundle {
+                account: to_ss58(&account),
+                grandpa: address(keys.grandpa.as_ref()),
+                babe: address(keys.babe.as_ref()),
+                im_online: address(keys.im_online.as_ref()),
+                keystore: vec![
+                    entry(KeyType::Babe, keys.babe.as_ref())?,
+                    entry(KeyType::Grandpa, keys.grandpa.as_ref())?,
+                    entry(KeyType::ImOnline, keys.im_online.as_ref())?,
+                ],
+            })
+        })
+        .collect()
+}
+
+pub fn to_json(bundles: &[KeyBundle]) -> String {
+    serde_json::to_string_pretty(bundles).expect("bundles serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Scheme;
+    use crate::keyring::authority_keys_from_seed;
+    use substrate_primitives::crypto::DEV_PHRASE;
+
+    #[test]
+    fn t_key_bundles() {
+        let bundles = key_bundles(DEV_PHRASE, "validator", 2).unwrap();
+        assert_eq!(bundles.len(), 2);
+        let (account, keys) = authority_keys_from_seed("validator/1");
+        let bundle = &bundles[1];
+        assert_eq!(bundle.account, to_ss58(&account));
+        assert_eq!(
+            bundle.grandpa,
+            to_ss58(&AccountId::from_slice(keys.grandpa.as_ref()))
+        );
+        assert_eq!(
+            bundle.babe,
+            to_ss58(&AccountId::from_slice(keys.babe.as_ref()))
+        );
+        let entry = &bundle.keystore[1];
+        assert_eq!(entry.key_type, "gran");
+        assert_eq!(entry.public, format!("0x{}", hex::encode(&keys.grandpa)));
+        // the node derives the key from the secret, which doesn't hold the mnemonic
+        assert!(!entry.suri.contains(DEV_PHRASE));
+        assert_eq!(
+            &Scheme::Ed25519.public(&entry.suri, "").unwrap()[..],
+            keys.grandpa.as_ref()
+        );
+        assert_ne!(bundles[0].account, bundle.account);
+        key_bundles("not a valid mnemonic", "validator", 1).unwrap_err();
+    }
+
+    #[test]
+    fn t_authorities_of_a_genesis_config() {
+        #[derive(serde::Deserialize)]
+        struct File {
+            authorities: Vec<crate::genesis_config::Authority>,
+        }
+        let json = to_json(&key_bundles(DEV_PHRASE, "validator", 1).unwrap());
+        let mut bundles: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        bundles[0].as_object_mut().unwrap().remove("keystore");
+        let file: File =
+            serde_json::from_value(serde_json::json!({ "authorities": bundles })).unwrap();
+        assert_eq!(
+            file.authorities[0].account,
+            authority_keys_from_seed("validator/0").0
+        );
+    }
+}
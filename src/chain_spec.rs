@@ -1,13 +1,39 @@
-use crate::serializable_genesis::ChainSpec;
+use crate::claims::ClaimsTree;
+use crate::genesis_config::{genesis_from_config, GenesisFile};
+#[cfg(any(test, feature = "dev-keys"))]
+use crate::keyring::{derived_accounts, Keyring};
+use crate::serializable_genesis::{ChainSpec, Extensions, Properties};
+use crate::ss58::{from_ss58, SS58_PREFIX};
+use codec::Decode;
 use erc20::Erc20Token;
+use eth_claims::EthereumAddress;
+use multisig::multi_account_id;
+use node_template_runtime::opaque::{Header, SessionKeys};
 use node_template_runtime::{
-    AccountId, BabeConfig, BalancesConfig, Erc20Config, GenesisConfig, GrandpaConfig,
-    IndicesConfig, SudoConfig, SystemConfig, WASM_BINARY,
+    AccountId, AssetsConfig, AuthorityDiscoveryConfig, BabeConfig, Balance, BalancesConfig,
+    BlockNumber, BlockRewardsConfig, BlockTimeConfig, BountiesConfig, Call, ContractsConfig,
+    CouncilConfig, DemocracyConfig, DexConfig, Erc20Config, EthClaimsConfig, FaucetConfig,
+    GenesisConfig, GrandpaConfig, ImOnlineConfig, ImOnlineId, IndicesConfig, MerkleClaimsConfig,
+    NftConfig, OracleConfig, OracleId, RegistrarsConfig, SchedulerConfig, SessionConfig,
+    StakerStatus, StakingConfig, SudoConfig, SystemConfig, TimelockConfig, TipsConfig,
+    TreasuryParamsConfig, TxPauseConfig, ValidatorSetConfig, VestingConfig, WhitelistConfig,
+    EXISTENTIAL_DEPOSIT, VERSION, WASM_BINARY,
 };
+use registrars::{field_bits, IDENTITY_FIELDS};
 use serde::{Deserialize, Serialize};
+use sr_primitives::traits::{AccountIdConversion, BlakeTwo256, Hash as _, Header as _, Zero};
+use sr_primitives::{BuildStorage, ChildrenStorageOverlay, Permill, StorageOverlay};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use substrate_consensus_babe_primitives::AuthorityId as BabeId;
 use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
-use substrate_primitives::{Pair, Public};
+use substrate_primitives::storage::well_known_keys;
+use substrate_primitives::{Public, H256};
+use substrate_telemetry::TelemetryEndpoints;
+use tx_pause::CallIndex;
 
 #[derive(
     structopt::StructOpt, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize,
@@ -24,91 +50,1152 @@ pub enum Chain {
         root_key: AccountId,
         #[structopt(parse(try_from_str = parse_pubkey))]
         treasury: AccountId,
+        /// Account of the validator, defaults to the root key
+        #[structopt(long = "validator-account", parse(try_from_str = parse_pubkey))]
+        validator_account: Option<AccountId>,
+        /// Key the validator signs im-online heartbeats with, defaults to the babe key
+        #[structopt(long = "validator-im-online", parse(try_from_str = parse_pubkey))]
+        validator_im_online: Option<ImOnlineId>,
+        #[structopt(flatten)]
+        options: TestnetOptions,
     },
     /// Outputs the chainspec for a testnet with Alice as validator, root, and treasury
-    Ved,
+    #[cfg(any(test, feature = "dev-keys"))]
+    Ved {
+        #[structopt(flatten)]
+        options: TestnetOptions,
+    },
+    /// Outputs the chainspec for a testnet with Alice and Bob as validators, and Alice as root and
+    /// treasury
+    #[cfg(any(test, feature = "dev-keys"))]
+    Local {
+        #[structopt(flatten)]
+        options: TestnetOptions,
+    },
+    /// Outputs the chainspec for a testnet like `ved`, but with a 2 of 3 multisig of Alice, Bob,
+    /// and Charlie as root
+    #[cfg(any(test, feature = "dev-keys"))]
+    DevMultisig,
+    /// Outputs the chainspec for the shared staging testnet, from the genesis config its operators
+    /// keep, named by WARMUP_STAGING_CONFIG
+    Staging,
+    /// Outputs the chainspec described by a toml genesis config file
+    Config {
+        /// Path to the toml genesis config
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Reads a chainspec from a json file, or generates a preset, and outputs it
+    Load {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+    },
+}
+
+/// A chainspec as named by `--chain`: one of the presets or the path to a chainspec json file.
+/// Commands taking one describe it with `CHAIN_HELP`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
+pub enum ChainSpecVariant {
+    /// `ved` with default options
+    #[cfg(any(test, feature = "dev-keys"))]
+    Dev,
+    /// `local` with default options
+    #[cfg(any(test, feature = "dev-keys"))]
+    Local,
+    /// The shared staging testnet
+    Staging,
+    /// A chainspec json file, human readable or raw
+    Custom(PathBuf),
+}
+
+/// Help of the arguments naming a `ChainSpecVariant`.
+pub const CHAIN_HELP: &str =
+    "\"dev\", \"local\", \"staging\", or the path to a chainspec json file";
+
+impl FromStr for ChainSpecVariant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            #[cfg(any(test, feature = "dev-keys"))]
+            "dev" => Ok(ChainSpecVariant::Dev),
+            #[cfg(any(test, feature = "dev-keys"))]
+            "local" => Ok(ChainSpecVariant::Local),
+            #[cfg(not(any(test, feature = "dev-keys")))]
+            "dev" | "local" => Err(format!("{} was left out of this build, see dev-keys", s)),
+            "staging" => Ok(ChainSpecVariant::Staging),
+            "" => Err("chain should be a preset name or a path to a chainspec file".into()),
+            path => Ok(ChainSpecVariant::Custom(PathBuf::from(path))),
+        }
+    }
+}
+
+impl ChainSpecVariant {
+    pub fn load(self) -> Result<ChainSpec<GenesisConfig>, String> {
+        match self {
+            #[cfg(any(test, feature = "dev-keys"))]
+            ChainSpecVariant::Dev => Chain::Ved {
+                options: TestnetOptions::default(),
+            }
+            .generate(),
+            #[cfg(any(test, feature = "dev-keys"))]
+            ChainSpecVariant::Local => Chain::Local {
+                options: TestnetOptions::default(),
+            }
+            .generate(),
+            ChainSpecVariant::Staging => Chain::Staging.generate(),
+            ChainSpecVariant::Custom(path) => from_json_file(&path),
+        }
+    }
+}
+
+/// Tunables shared by the custom and dev testnets.
+#[derive(
+    structopt::StructOpt,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+pub struct TestnetOptions {
+    /// Native token balance of the treasury at genesis, in the smallest unit
+    #[structopt(long = "endowment")]
+    pub endowment: Option<Balance>,
+    /// Telemetry endpoint nodes report to, with its verbosity: "<url> <verbosity>".
+    /// May be repeated.
+    #[structopt(long = "telemetry-url", parse(try_from_str = parse_telemetry_endpoint))]
+    pub telemetry_endpoints: Vec<(String, u8)>,
+    /// Multiaddress, including peer id, of a node to embed in the chainspec as a bootnode.
+    /// e.g. "/ip4/10.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR".
+    /// May be repeated.
+    #[structopt(long = "bootnode", parse(try_from_str = parse_bootnode))]
+    pub boot_nodes: Vec<String>,
+    /// Network protocol id. Nodes only peer with nodes using the same protocol id.
+    #[structopt(long = "protocol-id")]
+    pub protocol_id: Option<String>,
+    /// Number of load testing accounts, "//load/0", "//load/1", ..., to endow at genesis.
+    #[cfg(any(test, feature = "dev-keys"))]
+    #[structopt(long = "load-accounts", default_value = "0")]
+    pub load_accounts: usize,
+    /// Runtime wasm file to use as the genesis code, instead of the runtime chaingen was built
+    /// with.
+    #[structopt(long = "wasm", env = "WARMUP_WASM_PATH", parse(from_os_str))]
+    pub wasm: Option<PathBuf>,
+    /// Target time between blocks, e.g. 1000 for fast dev chains. Defaults to the runtime's
+    /// default of 6000.
+    #[structopt(long = "millisecs-per-block")]
+    pub millisecs_per_block: Option<u64>,
+    /// Account to endow at genesis, with its balance in the smallest unit: "<ss58>:<amount>".
+    /// May be repeated.
+    #[structopt(long = "endow", parse(try_from_str = parse_endowment))]
+    pub endowments: Vec<(AccountId, Balance)>,
+    /// Sudo key, as an ss58 address, instead of the chain's own.
+    #[structopt(long = "sudo-key", parse(try_from_str = from_ss58))]
+    pub sudo_key: Option<AccountId>,
+}
+
+/// Environment variable naming the genesis config file of the staging testnet, in the format of
+/// `genesis_config`. Its operators keep it, with their public keys and bootnodes.
+pub const STAGING_CONFIG_ENV: &str = "WARMUP_STAGING_CONFIG";
+
+/// Staging nodes use their own protocol id so they never peer with dev or custom chains.
+const STAGING_PROTOCOL_ID: &str = "substrate-warmup-staging";
+
+/// The staging testnet, as described by the operators' genesis config at `path`, which names their
+/// bootnodes and the telemetry they watch the testnet on. Its keys are held by the operators, and
+/// must never be derived from DEV_PHRASE or any other public seed.
+pub fn staging(path: &Path) -> Result<ChainSpec<GenesisConfig>, String> {
+    let invalid = |e: GenesisError| format!("Invalid staging config {}: {}", path.display(), e);
+    let file = GenesisFile::load(path)?;
+    if file.boot_nodes.is_empty() {
+        return Err(invalid(GenesisError::Missing("boot nodes")));
+    }
+    if file.telemetry_endpoints.is_empty() {
+        return Err(invalid(GenesisError::Missing("telemetry endpoints")));
+    }
+    file.into_builder()
+        .protocol_id(STAGING_PROTOCOL_ID)
+        .build()
+        .map_err(invalid)
 }
 
 impl Chain {
     /// Get an actual chain config from one of the alternatives.
-    pub fn generate(self) -> ChainSpec<GenesisConfig> {
-        match self {
+    pub fn generate(self) -> Result<ChainSpec<GenesisConfig>, String> {
+        let builder = match self {
             Chain::Custom {
                 validator_grandpa,
                 validator_babe,
                 root_key,
                 treasury,
-            } => ChainSpec::from_genesis(
-                "Substrate Warmup Custom Testnet",
-                "substrate-warmup-custom",
-                testnet_genesis(
-                    (validator_grandpa.clone(), validator_babe.clone()),
-                    root_key.clone(),
-                    treasury.clone(),
-                ),
-                vec![],
-                None,
-                Some(&format!(
+                validator_account,
+                validator_im_online,
+                options,
+            } => ChainSpecBuilder::default()
+                .name("Substrate Warmup Custom Testnet")
+                .id("substrate-warmup-custom")
+                .protocol_id(&format!(
                     "substrate-warmup-custom-{}-{}-{}-{}",
                     validator_grandpa, validator_babe, root_key, treasury
-                )),
-                None,
-                None,
-            ),
-            Chain::Ved => ChainSpec::from_genesis(
-                "Substrate Warmup Local Dev Testnet",
-                "substrate-warmup-local",
-                testnet_genesis(
-                    (
-                        get_from_seed::<GrandpaId>("Alice"),
-                        get_from_seed::<BabeId>("Alice"),
-                    ),
-                    get_from_seed::<AccountId>("Alice"),
-                    get_from_seed::<AccountId>("Alice"),
-                ),
-                vec![],
-                None,
-                None,
-                None,
-                None,
+                ))
+                .authority((
+                    validator_account.unwrap_or_else(|| root_key.clone()),
+                    SessionKeys {
+                        im_online: validator_im_online
+                            .unwrap_or_else(|| ImOnlineId::from_slice(validator_babe.as_ref())),
+                        grandpa: validator_grandpa,
+                        babe: validator_babe,
+                    },
+                ))
+                .sudo_key(root_key)
+                .treasury(treasury)
+                .options(options),
+            #[cfg(any(test, feature = "dev-keys"))]
+            Chain::Ved { options } => ChainSpecBuilder::default()
+                .name("Substrate Warmup Local Dev Testnet")
+                .id("substrate-warmup-local")
+                .dev_validator(Keyring::Alice)
+                .sudo_key(Keyring::Alice.account())
+                .treasury(Keyring::Alice.account())
+                .options(options),
+            #[cfg(any(test, feature = "dev-keys"))]
+            Chain::Local { options } => ChainSpecBuilder::default()
+                .name("Substrate Warmup Local Testnet")
+                .id("substrate-warmup-local-testnet")
+                // Alice's controller is endowed as the treasury
+                .endowed_accounts(vec![(Keyring::Bob.account(), DEFAULT_ENDOWMENT)])
+                .dev_validator(Keyring::Alice)
+                .dev_validator(Keyring::Bob)
+                .council(vec![
+                    Keyring::Alice.account(),
+                    Keyring::Bob.account(),
+                    Keyring::Charlie.account(),
+                ])
+                .sudo_key(Keyring::Alice.account())
+                .treasury(Keyring::Alice.account())
+                .options(options),
+            #[cfg(any(test, feature = "dev-keys"))]
+            Chain::DevMultisig => ChainSpecBuilder::default()
+                .name("Substrate Warmup Multisig Dev Testnet")
+                .id("substrate-warmup-multisig")
+                .dev_validator(Keyring::Alice)
+                .sudo_key(
+                    multisig_account(
+                        &[
+                            Keyring::Alice.account(),
+                            Keyring::Bob.account(),
+                            Keyring::Charlie.account(),
+                        ],
+                        2,
+                    )
+                    .expect("valid threshold"),
+                )
+                .treasury(Keyring::Alice.account()),
+            Chain::Staging => {
+                let path = std::env::var_os(STAGING_CONFIG_ENV).ok_or_else(|| {
+                    format!(
+                        "{} is not set, it names the genesis config of the staging testnet",
+                        STAGING_CONFIG_ENV
+                    )
+                })?;
+                return staging(Path::new(&path));
+            }
+            Chain::Config { path } => return genesis_from_config(&path),
+            Chain::Load { chain } => return chain.load(),
+        };
+        // presets are always complete, but options like --wasm may still be invalid
+        builder.build().map_err(|e| e.to_string())
+    }
+
+    /// Generate the chainspec and serialize its genesis as raw storage key/value pairs. Nodes
+    /// load a raw chainspec as-is, without re-running genesis construction.
+    pub fn to_raw_json(self) -> Result<String, String> {
+        self.generate()?.into_json(true)
+    }
+}
+
+/// Load a chainspec for this runtime from a json file. Both the human readable and the raw
+/// formats are accepted.
+pub fn from_json_file(path: &Path) -> Result<ChainSpec<GenesisConfig>, String> {
+    ChainSpec::from_json_file(path)
+}
+
+/// Composes a custom chainspec through method chaining.
+///
+/// ```
+/// use substrate_warmup_chaingen::chain_spec::ChainSpecBuilder;
+/// use substrate_warmup_chaingen::keyring::authority_keys_from_mnemonic;
+///
+/// # fn main() -> Result<(), String> {
+/// # let mnemonic = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+/// let (validator, session_keys) = authority_keys_from_mnemonic(mnemonic, "validator")?;
+/// let spec = ChainSpecBuilder::default()
+///     .name("My Testnet")
+///     .id("my-testnet")
+///     .authority((validator.clone(), session_keys))
+///     .sudo_key(validator)
+///     .build()
+///     .map_err(|e| e.to_string())?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A name, an id, a sudo key and at least one authority are required. The treasury defaults to
+/// the sudo key.
+#[derive(Clone, Default)]
+pub struct ChainSpecBuilder {
+    name: String,
+    id: String,
+    authorities: Vec<(AccountId, SessionKeys)>,
+    root_key: Option<AccountId>,
+    admin_key: Option<AccountId>,
+    admin_delay: Option<BlockNumber>,
+    treasury: Option<AccountId>,
+    treasury_endowment: Option<Balance>,
+    endowed_accounts: Vec<(AccountId, Balance)>,
+    vesting: Vec<Vesting>,
+    multisig_endowments: Vec<(Vec<AccountId>, u16, Balance)>,
+    stakers: Vec<Staker>,
+    tokens: Vec<TokenSpec>,
+    pools: Vec<PoolSpec>,
+    collections: Vec<CollectionSpec>,
+    whitelist: Vec<AccountId>,
+    enforce_whitelist: bool,
+    paused_calls: Vec<CallIndex>,
+    oracle_feeders: Vec<OracleId>,
+    oracle_endpoint: String,
+    council: Vec<AccountId>,
+    registrars: Vec<RegistrarSpec>,
+    scheduled_calls: Vec<ScheduledCall>,
+    vesting_schedules: Vec<(AccountId, VestingSchedule)>,
+    proposal_bond: Option<Permill>,
+    spend_period: Option<BlockNumber>,
+    fees_to_treasury: Option<Permill>,
+    faucet_endowment: Option<Balance>,
+    faucet_drip: Option<Balance>,
+    faucet_cooldown: Option<BlockNumber>,
+    block_reward: Option<Balance>,
+    tip_countdown: Option<BlockNumber>,
+    bounty_deposit: Option<Balance>,
+    claims: Vec<(AccountId, Balance)>,
+    eth_claims: Vec<(EthereumAddress, Balance)>,
+    boot_nodes: Vec<String>,
+    telemetry_endpoints: Option<TelemetryEndpoints>,
+    protocol_id: Option<String>,
+    properties: Option<Properties>,
+    max_total_issuance: Option<Balance>,
+    wasm: Option<PathBuf>,
+    fork_blocks: Option<Vec<(u64, H256)>>,
+    bad_blocks: Option<BTreeSet<H256>>,
+    millisecs_per_block: Option<u64>,
+}
+
+impl ChainSpecBuilder {
+    /// Human readable name of the chain.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    /// Machine readable id of the chain.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_owned();
+        self
+    }
+
+    /// Add a genesis validator, identified by its account, with its session keys.
+    pub fn authority(mut self, authority: (AccountId, SessionKeys)) -> Self {
+        self.authorities.push(authority);
+        self
+    }
+
+    /// Replace the list of genesis validators.
+    pub fn authorities(mut self, authorities: Vec<(AccountId, SessionKeys)>) -> Self {
+        self.authorities = authorities;
+        self
+    }
+
+    /// Account allowed to make sudo calls.
+    pub fn sudo_key(mut self, root_key: AccountId) -> Self {
+        self.root_key = Some(root_key);
+        self
+    }
+
+    /// Account announcing calls the timelock module dispatches as root after the admin delay.
+    /// Defaults to the sudo key.
+    pub fn admin_key(mut self, admin: AccountId) -> Self {
+        self.admin_key = Some(admin);
+        self
+    }
+
+    /// Blocks between the announcement of an admin call and its dispatch. Defaults to a day of 6
+    /// second blocks.
+    pub fn admin_delay(mut self, delay: BlockNumber) -> Self {
+        self.admin_delay = Some(delay);
+        self
+    }
+
+    /// Account owning the initial erc20 tokens.
+    pub fn treasury(mut self, treasury: AccountId) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    /// Native token balance of the treasury at genesis. Defaults to `DEFAULT_ENDOWMENT`.
+    pub fn treasury_endowment(mut self, endowment: Balance) -> Self {
+        self.treasury_endowment = Some(endowment);
+        self
+    }
+
+    /// Accounts, other than the treasury, and the native token balance each of them receives at
+    /// genesis.
+    pub fn endowed_accounts(mut self, endowed_accounts: Vec<(AccountId, Balance)>) -> Self {
+        self.endowed_accounts = endowed_accounts;
+        self
+    }
+
+    /// Endow the account controlled by `threshold` of `signatories`, e.g. a team treasury, with
+    /// `balance` at genesis. See `multisig_account`.
+    pub fn multisig_endowment(
+        mut self,
+        signatories: Vec<AccountId>,
+        threshold: u16,
+        balance: Balance,
+    ) -> Self {
+        self.multisig_endowments
+            .push((signatories, threshold, balance));
+        self
+    }
+
+    /// Lock part of the genesis balance of endowed accounts, releasing it linearly over time.
+    pub fn vesting(mut self, vesting: Vec<Vesting>) -> Self {
+        self.vesting = vesting;
+        self
+    }
+
+    /// Bond an account at genesis. Validators must also be added as authorities, keyed by their
+    /// stash.
+    pub fn staker(mut self, staker: Staker) -> Self {
+        self.stakers.push(staker);
+        self
+    }
+
+    /// Replace the list of accounts bonded at genesis.
+    pub fn stakers(mut self, stakers: Vec<Staker>) -> Self {
+        self.stakers = stakers;
+        self
+    }
+
+    /// Fungible tokens, other than the native token, created at genesis.
+    pub fn tokens(mut self, tokens: Vec<TokenSpec>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Members of the council at genesis. Defaults to no members.
+    pub fn council(mut self, council: Vec<AccountId>) -> Self {
+        self.council = council;
+        self
+    }
+
+    /// Identity registrars at genesis, in order of registrar index. Defaults to none.
+    pub fn registrars(mut self, registrars: Vec<RegistrarSpec>) -> Self {
+        self.registrars = registrars;
+        self
+    }
+
+    /// Calls the scheduler dispatches as root after genesis, e.g. a runtime upgrade. Defaults to
+    /// none.
+    pub fn scheduled_calls(mut self, scheduled_calls: Vec<ScheduledCall>) -> Self {
+        self.scheduled_calls = scheduled_calls;
+        self
+    }
+
+    /// Balances locked by the vesting module at genesis, e.g. token sale allocations released
+    /// after a cliff. Each account must be endowed with at least its schedule's amount. Defaults
+    /// to none.
+    pub fn vesting_schedules(
+        mut self,
+        vesting_schedules: Vec<(AccountId, VestingSchedule)>,
+    ) -> Self {
+        self.vesting_schedules = vesting_schedules;
+        self
+    }
+
+    /// Share of a treasury proposal's value that its proposer bonds. Defaults to 5%.
+    pub fn proposal_bond(mut self, proposal_bond: Permill) -> Self {
+        self.proposal_bond = Some(proposal_bond);
+        self
+    }
+
+    /// Blocks between treasury payouts. Defaults to a day of 6 second blocks.
+    pub fn spend_period(mut self, spend_period: BlockNumber) -> Self {
+        self.spend_period = Some(spend_period);
+        self
+    }
+
+    /// Share of transaction fees paid into the treasury, the rest is burnt. Defaults to 20%.
+    pub fn fees_to_treasury(mut self, share: Permill) -> Self {
+        self.fees_to_treasury = Some(share);
+        self
+    }
+
+    /// Native token balance of the faucet account. Defaults to `DEFAULT_FAUCET_ENDOWMENT`, 0
+    /// leaves the faucet empty.
+    pub fn faucet_endowment(mut self, endowment: Balance) -> Self {
+        self.faucet_endowment = Some(endowment);
+        self
+    }
+
+    /// Amount the faucet pays per drip. Defaults to ten tokens.
+    pub fn faucet_drip(mut self, drip: Balance) -> Self {
+        self.faucet_drip = Some(drip);
+        self
+    }
+
+    /// Blocks an account waits between faucet drips. Defaults to a day of 6 second blocks.
+    pub fn faucet_cooldown(mut self, cooldown: BlockNumber) -> Self {
+        self.faucet_cooldown = Some(cooldown);
+        self
+    }
+
+    /// Amount minted to the author of each block. Defaults to `block_rewards::DEFAULT_REWARD`, one
+    /// token, 0 leaves authors unrewarded.
+    pub fn block_reward(mut self, reward: Balance) -> Self {
+        self.block_reward = Some(reward);
+        self
+    }
+
+    /// Blocks between half the council tipping a tip and the tip closing. Defaults to a day of 6
+    /// second blocks.
+    pub fn tip_countdown(mut self, countdown: BlockNumber) -> Self {
+        self.tip_countdown = Some(countdown);
+        self
+    }
+
+    /// Deposit reserved from the proposer of a bounty until the council approves or rejects it.
+    /// Defaults to one token.
+    pub fn bounty_deposit(mut self, deposit: Balance) -> Self {
+        self.bounty_deposit = Some(deposit);
+        self
+    }
+
+    /// Allocations accounts claim after launch with merkle proofs, see `ClaimsTree`. Only the root
+    /// of their tree is stored at genesis.
+    pub fn claims(mut self, allocations: Vec<(AccountId, Balance)>) -> Self {
+        self.claims = allocations;
+        self
+    }
+
+    /// Balances of an erc20 snapshot, which each ethereum address claims by signing the account
+    /// to pay with its ethereum key.
+    pub fn eth_claims(mut self, snapshot: Vec<(EthereumAddress, Balance)>) -> Self {
+        self.eth_claims = snapshot;
+        self
+    }
+
+    /// Liquidity pools of the dex module opened at genesis with tokens their providers hold.
+    pub fn pools(mut self, pools: Vec<PoolSpec>) -> Self {
+        self.pools = pools;
+        self
+    }
+
+    /// Nft collections created at genesis, without items.
+    pub fn collections(mut self, collections: Vec<CollectionSpec>) -> Self {
+        self.collections = collections;
+        self
+    }
+
+    /// Accounts allowed to transfer native tokens while the whitelist is enforced.
+    pub fn whitelist(mut self, accounts: Vec<AccountId>) -> Self {
+        self.whitelist = accounts;
+        self
+    }
+
+    /// Reject native token transfers from accounts off the whitelist from genesis on. Defaults to
+    /// false.
+    pub fn enforce_whitelist(mut self, enforced: bool) -> Self {
+        self.enforce_whitelist = enforced;
+        self
+    }
+
+    /// Calls rejected from genesis on until they are unpaused, by module and call index, e.g.
+    /// `(5, 0)` for balances transfers.
+    pub fn paused_calls(mut self, calls: Vec<CallIndex>) -> Self {
+        self.paused_calls = calls;
+        self
+    }
+
+    /// Keys whose prices the oracle accepts, inserted in the keystore of the node of each feeder
+    /// with the "orcl" key type.
+    pub fn oracle_feeders(mut self, feeders: Vec<OracleId>) -> Self {
+        self.oracle_feeders = feeders;
+        self
+    }
+
+    /// Http url the offchain workers of feeders fetch the oracle price from, answering with the
+    /// price as the first number of the response body.
+    pub fn oracle_endpoint(mut self, endpoint: &str) -> Self {
+        self.oracle_endpoint = endpoint.to_string();
+        self
+    }
+
+    /// Run a dev account as a genesis validator. Its "//<name>//stash" account is endowed and
+    /// bonded, with the dev account as controller.
+    #[cfg(any(test, feature = "dev-keys"))]
+    pub fn dev_validator(mut self, key: Keyring) -> Self {
+        self.authorities.push(key.authority());
+        self.endowed_accounts.push((key.stash(), DEFAULT_ENDOWMENT));
+        self.staker((
+            key.stash(),
+            key.account(),
+            DEV_VALIDATOR_BOND,
+            StakerStatus::Validator,
+        ))
+    }
+
+    /// Apply the tunables passed on the command line.
+    pub fn options(self, options: TestnetOptions) -> Self {
+        let TestnetOptions {
+            endowment,
+            telemetry_endpoints,
+            boot_nodes,
+            protocol_id,
+            #[cfg(any(test, feature = "dev-keys"))]
+            load_accounts,
+            wasm,
+            millisecs_per_block,
+            endowments,
+            sudo_key,
+        } = options;
+        let mut ret = self;
+        if let Some(endowment) = endowment {
+            ret = ret.treasury_endowment(endowment);
+        }
+        if !telemetry_endpoints.is_empty() {
+            ret = ret.telemetry_endpoints(TelemetryEndpoints::new(telemetry_endpoints));
+        }
+        ret.boot_nodes.extend(boot_nodes);
+        if let Some(protocol_id) = protocol_id {
+            ret = ret.protocol_id(&protocol_id);
+        }
+        #[cfg(any(test, feature = "dev-keys"))]
+        ret.endowed_accounts.extend(
+            derived_accounts("load", load_accounts)
+                .into_iter()
+                .map(|account| (account, DEFAULT_ENDOWMENT)),
+        );
+        if let Some(wasm) = wasm {
+            ret = ret.wasm(&wasm);
+        }
+        if let Some(millisecs_per_block) = millisecs_per_block {
+            ret = ret.millisecs_per_block(millisecs_per_block);
+        }
+        ret.endowed_accounts.extend(endowments);
+        if let Some(sudo_key) = sudo_key {
+            ret = ret.sudo_key(sudo_key);
+        }
+        ret
+    }
+
+    pub fn boot_nodes(mut self, boot_nodes: Vec<String>) -> Self {
+        self.boot_nodes = boot_nodes;
+        self
+    }
+
+    pub fn telemetry_endpoints(mut self, telemetry_endpoints: TelemetryEndpoints) -> Self {
+        self.telemetry_endpoints = Some(telemetry_endpoints);
+        self
+    }
+
+    /// Network protocol id. Defaults to the chain id so that nodes of different chains don't
+    /// attempt to peer with each other.
+    pub fn protocol_id(mut self, protocol_id: &str) -> Self {
+        self.protocol_id = Some(protocol_id.to_owned());
+        self
+    }
+
+    /// Properties for display in wallets. Defaults to `spec_properties()`.
+    pub fn properties(mut self, properties: Properties) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Fail to build if the endowments and claimable allocations, including ethereum claims, sum
+    /// to more than `cap`.
+    pub fn max_total_issuance(mut self, cap: Balance) -> Self {
+        self.max_total_issuance = Some(cap);
+        self
+    }
+
+    /// Read the runtime code from a wasm file instead of using the runtime this binary was built
+    /// with. The file is read when the chainspec is built.
+    pub fn wasm(mut self, path: &Path) -> Self {
+        self.wasm = Some(path.to_owned());
+        self
+    }
+
+    /// Blocks, by number and hash, for nodes to treat as canonical. See `Extensions` for which
+    /// nodes do.
+    pub fn fork_blocks(mut self, fork_blocks: Vec<(u64, H256)>) -> Self {
+        self.fork_blocks = Some(fork_blocks);
+        self
+    }
+
+    /// Hashes of blocks for nodes never to import. See `Extensions` for which nodes do.
+    pub fn bad_blocks(mut self, bad_blocks: Vec<H256>) -> Self {
+        self.bad_blocks = Some(bad_blocks.into_iter().collect());
+        self
+    }
+
+    /// Target time between blocks. Defaults to the runtime's default block time.
+    pub fn millisecs_per_block(mut self, millisecs_per_block: u64) -> Self {
+        self.millisecs_per_block = Some(millisecs_per_block);
+        self
+    }
+
+    /// Assemble the chainspec, failing if a required field was never set.
+    pub fn build(self) -> Result<ChainSpec<GenesisConfig>, GenesisError> {
+        if self.name.is_empty() {
+            return Err(GenesisError::Missing("name"));
+        }
+        if self.id.is_empty() {
+            return Err(GenesisError::Missing("id"));
+        }
+        let root_key = self.root_key.ok_or(GenesisError::Missing("sudo key"))?;
+        let treasury = self.treasury.unwrap_or_else(|| root_key.clone());
+        let admin = self.admin_key.unwrap_or_else(|| root_key.clone());
+        let code = match &self.wasm {
+            Some(path) => wasm_from_file(path).map_err(GenesisError::Wasm)?,
+            None => WASM_BINARY.to_vec(),
+        };
+        let mut endowed_accounts = self.endowed_accounts;
+        for (signatories, threshold, balance) in self.multisig_endowments {
+            let account =
+                multisig_account(&signatories, threshold).map_err(GenesisError::InvalidMultisig)?;
+            endowed_accounts.push((account, balance));
+        }
+        let faucet_endowment = self.faucet_endowment.unwrap_or(DEFAULT_FAUCET_ENDOWMENT);
+        if faucet_endowment > 0 {
+            endowed_accounts.push((faucet_account(), faucet_endowment));
+        }
+        let mut genesis = testnet_genesis(
+            code,
+            self.authorities,
+            root_key,
+            (
+                treasury,
+                self.treasury_endowment.unwrap_or(DEFAULT_ENDOWMENT),
             ),
+            endowed_accounts,
+            self.vesting,
+            self.max_total_issuance,
+        )?;
+        if let Some(millisecs_per_block) = self.millisecs_per_block {
+            // babe slots last twice the timestamp module's minimum period of half a block
+            if millisecs_per_block < 2 {
+                return Err(GenesisError::InvalidBlockTime);
+            }
+            genesis.block_time = Some(BlockTimeConfig {
+                millisecs_per_block,
+            });
+        }
+        genesis.staking = Some(staking_genesis(&genesis, self.stakers)?);
+        genesis.dex = Some(dex_genesis(&self.tokens, self.pools)?);
+        genesis.assets = Some(assets_genesis(self.tokens)?);
+        genesis.nft = Some(nft_genesis(self.collections)?);
+        let mut whitelisted = BTreeSet::new();
+        for account in &self.whitelist {
+            if !whitelisted.insert(account) {
+                return Err(GenesisError::DuplicateWhitelisted(account.clone()));
+            }
         }
+        genesis.whitelist = Some(WhitelistConfig {
+            enforced: self.enforce_whitelist,
+            whitelist: self.whitelist,
+        });
+        genesis.tx_pause = Some(TxPauseConfig {
+            paused: self.paused_calls,
+        });
+        genesis.oracle = Some(oracle_genesis(self.oracle_feeders, self.oracle_endpoint)?);
+        let mut members = BTreeSet::new();
+        for member in &self.council {
+            if !members.insert(member) {
+                return Err(GenesisError::DuplicateCouncilMember(member.clone()));
+            }
+        }
+        genesis.collective_Instance1 = Some(CouncilConfig {
+            members: self.council,
+            phantom: Default::default(),
+        });
+        genesis.registrars = Some(registrars_genesis(self.registrars)?);
+        genesis.scheduler = Some(scheduler_genesis(self.scheduled_calls)?);
+        genesis.vesting = Some(vesting_genesis(&genesis, self.vesting_schedules)?);
+        let mut treasury_params = TreasuryParamsConfig::default();
+        if let Some(proposal_bond) = self.proposal_bond {
+            treasury_params.proposal_bond = proposal_bond;
+        }
+        if let Some(spend_period) = self.spend_period {
+            // the treasury pays out on blocks whose number is a multiple of the period
+            if spend_period.is_zero() {
+                return Err(GenesisError::InvalidSpendPeriod);
+            }
+            treasury_params.spend_period = spend_period;
+        }
+        if let Some(share) = self.fees_to_treasury {
+            treasury_params.fees_to_treasury = share;
+        }
+        genesis.treasury_params = Some(treasury_params);
+        let mut tips = TipsConfig::default();
+        if let Some(countdown) = self.tip_countdown {
+            tips.tip_countdown = countdown;
+        }
+        genesis.tips = Some(tips);
+        let mut bounties = BountiesConfig::default();
+        if let Some(deposit) = self.bounty_deposit {
+            bounties.bounty_deposit = deposit;
+        }
+        genesis.bounties = Some(bounties);
+        let mut timelock = TimelockConfig::default();
+        timelock.admin = admin;
+        if let Some(delay) = self.admin_delay {
+            timelock.delay = delay;
+        }
+        genesis.timelock = Some(timelock);
+        let mut faucet = genesis.faucet.take().unwrap_or_default();
+        if let Some(drip) = self.faucet_drip {
+            // a drip creates the account it pays
+            if drip < EXISTENTIAL_DEPOSIT {
+                return Err(GenesisError::InvalidFaucet(
+                    "faucet drips must be at least the existential deposit",
+                ));
+            }
+            faucet.drip_amount = drip;
+        }
+        if let Some(cooldown) = self.faucet_cooldown {
+            if cooldown == 0 {
+                return Err(GenesisError::InvalidFaucet(
+                    "faucet cooldown must be at least one block",
+                ));
+            }
+            faucet.cooldown = cooldown;
+        }
+        genesis.faucet = Some(faucet);
+        if let Some(reward) = self.block_reward {
+            genesis.block_rewards = Some(BlockRewardsConfig { reward });
+        }
+        let mut unclaimed: Balance = 0;
+        if !self.claims.is_empty() {
+            let claims = ClaimsTree::new(self.claims)?;
+            unclaimed = claims.total();
+            genesis.merkle_claims = Some(MerkleClaimsConfig {
+                root: claims.root(),
+            });
+        }
+        if !self.eth_claims.is_empty() {
+            let (eth_claims, total) = eth_claims_genesis(self.eth_claims)?;
+            unclaimed = unclaimed
+                .checked_add(total)
+                .ok_or(GenesisError::IssuanceOverflow)?;
+            genesis.eth_claims = Some(eth_claims);
+        }
+        if let Some(cap) = self.max_total_issuance.filter(|_| unclaimed > 0) {
+            // allocations count against the cap as though they were all claimed
+            let endowed: Balance = genesis
+                .balances
+                .iter()
+                .flat_map(|config| &config.balances)
+                .map(|(_, balance)| balance)
+                .sum();
+            let total_issuance = endowed
+                .checked_add(unclaimed)
+                .ok_or(GenesisError::IssuanceOverflow)?;
+            if total_issuance > cap {
+                return Err(GenesisError::IssuanceAboveCap {
+                    total_issuance,
+                    cap,
+                });
+            }
+        }
+        Ok(ChainSpec::from_genesis(
+            &self.name,
+            &self.id,
+            genesis,
+            self.boot_nodes,
+            self.telemetry_endpoints,
+            Some(self.protocol_id.as_ref().unwrap_or(&self.id).as_str()),
+            None,
+            Some(self.properties.unwrap_or_else(spec_properties)),
+            Extensions {
+                fork_blocks: self.fork_blocks,
+                bad_blocks: self.bad_blocks,
+            },
+        ))
+    }
+}
+
+/// Ticker of the native token, as displayed by wallets.
+pub const TOKEN_SYMBOL: &str = "DCK";
+/// Number of decimal places wallets use when displaying native token balances.
+pub const TOKEN_DECIMALS: u32 = 12;
+
+/// Chainspec properties telling wallets how to display tokens and addresses for this chain.
+pub fn spec_properties() -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+    properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+    properties.insert("ss58Format".into(), SS58_PREFIX.into());
+    properties
+}
+
+/// Vesting schedule of an endowed account. Everything but `liquid` is locked at genesis and is
+/// unlocked linearly over `length` blocks, starting at block `begin`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+    #[serde(deserialize_with = "crate::ss58::deserialize")]
+    pub account: AccountId,
+    pub begin: BlockNumber,
+    pub length: BlockNumber,
+    /// Amount of the genesis balance which is spendable right away.
+    #[serde(default)]
+    pub liquid: Balance,
+}
+
+/// Schedule of the vesting module: `amount` is released linearly over `duration` blocks from
+/// `start_block`, but nothing is before `cliff` blocks have passed.
+pub type VestingSchedule = vesting::VestingSchedule<Balance, BlockNumber>;
+
+/// A vesting module schedule, checked for satisfiability.
+pub fn vesting_schedule(
+    start_block: BlockNumber,
+    cliff: BlockNumber,
+    duration: BlockNumber,
+    amount: Balance,
+) -> Result<VestingSchedule, GenesisError> {
+    if duration == 0 {
+        return Err(GenesisError::InvalidVesting(
+            "vesting schedule duration must be at least one block",
+        ));
+    }
+    if cliff > duration {
+        return Err(GenesisError::InvalidVesting(
+            "vesting schedule cliff must not exceed its duration",
+        ));
     }
+    if amount == 0 {
+        return Err(GenesisError::InvalidVesting(
+            "vesting schedule amount must not be zero",
+        ));
+    }
+    Ok(VestingSchedule {
+        start_block,
+        cliff,
+        duration,
+        amount,
+    })
+}
+
+/// Native token balance the treasury is endowed with when not otherwise specified.
+pub const DEFAULT_ENDOWMENT: Balance = 1 << 60;
+
+/// Native token balance the faucet is endowed with when not otherwise specified.
+pub const DEFAULT_FAUCET_ENDOWMENT: Balance = DEFAULT_ENDOWMENT / 16;
+
+/// Account the faucet module drips from.
+pub fn faucet_account() -> AccountId {
+    faucet::MODULE_ID.into_account()
+}
+
+/// Price of a unit of contract gas, in the smallest unit of the native token.
+const CONTRACTS_GAS_PRICE: Balance = 10_000_000;
+
+/// Amount bonded by the validators of the dev presets.
+#[cfg(any(test, feature = "dev-keys"))]
+const DEV_VALIDATOR_BOND: Balance = DEFAULT_ENDOWMENT / 2;
+
+/// A fungible token created at genesis by the assets module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenSpec {
+    pub id: u32,
+    pub symbol: String,
+    pub decimals: u8,
+    pub owner: AccountId,
+    /// Accounts holding the token at genesis and their balances. The total supply is their sum.
+    pub distribution: Vec<(AccountId, Balance)>,
+}
+
+/// A liquidity pool of two genesis tokens, opened by `provider` with `amount_a` of `asset_a` and
+/// `amount_b` of `asset_b`, which set the pool's initial price.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolSpec {
+    pub asset_a: u32,
+    pub asset_b: u32,
+    pub provider: AccountId,
+    pub amount_a: Balance,
+    pub amount_b: Balance,
+}
+
+/// An nft collection created at genesis, in which `owner` mints items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollectionSpec {
+    pub id: u32,
+    pub owner: AccountId,
+    /// Description of the collection, e.g. a uri.
+    pub metadata: String,
+}
+
+/// An identity registrar added at genesis, in order of registrar index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistrarSpec {
+    pub account: AccountId,
+    /// Fee the registrar charges per judgement.
+    pub fee: Balance,
+    /// Names of the identity fields the registrar judges, e.g. "display" or "email".
+    pub fields: Vec<String>,
+}
+
+/// A call dispatched as root by the scheduler module at block `when` and, if `periodic` is
+/// (period, count), every `period` blocks after until it has been dispatched `count` times.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScheduledCall {
+    pub when: BlockNumber,
+    pub periodic: Option<(BlockNumber, u32)>,
+    /// The scale encoded runtime call.
+    pub call: Vec<u8>,
 }
 
+/// An account bonded at genesis: (stash, controller, bond, role).
+pub type Staker = (AccountId, AccountId, Balance, StakerStatus<AccountId>);
+
 fn testnet_genesis(
-    initial_authority: (GrandpaId, BabeId),
+    code: Vec<u8>,
+    initial_authorities: Vec<(AccountId, SessionKeys)>,
     root_key: AccountId,
-    treasury: AccountId,
-) -> GenesisConfig {
-    const ENDOWMENT: u128 = u128::max_value();
+    (treasury, treasury_endowment): (AccountId, Balance),
+    endowed_accounts: Vec<(AccountId, Balance)>,
+    vesting: Vec<Vesting>,
+    max_total_issuance: Option<Balance>,
+) -> Result<GenesisConfig, GenesisError> {
+    const TOKEN_SUPPLY: u128 = u128::max_value();
+
+    validate_wasm(&code).map_err(|e| GenesisError::Wasm(e.to_string()))?;
+    if initial_authorities.is_empty() {
+        return Err(GenesisError::NoAuthorities);
+    }
+    let balances: Vec<(AccountId, Balance)> = iter::once((treasury.clone(), treasury_endowment))
+        .chain(endowed_accounts.into_iter())
+        .collect();
+    let mut endowed = BTreeSet::new();
+    let mut total_issuance: Balance = 0;
+    for (account, balance) in &balances {
+        if !endowed.insert(account) {
+            return Err(GenesisError::DuplicateAccount(account.clone()));
+        }
+        if *balance < EXISTENTIAL_DEPOSIT {
+            return Err(GenesisError::BelowExistentialDeposit(account.clone()));
+        }
+        total_issuance = total_issuance
+            .checked_add(*balance)
+            .ok_or(GenesisError::IssuanceOverflow)?;
+    }
+    if let Some(cap) = max_total_issuance {
+        if total_issuance > cap {
+            return Err(GenesisError::IssuanceAboveCap {
+                total_issuance,
+                cap,
+            });
+        }
+    }
+    for schedule in &vesting {
+        if !endowed.contains(&schedule.account) {
+            return Err(GenesisError::InvalidVesting(
+                "vesting schedule set for an account without endowment",
+            ));
+        }
+        if schedule.length == 0 {
+            return Err(GenesisError::InvalidVesting(
+                "vesting schedule length must be at least one block",
+            ));
+        }
+    }
 
-    GenesisConfig {
+    Ok(GenesisConfig {
         system: Some(SystemConfig {
-            code: WASM_BINARY.to_vec(),
+            code,
             changes_trie_config: Default::default(),
         }),
         indices: Some(IndicesConfig {
-            ids: vec![treasury.clone()],
+            ids: balances
+                .iter()
+                .map(|(account, _)| account.clone())
+                .collect(),
         }),
         balances: Some(BalancesConfig {
-            balances: vec![(treasury.clone(), ENDOWMENT)],
-            vesting: vec![],
+            balances,
+            vesting: vesting
+                .into_iter()
+                .map(|v| (v.account, v.begin, v.length, v.liquid))
+                .collect(),
         }),
         sudo: Some(SudoConfig { key: root_key }),
+        // authorities of babe, grandpa and the other session key users are set by the session
+        // module from the session keys
         babe: Some(BabeConfig {
-            authorities: vec![(initial_authority.1, 1)],
+            authorities: vec![],
         }),
         grandpa: Some(GrandpaConfig {
-            authorities: vec![(initial_authority.0, 1)],
+            authorities: vec![],
         }),
+        im_online: Some(ImOnlineConfig { keys: vec![] }),
+        authority_discovery: Some(AuthorityDiscoveryConfig { keys: vec![] }),
+        staking: None,
+        assets: None,
+        democracy: Some(DemocracyConfig::default()),
+        collective_Instance1: None,
+        treasury_params: None,
+        registrars: None,
+        scheduler: None,
+        vesting: None,
+        faucet: Some(FaucetConfig::default()),
+        merkle_claims: None,
+        eth_claims: None,
+        dex: None,
+        nft: None,
+        whitelist: None,
+        tx_pause: None,
+        oracle: None,
+        block_rewards: Some(BlockRewardsConfig::default()),
+        tips: None,
+        bounties: None,
+        timelock: None,
+        contracts: Some(ContractsConfig {
+            current_schedule: Default::default(),
+            gas_price: CONTRACTS_GAS_PRICE,
+        }),
+        session: Some(SessionConfig {
+            keys: initial_authorities.clone(),
+        }),
+        validator_set: Some(ValidatorSetConfig {
+            validators: initial_authorities
+                .iter()
+                .map(|(account, _)| account.clone())
+                .collect(),
+        }),
+        block_time: None,
         erc20: Some(Erc20Config {
             initial_tokens: vec![
                 (
                     Erc20Token {
                         name: b"PSTABLE1".to_vec(),
                         ticker: b"PSTABLE1".to_vec(),
-                        total_supply: ENDOWMENT,
+                        total_supply: TOKEN_SUPPLY,
                     },
                     treasury.clone(),
                 ),
@@ -116,65 +1203,663 @@ fn testnet_genesis(
                     Erc20Token {
                         name: b"PSTABLE2".to_vec(),
                         ticker: b"PSTABLE2".to_vec(),
-                        total_supply: ENDOWMENT,
+                        total_supply: TOKEN_SUPPLY,
                     },
                     treasury.clone(),
                 ),
             ],
         }),
-    }
+    })
 }
 
-/// Helper function to generate a crypto pair from seed
-pub fn get_from_seed<P: Public>(seed: &str) -> <P::Pair as Pair>::Public {
-    P::Pair::from_string(&format!("//{}", seed), None)
-        .expect("invalid seed")
-        .public()
+/// What validators compare before launch to make sure they all start from the same genesis.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChainInfo {
+    pub name: String,
+    pub id: String,
+    pub genesis_hash: H256,
+    /// Version of the genesis runtime, known only when it is the runtime chaingen was built with.
+    pub spec_version: Option<u32>,
 }
 
-fn parse_pubkey<T: Public>(imp: &str) -> Result<T, &'static str> {
-    let imp: &[u8] = imp.as_bytes();
-
-    // check key is 0x prefixed, remove prefix
-    let imp: &[u8] = if imp.starts_with(b"0x") {
-        &imp[2..]
-    } else {
-        return Err("public key shoud be prefixed with '0x'");
-    };
-
-    // check key is correct len
-    if imp.len() != 64 {
-        return Err("256 bit public key should be 64 hex digits");
+impl ChainInfo {
+    pub fn of(spec: ChainSpec<GenesisConfig>) -> Result<Self, String> {
+        let name = spec.name().to_owned();
+        let id = spec.id().to_owned();
+        let storage = spec.build_storage()?;
+        let builtin_runtime =
+            storage.0.get(well_known_keys::CODE).map(Vec::as_slice) == Some(WASM_BINARY);
+        Ok(ChainInfo {
+            name,
+            id,
+            genesis_hash: genesis_hash_of_storage(storage),
+            spec_version: if builtin_runtime {
+                Some(VERSION.spec_version)
+            } else {
+                None
+            },
+        })
     }
+}
 
-    // decode hex
-    let pk: Vec<u8> = hex::decode(imp).map_err(|err| {
-        use hex::FromHexError::*;
-        match err {
-            InvalidHexCharacter { .. } => "invalid hex character, must be [0-9][a-z][A-Z]",
-            OddLength => panic!("this should not happen"),
-            InvalidStringLength => panic!("this should not happen"),
+impl fmt::Display for ChainInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "name: {}", self.name)?;
+        writeln!(f, "id: {}", self.id)?;
+        writeln!(f, "genesis hash: {:?}", self.genesis_hash)?;
+        match self.spec_version {
+            Some(version) => write!(f, "runtime spec_version: {}", version),
+            None => write!(f, "runtime spec_version: unknown, not the built-in runtime"),
         }
-    })?;
+    }
+}
 
-    assert_eq!(pk.len(), 32);
+/// Hash of the genesis block nodes will build from `spec`.
+pub fn genesis_hash(spec: ChainSpec<GenesisConfig>) -> Result<H256, String> {
+    Ok(genesis_hash_of_storage(spec.build_storage()?))
+}
 
-    Ok(Public::from_slice(&pk))
+/// Header of the genesis block of `spec`, e.g. to register the chain with infrastructure
+/// tracking its head.
+pub fn genesis_header(spec: ChainSpec<GenesisConfig>) -> Result<Header, String> {
+    Ok(genesis_header_of_storage(spec.build_storage()?))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Runtime code of the genesis of `spec`.
+pub fn genesis_wasm(spec: ChainSpec<GenesisConfig>) -> Result<Vec<u8>, String> {
+    let (mut top, _) = spec.build_storage()?;
+    top.remove(well_known_keys::CODE)
+        .ok_or_else(|| "the genesis has no runtime code".to_string())
+}
 
-    #[test]
-    fn t_parse_pk() {
-        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
-        parse_pubkey::<GrandpaId>(valid_pk).unwrap();
-        parse_pubkey::<BabeId>(valid_pk).unwrap();
-        parse_pubkey::<AccountId>(valid_pk).unwrap();
-        for invalid_pk in &[
-            "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305",
-            "6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c",
+pub fn genesis_hash_of_storage(storage: (StorageOverlay, ChildrenStorageOverlay)) -> H256 {
+    genesis_header_of_storage(storage).hash()
+}
+
+/// The genesis block commits to the storage root, where each child trie is included in the top
+/// trie by its root, and to an empty list of extrinsics.
+fn genesis_header_of_storage(
+    (mut top, children): (StorageOverlay, ChildrenStorageOverlay),
+) -> Header {
+    for (storage_key, child) in children {
+        if !child.is_empty() {
+            let root = BlakeTwo256::trie_root(child.into_iter().collect());
+            top.insert(storage_key, root.as_ref().to_vec());
+        }
+    }
+    let state_root = BlakeTwo256::trie_root(top.into_iter().collect());
+    let extrinsics_root = BlakeTwo256::ordered_trie_root(Vec::new());
+    Header::new(
+        Zero::zero(),
+        extrinsics_root,
+        state_root,
+        Default::default(),
+        Default::default(),
+    )
+}
+
+/// Reasons a genesis config could not be constructed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GenesisError {
+    /// A required chainspec field was not set.
+    Missing(&'static str),
+    /// Without authorities no blocks would ever be produced.
+    NoAuthorities,
+    /// An account was endowed more than once.
+    DuplicateAccount(AccountId),
+    /// An account was endowed with less than the existential deposit.
+    BelowExistentialDeposit(AccountId),
+    /// The sum of all endowments does not fit in a Balance.
+    IssuanceOverflow,
+    /// The sum of all endowments exceeds the configured maximum total issuance.
+    IssuanceAboveCap {
+        total_issuance: Balance,
+        cap: Balance,
+    },
+    /// A vesting schedule is unsatisfiable.
+    InvalidVesting(&'static str),
+    /// The runtime wasm could not be loaded.
+    Wasm(String),
+    /// Blocks must be at least 2 milliseconds apart.
+    InvalidBlockTime,
+    /// An account bonded at genesis is misconfigured.
+    InvalidStaker(&'static str),
+    /// A token created at genesis is misconfigured.
+    InvalidToken(&'static str),
+    /// An account was listed as council member more than once.
+    DuplicateCouncilMember(AccountId),
+    /// A multisig account endowed at genesis is misconfigured.
+    InvalidMultisig(&'static str),
+    /// An account was listed as identity registrar more than once.
+    DuplicateRegistrar(AccountId),
+    /// A registrar judges a field identities don't have.
+    UnknownIdentityField(String),
+    /// A call scheduled at genesis is misconfigured.
+    InvalidScheduledCall(&'static str),
+    /// The treasury spend period must be at least one block.
+    InvalidSpendPeriod,
+    /// The faucet is misconfigured.
+    InvalidFaucet(&'static str),
+    /// An ethereum snapshot claim is misconfigured.
+    InvalidEthClaim(&'static str),
+    /// A liquidity pool opened at genesis is misconfigured.
+    InvalidPool(&'static str),
+    /// An nft collection created at genesis is misconfigured.
+    InvalidCollection(&'static str),
+    /// An account was whitelisted more than once.
+    DuplicateWhitelisted(AccountId),
+    /// The oracle feeders or endpoint are misconfigured.
+    InvalidOracle(&'static str),
+}
+
+impl fmt::Display for GenesisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenesisError::Missing(field) => write!(f, "chainspec {} was not set", field),
+            GenesisError::NoAuthorities => write!(f, "chainspec must have at least one authority"),
+            GenesisError::DuplicateAccount(account) => {
+                write!(f, "account {} is endowed more than once", account)
+            }
+            GenesisError::BelowExistentialDeposit(account) => write!(
+                f,
+                "account {} is endowed with less than the existential deposit of {}",
+                account, EXISTENTIAL_DEPOSIT
+            ),
+            GenesisError::IssuanceOverflow => write!(f, "total issuance overflows"),
+            GenesisError::IssuanceAboveCap {
+                total_issuance,
+                cap,
+            } => write!(
+                f,
+                "total issuance of {} exceeds the maximum of {}",
+                total_issuance, cap
+            ),
+            GenesisError::InvalidVesting(reason) => write!(f, "{}", reason),
+            GenesisError::Wasm(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidBlockTime => {
+                write!(f, "block time must be at least 2 milliseconds")
+            }
+            GenesisError::InvalidStaker(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidToken(reason) => write!(f, "{}", reason),
+            GenesisError::DuplicateCouncilMember(account) => {
+                write!(f, "account {} is a council member more than once", account)
+            }
+            GenesisError::InvalidMultisig(reason) => write!(f, "{}", reason),
+            GenesisError::DuplicateRegistrar(account) => {
+                write!(f, "account {} is a registrar more than once", account)
+            }
+            GenesisError::UnknownIdentityField(field) => write!(
+                f,
+                "{} is not an identity field, expected one of {}",
+                field,
+                IDENTITY_FIELDS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            GenesisError::InvalidScheduledCall(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidSpendPeriod => {
+                write!(f, "treasury spend period must be at least one block")
+            }
+            GenesisError::InvalidFaucet(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidEthClaim(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidPool(reason) => write!(f, "{}", reason),
+            GenesisError::InvalidCollection(reason) => write!(f, "{}", reason),
+            GenesisError::DuplicateWhitelisted(account) => {
+                write!(f, "account {} is whitelisted more than once", account)
+            }
+            GenesisError::InvalidOracle(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Check `stakers` against the endowments and session keys of `genesis`. The resulting config
+/// elects up to twice as many validators as there are genesis authorities. Elections with fewer
+/// candidates than authorities keep the current validators.
+fn staking_genesis(
+    genesis: &GenesisConfig,
+    stakers: Vec<Staker>,
+) -> Result<StakingConfig, GenesisError> {
+    let endowment = |account: &AccountId| {
+        genesis
+            .balances
+            .iter()
+            .flat_map(|config| config.balances.iter())
+            .find(|(endowed, _)| endowed == account)
+            .map(|(_, balance)| *balance)
+            .unwrap_or(0)
+    };
+    let authorities: BTreeSet<&AccountId> = genesis
+        .session
+        .iter()
+        .flat_map(|config| config.keys.iter())
+        .map(|(account, _)| account)
+        .collect();
+    let validators: Vec<AccountId> = stakers
+        .iter()
+        .filter(|(_, _, _, role)| *role == StakerStatus::Validator)
+        .map(|(stash, _, _, _)| stash.clone())
+        .collect();
+    let mut bonded = BTreeSet::new();
+    for (stash, controller, bond, role) in &stakers {
+        if !bonded.insert(stash) || (controller != stash && !bonded.insert(controller)) {
+            return Err(GenesisError::InvalidStaker(
+                "account is bonded more than once",
+            ));
+        }
+        if *bond < EXISTENTIAL_DEPOSIT {
+            return Err(GenesisError::InvalidStaker(
+                "bond must be at least the existential deposit",
+            ));
+        }
+        if endowment(stash) < *bond {
+            return Err(GenesisError::InvalidStaker(
+                "stash must be endowed with at least its bond",
+            ));
+        }
+        match role {
+            StakerStatus::Validator if !authorities.contains(stash) => {
+                return Err(GenesisError::InvalidStaker(
+                    "validator stash must be a genesis authority",
+                ));
+            }
+            StakerStatus::Nominator(targets) if targets.iter().any(|t| !validators.contains(t)) => {
+                return Err(GenesisError::InvalidStaker(
+                    "nominations must target genesis validators",
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(StakingConfig {
+        validator_count: authorities.len() as u32 * 2,
+        minimum_validator_count: authorities.len() as u32,
+        invulnerables: validators,
+        stakers,
+        ..Default::default()
+    })
+}
+
+/// Check that token ids are unique and supplies fit in a Balance.
+fn assets_genesis(tokens: Vec<TokenSpec>) -> Result<AssetsConfig, GenesisError> {
+    let mut ids = BTreeSet::new();
+    for token in &tokens {
+        if !ids.insert(token.id) {
+            return Err(GenesisError::InvalidToken("token id is used twice"));
+        }
+        if token.symbol.is_empty() || token.symbol.len() > 32 {
+            return Err(GenesisError::InvalidToken(
+                "token symbol must be 1 to 32 bytes long",
+            ));
+        }
+        let mut holders = BTreeSet::new();
+        let mut total_supply: Balance = 0;
+        for (account, balance) in &token.distribution {
+            if !holders.insert(account) {
+                return Err(GenesisError::InvalidToken(
+                    "account is listed twice in a token distribution",
+                ));
+            }
+            total_supply = total_supply
+                .checked_add(*balance)
+                .ok_or(GenesisError::InvalidToken("token supply overflows"))?;
+        }
+    }
+    Ok(AssetsConfig {
+        assets: tokens
+            .into_iter()
+            .map(|token| {
+                (
+                    token.id,
+                    token.symbol.into_bytes(),
+                    token.decimals,
+                    token.owner,
+                    token.distribution,
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Check that pools pair two distinct genesis tokens, once, with deposits their providers hold.
+fn dex_genesis(tokens: &[TokenSpec], pools: Vec<PoolSpec>) -> Result<DexConfig, GenesisError> {
+    let mut holdings = BTreeMap::new();
+    for token in tokens {
+        for (account, balance) in &token.distribution {
+            holdings.insert((token.id, account), *balance);
+        }
+    }
+    let is_token = |id| tokens.iter().any(|token| token.id == id);
+    let mut pairs = BTreeSet::new();
+    for pool in &pools {
+        if pool.asset_a == pool.asset_b {
+            return Err(GenesisError::InvalidPool(
+                "a pool needs two different assets",
+            ));
+        }
+        if !is_token(pool.asset_a) || !is_token(pool.asset_b) {
+            return Err(GenesisError::InvalidPool(
+                "pool asset is not a genesis token",
+            ));
+        }
+        if !pairs.insert(dex::pool_key(pool.asset_a, pool.asset_b)) {
+            return Err(GenesisError::InvalidPool("two pools pair the same assets"));
+        }
+        if pool.amount_a == 0 || pool.amount_b == 0 {
+            return Err(GenesisError::InvalidPool(
+                "pools start with some of both assets",
+            ));
+        }
+        for (asset, amount) in &[(pool.asset_a, pool.amount_a), (pool.asset_b, pool.amount_b)] {
+            let held = holdings.entry((*asset, &pool.provider)).or_insert(0);
+            *held = held.checked_sub(*amount).ok_or(GenesisError::InvalidPool(
+                "pool provider holds less than it deposits",
+            ))?;
+        }
+    }
+    Ok(DexConfig {
+        pools: pools
+            .into_iter()
+            .map(|pool| {
+                (
+                    pool.asset_a,
+                    pool.asset_b,
+                    pool.provider,
+                    pool.amount_a,
+                    pool.amount_b,
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Check that collection ids are unique.
+fn nft_genesis(collections: Vec<CollectionSpec>) -> Result<NftConfig, GenesisError> {
+    let mut ids = BTreeSet::new();
+    for collection in &collections {
+        if !ids.insert(collection.id) {
+            return Err(GenesisError::InvalidCollection(
+                "collection id is used twice",
+            ));
+        }
+    }
+    Ok(NftConfig {
+        classes: collections
+            .into_iter()
+            .map(|collection| {
+                (
+                    collection.id,
+                    collection.owner,
+                    collection.metadata.into_bytes(),
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Check that oracle feeders are listed once and fetch from an http url.
+fn oracle_genesis(feeders: Vec<OracleId>, endpoint: String) -> Result<OracleConfig, GenesisError> {
+    let mut keys = BTreeSet::new();
+    for feeder in &feeders {
+        if !keys.insert(feeder) {
+            return Err(GenesisError::InvalidOracle("oracle feeder is listed twice"));
+        }
+    }
+    if !endpoint.is_empty() && !endpoint.starts_with("http://") && !endpoint.starts_with("https://")
+    {
+        return Err(GenesisError::InvalidOracle(
+            "oracle endpoint is not an http url",
+        ));
+    }
+    Ok(OracleConfig {
+        feeders,
+        endpoint: endpoint.into_bytes(),
+    })
+}
+
+/// Check that registrars are listed once and judge known identity fields.
+fn registrars_genesis(registrars: Vec<RegistrarSpec>) -> Result<RegistrarsConfig, GenesisError> {
+    let mut accounts = BTreeSet::new();
+    for registrar in &registrars {
+        if !accounts.insert(&registrar.account) {
+            return Err(GenesisError::DuplicateRegistrar(registrar.account.clone()));
+        }
+    }
+    Ok(RegistrarsConfig {
+        registrars: registrars
+            .into_iter()
+            .map(|registrar| {
+                let fields = field_bits(registrar.fields.iter().map(String::as_str))
+                    .map_err(|field| GenesisError::UnknownIdentityField(field.to_owned()))?;
+                Ok((registrar.account, registrar.fee, fields))
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Check that vesting accounts hold what they vest, and vest it only once.
+fn vesting_genesis(
+    genesis: &GenesisConfig,
+    schedules: Vec<(AccountId, VestingSchedule)>,
+) -> Result<VestingConfig, GenesisError> {
+    let mut vesting = BTreeSet::new();
+    for (account, schedule) in &schedules {
+        // schedules built by hand skip vesting_schedule's checks
+        vesting_schedule(
+            schedule.start_block,
+            schedule.cliff,
+            schedule.duration,
+            schedule.amount,
+        )?;
+        if !vesting.insert(account) {
+            return Err(GenesisError::InvalidVesting(
+                "account has more than one vesting schedule",
+            ));
+        }
+        let endowment = genesis
+            .balances
+            .iter()
+            .flat_map(|config| config.balances.iter())
+            .find(|(endowed, _)| endowed == account)
+            .map(|(_, balance)| *balance)
+            .unwrap_or(0);
+        if endowment < schedule.amount {
+            return Err(GenesisError::InvalidVesting(
+                "vesting schedule amount exceeds the account's endowment",
+            ));
+        }
+    }
+    Ok(VestingConfig { vesting: schedules })
+}
+
+/// Check that each ethereum address claims once and at least the existential deposit, returning
+/// the config along with the sum of the claims.
+fn eth_claims_genesis(
+    snapshot: Vec<(EthereumAddress, Balance)>,
+) -> Result<(EthClaimsConfig, Balance), GenesisError> {
+    let mut addresses = BTreeSet::new();
+    let mut total: Balance = 0;
+    for (address, balance) in &snapshot {
+        if !addresses.insert(address) {
+            return Err(GenesisError::InvalidEthClaim(
+                "ethereum address has more than one claim",
+            ));
+        }
+        // a claim creates the account it pays
+        if *balance < EXISTENTIAL_DEPOSIT {
+            return Err(GenesisError::InvalidEthClaim(
+                "ethereum claims must be at least the existential deposit",
+            ));
+        }
+        total = total
+            .checked_add(*balance)
+            .ok_or(GenesisError::IssuanceOverflow)?;
+    }
+    Ok((EthClaimsConfig { claims: snapshot }, total))
+}
+
+/// Check that scheduled calls decode and are due after genesis.
+fn scheduler_genesis(calls: Vec<ScheduledCall>) -> Result<SchedulerConfig, GenesisError> {
+    for scheduled in &calls {
+        if scheduled.when == 0 {
+            return Err(GenesisError::InvalidScheduledCall(
+                "calls must be scheduled after genesis",
+            ));
+        }
+        if let Some((period, count)) = scheduled.periodic {
+            if period == 0 || count == 0 {
+                return Err(GenesisError::InvalidScheduledCall(
+                    "periodic calls need a period and a count of at least 1",
+                ));
+            }
+        }
+        let mut input = &scheduled.call[..];
+        if Call::decode(&mut input).is_err() || !input.is_empty() {
+            return Err(GenesisError::InvalidScheduledCall(
+                "scheduled call is not an encoded runtime call",
+            ));
+        }
+    }
+    Ok(SchedulerConfig {
+        scheduled: calls
+            .into_iter()
+            .map(|scheduled| (scheduled.when, scheduled.periodic, scheduled.call))
+            .collect(),
+    })
+}
+
+/// Read a compiled runtime from a wasm file.
+pub fn wasm_from_file(path: &Path) -> Result<Vec<u8>, String> {
+    let code = std::fs::read(path)
+        .map_err(|e| format!("Error reading runtime wasm {}: {}", path.display(), e))?;
+    validate_wasm(&code).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(code)
+}
+
+/// Check that `code` starts with the header of a version 1 wasm module.
+pub fn validate_wasm(code: &[u8]) -> Result<(), &'static str> {
+    if code.is_empty() {
+        return Err("runtime wasm is empty, was the runtime built without the \
+                    wasm32-unknown-unknown target installed?");
+    }
+    if !code.starts_with(b"\0asm\x01\0\0\0") {
+        return Err("runtime wasm is not a wasm module");
+    }
+    Ok(())
+}
+
+/// Deterministically compute the account controlled by `threshold` of `signatories`. The order of
+/// signatories does not matter. The derivation is the multisig module's, so the signatories can
+/// make calls from the account as soon as the chain starts.
+pub fn multisig_account(
+    signatories: &[AccountId],
+    threshold: u16,
+) -> Result<AccountId, &'static str> {
+    let mut signatories = signatories.to_vec();
+    signatories.sort();
+    signatories.dedup();
+    if threshold == 0 || usize::from(threshold) > signatories.len() {
+        return Err("multisig threshold must be between 1 and the number of signatories");
+    }
+    Ok(multi_account_id(&signatories, threshold))
+}
+
+/// Check that a bootnode is a multiaddress ending with a peer id, e.g.
+/// "/dns4/example.com/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR".
+fn parse_bootnode(imp: &str) -> Result<String, &'static str> {
+    if !imp.starts_with('/') {
+        return Err("bootnode should be a multiaddress starting with '/'");
+    }
+    let mut protocols = imp.rsplit('/');
+    let peer_id = protocols.next().unwrap_or_default();
+    if protocols.next() != Some("p2p") || peer_id.is_empty() {
+        return Err("bootnode multiaddress should end with '/p2p/<peer id>'");
+    }
+    Ok(imp.to_owned())
+}
+
+/// Parse an endowment formatted as "<ss58>:<amount>".
+fn parse_endowment(imp: &str) -> Result<(AccountId, Balance), String> {
+    let mut parts = imp.splitn(2, ':');
+    match (parts.next(), parts.next()) {
+        (Some(address), Some(amount)) => {
+            let amount = amount
+                .parse()
+                .map_err(|_| format!("endowment {:?} should be a whole amount", amount))?;
+            Ok((from_ss58(address)?, amount))
+        }
+        _ => Err("endowment should be formatted as '<ss58>:<amount>'".into()),
+    }
+}
+
+/// Parse a telemetry endpoint formatted as "<url> <verbosity>".
+fn parse_telemetry_endpoint(imp: &str) -> Result<(String, u8), &'static str> {
+    let mut parts = imp.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(url), Some(verbosity), None) => {
+            let verbosity = verbosity
+                .parse()
+                .map_err(|_| "telemetry verbosity should be a number from 0 to 255")?;
+            Ok((url.to_owned(), verbosity))
+        }
+        _ => Err("telemetry endpoint should be formatted as '<url> <verbosity>'"),
+    }
+}
+
+pub(crate) fn parse_pubkey<T: Public>(imp: &str) -> Result<T, &'static str> {
+    let imp: &[u8] = imp.as_bytes();
+
+    // check key is 0x prefixed, remove prefix
+    let imp: &[u8] = if imp.starts_with(b"0x") {
+        &imp[2..]
+    } else {
+        return Err("public key shoud be prefixed with '0x'");
+    };
+
+    // check key is correct len
+    if imp.len() != 64 {
+        return Err("256 bit public key should be 64 hex digits");
+    }
+
+    // decode hex
+    let pk: Vec<u8> = hex::decode(imp).map_err(|err| {
+        use hex::FromHexError::*;
+        match err {
+            InvalidHexCharacter { .. } => "invalid hex character, must be [0-9][a-z][A-Z]",
+            OddLength => panic!("this should not happen"),
+            InvalidStringLength => panic!("this should not happen"),
+        }
+    })?;
+
+    assert_eq!(pk.len(), 32);
+
+    Ok(Public::from_slice(&pk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::{authority_keys_from_seed, get_from_seed};
+    use crate::ss58::to_ss58;
+    use codec::Encode;
+    use structopt::StructOpt;
+
+    fn ved() -> Chain {
+        Chain::Ved {
+            options: Default::default(),
+        }
+    }
+
+    #[test]
+    fn t_parse_pk() {
+        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
+        parse_pubkey::<GrandpaId>(valid_pk).unwrap();
+        parse_pubkey::<BabeId>(valid_pk).unwrap();
+        parse_pubkey::<AccountId>(valid_pk).unwrap();
+        for invalid_pk in &[
+            "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305",
+            "6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c",
             " 0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c",
             "5EZLPYKPLdfHutUAxx7hYVqwxmtjcw6MrtNygajayUDQzoSM",
             "//Alice",
@@ -199,11 +1884,18 @@ mod tests {
                 validator_babe: parse_pubkey::<BabeId>(valid_pk).unwrap(),
                 root_key: parse_pubkey::<AccountId>(valid_pk).unwrap(),
                 treasury: parse_pubkey::<AccountId>(valid_pk).unwrap(),
+                validator_account: None,
+                validator_im_online: None,
+                options: Default::default(),
+            },
+            ved(),
+            Chain::Local {
+                options: Default::default(),
             },
-            Chain::Ved,
+            Chain::DevMultisig,
         ] {
-            chain.clone().generate().into_json(true).unwrap();
-            chain.clone().generate().into_json(false).unwrap();
+            chain.clone().generate().unwrap().into_json(true).unwrap();
+            chain.clone().generate().unwrap().into_json(false).unwrap();
         }
     }
 
@@ -216,8 +1908,12 @@ mod tests {
             validator_babe: parse_pubkey::<BabeId>(valid_pk).unwrap(),
             root_key: parse_pubkey::<AccountId>(valid_pk).unwrap(),
             treasury: parse_pubkey::<AccountId>(valid_pk).unwrap(),
+            validator_account: None,
+            validator_im_online: None,
+            options: Default::default(),
         }
-        .generate();
+        .generate()
+        .unwrap();
         let prot_id = genesis.protocol_id().unwrap();
         assert_eq!(
             prot_id,
@@ -228,4 +1924,1849 @@ mod tests {
              5EZLPYKPLdfHutUAxx7hYVqwxmtjcw6MrtNygajayUDQzoSM"
         );
     }
+
+    #[test]
+    fn t_staging() {
+        let bootnode = "/ip4/10.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR";
+        let config = |boot_nodes: &str, telemetry: &str| {
+            format!(
+                r#"
+                name = "Staging"
+                id = "staging"
+                sudo_key = "{alice}"
+                boot_nodes = [{boot_nodes}]
+
+                [[authorities]]
+                account = "{bob}"
+                grandpa = "{bob_grandpa}"
+                babe = "{bob_babe}"
+                im_online = "{bob_im_online}"
+
+                [[endowed_accounts]]
+                account = "{charlie}"
+                balance = 1000
+                {telemetry}
+                "#,
+                alice = Keyring::Alice.account(),
+                bob = Keyring::Bob.account(),
+                bob_grandpa = Keyring::Bob.grandpa(),
+                bob_babe = Keyring::Bob.babe(),
+                bob_im_online = Keyring::Bob.im_online(),
+                charlie = Keyring::Charlie.account(),
+                boot_nodes = boot_nodes,
+                telemetry = telemetry,
+            )
+        };
+        let boot_nodes = format!("\"{}\"", bootnode);
+        let telemetry =
+            "[[telemetry_endpoints]]\nurl = \"wss://telemetry.example.com/submit/\"\nverbosity = 1";
+        let dir = std::env::temp_dir().join("substrate-warmup-staging");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("staging.toml");
+
+        std::fs::write(&path, config(&boot_nodes, telemetry)).unwrap();
+        let spec = staging(&path).unwrap();
+        assert_eq!(spec.protocol_id(), Some(STAGING_PROTOCOL_ID));
+        assert_eq!(spec.boot_nodes(), &[bootnode.to_string()][..]);
+        let json: serde_json::Value =
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap();
+        assert_eq!(
+            json["telemetryEndpoints"],
+            serde_json::json!([["wss://telemetry.example.com/submit/", 1]])
+        );
+        let runtime = &json["genesis"]["runtime"];
+        let (_, bob_keys) = authority_keys_from_seed("Bob");
+        assert_eq!(
+            runtime["session"]["keys"],
+            serde_json::to_value(vec![(Keyring::Bob.account(), bob_keys)]).unwrap()
+        );
+        assert_eq!(
+            runtime["sudo"]["key"],
+            serde_json::to_value(Keyring::Alice.account()).unwrap()
+        );
+        let balances: Vec<(AccountId, serde_json::Value)> =
+            serde_json::from_value(runtime["balances"]["balances"].clone()).unwrap();
+        assert!(balances.contains(&(
+            Keyring::Charlie.account(),
+            serde_json::to_value(1000 as Balance).unwrap()
+        )));
+
+        for (config, missing) in &[
+            (config("", telemetry), "boot nodes"),
+            (config(&boot_nodes, ""), "telemetry endpoints"),
+        ] {
+            std::fs::write(&path, config).unwrap();
+            assert_eq!(
+                staging(&path).unwrap_err(),
+                format!(
+                    "Invalid staging config {}: chainspec {} was not set",
+                    path.display(),
+                    missing
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn t_clear_boot_nodes() {
+        let bootnode = "/ip4/10.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR";
+        let mut spec = Chain::Ved {
+            options: TestnetOptions {
+                boot_nodes: vec![bootnode.into()],
+                ..Default::default()
+            },
+        }
+        .generate()
+        .unwrap();
+        assert_eq!(spec.boot_nodes(), &[bootnode.to_string()][..]);
+        spec.clear_boot_nodes();
+        let json: serde_json::Value =
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap();
+        assert_eq!(json["bootNodes"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn t_builder_requires_fields() {
+        let complete = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(get_from_seed::<AccountId>("Alice"))
+        };
+        complete().build().unwrap();
+        complete().name("").build().unwrap_err();
+        complete().id("").build().unwrap_err();
+        complete().authorities(vec![]).build().unwrap_err();
+        ChainSpecBuilder::default()
+            .name("Test")
+            .id("test")
+            .authority(authority_keys_from_seed("Alice"))
+            .build()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn t_builder_endowed_accounts() {
+        let spec = ChainSpecBuilder::default()
+            .name("Test")
+            .id("test")
+            .authority(authority_keys_from_seed("Alice"))
+            .sudo_key(get_from_seed::<AccountId>("Alice"))
+            .endowed_accounts(vec![
+                (get_from_seed::<AccountId>("Bob"), EXISTENTIAL_DEPOSIT),
+                (
+                    get_from_seed::<AccountId>("Charlie"),
+                    EXISTENTIAL_DEPOSIT + 1,
+                ),
+            ])
+            .build()
+            .unwrap();
+        spec.into_json(true).unwrap();
+    }
+
+    #[test]
+    fn t_load_json_file() {
+        let dir = std::env::temp_dir().join("substrate-warmup-t_load_json_file");
+        std::fs::create_dir_all(&dir).unwrap();
+        for raw in &[true, false] {
+            let path = dir.join(format!("spec-{}.json", raw));
+            let spec = Chain::Local {
+                options: Default::default(),
+            }
+            .generate()
+            .unwrap();
+            let protocol_id = spec.protocol_id().map(str::to_string);
+            let json = spec.into_json(*raw).unwrap();
+            std::fs::write(&path, &json).unwrap();
+            let loaded = Chain::Load {
+                chain: ChainSpecVariant::Custom(path),
+            }
+            .generate()
+            .unwrap();
+            assert_eq!(
+                loaded.protocol_id(),
+                protocol_id.as_ref().map(String::as_str)
+            );
+            // raw storage is a hashmap so the field order in the output is not stable
+            let reloaded: serde_json::Value =
+                serde_json::from_str(&loaded.into_json(*raw).unwrap()).unwrap();
+            let original: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(reloaded, original);
+        }
+        from_json_file(&dir.join("does-not-exist.json")).unwrap_err();
+    }
+
+    #[test]
+    fn t_to_raw_json() {
+        let json: serde_json::Value = serde_json::from_str(&ved().to_raw_json().unwrap()).unwrap();
+        let genesis = json["genesis"].as_object().unwrap();
+        assert!(genesis.contains_key("raw"));
+        assert!(!genesis.contains_key("runtime"));
+    }
+
+    #[test]
+    fn t_endowments() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let genesis = testnet_genesis(
+            WASM_BINARY.to_vec(),
+            vec![authority_keys_from_seed("Alice")],
+            alice.clone(),
+            (alice.clone(), 1000),
+            vec![(bob.clone(), 2000)],
+            vec![],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            genesis.balances.unwrap().balances,
+            vec![(alice.clone(), 1000), (bob.clone(), 2000)]
+        );
+        assert_eq!(genesis.indices.unwrap().ids, vec![alice, bob]);
+    }
+
+    #[test]
+    fn t_spec_properties() {
+        for chain in &[
+            ved(),
+            Chain::Local {
+                options: Default::default(),
+            },
+        ] {
+            let properties = chain.clone().generate().unwrap().properties();
+            assert_eq!(properties, spec_properties());
+            assert_eq!(properties["tokenSymbol"], TOKEN_SYMBOL);
+            assert_eq!(properties["tokenDecimals"], TOKEN_DECIMALS);
+            assert_eq!(properties["ss58Format"], SS58_PREFIX);
+        }
+    }
+
+    #[test]
+    fn t_parse_telemetry_endpoint() {
+        assert_eq!(
+            parse_telemetry_endpoint("wss://telemetry.example.com/submit/ 3"),
+            Ok(("wss://telemetry.example.com/submit/".to_string(), 3))
+        );
+        for invalid in &[
+            "",
+            "wss://telemetry.example.com/submit/",
+            "wss://telemetry.example.com/submit/ 256",
+            "wss://telemetry.example.com/submit/ -1",
+            "wss://telemetry.example.com/submit/ 1 2",
+        ] {
+            parse_telemetry_endpoint(invalid).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_telemetry_endpoints() {
+        let dev = Chain::Ved {
+            options: TestnetOptions {
+                telemetry_endpoints: vec![("ws://127.0.0.1:8000/submit/".into(), 1)],
+                ..Default::default()
+            },
+        };
+        let dev: serde_json::Value = serde_json::from_str(&dev.to_raw_json().unwrap()).unwrap();
+        assert_eq!(
+            dev["telemetryEndpoints"],
+            serde_json::json!([["ws://127.0.0.1:8000/submit/", 1]])
+        );
+    }
+
+    #[test]
+    fn t_parse_bootnode() {
+        let bootnode =
+            "/dns4/boot.example.com/tcp/30333/p2p/QmZT7tfWZb8dW8h4TEa64m46KJZLT6LJ6rdJrnbp9sAzLJ";
+        assert_eq!(parse_bootnode(bootnode).unwrap(), bootnode);
+        parse_bootnode(
+            "/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR",
+        )
+        .unwrap();
+        for invalid in &[
+            "",
+            "/ip4/127.0.0.1/tcp/30333",
+            "/ip4/127.0.0.1/tcp/30333/p2p/",
+            "ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR",
+            "127.0.0.1:30333",
+        ] {
+            parse_bootnode(invalid).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_local_bootnodes() {
+        let bootnode = "/ip4/10.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR";
+        let spec = Chain::Ved {
+            options: TestnetOptions {
+                boot_nodes: vec![bootnode.to_string()],
+                ..Default::default()
+            },
+        }
+        .generate()
+        .unwrap();
+        assert_eq!(spec.boot_nodes(), &[bootnode.to_string()]);
+        assert!(ved().generate().unwrap().boot_nodes().is_empty());
+    }
+
+    #[test]
+    fn t_default_protocol_id() {
+        assert_eq!(
+            ved().generate().unwrap().protocol_id(),
+            Some("substrate-warmup-local")
+        );
+        let spec = Chain::Ved {
+            options: TestnetOptions {
+                protocol_id: Some("warmup-ci".to_string()),
+                ..Default::default()
+            },
+        }
+        .generate()
+        .unwrap();
+        assert_eq!(spec.protocol_id(), Some("warmup-ci"));
+    }
+
+    #[test]
+    fn t_grandpa_authorities() {
+        let authorities = vec![
+            authority_keys_from_seed("Alice"),
+            authority_keys_from_seed("Bob"),
+        ];
+        let alice = get_from_seed::<AccountId>("Alice");
+        let genesis = testnet_genesis(
+            WASM_BINARY.to_vec(),
+            authorities.clone(),
+            alice.clone(),
+            (alice, DEFAULT_ENDOWMENT),
+            vec![],
+            vec![],
+            None,
+        )
+        .unwrap();
+        assert_eq!(genesis.session.unwrap().keys, authorities);
+        assert_eq!(
+            genesis.validator_set.unwrap().validators,
+            authorities
+                .iter()
+                .map(|(account, _)| account.clone())
+                .collect::<Vec<_>>()
+        );
+        assert!(genesis.grandpa.unwrap().authorities.is_empty());
+        assert!(genesis.babe.unwrap().authorities.is_empty());
+    }
+
+    #[test]
+    fn t_authorities_in_storage() {
+        let authorities = vec![authority_keys_from_seed("Alice")];
+        let alice = get_from_seed::<AccountId>("Alice");
+        let genesis = testnet_genesis(
+            WASM_BINARY.to_vec(),
+            authorities.clone(),
+            alice.clone(),
+            (alice, DEFAULT_ENDOWMENT),
+            vec![],
+            vec![],
+            None,
+        )
+        .unwrap();
+        // the session module hands the genesis keys to grandpa while building storage
+        let (top, _) = genesis.build_storage().unwrap();
+        let grandpa_authorities: Vec<(GrandpaId, u64)> =
+            codec::Decode::decode(&mut &top[well_known_keys::GRANDPA_AUTHORITIES][..]).unwrap();
+        assert_eq!(
+            grandpa_authorities,
+            vec![(authorities[0].1.grandpa.clone(), 1)]
+        );
+    }
+
+    #[test]
+    fn t_vesting() {
+        let bob = get_from_seed::<AccountId>("Bob");
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(get_from_seed::<AccountId>("Alice"))
+        };
+        let schedule = Vesting {
+            account: bob.clone(),
+            begin: 10,
+            length: 100,
+            liquid: 5,
+        };
+        builder()
+            .endowed_accounts(vec![(bob.clone(), 1000)])
+            .vesting(vec![schedule.clone()])
+            .build()
+            .unwrap()
+            .into_json(true)
+            .unwrap();
+        builder()
+            .vesting(vec![schedule.clone()])
+            .build()
+            .unwrap_err();
+        builder()
+            .endowed_accounts(vec![(bob.clone(), 1000)])
+            .vesting(vec![Vesting {
+                length: 0,
+                ..schedule.clone()
+            }])
+            .build()
+            .unwrap_err();
+
+        let alice = get_from_seed::<AccountId>("Alice");
+        let genesis = testnet_genesis(
+            WASM_BINARY.to_vec(),
+            vec![authority_keys_from_seed("Alice")],
+            alice.clone(),
+            (alice, 1000),
+            vec![(bob.clone(), 1000)],
+            vec![schedule],
+            None,
+        )
+        .unwrap();
+        assert_eq!(genesis.balances.unwrap().vesting, vec![(bob, 10, 100, 5)]);
+    }
+
+    #[test]
+    fn t_multisig_account() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let charlie = get_from_seed::<AccountId>("Charlie");
+        let abc = multisig_account(&[alice.clone(), bob.clone(), charlie.clone()], 2).unwrap();
+        assert_eq!(
+            multisig_account(&[charlie.clone(), alice.clone(), bob.clone()], 2),
+            Ok(abc.clone())
+        );
+        assert_ne!(
+            multisig_account(&[alice.clone(), bob.clone(), charlie.clone()], 3),
+            Ok(abc.clone())
+        );
+        assert_ne!(
+            multisig_account(&[alice.clone(), bob.clone()], 2),
+            Ok(abc.clone())
+        );
+        assert!(![alice.clone(), bob.clone(), charlie.clone()].contains(&abc));
+        multisig_account(&[alice.clone(), bob.clone()], 0).unwrap_err();
+        multisig_account(&[alice.clone(), bob.clone()], 3).unwrap_err();
+        multisig_account(&[alice.clone(), alice], 2).unwrap_err();
+    }
+
+    #[test]
+    fn t_load_accounts() {
+        let accounts = derived_accounts("load", 3);
+        let builder = ChainSpecBuilder::default().options(TestnetOptions {
+            load_accounts: 3,
+            ..Default::default()
+        });
+        assert_eq!(
+            builder.endowed_accounts,
+            accounts
+                .into_iter()
+                .map(|account| (account, DEFAULT_ENDOWMENT))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn t_endow_and_sudo_key_flags() {
+        let bob = Keyring::Bob.account();
+        let charlie = Keyring::Charlie.account();
+        let options = TestnetOptions::from_iter(&[
+            "options",
+            "--endow",
+            &format!("{}:{}", to_ss58(&bob), EXISTENTIAL_DEPOSIT),
+            "--endow",
+            &format!("{}:7000", charlie),
+            "--sudo-key",
+            &to_ss58(&charlie),
+        ]);
+        assert_eq!(
+            options.endowments,
+            vec![(bob.clone(), EXISTENTIAL_DEPOSIT), (charlie.clone(), 7000)]
+        );
+        let spec = Chain::Ved { options }.generate().unwrap();
+        let config = spec.genesis_config().unwrap();
+        let balances = &config.balances.as_ref().unwrap().balances;
+        assert!(balances.contains(&(bob, EXISTENTIAL_DEPOSIT)));
+        assert!(balances.contains(&(charlie.clone(), 7000)));
+        assert_eq!(config.sudo.as_ref().unwrap().key, charlie);
+
+        parse_endowment(&to_ss58(&charlie)).unwrap_err();
+        parse_endowment(&format!("{}:lots", charlie)).unwrap_err();
+        parse_endowment("5Grwva:1").unwrap_err();
+    }
+
+    #[test]
+    fn t_genesis_validation() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let genesis = |authorities, endowed_accounts| {
+            testnet_genesis(
+                WASM_BINARY.to_vec(),
+                authorities,
+                alice.clone(),
+                (alice.clone(), DEFAULT_ENDOWMENT),
+                endowed_accounts,
+                vec![],
+                None,
+            )
+        };
+        let authorities = || vec![authority_keys_from_seed("Alice")];
+        genesis(authorities(), vec![(bob.clone(), EXISTENTIAL_DEPOSIT)]).unwrap();
+        assert_eq!(
+            genesis(vec![], vec![]).unwrap_err(),
+            GenesisError::NoAuthorities
+        );
+        assert_eq!(
+            genesis(
+                authorities(),
+                vec![(bob.clone(), 1000), (bob.clone(), 1000)]
+            )
+            .unwrap_err(),
+            GenesisError::DuplicateAccount(bob.clone())
+        );
+        assert_eq!(
+            genesis(authorities(), vec![(alice.clone(), 1000)]).unwrap_err(),
+            GenesisError::DuplicateAccount(alice.clone())
+        );
+        assert_eq!(
+            genesis(authorities(), vec![(bob.clone(), 0)]).unwrap_err(),
+            GenesisError::BelowExistentialDeposit(bob.clone())
+        );
+        assert_eq!(
+            genesis(authorities(), vec![(bob.clone(), EXISTENTIAL_DEPOSIT - 1)]).unwrap_err(),
+            GenesisError::BelowExistentialDeposit(bob.clone())
+        );
+        assert_eq!(
+            genesis(authorities(), vec![(bob, Balance::max_value())]).unwrap_err(),
+            GenesisError::IssuanceOverflow
+        );
+    }
+
+    #[test]
+    fn t_max_total_issuance() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(get_from_seed::<AccountId>("Alice"))
+                .treasury_endowment(1000)
+                .endowed_accounts(vec![(get_from_seed::<AccountId>("Bob"), 500)])
+                .faucet_endowment(500)
+        };
+        builder().build().unwrap();
+        builder().max_total_issuance(2000).build().unwrap();
+        assert_eq!(
+            builder().max_total_issuance(1999).build().unwrap_err(),
+            GenesisError::IssuanceAboveCap {
+                total_issuance: 2000,
+                cap: 1999
+            }
+        );
+    }
+
+    #[test]
+    fn t_wasm_from_file() {
+        let dir = std::env::temp_dir().join("substrate-warmup-t_wasm_from_file");
+        std::fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let module = b"\0asm\x01\0\0\0".to_vec();
+        let valid = write("valid.wasm", &module);
+        assert_eq!(wasm_from_file(&valid).unwrap(), module);
+        wasm_from_file(&write("empty.wasm", b"")).unwrap_err();
+        wasm_from_file(&write("text.wasm", b"(module)")).unwrap_err();
+        wasm_from_file(&dir.join("missing.wasm")).unwrap_err();
+
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(get_from_seed::<AccountId>("Alice"))
+        };
+        builder().wasm(&valid).build().unwrap();
+        match builder().wasm(&dir.join("missing.wasm")).build() {
+            Err(GenesisError::Wasm(_)) => {}
+            other => panic!("expected a wasm error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn t_validate_wasm() {
+        validate_wasm(WASM_BINARY).unwrap();
+        let alice = get_from_seed::<AccountId>("Alice");
+        let genesis = |code: &[u8]| {
+            testnet_genesis(
+                code.to_vec(),
+                vec![authority_keys_from_seed("Alice")],
+                alice.clone(),
+                (alice.clone(), DEFAULT_ENDOWMENT),
+                vec![],
+                vec![],
+                None,
+            )
+        };
+        genesis(WASM_BINARY).unwrap();
+        for invalid in &[&b""[..], b"\0asm", b"\0asm\x02\0\0\0", b"not wasm"] {
+            match genesis(invalid) {
+                Err(GenesisError::Wasm(_)) => {}
+                _ => panic!("invalid wasm accepted"),
+            }
+        }
+    }
+
+    #[test]
+    fn t_chain_spec_variant() {
+        assert_eq!("dev".parse(), Ok(ChainSpecVariant::Dev));
+        assert_eq!("local".parse(), Ok(ChainSpecVariant::Local));
+        assert_eq!("staging".parse(), Ok(ChainSpecVariant::Staging));
+        assert_eq!(
+            "specs/dev.json".parse(),
+            Ok(ChainSpecVariant::Custom("specs/dev.json".into()))
+        );
+        "".parse::<ChainSpecVariant>().unwrap_err();
+
+        let json = |spec: ChainSpec<GenesisConfig>| -> serde_json::Value {
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap()
+        };
+        assert_eq!(
+            json(ChainSpecVariant::Dev.load().unwrap()),
+            json(ved().generate().unwrap())
+        );
+        assert_eq!(
+            json(ChainSpecVariant::Local.load().unwrap()),
+            json(
+                Chain::Local {
+                    options: TestnetOptions::default()
+                }
+                .generate()
+                .unwrap()
+            )
+        );
+        ChainSpecVariant::Custom("/does/not/exist.json".into())
+            .load()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn t_extensions() {
+        let json = ved().generate().unwrap().into_json(false).unwrap();
+        assert!(!json.contains("forkBlocks"));
+        assert!(!json.contains("badBlocks"));
+
+        let fork = H256::repeat_byte(1);
+        let bad = H256::repeat_byte(2);
+        let spec = ChainSpecBuilder::default()
+            .name("Test")
+            .id("test")
+            .authority(authority_keys_from_seed("Alice"))
+            .sudo_key(get_from_seed::<AccountId>("Alice"))
+            .fork_blocks(vec![(10, fork)])
+            .bad_blocks(vec![bad])
+            .build()
+            .unwrap();
+        let expected = Extensions {
+            fork_blocks: Some(vec![(10, fork)]),
+            bad_blocks: Some(iter::once(bad).collect()),
+        };
+        assert_eq!(spec.extensions(), &expected);
+
+        // operators add the extensions to an existing spec by hand
+        let mut edited: serde_json::Value = serde_json::from_str(&json).unwrap();
+        edited["forkBlocks"] = serde_json::json!([[10, format!("{:?}", fork)]]);
+        edited["badBlocks"] = serde_json::json!([format!("{:?}", bad)]);
+        let dir = std::env::temp_dir().join("substrate-warmup-t_extensions");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("edited.json");
+        std::fs::write(&path, edited.to_string()).unwrap();
+        let loaded = from_json_file(&path).unwrap();
+        assert_eq!(loaded.extensions(), &expected);
+        let reloaded: serde_json::Value =
+            serde_json::from_str(&loaded.into_json(false).unwrap()).unwrap();
+        assert_eq!(reloaded, edited);
+    }
+
+    #[test]
+    fn t_genesis_hash() {
+        let dev = genesis_hash(ved().generate().unwrap()).unwrap();
+        assert_eq!(dev, genesis_hash(ved().generate().unwrap()).unwrap());
+        assert_ne!(
+            dev,
+            genesis_hash(
+                Chain::Local {
+                    options: Default::default()
+                }
+                .generate()
+                .unwrap()
+            )
+            .unwrap()
+        );
+
+        // a raw chainspec has the same genesis as the config it was generated from
+        let dir = std::env::temp_dir().join("substrate-warmup-t_genesis_hash");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("raw.json");
+        std::fs::write(&path, ved().to_raw_json().unwrap()).unwrap();
+        assert_eq!(genesis_hash(from_json_file(&path).unwrap()).unwrap(), dev);
+    }
+
+    /// A chainspec's json with its runtime code left out, as that changes with every build.
+    fn without_code(json: &str) -> String {
+        let mut spec: serde_json::Value = serde_json::from_str(json).unwrap();
+        spec["genesis"]["runtime"]["system"]["code"] = serde_json::Value::String("0x".into());
+        serde_json::to_string_pretty(&spec).unwrap() + "\n"
+    }
+
+    /// Check `actual` against the golden file `file` under src/golden, returning its path. Run
+    /// with WARMUP_BLESS=1 to write golden files, the first time or after an intended change.
+    fn check_golden(file: &str, actual: &str) -> PathBuf {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/golden");
+        let path = dir.join(file);
+        if std::env::var_os("WARMUP_BLESS").is_some() {
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&path, actual).unwrap();
+        }
+        let golden = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("{}: {}, run with WARMUP_BLESS=1", path.display(), e));
+        assert!(
+            actual == golden,
+            "{} is out of date; if the change is intended, run with WARMUP_BLESS=1 and review \
+             the diff",
+            path.display()
+        );
+        path
+    }
+
+    /// Check the json of `chain` against its golden file, then load the golden file back.
+    fn check_golden_spec(chain: Chain, name: &str) {
+        let json = without_code(&chain.generate().unwrap().into_json(false).unwrap());
+        let path = check_golden(&format!("{}.json", name), &json);
+        // e.g. a renamed genesis field fails here, as configs deny unknown fields
+        let loaded = from_json_file(&path).unwrap();
+        assert_eq!(without_code(&loaded.into_json(false).unwrap()), json);
+    }
+
+    #[test]
+    fn t_golden_specs() {
+        check_golden_spec(ved(), "ved");
+        check_golden_spec(
+            Chain::Local {
+                options: Default::default(),
+            },
+            "local",
+        );
+    }
+
+    #[test]
+    fn t_pinned_genesis_hash() {
+        // the hash of the genesis header, whose state root covers the runtime code, so rebuilding
+        // the runtime, or building it with another toolchain, also needs a bless
+        let hash = genesis_header(ved().generate().unwrap()).unwrap().hash();
+        check_golden("ved.genesis-hash", &format!("{:?}\n", hash));
+    }
+
+    #[test]
+    fn t_genesis_header_and_wasm() {
+        let header = genesis_header(ved().generate().unwrap()).unwrap();
+        assert_eq!(*header.number(), 0);
+        assert_eq!(
+            header.hash(),
+            genesis_hash(ved().generate().unwrap()).unwrap()
+        );
+        assert_eq!(
+            genesis_wasm(ved().generate().unwrap()).unwrap(),
+            WASM_BINARY.to_vec()
+        );
+    }
+
+    #[test]
+    fn t_chain_info() {
+        let info = ChainInfo::of(ved().generate().unwrap()).unwrap();
+        assert_eq!(info.name, "Substrate Warmup Local Dev Testnet");
+        assert_eq!(info.id, "substrate-warmup-local");
+        assert_eq!(
+            info.genesis_hash,
+            genesis_hash(ved().generate().unwrap()).unwrap()
+        );
+        assert_eq!(info.spec_version, Some(VERSION.spec_version));
+        assert!(info
+            .to_string()
+            .contains(&format!("genesis hash: {:?}", info.genesis_hash)));
+
+        let dir = std::env::temp_dir().join("substrate-warmup-t_chain_info");
+        std::fs::create_dir_all(&dir).unwrap();
+        let wasm = dir.join("other.wasm");
+        std::fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+        let other = ChainSpecBuilder::default()
+            .name("Test")
+            .id("test")
+            .authority(authority_keys_from_seed("Alice"))
+            .sudo_key(get_from_seed::<AccountId>("Alice"))
+            .wasm(&wasm)
+            .build()
+            .unwrap();
+        assert_eq!(ChainInfo::of(other).unwrap().spec_version, None);
+    }
+
+    #[test]
+    fn t_millisecs_per_block() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(get_from_seed::<AccountId>("Alice"))
+        };
+        let json = |spec: ChainSpec<GenesisConfig>| -> serde_json::Value {
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap()
+        };
+        let default = json(builder().build().unwrap());
+        assert_eq!(
+            default["genesis"]["runtime"]["blockTime"],
+            serde_json::Value::Null
+        );
+        let fast = json(builder().millisecs_per_block(1000).build().unwrap());
+        assert_eq!(
+            fast["genesis"]["runtime"]["blockTime"]["millisecsPerBlock"],
+            1000
+        );
+        assert_ne!(
+            genesis_hash(builder().build().unwrap()).unwrap(),
+            genesis_hash(builder().millisecs_per_block(1000).build().unwrap()).unwrap()
+        );
+        assert_eq!(
+            builder().millisecs_per_block(1).build().unwrap_err(),
+            GenesisError::InvalidBlockTime
+        );
+    }
+
+    #[test]
+    fn t_stakers() {
+        let genesis = testnet_genesis(
+            WASM_BINARY.to_vec(),
+            vec![Keyring::Alice.authority()],
+            Keyring::Alice.account(),
+            (Keyring::Alice.stash(), DEFAULT_ENDOWMENT),
+            vec![(Keyring::Bob.stash(), 1000)],
+            vec![],
+            None,
+        )
+        .unwrap();
+        let staking = |stakers| staking_genesis(&genesis, stakers);
+        let alice = || {
+            (
+                Keyring::Alice.stash(),
+                Keyring::Alice.account(),
+                DEV_VALIDATOR_BOND,
+                StakerStatus::Validator,
+            )
+        };
+        let bob = |bond, role| (Keyring::Bob.stash(), Keyring::Bob.account(), bond, role);
+        let nominator = bob(1000, StakerStatus::Nominator(vec![Keyring::Alice.stash()]));
+        let config = staking(vec![alice(), nominator.clone()]).unwrap();
+        assert_eq!(config.stakers, vec![alice(), nominator]);
+        assert_eq!(config.invulnerables, vec![Keyring::Alice.stash()]);
+        assert_eq!(config.validator_count, 2);
+        assert_eq!(config.minimum_validator_count, 1);
+        staking(vec![]).unwrap();
+
+        let invalid = |stakers, reason| {
+            assert_eq!(
+                staking(stakers).unwrap_err(),
+                GenesisError::InvalidStaker(reason)
+            );
+        };
+        invalid(vec![alice(), alice()], "account is bonded more than once");
+        invalid(
+            vec![
+                alice(),
+                (
+                    Keyring::Bob.stash(),
+                    Keyring::Alice.account(),
+                    1000,
+                    StakerStatus::Idle,
+                ),
+            ],
+            "account is bonded more than once",
+        );
+        invalid(
+            vec![bob(EXISTENTIAL_DEPOSIT - 1, StakerStatus::Idle)],
+            "bond must be at least the existential deposit",
+        );
+        invalid(
+            vec![bob(1001, StakerStatus::Idle)],
+            "stash must be endowed with at least its bond",
+        );
+        invalid(
+            vec![bob(1000, StakerStatus::Validator)],
+            "validator stash must be a genesis authority",
+        );
+        invalid(
+            vec![bob(
+                1000,
+                StakerStatus::Nominator(vec![Keyring::Charlie.stash()]),
+            )],
+            "nominations must target genesis validators",
+        );
+    }
+
+    #[test]
+    fn t_dev_validator() {
+        let builder = ChainSpecBuilder::default().dev_validator(Keyring::Alice);
+        assert_eq!(builder.authorities, vec![Keyring::Alice.authority()]);
+        assert_eq!(
+            builder.endowed_accounts,
+            vec![(Keyring::Alice.stash(), DEFAULT_ENDOWMENT)]
+        );
+        assert_eq!(
+            builder.stakers,
+            vec![(
+                Keyring::Alice.stash(),
+                Keyring::Alice.account(),
+                DEV_VALIDATOR_BOND,
+                StakerStatus::Validator
+            )]
+        );
+        builder
+            .sudo_key(Keyring::Alice.account())
+            .name("Test")
+            .id("test")
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn t_local_stakers_are_endowed() {
+        let spec = Chain::Local {
+            options: Default::default(),
+        }
+        .generate()
+        .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap();
+        let balances: Vec<(AccountId, serde_json::Value)> =
+            serde_json::from_value(json["genesis"]["runtime"]["balances"]["balances"].clone())
+                .unwrap();
+        let endowed: Vec<AccountId> = balances.into_iter().map(|(account, _)| account).collect();
+        for key in &[Keyring::Alice, Keyring::Bob] {
+            assert!(endowed.contains(&key.stash()));
+            // controllers pay the fees of staking calls
+            assert!(endowed.contains(&key.account()));
+        }
+    }
+
+    #[test]
+    fn t_custom_im_online() {
+        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
+        let custom = |validator_im_online| {
+            let spec = Chain::Custom {
+                validator_grandpa: parse_pubkey::<GrandpaId>(valid_pk).unwrap(),
+                validator_babe: parse_pubkey::<BabeId>(valid_pk).unwrap(),
+                root_key: parse_pubkey::<AccountId>(valid_pk).unwrap(),
+                treasury: parse_pubkey::<AccountId>(valid_pk).unwrap(),
+                validator_account: None,
+                validator_im_online,
+                options: Default::default(),
+            }
+            .generate()
+            .unwrap();
+            genesis_hash(spec).unwrap()
+        };
+        // defaults to the babe key
+        assert_eq!(
+            custom(None),
+            custom(Some(parse_pubkey::<ImOnlineId>(valid_pk).unwrap()))
+        );
+        assert_ne!(custom(None), custom(Some(Keyring::Alice.im_online())));
+    }
+
+    #[test]
+    fn t_tokens() {
+        let token = |id, symbol: &str, distribution| TokenSpec {
+            id,
+            symbol: symbol.to_string(),
+            decimals: 6,
+            owner: Keyring::Alice.account(),
+            distribution,
+        };
+        let alice = || (Keyring::Alice.account(), 1000);
+        let bob = || (Keyring::Bob.account(), 10);
+        let config = assets_genesis(vec![
+            token(0, "USDX", vec![alice(), bob()]),
+            token(7, "GLD", vec![]),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.assets,
+            vec![
+                (
+                    0,
+                    b"USDX".to_vec(),
+                    6,
+                    Keyring::Alice.account(),
+                    vec![alice(), bob()]
+                ),
+                (7, b"GLD".to_vec(), 6, Keyring::Alice.account(), vec![]),
+            ]
+        );
+
+        let invalid = |tokens, reason| {
+            assert_eq!(
+                assets_genesis(tokens).unwrap_err(),
+                GenesisError::InvalidToken(reason)
+            );
+        };
+        invalid(
+            vec![token(0, "USDX", vec![]), token(0, "GLD", vec![])],
+            "token id is used twice",
+        );
+        invalid(
+            vec![token(0, "", vec![])],
+            "token symbol must be 1 to 32 bytes long",
+        );
+        invalid(
+            vec![token(0, &"X".repeat(33), vec![])],
+            "token symbol must be 1 to 32 bytes long",
+        );
+        invalid(
+            vec![token(0, "USDX", vec![alice(), alice()])],
+            "account is listed twice in a token distribution",
+        );
+        invalid(
+            vec![token(
+                0,
+                "USDX",
+                vec![alice(), (Keyring::Bob.account(), Balance::max_value())],
+            )],
+            "token supply overflows",
+        );
+    }
+
+    #[test]
+    fn t_council() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let council = vec![Keyring::Alice.account(), Keyring::Bob.account()];
+        assert_ne!(
+            genesis_hash(builder().build().unwrap()).unwrap(),
+            genesis_hash(builder().council(council.clone()).build().unwrap()).unwrap()
+        );
+        assert_eq!(
+            builder()
+                .council(vec![Keyring::Bob.account(), Keyring::Bob.account()])
+                .build()
+                .unwrap_err(),
+            GenesisError::DuplicateCouncilMember(Keyring::Bob.account())
+        );
+    }
+
+    #[test]
+    fn t_treasury_params() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let default = genesis_hash(builder().build().unwrap()).unwrap();
+        for custom in vec![
+            builder().proposal_bond(Permill::from_percent(1)),
+            builder().spend_period(100),
+            builder().fees_to_treasury(Permill::from_percent(50)),
+            builder().tip_countdown(100),
+            builder().bounty_deposit(EXISTENTIAL_DEPOSIT),
+        ] {
+            assert_ne!(default, genesis_hash(custom.build().unwrap()).unwrap());
+        }
+        assert_eq!(
+            builder().spend_period(0).build().unwrap_err(),
+            GenesisError::InvalidSpendPeriod
+        );
+    }
+
+    #[test]
+    fn t_registrars() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let registrar = |key: Keyring, fields: &[&str]| RegistrarSpec {
+            account: key.account(),
+            fee: 100,
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+        };
+        assert_eq!(
+            registrars_genesis(vec![
+                registrar(Keyring::Alice, &["display", "email"]),
+                registrar(Keyring::Bob, &[]),
+            ])
+            .unwrap()
+            .registrars,
+            vec![
+                (Keyring::Alice.account(), 100, 0b1_0001),
+                (Keyring::Bob.account(), 100, 0),
+            ]
+        );
+        assert_ne!(
+            genesis_hash(builder().build().unwrap()).unwrap(),
+            genesis_hash(
+                builder()
+                    .registrars(vec![registrar(Keyring::Alice, &["display"])])
+                    .build()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            builder()
+                .registrars(vec![
+                    registrar(Keyring::Bob, &["display"]),
+                    registrar(Keyring::Bob, &["email"]),
+                ])
+                .build()
+                .unwrap_err(),
+            GenesisError::DuplicateRegistrar(Keyring::Bob.account())
+        );
+        assert_eq!(
+            builder()
+                .registrars(vec![registrar(Keyring::Bob, &["display", "phone"])])
+                .build()
+                .unwrap_err(),
+            GenesisError::UnknownIdentityField("phone".into())
+        );
+    }
+
+    #[test]
+    fn t_multisig_endowment() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let team = vec![Keyring::Bob.account(), Keyring::Charlie.account()];
+        let team_account = multisig_account(&team, 2).unwrap();
+        assert_eq!(
+            genesis_hash(
+                builder()
+                    .multisig_endowment(team.clone(), 2, DEFAULT_ENDOWMENT)
+                    .build()
+                    .unwrap()
+            )
+            .unwrap(),
+            genesis_hash(
+                builder()
+                    .endowed_accounts(vec![(team_account.clone(), DEFAULT_ENDOWMENT)])
+                    .build()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            builder()
+                .multisig_endowment(team.clone(), 3, DEFAULT_ENDOWMENT)
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidMultisig(
+                "multisig threshold must be between 1 and the number of signatories"
+            )
+        );
+        assert_eq!(
+            builder()
+                .endowed_accounts(vec![(team_account.clone(), DEFAULT_ENDOWMENT)])
+                .multisig_endowment(team, 2, DEFAULT_ENDOWMENT)
+                .build()
+                .unwrap_err(),
+            GenesisError::DuplicateAccount(team_account)
+        );
+    }
+
+    #[test]
+    fn t_scheduled_calls() {
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let burn = Call::Erc20(erc20::Call::burn(0, 1)).encode();
+        let scheduled = |when, periodic, call: &[u8]| ScheduledCall {
+            when,
+            periodic,
+            call: call.to_vec(),
+        };
+        assert_eq!(
+            scheduler_genesis(vec![scheduled(10, Some((5, 3)), &burn)])
+                .unwrap()
+                .scheduled,
+            vec![(10, Some((5, 3)), burn.clone())]
+        );
+        assert_ne!(
+            genesis_hash(builder().build().unwrap()).unwrap(),
+            genesis_hash(
+                builder()
+                    .scheduled_calls(vec![scheduled(10, None, &burn)])
+                    .build()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        for (invalid, reason) in vec![
+            (
+                scheduled(0, None, &burn),
+                "calls must be scheduled after genesis",
+            ),
+            (
+                scheduled(10, Some((0, 3)), &burn),
+                "periodic calls need a period and a count of at least 1",
+            ),
+            (
+                scheduled(10, None, &burn[..burn.len() - 1]),
+                "scheduled call is not an encoded runtime call",
+            ),
+            (
+                scheduled(10, None, &[&burn[..], &[0]].concat()),
+                "scheduled call is not an encoded runtime call",
+            ),
+        ] {
+            assert_eq!(
+                builder()
+                    .scheduled_calls(vec![invalid])
+                    .build()
+                    .unwrap_err(),
+                GenesisError::InvalidScheduledCall(reason)
+            );
+        }
+    }
+
+    #[test]
+    fn t_vesting_schedules() {
+        let bob = Keyring::Bob.account();
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+                .endowed_accounts(vec![(bob.clone(), 1000)])
+        };
+        let schedule = vesting_schedule(10, 50, 100, 800).unwrap();
+        assert_eq!(
+            schedule,
+            VestingSchedule {
+                start_block: 10,
+                cliff: 50,
+                duration: 100,
+                amount: 800,
+            }
+        );
+        assert_ne!(
+            genesis_hash(builder().build().unwrap()).unwrap(),
+            genesis_hash(
+                builder()
+                    .vesting_schedules(vec![(bob.clone(), schedule.clone())])
+                    .build()
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        for (args, reason) in vec![
+            (
+                (10, 0, 0, 800),
+                "vesting schedule duration must be at least one block",
+            ),
+            (
+                (10, 101, 100, 800),
+                "vesting schedule cliff must not exceed its duration",
+            ),
+            ((10, 50, 100, 0), "vesting schedule amount must not be zero"),
+        ] {
+            let (start_block, cliff, duration, amount) = args;
+            assert_eq!(
+                vesting_schedule(start_block, cliff, duration, amount).unwrap_err(),
+                GenesisError::InvalidVesting(reason)
+            );
+        }
+        for (schedules, reason) in vec![
+            (
+                vec![
+                    (bob.clone(), schedule.clone()),
+                    (bob.clone(), schedule.clone()),
+                ],
+                "account has more than one vesting schedule",
+            ),
+            (
+                vec![(
+                    bob.clone(),
+                    VestingSchedule {
+                        amount: 1001,
+                        ..schedule.clone()
+                    },
+                )],
+                "vesting schedule amount exceeds the account's endowment",
+            ),
+            (
+                vec![(Keyring::Charlie.account(), schedule.clone())],
+                "vesting schedule amount exceeds the account's endowment",
+            ),
+            (
+                vec![(
+                    bob.clone(),
+                    VestingSchedule {
+                        duration: 0,
+                        ..schedule.clone()
+                    },
+                )],
+                "vesting schedule duration must be at least one block",
+            ),
+        ] {
+            assert_eq!(
+                builder().vesting_schedules(schedules).build().unwrap_err(),
+                GenesisError::InvalidVesting(reason)
+            );
+        }
+    }
+
+    #[test]
+    fn t_recovery_on_dev_chain() {
+        use node_template_runtime::{Origin, Runtime};
+        use sr_primitives::traits::Dispatchable;
+        use substrate_primitives::Blake2Hasher;
+
+        let spec = Chain::Ved {
+            options: TestnetOptions {
+                load_accounts: 1,
+                ..Default::default()
+            },
+        }
+        .generate()
+        .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        let lost = Keyring::Alice.account();
+        let rescuer = derived_accounts("load", 1).remove(0);
+        let mut friends = vec![Keyring::Bob.account(), Keyring::Charlie.account()];
+        friends.sort();
+        let signed = |account: &AccountId| Origin::signed(account.clone());
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(
+                Call::Recovery(recovery::Call::create_recovery(friends.clone(), 2, 0))
+                    .dispatch(signed(&lost)),
+                Ok(())
+            );
+            assert_eq!(
+                Call::Recovery(recovery::Call::initiate_recovery(lost.clone()))
+                    .dispatch(signed(&rescuer)),
+                Ok(())
+            );
+            for friend in &friends {
+                assert_eq!(
+                    Call::Recovery(recovery::Call::claim_recovery(lost.clone()))
+                        .dispatch(signed(&rescuer)),
+                    Err("not enough friends vouched")
+                );
+                assert_eq!(
+                    Call::Recovery(recovery::Call::vouch_recovery(
+                        lost.clone(),
+                        rescuer.clone()
+                    ))
+                    .dispatch(signed(friend)),
+                    Ok(())
+                );
+            }
+            assert_eq!(
+                Call::Recovery(recovery::Call::claim_recovery(lost.clone()))
+                    .dispatch(signed(&rescuer)),
+                Ok(())
+            );
+            assert_eq!(
+                recovery::Module::<Runtime>::proxy(&rescuer),
+                Some(lost.clone())
+            );
+
+            // the rescuer now acts for the lost account, e.g. to close the recovery it claimed
+            let close = Call::Recovery(recovery::Call::close_recovery(rescuer.clone()));
+            assert_eq!(
+                Call::Recovery(recovery::Call::as_recovered(lost.clone(), Box::new(close)))
+                    .dispatch(signed(&rescuer)),
+                Ok(())
+            );
+            assert_eq!(
+                recovery::Module::<Runtime>::active_recovery((lost.clone(), rescuer.clone())),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn t_faucet() {
+        use node_template_runtime::{Origin, Runtime};
+        use sr_primitives::traits::Dispatchable;
+        use substrate_primitives::Blake2Hasher;
+
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let hash = |builder: ChainSpecBuilder| genesis_hash(builder.build().unwrap()).unwrap();
+        assert_eq!(
+            hash(builder()),
+            hash(builder().faucet_endowment(DEFAULT_FAUCET_ENDOWMENT))
+        );
+        assert_ne!(hash(builder()), hash(builder().faucet_endowment(0)));
+        assert_ne!(
+            hash(builder()),
+            hash(builder().faucet_drip(EXISTENTIAL_DEPOSIT))
+        );
+        assert_ne!(hash(builder()), hash(builder().faucet_cooldown(1)));
+        assert_eq!(
+            builder()
+                .faucet_drip(EXISTENTIAL_DEPOSIT - 1)
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidFaucet("faucet drips must be at least the existential deposit")
+        );
+        assert_eq!(
+            builder().faucet_cooldown(0).build().unwrap_err(),
+            GenesisError::InvalidFaucet("faucet cooldown must be at least one block")
+        );
+
+        // the faucet of the dev chain pays new accounts right away
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> =
+            ved().generate().unwrap().build_storage().unwrap().into();
+        let dave = Keyring::Dave.account();
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(
+                Call::Faucet(faucet::Call::drip(dave.clone())).dispatch(Origin::NONE),
+                Ok(())
+            );
+            assert_eq!(faucet::Module::<Runtime>::last_drip(&dave), Some(0));
+            assert!(Call::Faucet(faucet::Call::drip(dave))
+                .dispatch(Origin::NONE)
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn t_claims() {
+        use node_template_runtime::{Origin, Runtime};
+        use sr_primitives::traits::Dispatchable;
+        use substrate_primitives::Blake2Hasher;
+
+        let dave = Keyring::Dave.account();
+        let eve = Keyring::Eve.account();
+        let allocations = vec![(dave.clone(), 1000), (eve.clone(), 2000)];
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+                .treasury_endowment(1000)
+                .faucet_endowment(0)
+        };
+        let hash = |builder: ChainSpecBuilder| genesis_hash(builder.build().unwrap()).unwrap();
+        assert_eq!(hash(builder()), hash(builder().claims(vec![])));
+        assert_ne!(hash(builder()), hash(builder().claims(allocations.clone())));
+        // unclaimed allocations count towards the total issuance
+        builder()
+            .claims(allocations.clone())
+            .max_total_issuance(4000)
+            .build()
+            .unwrap();
+        assert_eq!(
+            builder()
+                .claims(allocations.clone())
+                .max_total_issuance(3999)
+                .build()
+                .unwrap_err(),
+            GenesisError::IssuanceAboveCap {
+                total_issuance: 4000,
+                cap: 3999
+            }
+        );
+        assert_eq!(
+            builder()
+                .claims(vec![(dave.clone(), 1000), (dave.clone(), 1000)])
+                .build()
+                .unwrap_err(),
+            GenesisError::DuplicateAccount(dave.clone())
+        );
+
+        let tree = ClaimsTree::new(allocations.clone()).unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = builder()
+            .claims(allocations)
+            .build()
+            .unwrap()
+            .build_storage()
+            .unwrap()
+            .into();
+        sr_io::with_externalities(&mut ext, || {
+            let (amount, proof) = tree.claim(&dave).unwrap();
+            let claim = || {
+                Call::MerkleClaims(merkle_claims::Call::claim(
+                    dave.clone(),
+                    amount,
+                    proof.clone(),
+                ))
+            };
+            assert_eq!(claim().dispatch(Origin::NONE), Ok(()));
+            assert!(merkle_claims::Module::<Runtime>::claimed(&dave));
+            assert!(claim().dispatch(Origin::NONE).is_err());
+            let (_, proof) = tree.claim(&eve).unwrap();
+            assert!(
+                Call::MerkleClaims(merkle_claims::Call::claim(eve.clone(), 2001, proof))
+                    .dispatch(Origin::NONE)
+                    .is_err()
+            );
+        });
+    }
+
+    #[test]
+    fn t_eth_claims() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let holder = EthereumAddress::from_low_u64_be(1);
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+                .treasury_endowment(1000)
+                .faucet_endowment(0)
+        };
+        let hash = |builder: ChainSpecBuilder| genesis_hash(builder.build().unwrap()).unwrap();
+        assert_eq!(hash(builder()), hash(builder().eth_claims(vec![])));
+        assert_ne!(
+            hash(builder()),
+            hash(builder().eth_claims(vec![(holder, 1000)]))
+        );
+        assert_eq!(
+            builder()
+                .eth_claims(vec![(holder, 1000), (holder, 1000)])
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidEthClaim("ethereum address has more than one claim")
+        );
+        assert_eq!(
+            builder()
+                .eth_claims(vec![(holder, EXISTENTIAL_DEPOSIT - 1)])
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidEthClaim(
+                "ethereum claims must be at least the existential deposit"
+            )
+        );
+        // both kinds of claims count towards the total issuance
+        let claims = || {
+            builder()
+                .eth_claims(vec![(holder, 1000)])
+                .claims(vec![(Keyring::Dave.account(), 2000)])
+        };
+        claims().max_total_issuance(4000).build().unwrap();
+        assert_eq!(
+            claims().max_total_issuance(3999).build().unwrap_err(),
+            GenesisError::IssuanceAboveCap {
+                total_issuance: 4000,
+                cap: 3999
+            }
+        );
+
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> =
+            claims().build().unwrap().build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(eth_claims::Module::<Runtime>::claims(holder), Some(1000));
+            assert_eq!(eth_claims::Module::<Runtime>::total(), 1000);
+        });
+    }
+
+    #[test]
+    fn t_pools() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let alice = Keyring::Alice.account();
+        let token = |id| TokenSpec {
+            id,
+            symbol: "TKN".to_string(),
+            decimals: 0,
+            owner: alice.clone(),
+            distribution: vec![(alice.clone(), 1000)],
+        };
+        let pool = |asset_a, asset_b, amount_a, amount_b| PoolSpec {
+            asset_a,
+            asset_b,
+            provider: alice.clone(),
+            amount_a,
+            amount_b,
+        };
+        let builder = |pools| {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(alice.clone())
+                .tokens(vec![token(0), token(1), token(2)])
+                .pools(pools)
+        };
+        let invalid = |pools, reason| {
+            assert_eq!(
+                builder(pools).build().unwrap_err(),
+                GenesisError::InvalidPool(reason)
+            );
+        };
+        invalid(
+            vec![pool(0, 0, 10, 10)],
+            "a pool needs two different assets",
+        );
+        invalid(
+            vec![pool(0, 3, 10, 10)],
+            "pool asset is not a genesis token",
+        );
+        invalid(
+            vec![pool(0, 1, 10, 10), pool(1, 0, 10, 10)],
+            "two pools pair the same assets",
+        );
+        invalid(
+            vec![pool(0, 1, 10, 0)],
+            "pools start with some of both assets",
+        );
+        invalid(
+            vec![pool(0, 1, 600, 10), pool(2, 0, 10, 600)],
+            "pool provider holds less than it deposits",
+        );
+
+        let spec = builder(vec![pool(0, 1, 500, 10), pool(2, 0, 10, 500)])
+            .build()
+            .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(
+                dex::Module::<Runtime>::pool((0, 1)),
+                Some(dex::Pool {
+                    reserves: (500, 10),
+                    shares: 500,
+                })
+            );
+            assert_eq!(dex::Module::<Runtime>::pool((0, 2)).unwrap().shares, 500);
+            assert_eq!(
+                dex::Module::<Runtime>::shares_of(((0, 2), alice.clone())),
+                500
+            );
+        });
+    }
+
+    #[test]
+    fn t_collections() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let collection = |id, owner: &AccountId| CollectionSpec {
+            id,
+            owner: owner.clone(),
+            metadata: "ipfs://collection".to_string(),
+        };
+        let builder = |collections| {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(alice.clone())
+                .collections(collections)
+        };
+        assert_eq!(
+            builder(vec![collection(1, &alice), collection(1, &bob)])
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidCollection("collection id is used twice")
+        );
+
+        let spec = builder(vec![collection(3, &alice), collection(1, &bob)])
+            .build()
+            .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(
+                nft::Module::<Runtime>::class(1),
+                Some(nft::Class {
+                    owner: bob.clone(),
+                    instances: 0,
+                    metadata: b"ipfs://collection".to_vec(),
+                })
+            );
+            assert_eq!(nft::Module::<Runtime>::class(3).unwrap().owner, alice);
+            assert_eq!(nft::Module::<Runtime>::next_class_id(), 4);
+        });
+    }
+
+    #[test]
+    fn t_whitelist() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let builder = |whitelist| {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(alice.clone())
+                .whitelist(whitelist)
+        };
+        assert_eq!(
+            builder(vec![bob.clone(), bob.clone()]).build().unwrap_err(),
+            GenesisError::DuplicateWhitelisted(bob.clone())
+        );
+
+        let spec = builder(vec![bob.clone()])
+            .enforce_whitelist(true)
+            .build()
+            .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert!(whitelist::Module::<Runtime>::enforced());
+            assert!(whitelist::Module::<Runtime>::is_whitelisted(&bob));
+            assert!(!whitelist::Module::<Runtime>::is_whitelisted(&alice));
+        });
+    }
+
+    #[test]
+    fn t_paused_calls() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let spec = ChainSpecBuilder::default()
+            .name("Test")
+            .id("test")
+            .authority(authority_keys_from_seed("Alice"))
+            .sudo_key(Keyring::Alice.account())
+            .paused_calls(vec![(5, 0)])
+            .build()
+            .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert!(tx_pause::Module::<Runtime>::is_paused((5, 0)));
+            assert!(!tx_pause::Module::<Runtime>::is_paused((5, 1)));
+        });
+    }
+
+    #[test]
+    fn t_oracle() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let feeder = get_from_seed::<OracleId>("Alice");
+        let builder = |feeders: Vec<OracleId>, endpoint| {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+                .oracle_feeders(feeders)
+                .oracle_endpoint(endpoint)
+        };
+        assert_eq!(
+            builder(vec![feeder.clone(), feeder.clone()], "")
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidOracle("oracle feeder is listed twice")
+        );
+        assert_eq!(
+            builder(vec![feeder.clone()], "ftp://example.com")
+                .build()
+                .unwrap_err(),
+            GenesisError::InvalidOracle("oracle endpoint is not an http url")
+        );
+
+        let spec = builder(vec![feeder.clone()], "https://example.com/price")
+            .build()
+            .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || {
+            assert_eq!(oracle::Module::<Runtime>::feeders(), vec![feeder.clone()]);
+            assert_eq!(
+                oracle::Module::<Runtime>::endpoint(),
+                b"https://example.com/price".to_vec()
+            );
+            assert_eq!(oracle::Module::<Runtime>::median(), None);
+        });
+    }
+
+    #[test]
+    fn t_block_reward() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let reward = |builder: ChainSpecBuilder| {
+            let mut ext: sr_io::TestExternalities<Blake2Hasher> =
+                builder.build().unwrap().build_storage().unwrap().into();
+            sr_io::with_externalities(&mut ext, block_rewards::Module::<Runtime>::reward)
+        };
+        assert_eq!(reward(builder()), block_rewards::DEFAULT_REWARD.into());
+        assert_eq!(reward(builder().block_reward(0)), 0);
+    }
+
+    #[test]
+    fn t_timelock() {
+        use node_template_runtime::Runtime;
+        use substrate_primitives::Blake2Hasher;
+
+        let builder = || {
+            ChainSpecBuilder::default()
+                .name("Test")
+                .id("test")
+                .authority(authority_keys_from_seed("Alice"))
+                .sudo_key(Keyring::Alice.account())
+        };
+        let timelock = |builder: ChainSpecBuilder| {
+            let mut ext: sr_io::TestExternalities<Blake2Hasher> =
+                builder.build().unwrap().build_storage().unwrap().into();
+            sr_io::with_externalities(&mut ext, || {
+                (
+                    timelock::Module::<Runtime>::admin(),
+                    timelock::Module::<Runtime>::delay(),
+                )
+            })
+        };
+        assert_eq!(
+            timelock(builder()),
+            (Keyring::Alice.account(), timelock::DEFAULT_DELAY)
+        );
+        assert_eq!(
+            timelock(builder().admin_key(Keyring::Bob.account()).admin_delay(10)),
+            (Keyring::Bob.account(), 10)
+        );
+    }
 }
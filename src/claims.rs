@@ -0,0 +1,179 @@
+//! Airdrops through the merkle claims module.
+//!
+//! Rather than endowing every recipient at genesis, the chainspec stores the root of a merkle tree
+//! of (account, amount) allocations. Recipients claim by submitting their amount and the siblings
+//! on the path from their leaf to the root, which `ClaimsTree` computes from the allocation list.
+
+use crate::chain_spec::GenesisError;
+use crate::genesis_config::endowed_accounts_from_csv;
+use crate::ss58::to_ss58;
+use merkle_claims::{leaf, MerkleTree, Node};
+use node_template_runtime::{AccountId, Balance, EXISTENTIAL_DEPOSIT};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Merkle tree of the allocations claimable on a chain.
+pub struct ClaimsTree {
+    allocations: Vec<(AccountId, Balance)>,
+    // leaf index of each account
+    indices: BTreeMap<AccountId, usize>,
+    tree: MerkleTree,
+    total: Balance,
+}
+
+impl ClaimsTree {
+    /// Build the tree of `allocations`, which are leaves in the order given. Like endowments,
+    /// each account may be listed once, with at least the existential deposit.
+    pub fn new(allocations: Vec<(AccountId, Balance)>) -> Result<Self, GenesisError> {
+        let mut indices = BTreeMap::new();
+        let mut total: Balance = 0;
+        for (index, (account, amount)) in allocations.iter().enumerate() {
+            if indices.insert(account.clone(), index).is_some() {
+                return Err(GenesisError::DuplicateAccount(account.clone()));
+            }
+            // a claim creates the account it pays
+            if *amount < EXISTENTIAL_DEPOSIT {
+                return Err(GenesisError::BelowExistentialDeposit(account.clone()));
+            }
+            total = total
+                .checked_add(*amount)
+                .ok_or(GenesisError::IssuanceOverflow)?;
+        }
+        let tree = MerkleTree::new(
+            allocations
+                .iter()
+                .map(|(account, amount)| leaf(account, amount))
+                .collect(),
+        );
+        Ok(ClaimsTree {
+            allocations,
+            indices,
+            tree,
+            total,
+        })
+    }
+
+    /// Build the tree of the allocations in a csv file of "address,balance" rows, in the format
+    /// of `endowed_accounts_from_csv`.
+    pub fn from_csv(path: &Path) -> Result<Self, String> {
+        Self::new(endowed_accounts_from_csv(path)?)
+            .map_err(|e| format!("Invalid allocations {}: {}", path.display(), e))
+    }
+
+    /// Root stored at genesis.
+    pub fn root(&self) -> Node {
+        self.tree.root()
+    }
+
+    /// Sum of all allocations, minted as they are claimed.
+    pub fn total(&self) -> Balance {
+        self.total
+    }
+
+    /// Amount allocated to `account` and the proof it claims with.
+    pub fn claim(&self, account: &AccountId) -> Option<(Balance, Vec<Node>)> {
+        self.indices
+            .get(account)
+            .map(|&index| (self.allocations[index].1, self.tree.proof(index)))
+    }
+
+    /// Render the root and the claim of every account as json, for handing proofs out to
+    /// recipients.
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct Claim {
+            account: String,
+            amount: String,
+            proof: Vec<String>,
+        }
+        #[derive(Serialize)]
+        struct Claims {
+            root: String,
+            total: String,
+            claims: Vec<Claim>,
+        }
+
+        let node = |node: &Node| format!("0x{}", hex::encode(node));
+        let claims = Claims {
+            root: node(&self.root()),
+            total: self.total.to_string(),
+            claims: self
+                .allocations
+                .iter()
+                .enumerate()
+                .map(|(index, (account, amount))| Claim {
+                    account: to_ss58(account),
+                    amount: amount.to_string(),
+                    proof: self.tree.proof(index).iter().map(node).collect(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&claims).expect("claims serialize to json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+    use merkle_claims::root_of;
+
+    fn allocations() -> Vec<(AccountId, Balance)> {
+        vec![
+            (Keyring::Alice.account(), EXISTENTIAL_DEPOSIT),
+            (Keyring::Bob.account(), 2 * EXISTENTIAL_DEPOSIT),
+            (Keyring::Charlie.account(), 3 * EXISTENTIAL_DEPOSIT),
+        ]
+    }
+
+    #[test]
+    fn t_claims_tree() {
+        let tree = ClaimsTree::new(allocations()).unwrap();
+        assert_eq!(tree.total(), 6 * EXISTENTIAL_DEPOSIT);
+        for (account, amount) in allocations() {
+            let (claimed, proof) = tree.claim(&account).unwrap();
+            assert_eq!(claimed, amount);
+            assert_eq!(root_of(leaf(&account, &amount), &proof), tree.root());
+        }
+        assert_eq!(tree.claim(&Keyring::Dave.account()), None);
+
+        let mut duplicate = allocations();
+        duplicate.push((Keyring::Bob.account(), EXISTENTIAL_DEPOSIT));
+        assert_eq!(
+            ClaimsTree::new(duplicate).err(),
+            Some(GenesisError::DuplicateAccount(Keyring::Bob.account()))
+        );
+        let mut dust = allocations();
+        dust.push((Keyring::Dave.account(), EXISTENTIAL_DEPOSIT - 1));
+        assert_eq!(
+            ClaimsTree::new(dust).err(),
+            Some(GenesisError::BelowExistentialDeposit(
+                Keyring::Dave.account()
+            ))
+        );
+    }
+
+    #[test]
+    fn t_claims_to_json() {
+        let tree = ClaimsTree::new(allocations()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&tree.to_json()).unwrap();
+        assert_eq!(json["root"], format!("0x{}", hex::encode(tree.root())));
+        assert_eq!(json["total"], (6 * EXISTENTIAL_DEPOSIT).to_string());
+        assert_eq!(
+            json["claims"][1]["account"],
+            to_ss58(&Keyring::Bob.account())
+        );
+        assert_eq!(
+            json["claims"][1]["amount"],
+            (2 * EXISTENTIAL_DEPOSIT).to_string()
+        );
+        assert_eq!(
+            json["claims"][1]["proof"][0],
+            format!(
+                "0x{}",
+                hex::encode(leaf(&Keyring::Alice.account(), &EXISTENTIAL_DEPOSIT))
+            )
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use crate::address::{account_of, AddressInfo};
+use crate::benchmark::{run_benchmarks, weights_file, DEFAULT_WASM_FACTOR};
+use crate::blocks::{encode_blocks, fetch_blocks, Format};
+use crate::bundles::{key_bundles, to_json};
+use crate::chain_spec::{
+    genesis_header, genesis_wasm, Chain, ChainInfo, ChainSpecVariant, CHAIN_HELP,
+};
+use crate::claims::ClaimsTree;
+#[cfg(any(test, feature = "dev-keys"))]
+use crate::dev_validator::{prepare_node, DevValidatorFlags};
+use crate::diff_spec::diff_specs;
+use crate::faucet_server::{serve_faucet, Faucet};
+use crate::inspect::InspectCommand;
+use crate::key::KeyCommand;
+#[cfg(any(test, feature = "dev-keys"))]
+use crate::keyring::Keyring;
+use crate::keyring::{mnemonic_from_env, signer_from_suri, MNEMONIC_ENV};
+#[cfg(any(test, feature = "dev-keys"))]
+use crate::keystore::insert_dev_keys;
+use crate::keystore::{insert_key, KeyType};
+use crate::metrics::{serve_metrics, Exporter};
+use crate::purge::{database_path, default_base_path, purge_chain};
+use crate::retire_sudo::{encoded_retire_sudo_call, TryRun};
+use crate::rpc::{Rpc, DEFAULT_RPC_URL};
+use crate::transfers::watch_transfers;
+use crate::upgrade::submit_upgrade;
+use crate::verify_spec::verify_spec;
+use codec::Encode;
+use node_template_runtime::{AccountId, Balance, BlockNumber};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(structopt::StructOpt, Clone, Debug)]
+/// generate and inspect substrate chainspecs
+pub enum Command {
+    /// Outputs a chainspec as json
+    BuildSpec {
+        /// Serialize the genesis as raw storage key/value pairs, which nodes load as-is, whatever
+        /// runtime they were built with, e.g. the substrate node of the Dockerfile
+        #[structopt(long = "raw")]
+        raw: bool,
+        /// Leave out the boot nodes, e.g. those of the staging testnet
+        #[structopt(long = "disable-default-bootnode")]
+        disable_default_bootnode: bool,
+        #[structopt(subcommand)]
+        chain: Chain,
+    },
+    /// Prints the name, id, genesis hash, and runtime version of a chainspec so validators can
+    /// check they all start from the same genesis
+    ChainInfo {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+    },
+    /// Prints the scale encoded header of the genesis block of a chainspec, hex encoded
+    ExportGenesisState {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+    },
+    /// Prints the runtime code of the genesis of a chainspec, hex encoded
+    ExportGenesisWasm {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+    },
+    /// Checks a chainspec before a chain launches from it: its genesis, runtime, balances and
+    /// authorities, and prints its genesis hash
+    VerifySpec {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+    },
+    /// Prints what differs from one chainspec to another: authorities, endowments, properties and
+    /// the sudo key, and a count of the storage keys that differ
+    DiffSpec {
+        #[structopt(help = CHAIN_HELP)]
+        old: ChainSpecVariant,
+        #[structopt(help = CHAIN_HELP)]
+        new: ChainSpecVariant,
+    },
+    /// Deletes the database a node keeps a chain in, leaving its keystore and network keys
+    PurgeChain {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+        /// Base path of the node, defaults to the node's, e.g. ~/.local/share/substrate
+        #[structopt(long = "base-path", parse(from_os_str))]
+        base_path: Option<PathBuf>,
+        /// Delete without asking for confirmation
+        #[structopt(short = "y")]
+        yes: bool,
+    },
+    /// Writes blocks of the best chain of a running node to a file. The binary format is the one
+    /// `substrate import-blocks` imports into the database of another node
+    ExportBlocks {
+        #[structopt(parse(from_os_str))]
+        output: PathBuf,
+        /// First block, the genesis is built from the chainspec rather than imported
+        #[structopt(long = "from", default_value = "1")]
+        from: BlockNumber,
+        /// Last block, defaults to the best block
+        #[structopt(long = "to")]
+        to: Option<BlockNumber>,
+        /// "binary", or "json" as the rpc returns blocks
+        #[structopt(long = "format", default_value = "binary")]
+        format: Format,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Decodes a block of a running node or a hex encoded extrinsic with the types of the runtime
+    Inspect(InspectCommand),
+    /// Prints the native, asset and erc20 transfers from or to an account as blocks are finalized
+    WatchTransfers {
+        /// The account, as an ss58 address or a 0x prefixed hex public key
+        #[structopt(parse(try_from_str = account_of))]
+        account: AccountId,
+        /// First block to look at, defaults to the block after the finalized head
+        #[structopt(long = "from")]
+        from: Option<BlockNumber>,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Prints the merkle root of a csv file of "address,balance" allocations, for the claims
+    /// genesis option, along with the proof each account claims its allocation with
+    ClaimsTree {
+        #[structopt(parse(from_os_str))]
+        allocations: PathBuf,
+    },
+    /// Prints the hex encoded call the sudo key signs to clear itself, leaving root to governance
+    RetireSudo {
+        /// Instead apply the call to the state of a chainspec, named as with --chain, and print
+        /// what it leaves behind
+        #[structopt(long = "try-run")]
+        try_run: Option<ChainSpecVariant>,
+    },
+    /// Times the calls of the runtime's pallets on the state of a chainspec and prints their
+    /// weights
+    Benchmark {
+        #[structopt(long = "chain", help = CHAIN_HELP)]
+        chain: ChainSpecVariant,
+        /// Only benchmark this pallet, and print its weights as its weights.rs
+        #[structopt(long = "pallet")]
+        pallet: Option<String>,
+        /// Times each call runs, the median of which is its weight
+        #[structopt(long = "repeat", default_value = "50")]
+        repeat: usize,
+        /// How many times slower calls execute as wasm than natively, measured on the validators'
+        /// hardware; defaults to an estimate
+        #[structopt(long = "wasm-factor")]
+        wasm_factor: Option<u32>,
+    },
+    /// Signs `sudo(set_code(<wasm>))` with the sudo key, submits it to a node, and waits for the
+    /// upgraded runtime to take over
+    SubmitUpgrade {
+        /// The compiled runtime
+        #[structopt(parse(from_os_str))]
+        wasm: PathBuf,
+        /// Secret uri of the sudo key, e.g. "//Alice" or "<mnemonic>//path"; defaults to the
+        /// WARMUP_MNEMONIC environment variable, which keeps the key out of shell history
+        #[structopt(long = "suri")]
+        suri: Option<String>,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Serves an http endpoint, `POST /drip/<address>`, transferring testnet tokens from the faucet
+    /// key to the address through a node
+    ServeFaucet {
+        /// Secret uri of the account dripping; defaults to the WARMUP_MNEMONIC environment variable
+        #[structopt(long = "faucet-key")]
+        faucet_key: Option<String>,
+        /// Amount of a drip, defaults to the faucet pallet's default drip amount
+        #[structopt(long = "amount")]
+        amount: Option<Balance>,
+        /// Seconds an address, or an ip, waits between drips
+        #[structopt(long = "cooldown", default_value = "86400")]
+        cooldown: u64,
+        /// Address to serve on
+        #[structopt(long = "listen", default_value = "127.0.0.1:8000")]
+        listen: SocketAddr,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Serves prometheus metrics of a node on `GET /metrics`: its peers, sync state and best and
+    /// finalized blocks, and counts of the faucet drips and token transfers it finalizes. Also
+    /// answers liveness and readiness probes on `GET /health` and `GET /ready`
+    ServeMetrics {
+        /// Address to serve on
+        #[structopt(long = "listen", default_value = "127.0.0.1:9615")]
+        listen: SocketAddr,
+        /// Seconds since the last finalized block past which the node is not ready
+        #[structopt(long = "max-finalized-age", default_value = "60")]
+        max_finalized_age: u64,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Generates and inspects account and session keys, derived like genesis keys
+    Key(KeyCommand),
+    /// Prints an account as ss58 addresses, as a hex public key, and the accounts derived from it
+    Address {
+        /// An ss58 address of any network, or a 0x prefixed hex public key
+        address: String,
+        /// Also print the address with this ss58 prefix
+        #[structopt(long = "prefix")]
+        prefix: Option<u8>,
+        /// Print this many derivative accounts of the utility pallet
+        #[structopt(long = "derivatives", default_value = "0")]
+        derivatives: u16,
+        /// Another signatory of a multisig account, printed with --threshold. May be repeated
+        #[structopt(long = "signatory", parse(try_from_str = account_of))]
+        signatories: Vec<AccountId>,
+        /// Approvals the multisig account needs
+        #[structopt(long = "threshold", requires = "signatories")]
+        threshold: Option<u16>,
+    },
+    /// Derives the accounts and session keys of validators from the WARMUP_MNEMONIC environment
+    /// variable, and prints them as json: the addresses are `[[authorities]]` of a genesis config
+    /// file, and the keystore entries what each node's keystore needs. The output holds the secret
+    /// session keys
+    GenerateAccounts {
+        /// Number of validators
+        count: usize,
+        /// Keys are derived along "//<prefix>/<index>"
+        #[structopt(long = "prefix", default_value = "validator")]
+        prefix: String,
+    },
+    /// Writes a key into the keystore of a node before it starts, e.g. a session key of a
+    /// validator
+    InsertKey {
+        /// Directory of the keystore, <base-path>/chains/<chain id>/keystore
+        #[structopt(long = "keystore-path", parse(from_os_str))]
+        keystore_path: PathBuf,
+        /// "babe", "gran", or "imon"
+        #[structopt(long = "key-type")]
+        key_type: KeyType,
+        /// Secret uri of the key, e.g. "<mnemonic>//validator/0"; defaults to the WARMUP_MNEMONIC
+        /// environment variable
+        #[structopt(long = "suri")]
+        suri: Option<String>,
+    },
+    /// Writes the babe, grandpa, and im-online keys of a dev validator, e.g. "alice", into the
+    /// keystore of a node, derived as the dev chainspecs derive its session keys
+    #[cfg(any(test, feature = "dev-keys"))]
+    InsertDevKeys {
+        validator: Keyring,
+        /// Directory of the keystore, <base-path>/chains/<chain id>/keystore
+        #[structopt(long = "keystore-path", parse(from_os_str))]
+        keystore_path: PathBuf,
+    },
+    /// Prepares the base path of a node to run as a dev validator, named with e.g. --alice, and
+    /// prints the node arguments: the validator's session keys go into the keystore, and its name
+    /// becomes the node name. Fails if the chainspec has other authorities
+    #[cfg(any(test, feature = "dev-keys"))]
+    DevValidator {
+        #[structopt(flatten)]
+        validator: DevValidatorFlags,
+        /// Chainspec json file the node runs
+        #[structopt(long = "chain", parse(from_os_str))]
+        chain: PathBuf,
+        #[structopt(long = "base-path", parse(from_os_str))]
+        base_path: PathBuf,
+    },
+}
+
+impl Command {
+    /// Run the command, returning what should be written to stdout.
+    pub fn run(self) -> Result<String, String> {
+        match self {
+            Command::BuildSpec {
+                raw,
+                disable_default_bootnode,
+                chain,
+            } => {
+                let mut spec = chain.generate()?;
+                if disable_default_bootnode {
+                    spec.clear_boot_nodes();
+                }
+                spec.into_json(raw)
+            }
+            Command::ChainInfo { chain } => Ok(ChainInfo::of(chain.load()?)?.to_string()),
+            Command::ExportGenesisState { chain } => Ok(format!(
+                "0x{}",
+                hex::encode(genesis_header(chain.load()?)?.encode())
+            )),
+            Command::ExportGenesisWasm { chain } => {
+                Ok(format!("0x{}", hex::encode(genesis_wasm(chain.load()?)?)))
+            }
+            Command::VerifySpec { chain } => Ok(verify_spec(chain.load()?)?.to_string()),
+            Command::DiffSpec { old, new } => Ok(diff_specs(old.load()?, new.load()?)?.to_string()),
+            Command::PurgeChain {
+                chain,
+                base_path,
+                yes,
+            } => {
+                let base_path = match base_path {
+                    Some(base_path) => base_path,
+                    None => default_base_path()?,
+                };
+                purge_chain(&database_path(&base_path, chain.load()?.id())?, yes)
+            }
+            Command::ExportBlocks {
+                output,
+                from,
+                to,
+                format,
+                url,
+            } => {
+                let blocks = fetch_blocks(&Rpc::new(&url), from, to)?;
+                std::fs::write(&output, encode_blocks(&blocks, format))
+                    .map_err(|e| format!("{}: {}", output.display(), e))?;
+                Ok(format!(
+                    "exported {} blocks to {}",
+                    blocks.len(),
+                    output.display()
+                ))
+            }
+            Command::Inspect(inspect) => inspect.run(),
+            Command::WatchTransfers { account, from, url } => {
+                watch_transfers(&Rpc::new(&url), &account, from)
+            }
+            Command::ClaimsTree { allocations } => {
+                Ok(ClaimsTree::from_csv(&allocations)?.to_json())
+            }
+            Command::RetireSudo { try_run: None } => Ok(encoded_retire_sudo_call()),
+            Command::RetireSudo {
+                try_run: Some(chain),
+            } => Ok(TryRun::of(chain.load()?)?.to_string()),
+            Command::Benchmark {
+                chain,
+                pallet,
+                repeat,
+                wasm_factor,
+            } => {
+                let only = pallet.as_ref().map(String::as_str);
+                let wasm_factor = wasm_factor.unwrap_or(DEFAULT_WASM_FACTOR);
+                let measurements = run_benchmarks(chain.load()?, only, repeat, wasm_factor)?;
+                Ok(match pallet {
+                    Some(pallet) => weights_file(&pallet, &measurements),
+                    None => measurements
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                })
+            }
+            Command::SubmitUpgrade { wasm, suri, url } => {
+                let suri = match suri {
+                    Some(suri) => suri,
+                    None => mnemonic_from_env()
+                        .map_err(|e| format!("pass --suri or set {}: {}", MNEMONIC_ENV, e))?,
+                };
+                submit_upgrade(&Rpc::new(&url), &wasm, &signer_from_suri(&suri)?)
+            }
+            Command::ServeFaucet {
+                faucet_key,
+                amount,
+                cooldown,
+                listen,
+                url,
+            } => {
+                let faucet_key = match faucet_key {
+                    Some(faucet_key) => faucet_key,
+                    None => mnemonic_from_env()
+                        .map_err(|e| format!("pass --faucet-key or set {}: {}", MNEMONIC_ENV, e))?,
+                };
+                let faucet = Faucet::new(
+                    Rpc::new(&url),
+                    signer_from_suri(&faucet_key)?,
+                    amount.unwrap_or_else(|| faucet::DEFAULT_DRIP_AMOUNT.into()),
+                    Duration::from_secs(cooldown),
+                );
+                serve_faucet(listen, faucet)
+            }
+            Command::ServeMetrics {
+                listen,
+                max_finalized_age,
+                url,
+            } => {
+                let max_finalized_age = Duration::from_secs(max_finalized_age);
+                serve_metrics(listen, Exporter::new(Rpc::new(&url), max_finalized_age)?)
+            }
+            Command::Key(key) => key.run(),
+            Command::Address {
+                address,
+                prefix,
+                derivatives,
+                signatories,
+                threshold,
+            } => {
+                let mut info = AddressInfo::new(&address)?.derivatives(derivatives);
+                if let Some(prefix) = prefix {
+                    info = info.prefix(prefix);
+                }
+                if let Some(threshold) = threshold {
+                    info = info.multisig(&signatories, threshold)?;
+                }
+                Ok(info.to_string().trim_end().to_string())
+            }
+            Command::GenerateAccounts { count, prefix } => {
+                let mnemonic = mnemonic_from_env()?;
+                Ok(to_json(&key_bundles(&mnemonic, &prefix, count)?))
+            }
+            Command::InsertKey {
+                keystore_path,
+                key_type,
+                suri,
+            } => {
+                let suri = match suri {
+                    Some(suri) => suri,
+                    None => mnemonic_from_env()?,
+                };
+                let path = insert_key(&keystore_path, key_type, &suri)?;
+                Ok(format!("inserted {}", path.display()))
+            }
+            #[cfg(any(test, feature = "dev-keys"))]
+            Command::InsertDevKeys {
+                validator,
+                keystore_path,
+            } => {
+                let paths = insert_dev_keys(&keystore_path, validator)?;
+                let paths: Vec<String> = paths
+                    .iter()
+                    .map(|path| format!("inserted {}", path.display()))
+                    .collect();
+                Ok(paths.join("\n"))
+            }
+            #[cfg(any(test, feature = "dev-keys"))]
+            Command::DevValidator {
+                validator,
+                chain,
+                base_path,
+            } => {
+                let spec = ChainSpecVariant::Custom(chain.clone()).load()?;
+                Ok(prepare_node(spec, &chain, &base_path, validator.validator()?)?.join(" "))
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Running a node as one of the dev validators, like the node's own `--alice`, but with keys
+//! derived by chaingen and checked against the authorities of the chainspec.
+
+use crate::keyring::Keyring;
+use crate::keystore::insert_dev_keys;
+use crate::purge::chain_path;
+use crate::serializable_genesis::ChainSpec;
+use node_template_runtime::{GenesisConfig, Runtime};
+use sr_primitives::BuildStorage;
+use std::path::Path;
+use substrate_primitives::Blake2Hasher;
+
+/// Flags naming a dev validator, of which exactly one is set.
+#[derive(structopt::StructOpt, Clone, Default, Debug)]
+pub struct DevValidatorFlags {
+    #[structopt(long = "alice")]
+    alice: bool,
+    #[structopt(long = "bob")]
+    bob: bool,
+    #[structopt(long = "charlie")]
+    charlie: bool,
+    #[structopt(long = "dave")]
+    dave: bool,
+    #[structopt(long = "eve")]
+    eve: bool,
+    #[structopt(long = "ferdie")]
+    ferdie: bool,
+}
+
+impl DevValidatorFlags {
+    pub fn validator(&self) -> Result<Keyring, String> {
+        let set = [
+            (self.alice, Keyring::Alice),
+            (self.bob, Keyring::Bob),
+            (self.charlie, Keyring::Charlie),
+            (self.dave, Keyring::Dave),
+            (self.eve, Keyring::Eve),
+            (self.ferdie, Keyring::Ferdie),
+        ];
+        let mut set = set
+            .iter()
+            .filter(|(flag, _)| *flag)
+            .map(|(_, keyring)| *keyring);
+        match (set.next(), set.next()) {
+            (Some(validator), None) => Ok(validator),
+            (None, _) => Err("name a validator, e.g. with --alice".into()),
+            (Some(_), Some(_)) => Err("name only one validator".into()),
+        }
+    }
+}
+
+/// Write the session keys of `validator` to the keystore of a node running `spec` from
+/// `base_path`, and return the arguments the node runs with, naming the node after the validator.
+/// Fails if the validator is not a genesis authority of `spec`, whose blocks it could not author.
+pub fn prepare_node(
+    spec: ChainSpec<GenesisConfig>,
+    spec_path: &Path,
+    base_path: &Path,
+    validator: Keyring,
+) -> Result<Vec<String>, String> {
+    let id = spec.id().to_owned();
+    let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage()?.into();
+    let authorities = sr_io::with_externalities(&mut ext, babe::Module::<Runtime>::authorities);
+    if !authorities.iter().any(|(key, _)| *key == validator.babe()) {
+        return Err(format!(
+            "{} is not an authority of {}",
+            validator.name(),
+            spec_path.display()
+        ));
+    }
+    insert_dev_keys(&chain_path(base_path, &id)?.join("keystore"), validator)?;
+    Ok(vec![
+        "--chain".into(),
+        spec_path.display().to_string(),
+        "--base-path".into(),
+        base_path.display().to_string(),
+        "--name".into(),
+        validator.name().into(),
+        "--validator".into(),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{Chain, TestnetOptions};
+
+    #[test]
+    fn t_validator_flags() {
+        let flags = DevValidatorFlags {
+            bob: true,
+            ..Default::default()
+        };
+        assert_eq!(flags.validator(), Ok(Keyring::Bob));
+        DevValidatorFlags::default().validator().unwrap_err();
+        DevValidatorFlags {
+            alice: true,
+            bob: true,
+            ..Default::default()
+        }
+        .validator()
+        .unwrap_err();
+    }
+
+    #[test]
+    fn t_prepare_node() {
+        let local = || {
+            Chain::Local {
+                options: TestnetOptions::default(),
+            }
+            .generate()
+            .unwrap()
+        };
+        let base_path = std::env::temp_dir().join("t_prepare_node");
+        let _ = std::fs::remove_dir_all(&base_path);
+        let spec_path = Path::new("local.json");
+        let args = prepare_node(local(), spec_path, &base_path, Keyring::Bob).unwrap();
+        assert_eq!(&args[4..6], &["--name".to_string(), "Bob".to_string()]);
+        let keystore = base_path.join("chains/substrate-warmup-local-testnet/keystore");
+        assert_eq!(std::fs::read_dir(&keystore).unwrap().count(), 3);
+        prepare_node(local(), spec_path, &base_path, Keyring::Charlie).unwrap_err();
+        std::fs::remove_dir_all(&base_path).unwrap();
+    }
+}
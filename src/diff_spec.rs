@@ -0,0 +1,250 @@
+//! Comparing two chainspecs field by field, e.g. a hand edited spec against the preset it started
+//! from.
+//!
+//! Authorities, endowments and the sudo key are compared from human readable genesis configs.
+//! Raw specs no longer list them, so for those only the storage is compared, by key.
+
+use crate::serializable_genesis::ChainSpec;
+use crate::ss58::to_ss58;
+use node_template_runtime::{AccountId, Balance, GenesisConfig};
+use serde_json::Value;
+use sr_primitives::BuildStorage;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use substrate_primitives::storage::well_known_keys;
+
+/// A value on either side of a diff, `None` where a side lacks it.
+type Change<T> = (Option<T>, Option<T>);
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SpecDiff {
+    pub name: Option<(String, String)>,
+    pub id: Option<(String, String)>,
+    pub properties: BTreeMap<String, Change<Value>>,
+    pub authorities_added: Vec<AccountId>,
+    pub authorities_removed: Vec<AccountId>,
+    /// Endowments that differ, by account.
+    pub balances: BTreeMap<AccountId, Change<Balance>>,
+    pub sudo_key: Option<Change<AccountId>>,
+    pub runtime_changed: bool,
+    /// Storage keys only the new spec has, only the old spec has, and both have with different
+    /// values.
+    pub storage: (usize, usize, usize),
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        *self == SpecDiff::default()
+    }
+}
+
+/// The differences from `old` to `new`.
+pub fn diff_specs(
+    old: ChainSpec<GenesisConfig>,
+    new: ChainSpec<GenesisConfig>,
+) -> Result<SpecDiff, String> {
+    let mut diff = SpecDiff::default();
+    if old.name() != new.name() {
+        diff.name = Some((old.name().to_owned(), new.name().to_owned()));
+    }
+    if old.id() != new.id() {
+        diff.id = Some((old.id().to_owned(), new.id().to_owned()));
+    }
+    diff.properties = diff_maps(
+        old.properties().into_iter().collect(),
+        new.properties().into_iter().collect(),
+    );
+    if let (Some(old), Some(new)) = (old.genesis_config(), new.genesis_config()) {
+        let old_authorities = authorities(old);
+        let new_authorities = authorities(new);
+        diff.authorities_added = new_authorities
+            .difference(&old_authorities)
+            .cloned()
+            .collect();
+        diff.authorities_removed = old_authorities
+            .difference(&new_authorities)
+            .cloned()
+            .collect();
+        diff.balances = diff_maps(balances(old), balances(new));
+        let sudo_key = |config: &GenesisConfig| config.sudo.as_ref().map(|s| s.key.clone());
+        if sudo_key(old) != sudo_key(new) {
+            diff.sudo_key = Some((sudo_key(old), sudo_key(new)));
+        }
+    }
+
+    let (old, _) = old.build_storage()?;
+    let (new, _) = new.build_storage()?;
+    diff.runtime_changed = old.get(well_known_keys::CODE) != new.get(well_known_keys::CODE);
+    let added = new.keys().filter(|key| !old.contains_key(*key)).count();
+    let removed = old.keys().filter(|key| !new.contains_key(*key)).count();
+    let changed = old
+        .iter()
+        .filter(|(key, value)| new.get(*key).map_or(false, |new| new != *value))
+        .count();
+    diff.storage = (added, removed, changed);
+    Ok(diff)
+}
+
+fn authorities(config: &GenesisConfig) -> BTreeSet<AccountId> {
+    let keys = config
+        .session
+        .as_ref()
+        .map(|s| &s.keys[..])
+        .unwrap_or_default();
+    keys.iter().map(|(account, _)| account.clone()).collect()
+}
+
+fn balances(config: &GenesisConfig) -> BTreeMap<AccountId, Balance> {
+    let balances = config
+        .balances
+        .as_ref()
+        .map(|b| &b.balances[..])
+        .unwrap_or_default();
+    balances.iter().cloned().collect()
+}
+
+/// Entries of `old` and `new` that differ.
+fn diff_maps<K: Ord + Clone, V: PartialEq>(
+    mut old: BTreeMap<K, V>,
+    mut new: BTreeMap<K, V>,
+) -> BTreeMap<K, Change<V>> {
+    let keys: BTreeSet<K> = old.keys().chain(new.keys()).cloned().collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let change = (old.remove(&key), new.remove(&key));
+            if change.0 == change.1 {
+                None
+            } else {
+                Some((key, change))
+            }
+        })
+        .collect()
+}
+
+fn show<T: fmt::Display>(value: &Option<T>) -> String {
+    value.as_ref().map_or("-".to_string(), T::to_string)
+}
+
+fn show_account(account: &Option<AccountId>) -> String {
+    account.as_ref().map_or("-".to_string(), to_ss58)
+}
+
+impl fmt::Display for SpecDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "the chainspecs are the same");
+        }
+        if let Some((old, new)) = &self.name {
+            writeln!(f, "name: {} -> {}", old, new)?;
+        }
+        if let Some((old, new)) = &self.id {
+            writeln!(f, "id: {} -> {}", old, new)?;
+        }
+        for (key, (old, new)) in &self.properties {
+            writeln!(f, "property {}: {} -> {}", key, show(old), show(new))?;
+        }
+        for account in &self.authorities_added {
+            writeln!(f, "authority added: {}", to_ss58(account))?;
+        }
+        for account in &self.authorities_removed {
+            writeln!(f, "authority removed: {}", to_ss58(account))?;
+        }
+        for (account, (old, new)) in &self.balances {
+            writeln!(
+                f,
+                "balance of {}: {} -> {}",
+                to_ss58(account),
+                show(old),
+                show(new)
+            )?;
+        }
+        if let Some((old, new)) = &self.sudo_key {
+            writeln!(
+                f,
+                "sudo key: {} -> {}",
+                show_account(old),
+                show_account(new)
+            )?;
+        }
+        if self.runtime_changed {
+            writeln!(f, "runtime: changed")?;
+        }
+        let (added, removed, changed) = self.storage;
+        write!(
+            f,
+            "storage: {} keys added, {} removed, {} changed",
+            added, removed, changed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{Chain, TestnetOptions};
+    use crate::keyring::Keyring;
+
+    fn ved() -> ChainSpec<GenesisConfig> {
+        Chain::Ved {
+            options: TestnetOptions::default(),
+        }
+        .generate()
+        .unwrap()
+    }
+
+    fn local() -> ChainSpec<GenesisConfig> {
+        Chain::Local {
+            options: TestnetOptions::default(),
+        }
+        .generate()
+        .unwrap()
+    }
+
+    #[test]
+    fn t_same_spec() {
+        let diff = diff_specs(ved(), ved()).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "the chainspecs are the same");
+    }
+
+    #[test]
+    fn t_diff_specs() {
+        let diff = diff_specs(ved(), local()).unwrap();
+        assert_eq!(
+            diff.id,
+            Some((
+                "substrate-warmup-local".to_string(),
+                "substrate-warmup-local-testnet".to_string()
+            ))
+        );
+        assert_eq!(diff.authorities_added, vec![Keyring::Bob.stash()]);
+        assert!(diff.authorities_removed.is_empty());
+        assert!(diff.balances.contains_key(&Keyring::Bob.stash()));
+        assert_eq!(diff.sudo_key, None);
+        assert!(!diff.runtime_changed);
+        assert!(diff.storage.2 > 0);
+        let shown = diff.to_string();
+        assert!(shown.contains(&format!(
+            "authority added: {}",
+            to_ss58(&Keyring::Bob.stash())
+        )));
+
+        let reverse = diff_specs(local(), ved()).unwrap();
+        assert_eq!(reverse.authorities_removed, vec![Keyring::Bob.stash()]);
+    }
+
+    #[test]
+    fn t_diff_maps() {
+        let old: BTreeMap<u8, u8> = vec![(1, 1), (2, 2), (3, 3)].into_iter().collect();
+        let new: BTreeMap<u8, u8> = vec![(2, 2), (3, 4), (5, 5)].into_iter().collect();
+        let diff: Vec<_> = diff_maps(old, new).into_iter().collect();
+        assert_eq!(
+            diff,
+            vec![
+                (1, (Some(1), None)),
+                (3, (Some(3), Some(4))),
+                (5, (None, Some(5)))
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! The `serve-faucet` command: an http endpoint dripping native tokens to testnet users from an
+//! account the operator endows, for users who can't send the faucet pallet's unsigned `drip`
+//! themselves, e.g. from a web page.
+//!
+//! `POST /drip/<address>` signs a transfer of the drip amount to the address and submits it to a
+//! node over rpc. Each address, and each ip, drips at most once per cooldown. Requests are served
+//! one at a time, which keeps the nonces of the faucet account in order. Each drip takes the nonce
+//! the node counts past the drips in its pool, so a drip the pool drops doesn't hold back later
+//! ones.
+
+use crate::address::account_of;
+use crate::http::{path_of, serve};
+use crate::rpc::Rpc;
+use crate::upgrade::{sign, SigningContext};
+use codec::Encode;
+use node_template_runtime::{AccountId, Balance, Call};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use substrate_primitives::{sr25519, Pair};
+
+/// When each address and each ip last dripped.
+#[derive(Clone, Debug, Default)]
+struct Limits {
+    accounts: BTreeMap<AccountId, Instant>,
+    ips: BTreeMap<IpAddr, Instant>,
+}
+
+impl Limits {
+    fn check(
+        &self,
+        account: &AccountId,
+        ip: IpAddr,
+        now: Instant,
+        cooldown: Duration,
+    ) -> Result<(), String> {
+        let wait = |last: Option<&Instant>| {
+            last.map_or(Duration::from_secs(0), |last| {
+                cooldown.checked_sub(now - *last).unwrap_or_default()
+            })
+        };
+        let wait = wait(self.accounts.get(account)).max(wait(self.ips.get(&ip)));
+        if wait > Duration::from_secs(0) {
+            let secs = wait.as_secs() + if wait.subsec_nanos() > 0 { 1 } else { 0 };
+            return Err(format!("dripped recently, try again in {}s", secs));
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, account: AccountId, ip: IpAddr, now: Instant) {
+        self.accounts.insert(account, now);
+        self.ips.insert(ip, now);
+    }
+
+    /// Forget the drips older than `cooldown`, which no longer limit anyone.
+    fn prune(&mut self, now: Instant, cooldown: Duration) {
+        self.accounts.retain(|_, last| now - *last < cooldown);
+        self.ips.retain(|_, last| now - *last < cooldown);
+    }
+}
+
+pub struct Faucet {
+    rpc: Rpc,
+    key: sr25519::Pair,
+    amount: Balance,
+    cooldown: Duration,
+    limits: Limits,
+}
+
+impl Faucet {
+    pub fn new(rpc: Rpc, key: sr25519::Pair, amount: Balance, cooldown: Duration) -> Self {
+        Faucet {
+            rpc,
+            key,
+            amount,
+            cooldown,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Submit a drip to `account`, returning the hash of the extrinsic.
+    fn drip(&mut self, account: AccountId, ip: IpAddr, now: Instant) -> Result<String, String> {
+        let mut context = SigningContext::fetch(&self.rpc, &self.key.public())?;
+        // the nonce in storage lags behind the drips in the pool
+        context.nonce = self.rpc.next_index(&self.key.public())?;
+        let call = Call::Balances(balances::Call::transfer(
+            account.clone().into(),
+            self.amount,
+        ));
+        let hash = self
+            .rpc
+            .submit_extrinsic(&sign(call, &self.key, context).encode())?;
+        self.limits.record(account, ip, now);
+        Ok(hash)
+    }
+
+    /// The status line and body answering `request_line` from `ip`.
+    fn respond(&mut self, request_line: &str, ip: IpAddr) -> (&'static str, String) {
+        let address = match drip_address(request_line) {
+            Some(address) => address,
+            None => {
+                return (
+                    "404 Not Found",
+                    "POST /drip/<address> to receive tokens".into(),
+                )
+            }
+        };
+        let account = match account_of(address) {
+            Ok(account) => account,
+            Err(e) => return ("400 Bad Request", e),
+        };
+        let now = Instant::now();
+        self.limits.prune(now, self.cooldown);
+        if let Err(e) = self.limits.check(&account, ip, now, self.cooldown) {
+            return ("429 Too Many Requests", e);
+        }
+        match self.drip(account, ip, now) {
+            Ok(hash) => ("200 OK", hash),
+            Err(e) => ("502 Bad Gateway", e),
+        }
+    }
+}
+
+/// The address of a `POST /drip/<address>` request line.
+fn drip_address(request_line: &str) -> Option<&str> {
+    let path = path_of(request_line, "POST")?;
+    if !path.starts_with("/drip/") {
+        return None;
+    }
+    let address = &path["/drip/".len()..];
+    if address.is_empty() || address.contains('/') {
+        return None;
+    }
+    Some(address)
+}
+
+/// Serve `faucet` on `listen` until the port can't be listened on.
+pub fn serve_faucet(listen: SocketAddr, mut faucet: Faucet) -> Result<String, String> {
+    serve(listen, |request_line, ip| faucet.respond(request_line, ip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+
+    #[test]
+    fn t_drip_address() {
+        let alice = Keyring::Alice.account().to_string();
+        let request_line = format!("POST /drip/{} HTTP/1.1\r\n", alice);
+        assert_eq!(drip_address(&request_line), Some(alice.as_str()));
+        assert_eq!(drip_address(&format!("GET /drip/{} HTTP/1.1", alice)), None);
+        assert_eq!(drip_address("POST /drip/ HTTP/1.1"), None);
+        assert_eq!(drip_address("POST /drip/a/b HTTP/1.1"), None);
+        assert_eq!(drip_address("POST /other HTTP/1.1"), None);
+        assert_eq!(drip_address(""), None);
+    }
+
+    #[test]
+    fn t_limits() {
+        let cooldown = Duration::from_secs(60);
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let other_ip: IpAddr = "10.0.0.2".parse().unwrap();
+        let start = Instant::now();
+        let mut limits = Limits::default();
+        limits.check(&alice, ip, start, cooldown).unwrap();
+        limits.record(alice.clone(), ip, start);
+
+        let later = start + Duration::from_secs(30);
+        let e = limits.check(&alice, other_ip, later, cooldown).unwrap_err();
+        assert_eq!(e, "dripped recently, try again in 30s");
+        limits.check(&bob, ip, later, cooldown).unwrap_err();
+        limits.check(&bob, other_ip, later, cooldown).unwrap();
+        limits
+            .check(&alice, ip, start + cooldown, cooldown)
+            .unwrap();
+
+        limits.record(bob.clone(), other_ip, later);
+        limits.prune(start + cooldown, cooldown);
+        assert_eq!(limits.accounts.keys().collect::<Vec<_>>(), vec![&bob]);
+        assert_eq!(limits.ips.keys().collect::<Vec<_>>(), vec![&other_ip]);
+    }
+}
@@ -0,0 +1,1231 @@
+//! Human editable description of a chain, read from a toml file.
+//!
+//! ```toml
+//! name = "Substrate Warmup Example Testnet"
+//! id = "substrate-warmup-example"
+//! sudo_key = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! # optional, announces calls dispatched as root after the admin delay, defaults to the sudo key
+//! admin = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! # optional, blocks between the announcement of an admin call and its dispatch, defaults to a
+//! # day
+//! admin_delay = 14400
+//! # optional, defaults to the sudo key
+//! treasury = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! # optional, in the smallest unit
+//! treasury_endowment = "1000000000000000000000"
+//! boot_nodes = []
+//! # optional, members of the council at genesis
+//! council = ["5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"]
+//! # optional, [module index, call index] of calls rejected until unpaused, e.g. balances transfers
+//! paused_calls = [[5, 0]]
+//! # optional, in the smallest unit, minted to the author of each block, defaults to one token
+//! block_reward = 1000000000000
+//! # optional, fail if the endowments sum to more than this
+//! max_total_issuance = "1000000000000000000000000"
+//! # optional, runtime code to use instead of the runtime chaingen was built with, relative to
+//! # this file
+//! wasm = "runtime.compact.wasm"
+//! # optional, target time between blocks, defaults to 6000
+//! millisecs_per_block = 6000
+//! # optional, more endowed accounts as "address,balance" rows, relative to this file
+//! endowed_accounts_csv = "allocations.csv"
+//! # optional, "address,balance" rows accounts claim after launch with the proofs printed by
+//! # `chaingen claims-tree`, relative to this file; only their merkle root is stored at genesis
+//! claims_csv = "airdrop.csv"
+//! # optional, "address,balance" rows of an erc20 snapshot, each ethereum address claiming its
+//! # balance by signing the account to pay, relative to this file
+//! eth_claims_csv = "snapshot.csv"
+//!
+//! [[authorities]]
+//! account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! grandpa = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu"
+//! babe = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! # optional, defaults to the babe key
+//! im_online = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//!
+//! [[endowed_accounts]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! balance = 1000000000000
+//!
+//! # endows the account controlled by `threshold` of `signatories`, e.g. a team treasury
+//! [[multisig_endowments]]
+//! signatories = [
+//!     "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+//!     "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty",
+//! ]
+//! threshold = 2
+//! balance = 1000000000000
+//!
+//! [[vesting]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! begin = 0
+//! length = 1000
+//! liquid = 500000000000
+//!
+//! # locked by the vesting module, e.g. a token sale allocation, released linearly over
+//! # `duration` blocks from `start_block` but not before `cliff` blocks have passed
+//! [[vesting_schedules]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! start_block = 0
+//! cliff = 2592000
+//! duration = 10368000
+//! amount = 1000000000000
+//!
+//! # tokens other than the native token, the supply is the sum of the distribution
+//! [[tokens]]
+//! id = 0
+//! symbol = "USDX"
+//! decimals = 6
+//! owner = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//!
+//! [[tokens.distribution]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! balance = 1000000
+//!
+//! # liquidity pools of two tokens, opened with tokens the provider holds at genesis
+//! [[pools]]
+//! asset_a = 0
+//! asset_b = 1
+//! provider = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! amount_a = 500000
+//! amount_b = 1000
+//!
+//! # nft collections, in which their owner mints items
+//! [[collections]]
+//! id = 0
+//! owner = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! # optional, e.g. the uri of a description of the collection
+//! metadata = "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+//!
+//! # identity registrars, in order of registrar index
+//! [[registrars]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! # optional, in the smallest unit, defaults to 0
+//! fee = 0
+//! fields = ["display", "email"]
+//!
+//! # calls dispatched as root by the scheduler, e.g. a runtime upgrade
+//! [[scheduled_calls]]
+//! when = 100800
+//! # optional, [period, count] to dispatch the call `count` times, `period` blocks apart
+//! periodic = [14400, 7]
+//! # the scale encoded runtime call, e.g. as shown by the polkadot.js extrinsics tab
+//! call = "0x..."
+//!
+//! # telemetry servers nodes report to, at a verbosity from 0 to 255
+//! [[telemetry_endpoints]]
+//! url = "wss://telemetry.polkadot.io/submit/"
+//! verbosity = 0
+//!
+//! # optional, the faucet pays accounts asking for tokens through unsigned `drip` calls
+//! [faucet]
+//! # optional, in the smallest unit, 0 leaves the faucet empty
+//! endowment = "72057594037927936"
+//! # optional, in the smallest unit, defaults to ten tokens
+//! drip_amount = 10000000000000
+//! # optional, blocks between drips to an account, defaults to a day
+//! cooldown = 14400
+//!
+//! # optional, accounts allowed to transfer native tokens while the whitelist is enforced
+//! [whitelist]
+//! accounts = ["5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"]
+//! # optional, reject transfers from accounts off the whitelist from genesis on, defaults to false
+//! enforced = true
+//!
+//! # optional, the oracle takes the median of the prices the offchain workers of feeders fetch
+//! [oracle]
+//! # keys of the "orcl" type in the keystore of the node of each feeder
+//! feeders = ["5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"]
+//! # answers with the price as the first number of the response body
+//! endpoint = "https://min-api.cryptocompare.com/data/price?fsym=DOT&tsyms=USD"
+//!
+//! [properties]
+//! tokenSymbol = "DCK"
+//! tokenDecimals = 12
+//! ```
+//!
+//! Keys are ss58 encoded, with the project's prefix or the generic one. Balances may be written as integers or, when too large for a toml
+//! integer, as decimal strings.
+
+use crate::chain_spec::{
+    parse_pubkey, ChainSpecBuilder, CollectionSpec, PoolSpec, RegistrarSpec, ScheduledCall,
+    TokenSpec, Vesting, VestingSchedule,
+};
+use crate::serializable_genesis::{ChainSpec, Properties};
+use crate::ss58::{self, from_ss58};
+use eth_claims::EthereumAddress;
+use node_template_runtime::opaque::SessionKeys;
+use node_template_runtime::{AccountId, Balance, BlockNumber, GenesisConfig, ImOnlineId, OracleId};
+use serde::{Deserialize, Deserializer};
+use std::fs;
+use std::path::{Path, PathBuf};
+use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_primitives::Public;
+use substrate_telemetry::TelemetryEndpoints;
+use tx_pause::CallIndex;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisFile {
+    pub name: String,
+    pub id: String,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub sudo_key: AccountId,
+    #[serde(default, deserialize_with = "ss58::deserialize_option")]
+    pub admin: Option<AccountId>,
+    pub admin_delay: Option<BlockNumber>,
+    #[serde(default, deserialize_with = "ss58::deserialize_option")]
+    pub treasury: Option<AccountId>,
+    #[serde(default, deserialize_with = "optional_balance")]
+    pub treasury_endowment: Option<Balance>,
+    pub authorities: Vec<Authority>,
+    #[serde(default)]
+    pub endowed_accounts: Vec<Endowment>,
+    pub endowed_accounts_csv: Option<PathBuf>,
+    #[serde(default)]
+    pub multisig_endowments: Vec<MultisigEndowment>,
+    #[serde(default)]
+    pub vesting: Vec<Vesting>,
+    #[serde(default)]
+    pub vesting_schedules: Vec<CliffVesting>,
+    #[serde(default)]
+    pub tokens: Vec<Token>,
+    #[serde(default)]
+    pub pools: Vec<Pool>,
+    #[serde(default)]
+    pub collections: Vec<Collection>,
+    #[serde(default, deserialize_with = "ss58::deserialize_vec")]
+    pub council: Vec<AccountId>,
+    #[serde(default)]
+    pub paused_calls: Vec<CallIndex>,
+    #[serde(default, deserialize_with = "optional_balance")]
+    pub block_reward: Option<Balance>,
+    #[serde(default)]
+    pub registrars: Vec<Registrar>,
+    #[serde(default)]
+    pub scheduled_calls: Vec<Scheduled>,
+    pub faucet: Option<Faucet>,
+    pub whitelist: Option<Whitelist>,
+    pub oracle: Option<Oracle>,
+    pub claims_csv: Option<PathBuf>,
+    #[serde(skip)]
+    pub claims: Vec<(AccountId, Balance)>,
+    pub eth_claims_csv: Option<PathBuf>,
+    #[serde(skip)]
+    pub eth_claims: Vec<(EthereumAddress, Balance)>,
+    #[serde(default)]
+    pub boot_nodes: Vec<String>,
+    #[serde(default)]
+    pub telemetry_endpoints: Vec<Telemetry>,
+    pub protocol_id: Option<String>,
+    pub properties: Option<Properties>,
+    #[serde(default, deserialize_with = "optional_balance")]
+    pub max_total_issuance: Option<Balance>,
+    pub wasm: Option<PathBuf>,
+    pub millisecs_per_block: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Authority {
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub account: AccountId,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub grandpa: GrandpaId,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub babe: BabeId,
+    #[serde(default, deserialize_with = "ss58::deserialize_option")]
+    pub im_online: Option<ImOnlineId>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CliffVesting {
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub account: AccountId,
+    pub start_block: BlockNumber,
+    #[serde(default)]
+    pub cliff: BlockNumber,
+    pub duration: BlockNumber,
+    #[serde(deserialize_with = "balance")]
+    pub amount: Balance,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Token {
+    pub id: u32,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub owner: AccountId,
+    #[serde(default)]
+    pub distribution: Vec<Endowment>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pool {
+    pub asset_a: u32,
+    pub asset_b: u32,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub provider: AccountId,
+    #[serde(deserialize_with = "balance")]
+    pub amount_a: Balance,
+    #[serde(deserialize_with = "balance")]
+    pub amount_b: Balance,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Collection {
+    pub id: u32,
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub owner: AccountId,
+    #[serde(default)]
+    pub metadata: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MultisigEndowment {
+    #[serde(deserialize_with = "ss58::deserialize_vec")]
+    pub signatories: Vec<AccountId>,
+    pub threshold: u16,
+    #[serde(deserialize_with = "balance")]
+    pub balance: Balance,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Registrar {
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub account: AccountId,
+    #[serde(default, deserialize_with = "balance")]
+    pub fee: Balance,
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scheduled {
+    pub when: BlockNumber,
+    pub periodic: Option<(BlockNumber, u32)>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub call: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Telemetry {
+    pub url: String,
+    pub verbosity: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Faucet {
+    #[serde(default, deserialize_with = "optional_balance")]
+    pub endowment: Option<Balance>,
+    #[serde(default, deserialize_with = "optional_balance")]
+    pub drip_amount: Option<Balance>,
+    pub cooldown: Option<BlockNumber>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Whitelist {
+    #[serde(default, deserialize_with = "ss58::deserialize_vec")]
+    pub accounts: Vec<AccountId>,
+    #[serde(default)]
+    pub enforced: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Oracle {
+    #[serde(default, deserialize_with = "ss58::deserialize_vec")]
+    pub feeders: Vec<OracleId>,
+    #[serde(default)]
+    pub endpoint: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowment {
+    #[serde(deserialize_with = "ss58::deserialize")]
+    pub account: AccountId,
+    #[serde(deserialize_with = "balance")]
+    pub balance: Balance,
+}
+
+impl GenesisFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading genesis config {}: {}", path.display(), e))?;
+        let mut file: Self = toml::from_str(&contents)
+            .map_err(|e| format!("Error parsing genesis config {}: {}", path.display(), e))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        file.wasm = file.wasm.map(|wasm| dir.join(wasm));
+        if let Some(csv) = file.endowed_accounts_csv.take() {
+            let csv = dir.join(csv);
+            file.endowed_accounts.extend(
+                endowed_accounts_from_csv(&csv)?
+                    .into_iter()
+                    .map(|(account, balance)| Endowment { account, balance }),
+            );
+        }
+        if let Some(csv) = file.claims_csv.take() {
+            file.claims = endowed_accounts_from_csv(&dir.join(csv))?;
+        }
+        if let Some(csv) = file.eth_claims_csv.take() {
+            file.eth_claims = eth_snapshot_from_csv(&dir.join(csv))?;
+        }
+        Ok(file)
+    }
+
+    pub fn into_builder(self) -> ChainSpecBuilder {
+        let mut builder = ChainSpecBuilder::default()
+            .name(&self.name)
+            .id(&self.id)
+            .authorities(
+                self.authorities
+                    .into_iter()
+                    .map(|authority| {
+                        let im_online = authority
+                            .im_online
+                            .unwrap_or_else(|| ImOnlineId::from_slice(authority.babe.as_ref()));
+                        let keys = SessionKeys {
+                            grandpa: authority.grandpa,
+                            babe: authority.babe,
+                            im_online,
+                        };
+                        (authority.account, keys)
+                    })
+                    .collect(),
+            )
+            .sudo_key(self.sudo_key)
+            .endowed_accounts(
+                self.endowed_accounts
+                    .into_iter()
+                    .map(|Endowment { account, balance }| (account, balance))
+                    .collect(),
+            )
+            .vesting(self.vesting)
+            .vesting_schedules(
+                self.vesting_schedules
+                    .into_iter()
+                    .map(|vesting| {
+                        let schedule = VestingSchedule {
+                            start_block: vesting.start_block,
+                            cliff: vesting.cliff,
+                            duration: vesting.duration,
+                            amount: vesting.amount,
+                        };
+                        (vesting.account, schedule)
+                    })
+                    .collect(),
+            )
+            .tokens(
+                self.tokens
+                    .into_iter()
+                    .map(|token| TokenSpec {
+                        id: token.id,
+                        symbol: token.symbol,
+                        decimals: token.decimals,
+                        owner: token.owner,
+                        distribution: token
+                            .distribution
+                            .into_iter()
+                            .map(|Endowment { account, balance }| (account, balance))
+                            .collect(),
+                    })
+                    .collect(),
+            )
+            .pools(
+                self.pools
+                    .into_iter()
+                    .map(|pool| PoolSpec {
+                        asset_a: pool.asset_a,
+                        asset_b: pool.asset_b,
+                        provider: pool.provider,
+                        amount_a: pool.amount_a,
+                        amount_b: pool.amount_b,
+                    })
+                    .collect(),
+            )
+            .collections(
+                self.collections
+                    .into_iter()
+                    .map(|collection| CollectionSpec {
+                        id: collection.id,
+                        owner: collection.owner,
+                        metadata: collection.metadata,
+                    })
+                    .collect(),
+            )
+            .council(self.council)
+            .paused_calls(self.paused_calls)
+            .registrars(
+                self.registrars
+                    .into_iter()
+                    .map(|registrar| RegistrarSpec {
+                        account: registrar.account,
+                        fee: registrar.fee,
+                        fields: registrar.fields,
+                    })
+                    .collect(),
+            )
+            .scheduled_calls(
+                self.scheduled_calls
+                    .into_iter()
+                    .map(|scheduled| ScheduledCall {
+                        when: scheduled.when,
+                        periodic: scheduled.periodic,
+                        call: scheduled.call,
+                    })
+                    .collect(),
+            )
+            .boot_nodes(self.boot_nodes)
+            .claims(self.claims)
+            .eth_claims(self.eth_claims);
+        for endowment in self.multisig_endowments {
+            builder = builder.multisig_endowment(
+                endowment.signatories,
+                endowment.threshold,
+                endowment.balance,
+            );
+        }
+        if let Some(treasury) = self.treasury {
+            builder = builder.treasury(treasury);
+        }
+        if let Some(admin) = self.admin {
+            builder = builder.admin_key(admin);
+        }
+        if let Some(delay) = self.admin_delay {
+            builder = builder.admin_delay(delay);
+        }
+        if let Some(faucet) = self.faucet {
+            if let Some(endowment) = faucet.endowment {
+                builder = builder.faucet_endowment(endowment);
+            }
+            if let Some(drip) = faucet.drip_amount {
+                builder = builder.faucet_drip(drip);
+            }
+            if let Some(cooldown) = faucet.cooldown {
+                builder = builder.faucet_cooldown(cooldown);
+            }
+        }
+        if let Some(whitelist) = self.whitelist {
+            builder = builder
+                .whitelist(whitelist.accounts)
+                .enforce_whitelist(whitelist.enforced);
+        }
+        if let Some(oracle) = self.oracle {
+            builder = builder
+                .oracle_feeders(oracle.feeders)
+                .oracle_endpoint(&oracle.endpoint);
+        }
+        if let Some(endowment) = self.treasury_endowment {
+            builder = builder.treasury_endowment(endowment);
+        }
+        if !self.telemetry_endpoints.is_empty() {
+            builder = builder.telemetry_endpoints(TelemetryEndpoints::new(
+                self.telemetry_endpoints
+                    .into_iter()
+                    .map(|Telemetry { url, verbosity }| (url, verbosity))
+                    .collect(),
+            ));
+        }
+        if let Some(protocol_id) = self.protocol_id {
+            builder = builder.protocol_id(&protocol_id);
+        }
+        if let Some(properties) = self.properties {
+            builder = builder.properties(properties);
+        }
+        if let Some(reward) = self.block_reward {
+            builder = builder.block_reward(reward);
+        }
+        if let Some(cap) = self.max_total_issuance {
+            builder = builder.max_total_issuance(cap);
+        }
+        if let Some(wasm) = self.wasm {
+            builder = builder.wasm(&wasm);
+        }
+        if let Some(millisecs_per_block) = self.millisecs_per_block {
+            builder = builder.millisecs_per_block(millisecs_per_block);
+        }
+        builder
+    }
+}
+
+/// Read a toml genesis config file and build the chainspec it describes.
+pub fn genesis_from_config(path: &Path) -> Result<ChainSpec<GenesisConfig>, String> {
+    GenesisFile::load(path)?
+        .into_builder()
+        .build()
+        .map_err(|e| format!("Invalid genesis config {}: {}", path.display(), e))
+}
+
+/// Read endowments from a csv file of "address,balance" rows. Addresses are either ss58 encoded or
+/// 0x prefixed hex public keys. Balances are in the smallest unit. An "address,balance" header,
+/// blank lines, and lines starting with '#' are ignored.
+pub fn endowed_accounts_from_csv(path: &Path) -> Result<Vec<(AccountId, Balance)>, String> {
+    balances_from_csv(path, "endowments", |address| {
+        if address.starts_with("0x") {
+            parse_pubkey(address)
+        } else {
+            from_ss58(address)
+        }
+    })
+}
+
+/// Read an erc20 snapshot from a csv file of "address,balance" rows, in the format of
+/// `endowed_accounts_from_csv` but with 0x prefixed ethereum addresses.
+pub fn eth_snapshot_from_csv(path: &Path) -> Result<Vec<(EthereumAddress, Balance)>, String> {
+    balances_from_csv(path, "snapshot", |address| {
+        if !address.starts_with("0x") {
+            return Err("expected a 0x prefixed ethereum address".into());
+        }
+        match hex::decode(&address[2..]) {
+            Ok(ref bytes) if bytes.len() == 20 => Ok(EthereumAddress::from_slice(bytes)),
+            _ => Err(format!("invalid ethereum address {:?}", address)),
+        }
+    })
+}
+
+fn balances_from_csv<A>(
+    path: &Path,
+    what: &str,
+    parse_address: impl Fn(&str) -> Result<A, String>,
+) -> Result<Vec<(A, Balance)>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Error reading {} {}: {}", what, path.display(), e))?;
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .filter(|(_, line)| !line.eq_ignore_ascii_case("address,balance"))
+        .map(|(number, line)| {
+            parse_row(line, &parse_address)
+                .map_err(|e| format!("{} line {}: {}", path.display(), number, e))
+        })
+        .collect()
+}
+
+fn parse_row<A>(
+    row: &str,
+    parse_address: impl Fn(&str) -> Result<A, String>,
+) -> Result<(A, Balance), String> {
+    let mut fields = row.split(',').map(str::trim);
+    let (address, balance) = match (fields.next(), fields.next(), fields.next()) {
+        (Some(address), Some(balance), None) => (address, balance),
+        _ => return Err("expected two columns, address and balance".into()),
+    };
+    let address = parse_address(address)?;
+    let balance = balance
+        .parse()
+        .map_err(|e| format!("invalid balance {:?}: {}", balance, e))?;
+    Ok((address, balance))
+}
+
+/// Bytes written as a 0x prefixed hex string.
+fn hex_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    if !hex.starts_with("0x") {
+        return Err(serde::de::Error::custom(
+            "expected a 0x prefixed hex string",
+        ));
+    }
+    hex::decode(&hex[2..]).map_err(serde::de::Error::custom)
+}
+
+/// toml integers are limited to 64 bits so large balances are accepted as strings too.
+fn balance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Int(u64),
+        Str(String),
+    }
+    match Repr::deserialize(deserializer)? {
+        Repr::Int(balance) => Ok(balance.into()),
+        Repr::Str(balance) => balance.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+fn optional_balance<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Balance>, D::Error> {
+    balance(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::genesis_hash;
+    use crate::keyring::get_from_seed;
+    use codec::Encode;
+    use node_template_runtime::{Call, EXISTENTIAL_DEPOSIT};
+
+    fn write_config(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join("substrate-warmup-genesis-config");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn example() -> String {
+        format!(
+            r#"
+            name = "Example"
+            id = "example"
+            sudo_key = "{alice}"
+            treasury_endowment = "1000000000000000000000000"
+
+            [[authorities]]
+            account = "{alice}"
+            grandpa = "{alice_grandpa}"
+            babe = "{alice_babe}"
+
+            [[endowed_accounts]]
+            account = "{bob}"
+            balance = 1000
+
+            [[vesting]]
+            account = "{bob}"
+            begin = 0
+            length = 10
+            liquid = 100
+
+            [properties]
+            tokenSymbol = "EXM"
+            "#,
+            alice = get_from_seed::<AccountId>("Alice"),
+            alice_grandpa = get_from_seed::<GrandpaId>("Alice"),
+            alice_babe = get_from_seed::<BabeId>("Alice"),
+            bob = get_from_seed::<AccountId>("Bob"),
+        )
+    }
+
+    #[test]
+    fn t_telemetry_endpoints() {
+        let config = example()
+            + r#"
+            [[telemetry_endpoints]]
+            url = "wss://telemetry.example.com/submit/"
+            verbosity = 1
+            "#;
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        let spec = file.into_builder().build().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&spec.into_json(false).unwrap()).unwrap();
+        assert_eq!(
+            json["telemetryEndpoints"],
+            serde_json::json!([["wss://telemetry.example.com/submit/", 1]])
+        );
+    }
+
+    #[test]
+    fn t_load() {
+        let file: GenesisFile = toml::from_str(&example()).unwrap();
+        assert_eq!(file.sudo_key, get_from_seed::<AccountId>("Alice"));
+        assert_eq!(
+            file.treasury_endowment,
+            Some(1_000_000_000_000_000_000_000_000)
+        );
+        assert_eq!(file.authorities.len(), 1);
+        assert_eq!(file.authorities[0].im_online, None);
+        assert_eq!(file.endowed_accounts[0].balance, 1000);
+        assert_eq!(file.vesting[0].length, 10);
+    }
+
+    #[test]
+    fn t_project_prefix() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let alice_grandpa = AccountId::from_slice(get_from_seed::<GrandpaId>("Alice").as_ref());
+        // addresses as wallets show them on the project's networks
+        let mut config = example();
+        for public in &[&alice, &bob, &alice_grandpa] {
+            config = config.replace(&public.to_string(), &ss58::to_ss58(public));
+        }
+        assert!(!config.contains(&alice.to_string()));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.sudo_key, alice);
+        assert_eq!(file.authorities[0].account, alice);
+        assert_eq!(
+            file.authorities[0].grandpa,
+            get_from_seed::<GrandpaId>("Alice")
+        );
+        assert_eq!(file.endowed_accounts[0].account, bob);
+        assert_eq!(file.vesting[0].account, bob);
+
+        let other_network = example().replace(&alice.to_string(), &ss58::encode(2, &alice));
+        assert!(toml::from_str::<GenesisFile>(&other_network).is_err());
+    }
+
+    #[test]
+    fn t_genesis_from_config() {
+        let spec = genesis_from_config(&write_config("example.toml", &example())).unwrap();
+        assert_eq!(spec.protocol_id(), Some("example"));
+        assert_eq!(spec.properties()["tokenSymbol"], "EXM");
+        spec.into_json(true).unwrap();
+    }
+
+    #[test]
+    fn t_invalid_config() {
+        genesis_from_config(&write_config("empty.toml", "")).unwrap_err();
+        let no_authorities = example().replace("[[authorities]]", "[[unknown]]");
+        genesis_from_config(&write_config("unknown.toml", &no_authorities)).unwrap_err();
+        genesis_from_config(Path::new("/does/not/exist.toml")).unwrap_err();
+    }
+
+    #[test]
+    fn t_endowed_accounts_from_csv() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let csv = format!(
+            "address,balance\n\
+             {},1000000000000000000000000\n\
+             \n\
+             # comment\n\
+             0x{}, 20\n",
+            alice,
+            hex::encode(&bob),
+        );
+        assert_eq!(
+            endowed_accounts_from_csv(&write_config("endowed.csv", &csv)).unwrap(),
+            vec![
+                (alice.clone(), 1_000_000_000_000_000_000_000_000),
+                (bob, 20)
+            ]
+        );
+        for invalid in &[
+            format!("{}", alice),
+            format!("{},", alice),
+            format!("{},-1", alice),
+            format!("{},1,2", alice),
+            "5notanaddress,1".to_string(),
+            "0x1234,1".to_string(),
+        ] {
+            endowed_accounts_from_csv(&write_config("invalid.csv", invalid)).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_config_with_csv() {
+        let csv = format!("{},7\n", get_from_seed::<AccountId>("Charlie"));
+        write_config("allocations.csv", &csv);
+        let config = example().replace(
+            "[[authorities]]",
+            "endowed_accounts_csv = \"allocations.csv\"\n[[authorities]]",
+        );
+        let file = GenesisFile::load(&write_config("with-csv.toml", &config)).unwrap();
+        assert_eq!(file.endowed_accounts.len(), 2);
+        assert_eq!(file.endowed_accounts[1].balance, 7);
+    }
+
+    #[test]
+    fn t_config_with_tokens() {
+        let tokens = format!(
+            r#"
+            [[tokens]]
+            id = 0
+            symbol = "USDX"
+            decimals = 6
+            owner = "{alice}"
+
+            [[tokens.distribution]]
+            account = "{alice}"
+            balance = "1000000000000000000000000"
+
+            [[tokens.distribution]]
+            account = "{bob}"
+            balance = 10
+
+            [[tokens]]
+            id = 1
+            symbol = "GLD"
+            decimals = 0
+            owner = "{bob}"
+            "#,
+            alice = get_from_seed::<AccountId>("Alice"),
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", tokens));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.tokens.len(), 2);
+        assert_eq!(file.tokens[0].symbol, "USDX");
+        assert_eq!(
+            file.tokens[0].distribution[0].balance,
+            1_000_000_000_000_000_000_000_000
+        );
+        assert!(file.tokens[1].distribution.is_empty());
+        genesis_from_config(&write_config("tokens.toml", &config)).unwrap();
+
+        let duplicate = config.replace("id = 1", "id = 0");
+        genesis_from_config(&write_config("duplicate-tokens.toml", &duplicate)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_pools() {
+        let pools = format!(
+            r#"
+            [[tokens]]
+            id = 0
+            symbol = "USDX"
+            decimals = 6
+            owner = "{bob}"
+
+            [[tokens.distribution]]
+            account = "{bob}"
+            balance = 1000
+
+            [[tokens]]
+            id = 1
+            symbol = "GLD"
+            decimals = 0
+            owner = "{bob}"
+
+            [[tokens.distribution]]
+            account = "{bob}"
+            balance = 10
+
+            [[pools]]
+            asset_a = 1
+            asset_b = 0
+            provider = "{bob}"
+            amount_a = 10
+            amount_b = "1000"
+            "#,
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", pools));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.pools.len(), 1);
+        assert_eq!(file.pools[0].amount_b, 1000);
+        genesis_from_config(&write_config("pools.toml", &config)).unwrap();
+
+        let overdrawn = config.replace("amount_a = 10", "amount_a = 11");
+        genesis_from_config(&write_config("overdrawn-pools.toml", &overdrawn)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_collections() {
+        let collections = format!(
+            r#"
+            [[collections]]
+            id = 0
+            owner = "{alice}"
+            metadata = "ipfs://punks"
+
+            [[collections]]
+            id = 1
+            owner = "{bob}"
+            "#,
+            alice = get_from_seed::<AccountId>("Alice"),
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", collections));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.collections.len(), 2);
+        assert_eq!(file.collections[0].metadata, "ipfs://punks");
+        assert!(file.collections[1].metadata.is_empty());
+        genesis_from_config(&write_config("collections.toml", &config)).unwrap();
+
+        let duplicate = config.replace("id = 1", "id = 0");
+        genesis_from_config(&write_config("duplicate-collections.toml", &duplicate)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_registrars() {
+        let registrars = format!(
+            r#"
+            [[registrars]]
+            account = "{alice}"
+            fee = "1000000000000"
+            fields = ["display", "web"]
+
+            [[registrars]]
+            account = "{bob}"
+            "#,
+            alice = get_from_seed::<AccountId>("Alice"),
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", registrars));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.registrars.len(), 2);
+        assert_eq!(file.registrars[0].fee, 1_000_000_000_000);
+        assert_eq!(file.registrars[0].fields, vec!["display", "web"]);
+        assert_eq!(file.registrars[1].fee, 0);
+        assert!(file.registrars[1].fields.is_empty());
+        genesis_from_config(&write_config("registrars.toml", &config)).unwrap();
+
+        let unknown = config.replace("\"web\"", "\"phone\"");
+        genesis_from_config(&write_config("unknown-field.toml", &unknown)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_multisig_endowments() {
+        let multisig = format!(
+            r#"
+            [[multisig_endowments]]
+            signatories = ["{alice}", "{bob}"]
+            threshold = 2
+            balance = "1000000000000000000000"
+            "#,
+            alice = get_from_seed::<AccountId>("Alice"),
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", multisig));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.multisig_endowments.len(), 1);
+        assert_eq!(file.multisig_endowments[0].threshold, 2);
+        assert_eq!(
+            file.multisig_endowments[0].balance,
+            1_000_000_000_000_000_000_000
+        );
+        genesis_from_config(&write_config("multisig.toml", &config)).unwrap();
+
+        let invalid = config.replace("threshold = 2", "threshold = 3");
+        genesis_from_config(&write_config("invalid-multisig.toml", &invalid)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_scheduled_calls() {
+        let burn = Call::Erc20(erc20::Call::burn(0, 1)).encode();
+        let scheduled = format!(
+            r#"
+            [[scheduled_calls]]
+            when = 10
+            call = "0x{burn}"
+
+            [[scheduled_calls]]
+            when = 20
+            periodic = [5, 3]
+            call = "0x{burn}"
+            "#,
+            burn = hex::encode(&burn),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", scheduled));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.scheduled_calls.len(), 2);
+        assert_eq!(file.scheduled_calls[0].periodic, None);
+        assert_eq!(file.scheduled_calls[1].periodic, Some((5, 3)));
+        assert_eq!(file.scheduled_calls[1].call, burn);
+        genesis_from_config(&write_config("scheduled.toml", &config)).unwrap();
+
+        let unprefixed = config.replace("\"0x", "\"");
+        toml::from_str::<GenesisFile>(&unprefixed).unwrap_err();
+        let at_genesis = config.replace("when = 20", "when = 0");
+        genesis_from_config(&write_config("scheduled-at-genesis.toml", &at_genesis)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_vesting_schedules() {
+        let vesting = format!(
+            r#"
+            [[vesting_schedules]]
+            account = "{bob}"
+            start_block = 10
+            cliff = 50
+            duration = 100
+            amount = "900"
+            "#,
+            bob = get_from_seed::<AccountId>("Bob"),
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", vesting));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.vesting_schedules.len(), 1);
+        assert_eq!(file.vesting_schedules[0].cliff, 50);
+        assert_eq!(file.vesting_schedules[0].amount, 900);
+        genesis_from_config(&write_config("vesting-schedules.toml", &config)).unwrap();
+
+        let without_cliff = config.replace("cliff = 50\n", "");
+        let file: GenesisFile = toml::from_str(&without_cliff).unwrap();
+        assert_eq!(file.vesting_schedules[0].cliff, 0);
+        let above_endowment = config.replace("amount = \"900\"", "amount = \"1001\"");
+        genesis_from_config(&write_config(
+            "vesting-above-endowment.toml",
+            &above_endowment,
+        ))
+        .unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_faucet() {
+        let faucet = r#"
+            [faucet]
+            endowment = "1000000000000000000"
+            cooldown = 100
+            "#;
+        let config = example().replace("[properties]", &format!("{}\n[properties]", faucet));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        let parsed = file.faucet.as_ref().unwrap();
+        assert_eq!(parsed.endowment, Some(1_000_000_000_000_000_000));
+        assert_eq!(parsed.drip_amount, None);
+        assert_eq!(parsed.cooldown, Some(100));
+        assert_ne!(
+            genesis_hash(genesis_from_config(&write_config("faucet.toml", &config)).unwrap()),
+            genesis_hash(genesis_from_config(&write_config("no-faucet.toml", &example())).unwrap())
+        );
+
+        let no_cooldown = config.replace("cooldown = 100", "cooldown = 0");
+        genesis_from_config(&write_config("faucet-no-cooldown.toml", &no_cooldown)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_whitelist() {
+        let alice = get_from_seed::<AccountId>("Alice");
+        let bob = get_from_seed::<AccountId>("Bob");
+        let whitelist = format!(
+            r#"
+            [whitelist]
+            accounts = ["{}", "{}"]
+            enforced = true
+            "#,
+            alice, bob,
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", whitelist));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        let parsed = file.whitelist.as_ref().unwrap();
+        assert_eq!(parsed.accounts.len(), 2);
+        assert!(parsed.enforced);
+        genesis_from_config(&write_config("whitelist.toml", &config)).unwrap();
+
+        let duplicate = config.replace(&format!("\"{}\"]", bob), &format!("\"{}\"]", alice));
+        genesis_from_config(&write_config("duplicate-whitelist.toml", &duplicate)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_paused_calls() {
+        let config = example().replace(
+            "[[authorities]]",
+            "paused_calls = [[5, 0], [5, 3]]\n[[authorities]]",
+        );
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.paused_calls, vec![(5, 0), (5, 3)]);
+        genesis_from_config(&write_config("paused-calls.toml", &config)).unwrap();
+
+        let out_of_range = config.replace("[5, 3]", "[5, 256]");
+        toml::from_str::<GenesisFile>(&out_of_range).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_block_reward() {
+        let config = example().replace(
+            "[[authorities]]",
+            "block_reward = \"2000000000000\"\n[[authorities]]",
+        );
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.block_reward, Some(2_000_000_000_000));
+        assert_ne!(
+            genesis_hash(genesis_from_config(&write_config("block-reward.toml", &config)).unwrap()),
+            genesis_hash(
+                genesis_from_config(&write_config("no-block-reward.toml", &example())).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn t_config_with_admin() {
+        let bob = get_from_seed::<AccountId>("Bob");
+        let config = example().replace(
+            "[[authorities]]",
+            &format!("admin = \"{}\"\nadmin_delay = 100\n[[authorities]]", bob),
+        );
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        assert_eq!(file.admin, Some(bob));
+        assert_eq!(file.admin_delay, Some(100));
+        assert_ne!(
+            genesis_hash(genesis_from_config(&write_config("admin.toml", &config)).unwrap()),
+            genesis_hash(genesis_from_config(&write_config("no-admin.toml", &example())).unwrap())
+        );
+    }
+
+    #[test]
+    fn t_config_with_oracle() {
+        let alice = get_from_seed::<OracleId>("Alice");
+        let bob = get_from_seed::<OracleId>("Bob");
+        let oracle = format!(
+            r#"
+            [oracle]
+            feeders = ["{}", "{}"]
+            endpoint = "https://example.com/price"
+            "#,
+            alice, bob,
+        );
+        let config = example().replace("[properties]", &format!("{}\n[properties]", oracle));
+        let file: GenesisFile = toml::from_str(&config).unwrap();
+        let parsed = file.oracle.as_ref().unwrap();
+        assert_eq!(parsed.feeders, vec![alice.clone(), bob.clone()]);
+        assert_eq!(parsed.endpoint, "https://example.com/price");
+        genesis_from_config(&write_config("oracle.toml", &config)).unwrap();
+
+        let not_http = config.replace("https://", "ftp://");
+        genesis_from_config(&write_config("oracle-not-http.toml", &not_http)).unwrap_err();
+    }
+
+    #[test]
+    fn t_config_with_claims() {
+        let csv = format!(
+            "address,balance\n{},{}\n",
+            get_from_seed::<AccountId>("Dave"),
+            EXISTENTIAL_DEPOSIT
+        );
+        write_config("airdrop.csv", &csv);
+        let config = example().replace(
+            "[[authorities]]",
+            "claims_csv = \"airdrop.csv\"\n[[authorities]]",
+        );
+        let file = GenesisFile::load(&write_config("with-claims.toml", &config)).unwrap();
+        assert_eq!(
+            file.claims,
+            vec![(get_from_seed::<AccountId>("Dave"), EXISTENTIAL_DEPOSIT)]
+        );
+        assert_ne!(
+            genesis_hash(genesis_from_config(&write_config("with-claims.toml", &config)).unwrap()),
+            genesis_hash(genesis_from_config(&write_config("no-claims.toml", &example())).unwrap())
+        );
+    }
+
+    #[test]
+    fn t_config_with_eth_claims() {
+        let holder = "0x00000000000000000000000000000000000000aa";
+        let csv = format!("address,balance\n{},{}\n", holder, EXISTENTIAL_DEPOSIT);
+        write_config("snapshot.csv", &csv);
+        let config = example().replace(
+            "[[authorities]]",
+            "eth_claims_csv = \"snapshot.csv\"\n[[authorities]]",
+        );
+        let file = GenesisFile::load(&write_config("with-eth-claims.toml", &config)).unwrap();
+        assert_eq!(
+            file.eth_claims,
+            vec![(EthereumAddress::from_low_u64_be(0xaa), EXISTENTIAL_DEPOSIT)]
+        );
+        assert_ne!(
+            genesis_hash(genesis_from_config(&write_config("eth-claims.toml", &config)).unwrap()),
+            genesis_hash(
+                genesis_from_config(&write_config("no-eth-claims.toml", &example())).unwrap()
+            )
+        );
+
+        write_config("bad-snapshot.csv", &format!("{},{}\n", &holder[2..], 1));
+        let bad = config.replace("snapshot.csv", "bad-snapshot.csv");
+        assert_eq!(
+            GenesisFile::load(&write_config("bad-eth-claims.toml", &bad)).err(),
+            Some(format!(
+                "{} line 1: expected a 0x prefixed ethereum address",
+                std::env::temp_dir()
+                    .join("substrate-warmup-genesis-config")
+                    .join("bad-snapshot.csv")
+                    .display()
+            ))
+        );
+    }
+}
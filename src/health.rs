@@ -0,0 +1,142 @@
+//! Liveness and readiness of a node, which `serve-metrics` answers load balancers and kubernetes
+//! probes with on `/health` and `/ready`.
+//!
+//! A node is live while it answers rpc, restarting it helps nothing else. It is ready once it also
+//! has peers, unless it is the only node of a dev chain, is synced and has finalized a block
+//! recently, so that it serves current state.
+
+use crate::rpc::Rpc;
+use codec::Decode;
+use node_template_runtime::opaque::Header;
+use node_template_runtime::BlockNumber;
+use serde_json::json;
+use sr_primitives::traits::Header as _;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use substrate_primitives::{twox_128, H256};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Health {
+    pub peers: u64,
+    pub syncing: bool,
+    /// False on chains nodes run alone, e.g. with `--dev`.
+    pub should_have_peers: bool,
+    pub finalized: BlockNumber,
+    /// Time since the last finalized block was authored.
+    pub finalized_age: Duration,
+}
+
+impl Health {
+    pub fn fetch(rpc: &Rpc) -> Result<Self, String> {
+        let health = rpc.call("system_health", json!([]))?;
+        let invalid = || "invalid system health".to_string();
+        let hash: H256 = serde_json::from_value(rpc.call("chain_getFinalizedHead", json!([]))?)
+            .map_err(|e| format!("invalid block hash: {}", e))?;
+        let header: Header = serde_json::from_value(rpc.call("chain_getHeader", json!([hash]))?)
+            .map_err(|e| format!("invalid header: {}", e))?;
+        let authored = match rpc.storage_at(&twox_128(b"Timestamp Now"), Some(hash))? {
+            Some(now) => u64::decode(&mut &now[..]).map_err(|_| "invalid timestamp")?,
+            // the genesis has no timestamp, a chain that never finalized is as old as it gets
+            None => 0,
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?;
+        Ok(Health {
+            peers: health["peers"].as_u64().ok_or_else(invalid)?,
+            syncing: health["isSyncing"].as_bool().ok_or_else(invalid)?,
+            should_have_peers: health["shouldHavePeers"].as_bool().ok_or_else(invalid)?,
+            finalized: *header.number(),
+            finalized_age: now
+                .checked_sub(Duration::from_millis(authored))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Whether the node works, which it does once it answered rpc: a node without peers keeps
+    /// looking for some, restarting it would only lose those it finds.
+    pub fn liveness(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Whether the node serves current state, finalized at most `max_age` ago, and why not.
+    pub fn readiness(&self, max_age: Duration) -> Result<(), String> {
+        if self.should_have_peers && self.peers == 0 {
+            return Err("no peers".into());
+        }
+        if self.syncing {
+            return Err("syncing".into());
+        }
+        if self.finalized_age > max_age {
+            return Err(format!(
+                "the last finalized block is {}s old",
+                self.finalized_age.as_secs()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "peers: {}", self.peers)?;
+        writeln!(f, "syncing: {}", self.syncing)?;
+        write!(
+            f,
+            "finalized: #{}, {}s ago",
+            self.finalized,
+            self.finalized_age.as_secs()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_probes() {
+        let max_age = Duration::from_secs(60);
+        let healthy = Health {
+            peers: 2,
+            syncing: false,
+            should_have_peers: true,
+            finalized: 10,
+            finalized_age: Duration::from_secs(12),
+        };
+        healthy.liveness().unwrap();
+        healthy.readiness(max_age).unwrap();
+        assert_eq!(
+            healthy.to_string(),
+            "peers: 2\nsyncing: false\nfinalized: #10, 12s ago"
+        );
+
+        let alone = Health {
+            peers: 0,
+            ..healthy.clone()
+        };
+        alone.liveness().unwrap();
+        assert_eq!(alone.readiness(max_age), Err("no peers".into()));
+        let dev = Health {
+            should_have_peers: false,
+            ..alone
+        };
+        dev.readiness(max_age).unwrap();
+
+        let syncing = Health {
+            syncing: true,
+            ..healthy.clone()
+        };
+        syncing.liveness().unwrap();
+        assert_eq!(syncing.readiness(max_age), Err("syncing".into()));
+        let stalled = Health {
+            finalized_age: Duration::from_secs(90),
+            ..healthy
+        };
+        stalled.liveness().unwrap();
+        assert_eq!(
+            stalled.readiness(max_age),
+            Err("the last finalized block is 90s old".into())
+        );
+    }
+}
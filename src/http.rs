@@ -0,0 +1,132 @@
+//! A minimal http server for chaingen's endpoints, which answer each request from its request line
+//! alone with a plain text body. Requests are served one at a time, so a client is given
+//! `TIMEOUT` to send its request and read the response, and requests are read up to
+//! `MAX_REQUEST_HEAD` bytes, so that no client holds up the others.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+/// How long a client may take to send its request, and to read its response.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Most bytes read of the request line and headers together. Longer requests are refused.
+const MAX_REQUEST_HEAD: u64 = 16 * 1024;
+
+fn handle<F>(respond: &mut F, mut stream: TcpStream) -> io::Result<()>
+where
+    F: FnMut(&str, IpAddr) -> (&'static str, String),
+{
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let ip = stream.peer_addr()?.ip();
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_HEAD));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // skip the headers, no endpoint has a use for them or for a body
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header)? <= 2 {
+            break;
+        }
+    }
+    // a request cut off by the limit, or by the client, ends without the blank line
+    let complete = request_line.ends_with('\n') && header.ends_with('\n');
+    let (status, body) = if complete {
+        respond(&request_line, ip)
+    } else {
+        ("400 Bad Request", "incomplete or oversized request".into())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Answer requests on `listen` with the status line and body `respond` returns for their request
+/// line and the ip they come from, until the port can't be listened on.
+pub fn serve<F>(listen: SocketAddr, mut respond: F) -> Result<String, String>
+where
+    F: FnMut(&str, IpAddr) -> (&'static str, String),
+{
+    let listener = TcpListener::bind(listen).map_err(|e| format!("{}: {}", listen, e))?;
+    for stream in listener.incoming() {
+        let stream = stream.map_err(|e| format!("{}: {}", listen, e))?;
+        // a client hanging up only loses its own response
+        let _ = handle(&mut respond, stream);
+    }
+    Ok(String::new())
+}
+
+/// The path of a request line, if it requests it with `method`.
+pub fn path_of<'a>(request_line: &'a str, method: &str) -> Option<&'a str> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next() != Some(method) {
+        return None;
+    }
+    parts.next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_path_of() {
+        assert_eq!(
+            path_of("GET /metrics HTTP/1.1\r\n", "GET"),
+            Some("/metrics")
+        );
+        assert_eq!(path_of("POST /metrics HTTP/1.1\r\n", "GET"), None);
+        assert_eq!(path_of("GET", "GET"), None);
+        assert_eq!(path_of("", "GET"), None);
+    }
+
+    // send `request` to `handle`, returning the response and the request lines it was given
+    fn exchange(request: Vec<u8>) -> (String, Vec<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // the server may answer and hang up before reading all of it
+            let _ = stream.write_all(&request);
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            response
+        });
+        let mut requests = Vec::new();
+        let (stream, _) = listener.accept().unwrap();
+        handle(
+            &mut |request_line: &str, _| {
+                requests.push(request_line.to_string());
+                ("200 OK", "ok".into())
+            },
+            stream,
+        )
+        .unwrap();
+        (client.join().unwrap(), requests)
+    }
+
+    #[test]
+    fn t_handle() {
+        let (response, requests) = exchange(b"GET /health HTTP/1.1\r\nHost: a\r\n\r\n".to_vec());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nok"));
+        assert_eq!(requests, vec!["GET /health HTTP/1.1\r\n"]);
+
+        // requests as long as the limit, read to the end so that the client gets the response
+        let mut long_header = b"GET /health HTTP/1.1\r\nX: ".to_vec();
+        long_header.resize(MAX_REQUEST_HEAD as usize, b'a');
+        let (response, requests) = exchange(long_header);
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(requests.is_empty());
+
+        let (response, requests) = exchange(vec![b'a'; MAX_REQUEST_HEAD as usize]);
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(requests.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! The `inspect` command: blocks and extrinsics decoded with the types of the runtime chaingen
+//! was built with, so only chains running that runtime decode correctly.
+
+use crate::blocks::SignedBlock;
+use crate::rpc::{Rpc, DEFAULT_RPC_URL};
+use codec::{Decode, Encode};
+use node_template_runtime::{BlockNumber, UncheckedExtrinsic};
+use serde_json::json;
+use sr_primitives::traits::Header as _;
+use std::fmt::Write;
+use std::str::FromStr;
+use substrate_primitives::H256;
+
+#[derive(structopt::StructOpt, Clone, Debug)]
+/// decode blocks and extrinsics
+pub enum InspectCommand {
+    /// Prints the header and the decoded extrinsics of a block of a running node
+    Block {
+        /// Number or 0x prefixed hash of the block
+        id: BlockRef,
+        /// Http rpc endpoint of the node
+        #[structopt(long = "url", default_value = DEFAULT_RPC_URL)]
+        url: String,
+    },
+    /// Prints a hex encoded extrinsic, e.g. as wallets show them, decoded
+    Extrinsic {
+        #[structopt(parse(try_from_str = from_hex))]
+        extrinsic: Vec<u8>,
+    },
+}
+
+impl InspectCommand {
+    pub fn run(self) -> Result<String, String> {
+        match self {
+            InspectCommand::Block { id, url } => inspect_block(&Rpc::new(&url), id),
+            InspectCommand::Extrinsic { extrinsic } => {
+                Ok(format!("{:#?}", decode_extrinsic(&extrinsic)?))
+            }
+        }
+    }
+}
+
+/// A block, by number on the best chain or by hash.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockRef {
+    Number(BlockNumber),
+    Hash(H256),
+}
+
+impl FromStr for BlockRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        if s.starts_with("0x") {
+            let hash = from_hex(s)?;
+            if hash.len() != 32 {
+                return Err(format!("{} is not a block hash", s));
+            }
+            Ok(BlockRef::Hash(H256::from_slice(&hash)))
+        } else {
+            s.parse()
+                .map(BlockRef::Number)
+                .map_err(|_| format!("{} is neither a block number nor a hash", s))
+        }
+    }
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    hex::decode(s.trim_start_matches("0x")).map_err(|e| format!("invalid hex {:?}: {}", s, e))
+}
+
+/// Decode an extrinsic of this runtime, all of whose bytes must be used.
+pub fn decode_extrinsic(encoded: &[u8]) -> Result<UncheckedExtrinsic, String> {
+    let mut input = encoded;
+    let extrinsic = UncheckedExtrinsic::decode(&mut input)
+        .map_err(|e| format!("not an extrinsic of this runtime: {}", e.what()))?;
+    if !input.is_empty() {
+        return Err(format!(
+            "not an extrinsic of this runtime: {} bytes left over",
+            input.len()
+        ));
+    }
+    Ok(extrinsic)
+}
+
+fn inspect_block(rpc: &Rpc, id: BlockRef) -> Result<String, String> {
+    let hash = match id {
+        BlockRef::Hash(hash) => json!(hash),
+        BlockRef::Number(number) => rpc.call("chain_getBlockHash", json!([number]))?,
+    };
+    let block = rpc.call("chain_getBlock", json!([hash]))?;
+    if block.is_null() {
+        return Err(format!("the node has no block {:?}", id));
+    }
+    let block: SignedBlock =
+        serde_json::from_value(block).map_err(|e| format!("invalid block: {}", e))?;
+    Ok(describe_block(&block))
+}
+
+fn describe_block(block: &SignedBlock) -> String {
+    let header = &block.block.header;
+    let mut out = String::new();
+    writeln!(out, "number: {}", header.number()).unwrap();
+    writeln!(out, "hash: {:?}", header.hash()).unwrap();
+    writeln!(out, "parent hash: {:?}", header.parent_hash()).unwrap();
+    writeln!(out, "state root: {:?}", header.state_root()).unwrap();
+    write!(out, "digest: {:?}", header.digest()).unwrap();
+    for (index, extrinsic) in block.block.extrinsics.iter().enumerate() {
+        // opaque extrinsics encode like the extrinsic they hold
+        match decode_extrinsic(&extrinsic.encode()) {
+            Ok(extrinsic) => write!(out, "\nextrinsic {}: {:#?}", index, extrinsic),
+            Err(e) => write!(out, "\nextrinsic {}: {}", index, e),
+        }
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use node_template_runtime::opaque::{Block, Header};
+    use node_template_runtime::Call;
+    use sr_primitives::OpaqueExtrinsic;
+
+    #[test]
+    fn t_decode_extrinsic() {
+        let remark = UncheckedExtrinsic::new_unsigned(Call::System(system::Call::remark(vec![7])));
+        let encoded = remark.encode();
+        assert_eq!(
+            decode_extrinsic(&encoded).unwrap().function,
+            remark.function
+        );
+        decode_extrinsic(&encoded[1..]).unwrap_err();
+        let mut longer = encoded.clone();
+        longer.push(0);
+        decode_extrinsic(&longer).unwrap_err();
+
+        let block = SignedBlock {
+            block: Block {
+                header: Header::new(
+                    3,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                ),
+                extrinsics: vec![
+                    OpaqueExtrinsic::decode(&mut &encoded[..]).unwrap(),
+                    OpaqueExtrinsic(vec![0xff]),
+                ],
+            },
+            justification: None,
+        };
+        let description = describe_block(&block);
+        assert!(description.starts_with("number: 3\n"));
+        assert!(description.contains("extrinsic 0: UncheckedExtrinsic("));
+        assert!(description.contains("remark"));
+        assert!(description.contains("extrinsic 1: not an extrinsic of this runtime"));
+    }
+
+    #[test]
+    fn t_block_ref() {
+        assert_eq!("12".parse(), Ok(BlockRef::Number(12)));
+        let hash = format!("0x{}", "ab".repeat(32));
+        assert_eq!(hash.parse(), Ok(BlockRef::Hash(H256::repeat_byte(0xab))));
+        "0xab".parse::<BlockRef>().unwrap_err();
+        "best".parse::<BlockRef>().unwrap_err();
+    }
+}
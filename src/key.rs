@@ -0,0 +1,198 @@
+//! The `key` command: keys derived the way chainspecs derive genesis keys, so what it prints
+//! matches the accounts and session keys of a chainspec built from the same phrase.
+
+use crate::keyring::{key_from_path, mnemonic_from_env, pair_from_path, MNEMONIC_ENV};
+use crate::ss58::to_ss58;
+use bip39::{Language, Mnemonic, MnemonicType};
+use node_template_runtime::AccountId;
+use std::fmt;
+use std::str::FromStr;
+use substrate_primitives::{ed25519, sr25519, Pair};
+
+#[derive(structopt::StructOpt, Clone, Debug)]
+/// generate and inspect keys
+pub enum KeyCommand {
+    /// Generates a mnemonic and prints it with its key
+    Generate {
+        /// "sr25519" for accounts, babe and im-online keys, or "ed25519" for grandpa keys
+        #[structopt(long = "scheme", default_value = "sr25519")]
+        scheme: Scheme,
+    },
+    /// Prints the public key and address of a secret uri, e.g. "//Alice" or "<mnemonic>//stash"
+    Inspect {
+        uri: String,
+        #[structopt(long = "scheme", default_value = "sr25519")]
+        scheme: Scheme,
+    },
+    /// Prints the key derived from the WARMUP_MNEMONIC environment variable along a path, e.g.
+    /// "//validator//0", which keeps the mnemonic out of shell history
+    Derive {
+        path: String,
+        #[structopt(long = "scheme", default_value = "sr25519")]
+        scheme: Scheme,
+    },
+}
+
+impl KeyCommand {
+    pub fn run(self) -> Result<String, String> {
+        let key = match self {
+            KeyCommand::Generate { scheme } => {
+                let phrase = Mnemonic::new(MnemonicType::Words12, Language::English)
+                    .phrase()
+                    .to_owned();
+                KeyInfo {
+                    public: scheme.public(&phrase, "")?,
+                    phrase: Some(phrase),
+                }
+            }
+            KeyCommand::Inspect { uri, scheme } => KeyInfo {
+                public: scheme.public(&uri, "")?,
+                phrase: None,
+            },
+            KeyCommand::Derive { path, scheme } => {
+                let phrase = mnemonic_from_env()
+                    .map_err(|e| format!("derive needs a mnemonic in {}: {}", MNEMONIC_ENV, e))?;
+                KeyInfo {
+                    public: scheme.public(&phrase, &path)?,
+                    phrase: None,
+                }
+            }
+        };
+        Ok(key.to_string())
+    }
+}
+
+/// Signature scheme of a key.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Scheme {
+    Sr25519,
+    Ed25519,
+}
+
+impl Scheme {
+    /// Public key derived from `phrase` along `path`, with `keyring::key_from_path`.
+    pub fn public(self, phrase: &str, path: &str) -> Result<[u8; 32], String> {
+        let mut public = [0; 32];
+        match self {
+            Scheme::Sr25519 => {
+                public.copy_from_slice(key_from_path::<sr25519::Public>(phrase, path)?.as_ref())
+            }
+            Scheme::Ed25519 => {
+                public.copy_from_slice(key_from_path::<ed25519::Public>(phrase, path)?.as_ref())
+            }
+        }
+        Ok(public)
+    }
+
+    /// Secret key derived from `phrase` along `path`, as a hex secret uri a node's keystore
+    /// takes. Unlike "<phrase><path>", it reveals no other key derived from `phrase`.
+    pub fn secret(self, phrase: &str, path: &str) -> Result<String, String> {
+        let raw = match self {
+            Scheme::Sr25519 => pair_from_path::<sr25519::Pair>(phrase, path)?.to_raw_vec(),
+            Scheme::Ed25519 => pair_from_path::<ed25519::Pair>(phrase, path)?.to_raw_vec(),
+        };
+        Ok(format!("0x{}", hex::encode(raw)))
+    }
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "sr25519" => Ok(Scheme::Sr25519),
+            "ed25519" => Ok(Scheme::Ed25519),
+            "ecdsa" => Err("ecdsa keys need a newer substrate than this chain runs".into()),
+            _ => Err(format!(
+                "unknown scheme {:?}, expected sr25519 or ed25519",
+                s
+            )),
+        }
+    }
+}
+
+/// A key as the key command prints it.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyInfo {
+    pub public: [u8; 32],
+    /// The mnemonic of a generated key.
+    pub phrase: Option<String>,
+}
+
+impl fmt::Display for KeyInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(phrase) = &self.phrase {
+            writeln!(f, "secret phrase: {}", phrase)?;
+        }
+        writeln!(f, "public key: 0x{}", hex::encode(self.public))?;
+        // addresses encode the public key of either scheme the same way
+        write!(f, "address: {}", to_ss58(&AccountId::from_raw(self.public)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::{get_from_seed, Keyring};
+    use node_template_runtime::opaque::SessionKeys;
+    use substrate_primitives::crypto::DEV_PHRASE;
+
+    #[test]
+    fn t_matches_genesis_keys() {
+        let SessionKeys { grandpa, babe, .. } = Keyring::Alice.authority().1;
+        let public = |scheme: Scheme, path| scheme.public(DEV_PHRASE, path).unwrap();
+        assert_eq!(
+            public(Scheme::Sr25519, "//Alice"),
+            Keyring::Alice.account().0
+        );
+        assert_eq!(&public(Scheme::Sr25519, "//Alice")[..], babe.as_ref());
+        assert_eq!(&public(Scheme::Ed25519, "//Alice")[..], grandpa.as_ref());
+        assert_eq!(
+            public(Scheme::Sr25519, "//Alice//stash"),
+            get_from_seed::<AccountId>("Alice//stash").0
+        );
+        assert_eq!(
+            Scheme::Sr25519.public("//Alice", "").unwrap(),
+            public(Scheme::Sr25519, "//Alice")
+        );
+    }
+
+    #[test]
+    fn t_secret() {
+        for scheme in &[Scheme::Sr25519, Scheme::Ed25519] {
+            let secret = scheme.secret(DEV_PHRASE, "//validator/0").unwrap();
+            assert!(secret.starts_with("0x"));
+            assert!(!secret.contains(DEV_PHRASE));
+            assert_eq!(
+                scheme.public(&secret, "").unwrap(),
+                scheme.public(DEV_PHRASE, "//validator/0").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn t_scheme() {
+        assert_eq!("ed25519".parse(), Ok(Scheme::Ed25519));
+        "ecdsa".parse::<Scheme>().unwrap_err();
+        "rsa".parse::<Scheme>().unwrap_err();
+    }
+
+    #[test]
+    fn t_generate() {
+        let generated = KeyCommand::Generate {
+            scheme: Scheme::Sr25519,
+        }
+        .run()
+        .unwrap();
+        let phrase = generated.lines().next().unwrap();
+        let phrase = phrase.trim_start_matches("secret phrase: ");
+        assert_eq!(phrase.split(' ').count(), 12);
+        let inspected = KeyCommand::Inspect {
+            uri: phrase.to_string(),
+            scheme: Scheme::Sr25519,
+        }
+        .run()
+        .unwrap();
+        assert!(generated.ends_with(&inspected));
+    }
+}
@@ -0,0 +1,277 @@
+//! Keys of the well known development accounts, and helpers deriving keys from a phrase.
+//!
+//! Anyone can sign for keys derived from the public dev phrase. They must only ever control
+//! development chains. Builds without the default `dev-keys` feature leave them out, together
+//! with the presets using them.
+
+use node_template_runtime::opaque::SessionKeys;
+use node_template_runtime::{AccountId, ImOnlineId};
+#[cfg(any(test, feature = "dev-keys"))]
+use rayon::prelude::*;
+use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_primitives::{sr25519, Pair, Public};
+
+/// The development accounts known to substrate tooling, e.g. the polkadot js UI and `--alice`.
+#[cfg(any(test, feature = "dev-keys"))]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Keyring {
+    Alice,
+    Bob,
+    Charlie,
+    Dave,
+    Eve,
+    Ferdie,
+}
+
+#[cfg(any(test, feature = "dev-keys"))]
+impl Keyring {
+    pub const ALL: [Keyring; 6] = [
+        Keyring::Alice,
+        Keyring::Bob,
+        Keyring::Charlie,
+        Keyring::Dave,
+        Keyring::Eve,
+        Keyring::Ferdie,
+    ];
+
+    /// The name, which is also the hard derivation path from the dev phrase, e.g. "Alice".
+    pub fn name(self) -> &'static str {
+        match self {
+            Keyring::Alice => "Alice",
+            Keyring::Bob => "Bob",
+            Keyring::Charlie => "Charlie",
+            Keyring::Dave => "Dave",
+            Keyring::Eve => "Eve",
+            Keyring::Ferdie => "Ferdie",
+        }
+    }
+
+    pub fn account(self) -> AccountId {
+        get_from_seed::<AccountId>(self.name())
+    }
+
+    pub fn grandpa(self) -> GrandpaId {
+        get_from_seed::<GrandpaId>(self.name())
+    }
+
+    pub fn babe(self) -> BabeId {
+        get_from_seed::<BabeId>(self.name())
+    }
+
+    pub fn im_online(self) -> ImOnlineId {
+        get_from_seed::<ImOnlineId>(self.name())
+    }
+
+    /// The "//<name>//stash" account, which holds the funds bonded by this account.
+    pub fn stash(self) -> AccountId {
+        get_from_seed::<AccountId>(&format!("{}//stash", self.name()))
+    }
+
+    /// Stash account and session keys for running this account as an authority.
+    pub fn authority(self) -> (AccountId, SessionKeys) {
+        let (_, keys) = authority_keys_from_seed(self.name());
+        (self.stash(), keys)
+    }
+}
+
+/// Parses a name in any case, e.g. "alice".
+#[cfg(any(test, feature = "dev-keys"))]
+impl std::str::FromStr for Keyring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Keyring::ALL
+            .iter()
+            .cloned()
+            .find(|keyring| keyring.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("{:?} is not a dev account, e.g. alice", s))
+    }
+}
+
+/// Helper function to generate the account and session keys of an authority from seed
+#[cfg(any(test, feature = "dev-keys"))]
+pub fn authority_keys_from_seed(seed: &str) -> (AccountId, SessionKeys) {
+    (
+        get_from_seed::<AccountId>(seed),
+        SessionKeys {
+            grandpa: get_from_seed::<GrandpaId>(seed),
+            babe: get_from_seed::<BabeId>(seed),
+            im_online: get_from_seed::<ImOnlineId>(seed),
+        },
+    )
+}
+
+/// Helper function to generate a crypto pair from seed
+#[cfg(any(test, feature = "dev-keys"))]
+pub fn get_from_seed<P: Public>(seed: &str) -> <P::Pair as Pair>::Public {
+    P::Pair::from_string(&format!("//{}", seed), None)
+        .expect("invalid seed")
+        .public()
+}
+
+/// Dev accounts "//<prefix>/0" through "//<prefix>/<n - 1>", for load testing. Keys are derived
+/// in parallel; the result is in index order.
+#[cfg(any(test, feature = "dev-keys"))]
+pub fn derived_accounts(prefix: &str, n: usize) -> Vec<AccountId> {
+    (0..n)
+        .into_par_iter()
+        .map(|i| get_from_seed::<AccountId>(&format!("{}/{}", prefix, i)))
+        .collect()
+}
+
+/// Environment variable read by `mnemonic_from_env`.
+pub const MNEMONIC_ENV: &str = "WARMUP_MNEMONIC";
+
+/// Read a secret mnemonic from the `WARMUP_MNEMONIC` environment variable.
+pub fn mnemonic_from_env() -> Result<String, String> {
+    std::env::var(MNEMONIC_ENV).map_err(|e| format!("{}: {}", MNEMONIC_ENV, e))
+}
+
+/// Like `get_from_seed`, but derives "//<seed>" from `mnemonic` rather than the public dev phrase.
+pub fn get_from_mnemonic<P: Public>(
+    mnemonic: &str,
+    seed: &str,
+) -> Result<<P::Pair as Pair>::Public, String> {
+    key_from_path::<P>(mnemonic, &format!("//{}", seed))
+}
+
+/// Derive a key from `phrase` along a substrate derivation path, e.g. "//Alice//stash" or
+/// "//treasury/0///password". "//" starts a hard junction, "/" a soft one, and an optional
+/// trailing "///" separates the password.
+pub fn key_from_path<P: Public>(
+    phrase: &str,
+    path: &str,
+) -> Result<<P::Pair as Pair>::Public, String> {
+    pair_from_path::<P::Pair>(phrase, path).map(|pair| pair.public())
+}
+
+/// The key pair of the public key `key_from_path` derives.
+pub fn pair_from_path<P: Pair>(phrase: &str, path: &str) -> Result<P, String> {
+    if !path.is_empty() && !path.starts_with('/') {
+        return Err(format!("derivation path {:?} should start with '/'", path));
+    }
+    P::from_string(&format!("{}{}", phrase, path), None)
+        .map_err(|e| format!("invalid phrase or derivation path {:?}: {:?}", path, e))
+}
+
+/// Account key of a secret uri, e.g. "//Alice" or "<mnemonic>//stash", for signing extrinsics.
+/// Errors leave out the uri, which is secret.
+pub fn signer_from_suri(suri: &str) -> Result<sr25519::Pair, String> {
+    sr25519::Pair::from_string(suri, None).map_err(|e| format!("invalid secret uri: {:?}", e))
+}
+
+/// Like `authority_keys_from_seed`, but derives from `mnemonic` rather than the public dev phrase.
+pub fn authority_keys_from_mnemonic(
+    mnemonic: &str,
+    seed: &str,
+) -> Result<(AccountId, SessionKeys), String> {
+    Ok((
+        get_from_mnemonic::<AccountId>(mnemonic, seed)?,
+        SessionKeys {
+            grandpa: get_from_mnemonic::<GrandpaId>(mnemonic, seed)?,
+            babe: get_from_mnemonic::<BabeId>(mnemonic, seed)?,
+            im_online: get_from_mnemonic::<ImOnlineId>(mnemonic, seed)?,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_keyring() {
+        for keyring in Keyring::ALL.iter().cloned() {
+            assert_eq!(
+                keyring.account(),
+                get_from_seed::<AccountId>(keyring.name())
+            );
+            let (stash, keys) = keyring.authority();
+            assert_eq!(stash, keyring.stash());
+            assert_ne!(stash, keyring.account());
+            assert_eq!(keys.grandpa, keyring.grandpa());
+            assert_eq!(keys.babe, keyring.babe());
+            assert_eq!(keys.im_online, keyring.im_online());
+        }
+        // the well known address of Alice
+        assert_eq!(
+            Keyring::Alice.account().to_string(),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        );
+        let mut accounts: Vec<AccountId> = Keyring::ALL.iter().map(|k| k.account()).collect();
+        accounts.sort();
+        accounts.dedup();
+        assert_eq!(accounts.len(), Keyring::ALL.len());
+        assert_eq!("bob".parse(), Ok(Keyring::Bob));
+        assert_eq!("Bob".parse(), Ok(Keyring::Bob));
+        "mallory".parse::<Keyring>().unwrap_err();
+    }
+
+    #[test]
+    fn t_keys_from_mnemonic() {
+        use substrate_primitives::crypto::DEV_PHRASE;
+        assert_eq!(
+            authority_keys_from_mnemonic(DEV_PHRASE, "Alice").unwrap(),
+            authority_keys_from_seed("Alice")
+        );
+        let (mnemonic, _) = substrate_primitives::sr25519::Pair::generate_with_phrase(None);
+        let secret = authority_keys_from_mnemonic(&mnemonic, "Alice").unwrap();
+        assert_ne!(secret, authority_keys_from_seed("Alice"));
+        assert_eq!(
+            secret.0,
+            get_from_mnemonic::<AccountId>(&mnemonic, "Alice").unwrap()
+        );
+        get_from_mnemonic::<AccountId>("not a valid mnemonic", "Alice").unwrap_err();
+
+        std::env::remove_var(MNEMONIC_ENV);
+        mnemonic_from_env().unwrap_err();
+        std::env::set_var(MNEMONIC_ENV, &mnemonic);
+        assert_eq!(mnemonic_from_env().unwrap(), mnemonic);
+        std::env::remove_var(MNEMONIC_ENV);
+    }
+
+    #[test]
+    fn t_key_from_path() {
+        use substrate_primitives::crypto::DEV_PHRASE;
+        let key = |path| key_from_path::<AccountId>(DEV_PHRASE, path);
+        assert_eq!(key("//Alice").unwrap(), get_from_seed::<AccountId>("Alice"));
+        assert_eq!(
+            key("//Alice//stash").unwrap(),
+            get_from_seed::<AccountId>("Alice//stash")
+        );
+        assert_eq!(
+            key_from_path::<GrandpaId>(DEV_PHRASE, "//Bob").unwrap(),
+            get_from_seed::<GrandpaId>("Bob")
+        );
+        assert_ne!(
+            key("//Alice/stash").unwrap(),
+            key("//Alice//stash").unwrap()
+        );
+        assert_ne!(key("//Alice///password").unwrap(), key("//Alice").unwrap());
+        assert_ne!(key("").unwrap(), key("//Alice").unwrap());
+        key("Alice").unwrap_err();
+        key_from_path::<AccountId>("not a valid mnemonic", "//Alice").unwrap_err();
+    }
+
+    #[test]
+    fn t_signer_from_suri() {
+        let alice = signer_from_suri("//Alice").unwrap();
+        assert_eq!(alice.public(), Keyring::Alice.account());
+        let err = signer_from_suri("not a valid mnemonic//Alice").unwrap_err();
+        assert!(!err.contains("mnemonic"), "{}", err);
+    }
+
+    #[test]
+    fn t_derived_accounts() {
+        let accounts = derived_accounts("load", 3);
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[2], get_from_seed::<AccountId>("load/2"));
+        assert_eq!(&derived_accounts("load", 5)[..3], &accounts[..]);
+        assert!(derived_accounts("load", 0).is_empty());
+        let serial: Vec<AccountId> = (0..1000)
+            .map(|i| get_from_seed::<AccountId>(&format!("load/{}", i)))
+            .collect();
+        assert_eq!(derived_accounts("load", 1000), serial);
+    }
+}
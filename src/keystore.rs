@@ -0,0 +1,120 @@
+//! Keys written into the keystore of a node before it starts.
+//!
+//! A node keystore is a directory, `<base-path>/chains/<chain id>/keystore`, with a file per key
+//! named by the hex of its key type and public key, holding the json string of its secret uri.
+//! Keys are derived with `key::Scheme`, like every other key chaingen prints.
+
+use crate::key::Scheme;
+#[cfg(any(test, feature = "dev-keys"))]
+use crate::keyring::Keyring;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Session keys a validator's node signs with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyType {
+    Babe,
+    Grandpa,
+    ImOnline,
+}
+
+impl KeyType {
+    pub const ALL: [KeyType; 3] = [KeyType::Babe, KeyType::Grandpa, KeyType::ImOnline];
+
+    /// The id the keystore and the `author_insertKey` rpc know the key type by.
+    pub fn id(self) -> &'static str {
+        match self {
+            KeyType::Babe => "babe",
+            KeyType::Grandpa => "gran",
+            KeyType::ImOnline => "imon",
+        }
+    }
+
+    pub fn scheme(self) -> Scheme {
+        match self {
+            KeyType::Grandpa => Scheme::Ed25519,
+            KeyType::Babe | KeyType::ImOnline => Scheme::Sr25519,
+        }
+    }
+}
+
+impl FromStr for KeyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        KeyType::ALL
+            .iter()
+            .cloned()
+            .find(|key_type| key_type.id() == s)
+            .ok_or_else(|| format!("unknown key type {:?}, expected babe, gran, or imon", s))
+    }
+}
+
+/// Write the key of `suri` to the keystore at `keystore`, returning the path of its file.
+pub fn insert_key(keystore: &Path, key_type: KeyType, suri: &str) -> Result<PathBuf, String> {
+    let public = key_type.scheme().public(suri, "")?;
+    fs::create_dir_all(keystore).map_err(|e| format!("{}: {}", keystore.display(), e))?;
+    let path = keystore.join(format!(
+        "{}{}",
+        hex::encode(key_type.id()),
+        hex::encode(public)
+    ));
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        // readable only by the node's user, like the keys the node generates
+        options.mode(0o600);
+    }
+    let contents = serde_json::to_string(suri).expect("strings serialize");
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Write every session key of a dev validator, as `--alice` does for the keystore of a node.
+#[cfg(any(test, feature = "dev-keys"))]
+pub fn insert_dev_keys(keystore: &Path, validator: Keyring) -> Result<Vec<PathBuf>, String> {
+    KeyType::ALL
+        .iter()
+        .map(|key_type| insert_key(keystore, *key_type, &format!("//{}", validator.name())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_insert_dev_keys() {
+        let keystore = std::env::temp_dir().join("t_insert_dev_keys");
+        let _ = fs::remove_dir_all(&keystore);
+        let paths = insert_dev_keys(&keystore, Keyring::Alice).unwrap();
+        let keys = Keyring::Alice.authority().1;
+        assert_eq!(
+            paths[1],
+            keystore.join(format!(
+                "{}{}",
+                hex::encode("gran"),
+                hex::encode(&keys.grandpa)
+            ))
+        );
+        assert_eq!(
+            paths[0].file_name().unwrap().to_str().unwrap(),
+            format!("{}{}", hex::encode("babe"), hex::encode(&keys.babe))
+        );
+        assert_eq!(fs::read_to_string(&paths[2]).unwrap(), "\"//Alice\"");
+        fs::remove_dir_all(&keystore).unwrap();
+    }
+
+    #[test]
+    fn t_key_type() {
+        assert_eq!("gran".parse(), Ok(KeyType::Grandpa));
+        "aura".parse::<KeyType>().unwrap_err();
+    }
+}
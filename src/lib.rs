@@ -0,0 +1,33 @@
+//! chaingen: generates, checks and inspects chainspecs of the substrate warmup runtime, and talks
+//! to its nodes. The `substrate-warmup-chaingen` binary runs `cli::Command`; the chainspec presets
+//! and `chain_spec::ChainSpecBuilder` are also usable as a library.
+
+mod address;
+mod benchmark;
+mod blocks;
+mod bundles;
+pub mod chain_spec;
+mod claims;
+pub mod cli;
+#[cfg(any(test, feature = "dev-keys"))]
+mod dev_validator;
+mod diff_spec;
+mod faucet_server;
+mod genesis_config;
+mod health;
+mod http;
+mod inspect;
+mod key;
+pub mod keyring;
+mod keystore;
+mod metrics;
+mod purge;
+mod retire_sudo;
+mod rpc;
+mod serializable_genesis;
+mod ss58;
+#[cfg(test)]
+mod test_utils;
+mod transfers;
+mod upgrade;
+mod verify_spec;
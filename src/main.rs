@@ -1,8 +1,10 @@
-mod chain_spec;
-mod serializable_genesis;
+use substrate_warmup_chaingen::cli;
 
 #[paw::main]
-fn main(chain: chain_spec::Chain) {
-    let spec = chain.generate();
-    println!("{}", spec.into_json(true).unwrap());
+fn main(command: cli::Command) {
+    let output = command.run().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    });
+    println!("{}", output);
 }
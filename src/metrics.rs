@@ -0,0 +1,274 @@
+//! The `serve-metrics` command: a prometheus exporter for a node, which at substrate 870b976
+//! serves no metrics of its own.
+//!
+//! Each scrape of `/metrics` asks the node over rpc for its peers, its sync state and its best and
+//! finalized blocks, and counts the faucet drips and token transfers in the blocks finalized since
+//! the previous scrape. Counters start at zero with the exporter, as prometheus expects of a
+//! restarted process. Every metric is labelled with the genesis hash of the node's chain, which
+//! unlike its display name is unique to the chain.
+//!
+//! Requests are served one at a time, so a scrape counts at most `MAX_BLOCKS_PER_SCRAPE` blocks,
+//! e.g. after the node was down, and the following scrapes count the rest. Probes wait for at most
+//! one such scrape.
+//!
+//! The exporter also answers the node's probes, `/health` and `/ready`, described in `health`.
+
+use crate::health::Health;
+use crate::http::{path_of, serve};
+use crate::rpc::Rpc;
+use crate::transfers::{events_at, finalized_number, transfer_of, EventRecord, Token};
+use node_template_runtime::opaque::Header;
+use node_template_runtime::{BlockNumber, Event};
+use serde_json::json;
+use sr_primitives::traits::Header as _;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+use substrate_primitives::H256;
+
+/// Most blocks a scrape reads the events of.
+const MAX_BLOCKS_PER_SCRAPE: BlockNumber = 100;
+
+/// Events counted since the exporter started.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct Counters {
+    drips: u64,
+    /// Transfers by kind of token.
+    transfers: BTreeMap<&'static str, u64>,
+}
+
+impl Counters {
+    fn count(&mut self, events: &[EventRecord]) {
+        for record in events {
+            if let Event::faucet(faucet::RawEvent::Dripped(..)) = record.event {
+                self.drips += 1;
+            }
+            if let Some(transfer) = transfer_of(&record.event) {
+                let token = match transfer.token {
+                    Token::Native => "native",
+                    Token::Asset(_) => "asset",
+                    Token::Erc20(_) => "erc20",
+                };
+                *self.transfers.entry(token).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+/// What a scrape reports besides the counters.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct NodeState {
+    peers: u64,
+    syncing: bool,
+    best: BlockNumber,
+    finalized: BlockNumber,
+}
+
+pub struct Exporter {
+    rpc: Rpc,
+    genesis: H256,
+    /// First block not counted yet.
+    next: BlockNumber,
+    counters: Counters,
+    /// Age of the last finalized block past which the node is not ready.
+    max_finalized_age: Duration,
+}
+
+impl Exporter {
+    /// An exporter for the node at `rpc`, counting from the block after its finalized head.
+    pub fn new(rpc: Rpc, max_finalized_age: Duration) -> Result<Self, String> {
+        let genesis = rpc.genesis_hash()?;
+        let next = finalized_number(&rpc)? + 1;
+        Ok(Exporter {
+            rpc,
+            genesis,
+            next,
+            counters: Counters::default(),
+            max_finalized_age,
+        })
+    }
+
+    fn scrape(&mut self) -> Result<String, String> {
+        let health = self.rpc.call("system_health", json!([]))?;
+        let best: Header = serde_json::from_value(self.rpc.call("chain_getHeader", json!([]))?)
+            .map_err(|e| format!("invalid header: {}", e))?;
+        let finalized = finalized_number(&self.rpc)?;
+        let last = last_to_count(self.next, finalized);
+        while self.next <= last {
+            self.counters.count(&events_at(&self.rpc, self.next)?);
+            self.next += 1;
+        }
+        let state = NodeState {
+            peers: health["peers"].as_u64().ok_or("invalid system health")?,
+            syncing: health["isSyncing"]
+                .as_bool()
+                .ok_or("invalid system health")?,
+            best: *best.number(),
+            finalized,
+        };
+        Ok(render(&self.genesis, &state, &self.counters))
+    }
+
+    /// Answer a probe of the node, which passes `check`.
+    fn probe<F>(&self, check: F) -> (&'static str, String)
+    where
+        F: Fn(&Health) -> Result<(), String>,
+    {
+        match Health::fetch(&self.rpc) {
+            Ok(health) => match check(&health) {
+                Ok(()) => ("200 OK", format!("ok\n{}", health)),
+                Err(e) => ("503 Service Unavailable", format!("{}\n{}", e, health)),
+            },
+            Err(e) => ("503 Service Unavailable", e),
+        }
+    }
+
+    fn respond(&mut self, request_line: &str) -> (&'static str, String) {
+        let max_finalized_age = self.max_finalized_age;
+        match path_of(request_line, "GET") {
+            Some("/metrics") => match self.scrape() {
+                Ok(metrics) => ("200 OK", metrics),
+                Err(e) => ("502 Bad Gateway", e),
+            },
+            Some("/health") => self.probe(Health::liveness),
+            Some("/ready") => self.probe(|health| health.readiness(max_finalized_age)),
+            _ => ("404 Not Found", "GET /metrics, /health or /ready".into()),
+        }
+    }
+}
+
+/// The last block a scrape counts, from block `next` on, when `finalized` is finalized.
+fn last_to_count(next: BlockNumber, finalized: BlockNumber) -> BlockNumber {
+    finalized.min(next.saturating_add(MAX_BLOCKS_PER_SCRAPE - 1))
+}
+
+/// Append a metric in the prometheus text format.
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, u64)]) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, kind).unwrap();
+    for (labels, value) in samples {
+        writeln!(out, "{}{{{}}} {}", name, labels, value).unwrap();
+    }
+}
+
+fn render(genesis: &H256, state: &NodeState, counters: &Counters) -> String {
+    let genesis = format!("genesis=\"{:?}\"", genesis);
+    let sample = |value| vec![(genesis.clone(), value)];
+    let mut out = String::new();
+    let gauges = [
+        (
+            "warmup_peers",
+            "Peers the node is connected to.",
+            state.peers,
+        ),
+        (
+            "warmup_is_syncing",
+            "1 while the node is syncing.",
+            state.syncing as u64,
+        ),
+        (
+            "warmup_best_block",
+            "Number of the best block.",
+            state.best.into(),
+        ),
+        (
+            "warmup_finalized_block",
+            "Number of the last finalized block.",
+            state.finalized.into(),
+        ),
+    ];
+    for (name, help, value) in &gauges {
+        metric(&mut out, name, "gauge", help, &sample(*value));
+    }
+    metric(
+        &mut out,
+        "warmup_faucet_drips_total",
+        "counter",
+        "Faucet drips in the finalized blocks.",
+        &sample(counters.drips),
+    );
+    let transfers: Vec<(String, u64)> = ["native", "asset", "erc20"]
+        .iter()
+        .map(|token| {
+            let count = counters.transfers.get(token).cloned().unwrap_or(0);
+            (format!("{},token=\"{}\"", genesis, token), count)
+        })
+        .collect();
+    metric(
+        &mut out,
+        "warmup_token_transfers_total",
+        "counter",
+        "Transfers of native, asset and erc20 tokens in the finalized blocks.",
+        &transfers,
+    );
+    out
+}
+
+/// Serve the metrics of the node behind `exporter` on `listen`.
+pub fn serve_metrics(listen: SocketAddr, mut exporter: Exporter) -> Result<String, String> {
+    serve(listen, |request_line, _| exporter.respond(request_line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+
+    fn record(event: Event) -> EventRecord {
+        EventRecord {
+            phase: system::Phase::ApplyExtrinsic(1),
+            event,
+            topics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn t_last_to_count() {
+        assert_eq!(last_to_count(11, 12), 12);
+        assert_eq!(last_to_count(13, 12), 12);
+        assert_eq!(last_to_count(1, 1_000), MAX_BLOCKS_PER_SCRAPE);
+        assert_eq!(
+            last_to_count(BlockNumber::max_value(), BlockNumber::max_value()),
+            BlockNumber::max_value()
+        );
+    }
+
+    #[test]
+    fn t_render() {
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let mut counters = Counters::default();
+        counters.count(&[
+            record(Event::faucet(faucet::RawEvent::Dripped(alice.clone(), 10))),
+            record(Event::balances(balances::RawEvent::Transfer(
+                alice.clone(),
+                bob.clone(),
+                10,
+                1,
+            ))),
+            record(Event::erc20(erc20::RawEvent::Transfer(0, alice, bob, 20))),
+        ]);
+        let state = NodeState {
+            peers: 3,
+            syncing: false,
+            best: 12,
+            finalized: 10,
+        };
+        let out = render(&H256::repeat_byte(0xab), &state, &counters);
+        let genesis = format!("genesis=\"0x{}\"", "ab".repeat(32));
+        assert!(out.contains("# TYPE warmup_peers gauge\n"));
+        assert!(out.contains(&format!("warmup_peers{{{}}} 3\n", genesis)));
+        assert!(out.contains(&format!("warmup_is_syncing{{{}}} 0\n", genesis)));
+        assert!(out.contains(&format!("warmup_best_block{{{}}} 12\n", genesis)));
+        assert!(out.contains(&format!("warmup_finalized_block{{{}}} 10\n", genesis)));
+        assert!(out.contains("# TYPE warmup_faucet_drips_total counter\n"));
+        assert!(out.contains(&format!("warmup_faucet_drips_total{{{}}} 1\n", genesis)));
+        for (token, count) in &[("native", 1), ("asset", 0), ("erc20", 1)] {
+            assert!(out.contains(&format!(
+                "warmup_token_transfers_total{{{},token=\"{}\"}} {}\n",
+                genesis, token, count
+            )));
+        }
+    }
+}
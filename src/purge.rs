@@ -0,0 +1,92 @@
+//! Deleting the database of a chain, where the node keeps it.
+//!
+//! A node keeps the data of a chain under `<base-path>/chains/<chain id>`: the database in `db`,
+//! next to the keystore and network keys, which purging leaves in place. Without `--base-path`
+//! the node uses the data directory of the platform, e.g. ~/.local/share/substrate on Linux.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Directory name of the data of the substrate node the Dockerfile runs.
+const NODE_DATA_DIR: &str = "substrate";
+
+/// Base path the node uses when `--base-path` is not given.
+pub fn default_base_path() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(NODE_DATA_DIR))
+        .ok_or_else(|| "no data directory on this platform, pass --base-path".to_string())
+}
+
+/// Directory of the data of the chain with id `chain_id` of a node running from `base_path`.
+/// Chain ids come from chainspecs, which anyone may write, so ids that would name a directory
+/// elsewhere, e.g. "../.." or "/home", are refused.
+pub fn chain_path(base_path: &Path, chain_id: &str) -> Result<PathBuf, String> {
+    if chain_id.is_empty()
+        || chain_id.contains('/')
+        || chain_id.contains('\\')
+        || chain_id.contains("..")
+    {
+        return Err(format!("invalid chain id {:?}", chain_id));
+    }
+    Ok(base_path.join("chains").join(chain_id))
+}
+
+/// Database of the chain with id `chain_id` of a node running from `base_path`.
+pub fn database_path(base_path: &Path, chain_id: &str) -> Result<PathBuf, String> {
+    Ok(chain_path(base_path, chain_id)?.join("db"))
+}
+
+/// Delete the database at `db`, after asking on the terminal unless `confirmed`.
+pub fn purge_chain(db: &Path, confirmed: bool) -> Result<String, String> {
+    if !db.exists() {
+        return Ok(format!("{} does not exist, nothing to purge", db.display()));
+    }
+    if !confirmed {
+        eprint!("Are you sure to remove {}? [y/N]: ", db.display());
+        io::stderr().flush().map_err(|e| e.to_string())?;
+        let mut answer = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .map_err(|e| e.to_string())?;
+        if !["y", "yes"].contains(&answer.trim().to_lowercase().as_str()) {
+            return Ok("aborted".into());
+        }
+    }
+    fs::remove_dir_all(db).map_err(|e| format!("{}: {}", db.display(), e))?;
+    Ok(format!("{} removed", db.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_purge_chain() {
+        let base_path = std::env::temp_dir().join("substrate-warmup-t_purge_chain");
+        let db = database_path(&base_path, "substrate-warmup-local").unwrap();
+        assert_eq!(db, base_path.join("chains/substrate-warmup-local/db"));
+        let keystore = db.with_file_name("keystore");
+        fs::create_dir_all(&db).unwrap();
+        fs::create_dir_all(&keystore).unwrap();
+        purge_chain(&db, true).unwrap();
+        assert!(!db.exists());
+        assert!(keystore.exists());
+        assert!(purge_chain(&db, true).unwrap().contains("nothing to purge"));
+        fs::remove_dir_all(&base_path).unwrap();
+    }
+
+    #[test]
+    fn t_chain_path() {
+        let base_path = Path::new("/data");
+        assert_eq!(
+            chain_path(base_path, "substrate-warmup-local"),
+            Ok(PathBuf::from("/data/chains/substrate-warmup-local"))
+        );
+        for id in &["", "..", "../..", "a/../b", "/home", "a\\b"] {
+            chain_path(base_path, id).unwrap_err();
+            database_path(base_path, id).unwrap_err();
+        }
+    }
+}
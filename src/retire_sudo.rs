@@ -0,0 +1,115 @@
+//! Retiring the sudo key once governance is in place.
+//!
+//! The sudo key signs `retire_sudo_call`, which has sudo dispatch `Timelock::retire_sudo` as root.
+//! That clears the key in the same extrinsic, so root is from then on only reached through
+//! referenda and the calls the timelock admin announces, which the council can cancel.
+//! `TryRun` applies the call to the state of a chainspec first, to check what it leaves behind.
+
+use crate::serializable_genesis::ChainSpec;
+use crate::ss58::to_ss58;
+use codec::Encode;
+use node_template_runtime::{AccountId, BlockNumber, Call, GenesisConfig, Origin, Runtime};
+use sr_primitives::traits::Dispatchable;
+use sr_primitives::BuildStorage;
+use std::fmt;
+use substrate_primitives::Blake2Hasher;
+
+type Sudo = sudo::Module<Runtime>;
+type Timelock = timelock::Module<Runtime>;
+
+/// The call the sudo key signs to retire itself.
+pub fn retire_sudo_call() -> Call {
+    Call::Sudo(sudo::Call::sudo(Box::new(Call::Timelock(
+        timelock::Call::retire_sudo(),
+    ))))
+}
+
+/// `retire_sudo_call` hex encoded, as wallets accept calls to sign.
+pub fn encoded_retire_sudo_call() -> String {
+    format!("0x{}", hex::encode(retire_sudo_call().encode()))
+}
+
+/// What `retire_sudo_call` does to a chain, simulated on the state of its chainspec.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TryRun {
+    /// Key that signed the call.
+    pub sudo_key: AccountId,
+    /// Whether the key was cleared. Sudo reports success even when the call it dispatches fails.
+    pub retired: bool,
+    /// Timelock admin, the only account left to propose root calls outside of referenda.
+    pub admin: AccountId,
+    pub delay: BlockNumber,
+}
+
+impl TryRun {
+    pub fn of(spec: ChainSpec<GenesisConfig>) -> Result<Self, String> {
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage()?.into();
+        sr_io::with_externalities(&mut ext, || {
+            if Timelock::sudo_retired() {
+                return Err("sudo is already retired".to_string());
+            }
+            let sudo_key = Sudo::key();
+            retire_sudo_call().dispatch(Origin::signed(sudo_key.clone()))?;
+            Ok(TryRun {
+                sudo_key,
+                retired: Timelock::sudo_retired(),
+                admin: Timelock::admin(),
+                delay: Timelock::delay(),
+            })
+        })
+    }
+}
+
+impl fmt::Display for TryRun {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "sudo key: {}", to_ss58(&self.sudo_key))?;
+        if self.retired {
+            writeln!(f, "sudo retired: yes")?;
+        } else {
+            writeln!(
+                f,
+                "sudo retired: no, the call failed and the key is unchanged"
+            )?;
+        }
+        writeln!(f, "timelock admin: {}", to_ss58(&self.admin))?;
+        write!(f, "timelock delay: {} blocks", self.delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{Chain, TestnetOptions};
+    use crate::keyring::Keyring;
+    use codec::Decode;
+
+    #[test]
+    fn t_call_encoding() {
+        let call = retire_sudo_call().encode();
+        assert_eq!(Call::decode(&mut &call[..]), Ok(retire_sudo_call()));
+        assert_eq!(
+            encoded_retire_sudo_call(),
+            format!("0x{}", hex::encode(&call))
+        );
+    }
+
+    #[test]
+    fn t_try_run() {
+        let ved = Chain::Ved {
+            options: TestnetOptions::default(),
+        }
+        .generate()
+        .unwrap();
+        let run = TryRun::of(ved).unwrap();
+        assert_eq!(
+            run,
+            TryRun {
+                sudo_key: Keyring::Alice.account(),
+                retired: true,
+                admin: Keyring::Alice.account(),
+                delay: timelock::DEFAULT_DELAY,
+            }
+        );
+        assert!(run.to_string().contains("sudo retired: yes"));
+    }
+}
@@ -0,0 +1,112 @@
+//! A minimal client of the json-rpc http endpoint of a node, e.g. http://localhost:9933, for the
+//! commands talking to a running chain.
+
+use node_template_runtime::{AccountId, Index};
+use serde_json::{json, Value};
+use substrate_primitives::H256;
+
+/// Endpoint nodes serve http rpc on by default.
+pub const DEFAULT_RPC_URL: &str = "http://localhost:9933";
+
+pub struct Rpc {
+    url: String,
+}
+
+impl Rpc {
+    pub fn new(url: &str) -> Self {
+        Rpc {
+            url: url.to_owned(),
+        }
+    }
+
+    /// Call `method` and return its result.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string());
+        if let Some(err) = response.synthetic_error() {
+            return Err(format!("{}: {}", self.url, err));
+        }
+        let body = response
+            .into_string()
+            .map_err(|e| format!("{}: {}", self.url, e))?;
+        let mut body: Value = serde_json::from_str(&body)
+            .map_err(|e| format!("{} returned invalid json: {}", self.url, e))?;
+        if let Some(error) = body.get("error") {
+            return Err(format!("{} failed: {}", method, error));
+        }
+        Ok(body["result"].take())
+    }
+
+    pub fn genesis_hash(&self) -> Result<H256, String> {
+        let hash = from_hex(&self.call("chain_getBlockHash", json!([0]))?)?;
+        if hash.len() != 32 {
+            return Err(format!("invalid genesis hash 0x{}", hex::encode(hash)));
+        }
+        Ok(H256::from_slice(&hash))
+    }
+
+    /// Spec version of the runtime of the best block.
+    pub fn spec_version(&self) -> Result<u32, String> {
+        let version = self.call("state_getRuntimeVersion", json!([]))?;
+        version["specVersion"]
+            .as_u64()
+            .map(|v| v as u32)
+            .ok_or_else(|| format!("invalid runtime version {}", version))
+    }
+
+    /// Raw value under a storage key in the best block.
+    pub fn storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        self.storage_at(key, None)
+    }
+
+    /// Raw value under a storage key in block `at`, the best block if `None`.
+    pub fn storage_at(&self, key: &[u8], at: Option<H256>) -> Result<Option<Vec<u8>>, String> {
+        let key = format!("0x{}", hex::encode(key));
+        let params = match at {
+            Some(at) => json!([key, at]),
+            None => json!([key]),
+        };
+        match self.call("state_getStorage", params)? {
+            Value::Null => Ok(None),
+            value => from_hex(&value).map(Some),
+        }
+    }
+
+    /// Nonce of the next extrinsic of `account`, counting its extrinsics in the transaction pool.
+    pub fn next_index(&self, account: &AccountId) -> Result<Index, String> {
+        let index = self.call("system_accountNextIndex", json!([account]))?;
+        index
+            .as_u64()
+            .map(|index| index as Index)
+            .ok_or_else(|| format!("invalid account index {}", index))
+    }
+
+    /// Submit an encoded extrinsic to the transaction pool, returning its hash.
+    pub fn submit_extrinsic(&self, extrinsic: &[u8]) -> Result<String, String> {
+        let extrinsic = format!("0x{}", hex::encode(extrinsic));
+        let hash = self.call("author_submitExtrinsic", json!([extrinsic]))?;
+        Ok(hash.as_str().unwrap_or_default().to_owned())
+    }
+}
+
+fn from_hex(value: &Value) -> Result<Vec<u8>, String> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| format!("expected a hex string, got {}", value))?;
+    hex::decode(s.trim_start_matches("0x")).map_err(|e| format!("invalid hex {:?}: {}", s, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_from_hex() {
+        assert_eq!(from_hex(&json!("0x0102")), Ok(vec![1, 2]));
+        assert_eq!(from_hex(&json!("0102")), Ok(vec![1, 2]));
+        from_hex(&json!("0x0")).unwrap_err();
+        from_hex(&json!(1)).unwrap_err();
+    }
+}
@@ -25,17 +25,21 @@
 use serde::{Deserialize, Serialize};
 use serde_json as json;
 use sr_primitives::{BuildStorage, ChildrenStorageOverlay, StorageOverlay};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use substrate_primitives::storage::{StorageData, StorageKey};
+use substrate_primitives::H256;
 use substrate_service::RuntimeGenesis;
 use substrate_telemetry::TelemetryEndpoints;
 
 #[derive(Clone)]
-struct GenesisSource<G>(G);
+struct GenesisSource<G>(Genesis<G>);
 
 impl<G: RuntimeGenesis> GenesisSource<G> {
     fn resolve(self) -> Genesis<G> {
-        Genesis::Runtime(self.0)
+        self.0
     }
 }
 
@@ -61,7 +65,7 @@ impl<G: RuntimeGenesis> BuildStorage for ChainSpec<G> {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 enum Genesis<G> {
@@ -82,6 +86,32 @@ struct ChainSpecFile {
     pub protocol_id: Option<String>,
     pub consensus_engine: Option<String>,
     pub properties: Option<Properties>,
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// Overrides to block import that operators can ship through the chainspec alone, e.g. after an
+/// incident.
+///
+/// The substrate binary at 870b976 ignores both: they only take effect with a node that reads
+/// them from the chainspec, like those of later substrate versions do. Until then they are carried
+/// through, so that specs written for such nodes load and round-trip unchanged.
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+    /// Blocks, by number and hash, that a node reading them treats as canonical.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_blocks: Option<Vec<(u64, H256)>>,
+    /// Hashes of blocks that a node reading them never imports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bad_blocks: Option<BTreeSet<H256>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Container<G> {
+    #[serde(flatten)]
+    spec: ChainSpecFile,
+    genesis: Genesis<G>,
 }
 
 /// Arbitrary properties defined in chain spec as a JSON object
@@ -105,6 +135,7 @@ impl<G> ChainSpec<G> {
         protocol_id: Option<&str>,
         consensus_engine: Option<&str>,
         properties: Option<Properties>,
+        extensions: Extensions,
     ) -> Self {
         let spec = ChainSpecFile {
             name: name.to_owned(),
@@ -114,27 +145,76 @@ impl<G> ChainSpec<G> {
             protocol_id: protocol_id.map(str::to_owned),
             consensus_engine: consensus_engine.map(str::to_owned),
             properties,
+            extensions,
         };
         ChainSpec {
             spec,
-            genesis: GenesisSource(genesis),
+            genesis: GenesisSource(Genesis::Runtime(genesis)),
         }
     }
 
+    pub fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    pub fn id(&self) -> &str {
+        &self.spec.id
+    }
+
     pub fn protocol_id(&self) -> Option<&str> {
         self.spec.protocol_id.as_ref().map(|x| &**x)
     }
+
+    pub fn boot_nodes(&self) -> &[String] {
+        &self.spec.boot_nodes
+    }
+
+    /// Leave out the boot nodes, e.g. of a chainspec handed to nodes of a private deployment.
+    pub fn clear_boot_nodes(&mut self) {
+        self.spec.boot_nodes.clear();
+    }
+
+    /// Properties of the chain, empty if none were set.
+    pub fn properties(&self) -> Properties {
+        self.spec.properties.clone().unwrap_or_default()
+    }
+
+    pub fn extensions(&self) -> &Extensions {
+        &self.spec.extensions
+    }
 }
 
 impl<G: RuntimeGenesis> ChainSpec<G> {
+    /// The genesis config, unless the genesis is raw storage.
+    pub fn genesis_config(&self) -> Option<&G> {
+        match &self.genesis.0 {
+            Genesis::Runtime(config) => Some(config),
+            Genesis::Raw(..) => None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn genesis_config_mut(&mut self) -> Option<&mut G> {
+        match &mut self.genesis.0 {
+            Genesis::Runtime(config) => Some(config),
+            Genesis::Raw(..) => None,
+        }
+    }
+
+    /// Parse a json file, as output by `into_json`, into a `ChainSpec`.
+    pub fn from_json_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Error opening spec file {}: {}", path.display(), e))?;
+        let container: Container<G> = json::from_reader(BufReader::new(file))
+            .map_err(|e| format!("Error parsing spec file {}: {}", path.display(), e))?;
+        Ok(ChainSpec {
+            spec: container.spec,
+            genesis: GenesisSource(container.genesis),
+        })
+    }
+
     /// Dump to json string.
     pub fn into_json(self, raw: bool) -> Result<String, String> {
-        #[derive(Serialize, Deserialize)]
-        struct Container<G> {
-            #[serde(flatten)]
-            spec: ChainSpecFile,
-            genesis: Genesis<G>,
-        };
         let genesis = match (raw, self.genesis.resolve()) {
             (true, Genesis::Runtime(g)) => {
                 let storage = g.build_storage()?;
@@ -0,0 +1,147 @@
+//! Ss58 addresses, as shown to and entered by users.
+//!
+//! Wallets render addresses with the prefix from the chainspec's "ss58Format" property. Addresses
+//! with the generic substrate prefix are still accepted, so keys written down before the project
+//! prefix existed stay usable.
+//!
+//! Keys deserialize from addresses with the generic prefix only, so fields users write addresses
+//! in, e.g. in genesis config files, deserialize with the functions here instead.
+
+use blake2_rfc::blake2b::blake2b;
+use node_template_runtime::AccountId;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use substrate_primitives::Public;
+
+/// Address prefix of this project's networks.
+pub const SS58_PREFIX: u8 = 22;
+/// Prefix used by substrate tooling when a chain does not pick its own.
+pub const GENERIC_PREFIX: u8 = 42;
+
+const CHECKSUM_PREIMAGE_PREFIX: &[u8] = b"SS58PRE";
+const CHECKSUM_LEN: usize = 2;
+
+/// Render an account as an address with the project prefix.
+pub fn to_ss58(account: &AccountId) -> String {
+    encode(SS58_PREFIX, account)
+}
+
+/// Parse an address with either the project or the generic prefix.
+pub fn from_ss58(address: &str) -> Result<AccountId, String> {
+    let (prefix, account) = decode(address)?;
+    if prefix != SS58_PREFIX && prefix != GENERIC_PREFIX {
+        return Err(format!(
+            "address {:?} has prefix {}, expected {} or {}",
+            address, prefix, SS58_PREFIX, GENERIC_PREFIX
+        ));
+    }
+    Ok(account)
+}
+
+/// Parse a public key, e.g. a session key, written as an address with either prefix.
+pub fn public_from_ss58<P: Public>(address: &str) -> Result<P, String> {
+    from_ss58(address).map(|account| P::from_slice(account.as_ref()))
+}
+
+/// Deserialize a key written as an address with either prefix.
+pub fn deserialize<'de, D, P>(deserializer: D) -> Result<P, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Public,
+{
+    public_from_ss58(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+/// Deserialize an optional key written as an address with either prefix.
+pub fn deserialize_option<'de, D, P>(deserializer: D) -> Result<Option<P>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Public,
+{
+    deserialize(deserializer).map(Some)
+}
+
+/// Deserialize keys written as addresses with either prefix.
+pub fn deserialize_vec<'de, D, P>(deserializer: D) -> Result<Vec<P>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: Public,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|address| public_from_ss58(address).map_err(D::Error::custom))
+        .collect()
+}
+
+/// Parse an address with any prefix, returning the prefix with the account.
+pub fn decode(address: &str) -> Result<(u8, AccountId), String> {
+    let data = bs58::decode(address)
+        .into_vec()
+        .map_err(|e| format!("address {:?} is not base58: {}", address, e))?;
+    if data.len() != 1 + 32 + CHECKSUM_LEN {
+        return Err(format!("address {:?} has the wrong length", address));
+    }
+    let (body, checksum) = data.split_at(1 + 32);
+    if ss58_hash(body)[..CHECKSUM_LEN] != *checksum {
+        return Err(format!("address {:?} has an invalid checksum", address));
+    }
+    let mut raw = [0u8; 32];
+    raw.copy_from_slice(&body[1..]);
+    Ok((body[0], AccountId::from_raw(raw)))
+}
+
+/// Render an account as an address with any prefix.
+pub fn encode(prefix: u8, account: &AccountId) -> String {
+    let mut data = vec![prefix];
+    data.extend_from_slice(account.as_ref());
+    let checksum = ss58_hash(&data);
+    data.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+    bs58::encode(data).into_string()
+}
+
+fn ss58_hash(data: &[u8]) -> Vec<u8> {
+    let mut preimage = CHECKSUM_PREIMAGE_PREFIX.to_vec();
+    preimage.extend_from_slice(data);
+    blake2b(64, &[], &preimage).as_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+
+    #[test]
+    fn t_generic_prefix() {
+        let alice = Keyring::Alice.account();
+        assert_eq!(encode(GENERIC_PREFIX, &alice), alice.to_string());
+        assert_eq!(
+            from_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            Ok(alice)
+        );
+    }
+
+    #[test]
+    fn t_roundtrip() {
+        for keyring in Keyring::ALL.iter() {
+            let account = keyring.account();
+            let address = to_ss58(&account);
+            assert_ne!(address, account.to_string());
+            assert_eq!(from_ss58(&address), Ok(account));
+        }
+    }
+
+    #[test]
+    fn t_invalid() {
+        let alice = Keyring::Alice.account();
+        from_ss58("").unwrap_err();
+        from_ss58("0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+            .unwrap_err();
+        // another network's prefix
+        from_ss58(&encode(0, &alice)).unwrap_err();
+        assert_eq!(decode(&encode(0, &alice)), Ok((0, alice.clone())));
+        // flipped checksum byte
+        let mut data = bs58::decode(to_ss58(&alice)).into_vec().unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        from_ss58(&bs58::encode(data).into_string()).unwrap_err();
+    }
+}
@@ -0,0 +1,104 @@
+//! A test helper applying signed extrinsics to the genesis state of the local testnet spec: dev
+//! keyring accounts sign extrinsics that are applied as a block author applies them, signed
+//! extensions and fees included.
+//!
+//! This is not a network. Running nodes, and awaiting finality, takes a node service, which this
+//! repository doesn't build (see "Node Service" in OVERVIEW.md), so no blocks are authored or
+//! finalized here. Extrinsics apply one after another, as if in block 1.
+
+use crate::chain_spec::{Chain, TestnetOptions, DEFAULT_ENDOWMENT};
+use crate::keyring::{signer_from_suri, Keyring};
+use crate::upgrade::{sign, SigningContext};
+use node_template_runtime::{Call, Event, Executive, Runtime, VERSION};
+use sr_primitives::{ApplyResult, BuildStorage};
+use substrate_primitives::{Blake2Hasher, Pair};
+
+type System = system::Module<Runtime>;
+
+pub struct TestChain {
+    ext: sr_io::TestExternalities<Blake2Hasher>,
+}
+
+impl TestChain {
+    /// A chain in the genesis state of the local testnet spec, with Bob and Charlie endowed to pay
+    /// fees with, as Alice is, being the treasury.
+    pub fn local() -> Self {
+        let endowments = [Keyring::Bob, Keyring::Charlie]
+            .iter()
+            .map(|key| (key.account(), DEFAULT_ENDOWMENT))
+            .collect();
+        let spec = Chain::Local {
+            options: TestnetOptions {
+                endowments,
+                ..TestnetOptions::default()
+            },
+        }
+        .generate()
+        .unwrap();
+        let mut ext: sr_io::TestExternalities<Blake2Hasher> = spec.build_storage().unwrap().into();
+        sr_io::with_externalities(&mut ext, || System::set_block_number(1));
+        TestChain { ext }
+    }
+
+    /// Run `f` on the state of the chain, e.g. to read storage.
+    pub fn execute_with<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        sr_io::with_externalities(&mut self.ext, f)
+    }
+
+    /// Apply `call` signed by `signer` with its next nonce.
+    pub fn submit(&mut self, signer: Keyring, call: Call) -> ApplyResult {
+        let pair = signer_from_suri(&format!("//{}", signer.name())).unwrap();
+        self.execute_with(|| {
+            let context = SigningContext {
+                genesis_hash: System::block_hash(0),
+                spec_version: VERSION.spec_version,
+                nonce: System::account_nonce(&pair.public()),
+            };
+            Executive::apply_extrinsic(sign(call, &pair, context))
+        })
+    }
+
+    /// Events deposited so far.
+    pub fn events(&mut self) -> Vec<Event> {
+        self.execute_with(|| System::events().into_iter().map(|r| r.event).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sr_primitives::ApplyOutcome;
+
+    type Balances = balances::Module<Runtime>;
+
+    #[test]
+    fn t_submit() {
+        let mut chain = TestChain::local();
+        let dave = Keyring::Dave.account();
+        let before = chain.execute_with(|| Balances::free_balance(&dave));
+        for _ in 0..2 {
+            let call = Call::Balances(balances::Call::transfer(dave.clone().into(), 1_000));
+            assert_eq!(
+                chain.submit(Keyring::Alice, call),
+                Ok(ApplyOutcome::Success)
+            );
+        }
+        chain.execute_with(|| {
+            assert_eq!(Balances::free_balance(&dave), before + 2_000);
+            assert_eq!(System::account_nonce(&Keyring::Alice.account()), 2);
+        });
+        let transfers = chain
+            .events()
+            .into_iter()
+            .filter(|event| match event {
+                Event::balances(balances::RawEvent::Transfer(..)) => true,
+                _ => false,
+            })
+            .count();
+        assert_eq!(transfers, 2);
+
+        // Ferdie has no funds to pay fees with
+        let call = Call::Balances(balances::Call::transfer(Keyring::Eve.account().into(), 1));
+        assert!(chain.submit(Keyring::Ferdie, call).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! The `watch-transfers` command: the native, asset and erc20 transfers from or to an account, as
+//! blocks are finalized.
+//!
+//! Nodes have no subscription for them, so the finalized head is polled over rpc and the events
+//! of each new finalized block are read from the system module's storage, decoded with the types
+//! of the runtime chaingen was built with.
+
+use crate::rpc::Rpc;
+use crate::ss58::to_ss58;
+use codec::Decode;
+use node_template_runtime::opaque::Header;
+use node_template_runtime::{AccountId, Balance, BlockNumber, Event};
+use serde_json::json;
+use sr_primitives::traits::Header as _;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+use substrate_primitives::{twox_128, H256};
+
+/// How often the finalized head is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+pub type EventRecord = system::EventRecord<Event, H256>;
+
+/// What a transfer moved.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Token {
+    Native,
+    Asset(u32),
+    Erc20(u32),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Transfer {
+    pub token: Token,
+    pub from: AccountId,
+    pub to: AccountId,
+    pub value: Balance,
+    /// Whether an admin forced the transfer.
+    pub forced: bool,
+}
+
+impl fmt::Display for Transfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.token {
+            Token::Native => write!(f, "native")?,
+            Token::Asset(id) => write!(f, "asset {}", id)?,
+            Token::Erc20(id) => write!(f, "erc20 {}", id)?,
+        }
+        write!(
+            f,
+            ": {} -> {}, {}",
+            to_ss58(&self.from),
+            to_ss58(&self.to),
+            self.value
+        )?;
+        if self.forced {
+            write!(f, " (forced)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The transfer `event` records, if it records one.
+pub fn transfer_of(event: &Event) -> Option<Transfer> {
+    let transfer = |token, from: &AccountId, to: &AccountId, value, forced| Transfer {
+        token,
+        from: from.clone(),
+        to: to.clone(),
+        value,
+        forced,
+    };
+    match event {
+        Event::balances(balances::RawEvent::Transfer(from, to, value, _fees)) => {
+            Some(transfer(Token::Native, from, to, *value, false))
+        }
+        Event::assets(assets::RawEvent::Transfer(id, from, to, value)) => {
+            Some(transfer(Token::Asset(*id), from, to, *value, false))
+        }
+        Event::assets(assets::RawEvent::ForceTransfer(id, from, to, value)) => {
+            Some(transfer(Token::Asset(*id), from, to, *value, true))
+        }
+        Event::erc20(erc20::RawEvent::Transfer(id, from, to, value)) => {
+            Some(transfer(Token::Erc20(*id), from, to, *value, false))
+        }
+        Event::erc20(erc20::RawEvent::ForceTransfer(id, from, to, value)) => {
+            Some(transfer(Token::Erc20(*id), from, to, *value, true))
+        }
+        _ => None,
+    }
+}
+
+/// The transfers among `events` from or to `account`.
+pub fn transfers_of(events: &[EventRecord], account: &AccountId) -> Vec<Transfer> {
+    events
+        .iter()
+        .filter_map(|record| transfer_of(&record.event))
+        .filter(|transfer| transfer.from == *account || transfer.to == *account)
+        .collect()
+}
+
+/// Number of the block the node last finalized.
+pub fn finalized_number(rpc: &Rpc) -> Result<BlockNumber, String> {
+    let hash = rpc.call("chain_getFinalizedHead", json!([]))?;
+    let header = rpc.call("chain_getHeader", json!([hash]))?;
+    let header: Header =
+        serde_json::from_value(header).map_err(|e| format!("invalid header: {}", e))?;
+    Ok(*header.number())
+}
+
+/// Events of block `number` of the best chain.
+pub fn events_at(rpc: &Rpc, number: BlockNumber) -> Result<Vec<EventRecord>, String> {
+    let hash = rpc.call("chain_getBlockHash", json!([number]))?;
+    let hash: H256 =
+        serde_json::from_value(hash).map_err(|_| format!("the node has no block {}", number))?;
+    match rpc.storage_at(&twox_128(b"System Events"), Some(hash))? {
+        Some(events) => Vec::<EventRecord>::decode(&mut &events[..])
+            .map_err(|_| format!("events of block {} are not of this runtime", number)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Print the transfers from or to `account` in each block finalized from block `from` on, or
+/// from the next finalized block. Runs until the node can't be reached.
+pub fn watch_transfers(
+    rpc: &Rpc,
+    account: &AccountId,
+    from: Option<BlockNumber>,
+) -> Result<String, String> {
+    let mut next = match from {
+        Some(from) => from,
+        None => finalized_number(rpc)? + 1,
+    };
+    loop {
+        let finalized = finalized_number(rpc)?;
+        while next <= finalized {
+            for transfer in transfers_of(&events_at(rpc, next)?, account) {
+                println!("block {}: {}", next, transfer);
+            }
+            next += 1;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyring::Keyring;
+    use codec::Encode;
+
+    fn record(event: Event) -> EventRecord {
+        EventRecord {
+            phase: system::Phase::ApplyExtrinsic(1),
+            event,
+            topics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn t_transfers_of() {
+        let alice = Keyring::Alice.account();
+        let bob = Keyring::Bob.account();
+        let charlie = Keyring::Charlie.account();
+        let events = vec![
+            record(Event::balances(balances::RawEvent::Transfer(
+                alice.clone(),
+                bob.clone(),
+                10,
+                1,
+            ))),
+            record(Event::erc20(erc20::RawEvent::Transfer(
+                0,
+                bob.clone(),
+                charlie.clone(),
+                20,
+            ))),
+            record(Event::assets(assets::RawEvent::ForceTransfer(
+                3,
+                charlie.clone(),
+                alice.clone(),
+                30,
+            ))),
+            record(Event::erc20(erc20::RawEvent::TokenFrozen(0))),
+        ];
+        // as read from storage
+        let events = Vec::<EventRecord>::decode(&mut &events.encode()[..]).unwrap();
+
+        let transfers = transfers_of(&events, &alice);
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].token, Token::Native);
+        assert_eq!(transfers[0].value, 10);
+        assert_eq!(transfers[1].token, Token::Asset(3));
+        assert!(transfers[1].forced);
+        assert_eq!(
+            transfers[1].to_string(),
+            format!(
+                "asset 3: {} -> {}, 30 (forced)",
+                to_ss58(&charlie),
+                to_ss58(&alice)
+            )
+        );
+
+        let transfers = transfers_of(&events, &charlie);
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].token, Token::Erc20(0));
+        assert!(transfers_of(&events, &Keyring::Dave.account()).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+//! Runtime upgrades submitted from the command line.
+//!
+//! The sudo key signs `sudo(set_code(<wasm>))`, which is submitted over rpc. The node executes
+//! the new runtime from the block after the one including the call, so the upgrade is done once
+//! the runtime version reported by the node changes.
+
+use crate::rpc::Rpc;
+use codec::{Decode, Encode};
+use node_template_runtime::{AccountId, Call, Index, SignedExtra, UncheckedExtrinsic};
+use sr_primitives::generic::Era;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use substrate_primitives::{blake2_256, sr25519, Pair, H256};
+
+/// How often, and how many times, the runtime version is checked after submitting an upgrade.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+const POLLS: u32 = 40;
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// What a signed extrinsic commits to besides its call.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SigningContext {
+    pub genesis_hash: H256,
+    pub spec_version: u32,
+    pub nonce: Index,
+}
+
+impl SigningContext {
+    /// The context of the next extrinsic of `account` on the chain a node follows.
+    pub fn fetch(rpc: &Rpc, account: &AccountId) -> Result<Self, String> {
+        let nonce = match rpc.storage(&nonce_key(account))? {
+            Some(nonce) => Index::decode(&mut &nonce[..])
+                .map_err(|_| "invalid account nonce in storage".to_string())?,
+            None => 0,
+        };
+        Ok(SigningContext {
+            genesis_hash: rpc.genesis_hash()?,
+            spec_version: rpc.spec_version()?,
+            nonce,
+        })
+    }
+}
+
+/// Storage key of the nonce of `account`, as `decl_storage` keys the system module's map.
+fn nonce_key(account: &AccountId) -> [u8; 32] {
+    let mut key = b"System AccountNonce".to_vec();
+    account.encode_to(&mut key);
+    blake2_256(&key)
+}
+
+/// Read a compiled runtime, e.g. the compact wasm the runtime build leaves under target/.
+pub fn read_wasm(path: &Path) -> Result<Vec<u8>, String> {
+    let code = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if !code.starts_with(WASM_MAGIC) {
+        return Err(format!("{} is not a wasm binary", path.display()));
+    }
+    Ok(code)
+}
+
+/// The call the sudo key signs to replace the runtime with `code`.
+pub fn set_code_call(code: Vec<u8>) -> Call {
+    Call::Sudo(sudo::Call::sudo(Box::new(Call::System(
+        system::Call::set_code(code),
+    ))))
+}
+
+/// `call` signed by `signer`, immortal and paying no tip.
+pub fn sign(call: Call, signer: &sr25519::Pair, context: SigningContext) -> UncheckedExtrinsic {
+    let extra: SignedExtra = (
+        system::CheckVersion::new(),
+        system::CheckGenesis::new(),
+        system::CheckEra::from(Era::Immortal),
+        system::CheckNonce::from(context.nonce),
+        system::CheckWeight::new(),
+        balances::TakeFees::from(0),
+        whitelist::CheckWhitelist::new(),
+        tx_pause::CheckPaused::new(),
+        rate_limit::CheckRateLimit::new(),
+    );
+    // what `SignedExtra` adds to the signed payload: the version, the genesis hash, and the hash
+    // of the block an immortal era starts at, which is also the genesis
+    let additional = (
+        context.spec_version,
+        context.genesis_hash,
+        context.genesis_hash,
+        (),
+        (),
+        (),
+        (),
+        (),
+        (),
+    );
+    let signature = (&call, &extra, &additional).using_encoded(|payload| {
+        // the runtime verifies long payloads by their hash
+        if payload.len() > 256 {
+            signer.sign(&blake2_256(payload))
+        } else {
+            signer.sign(payload)
+        }
+    });
+    let signer: AccountId = signer.public();
+    UncheckedExtrinsic::new_signed(call, signer.into(), signature.into(), extra)
+}
+
+/// Submit `sudo(set_code(<wasm>))` signed by `sudo_key` to the node at `rpc`, and wait for the
+/// runtime version to change.
+pub fn submit_upgrade(rpc: &Rpc, wasm: &Path, sudo_key: &sr25519::Pair) -> Result<String, String> {
+    let code = read_wasm(wasm)?;
+    let context = SigningContext::fetch(rpc, &sudo_key.public())?;
+    let extrinsic = sign(set_code_call(code), sudo_key, context);
+    let hash = rpc.submit_extrinsic(&extrinsic.encode())?;
+    for _ in 0..POLLS {
+        thread::sleep(POLL_INTERVAL);
+        let spec_version = rpc.spec_version()?;
+        if spec_version != context.spec_version {
+            return Ok(format!(
+                "submitted {}\nruntime upgraded: spec_version {} -> {}",
+                hash, context.spec_version, spec_version
+            ));
+        }
+    }
+    // sudo reports success even when set_code fails, e.g. when the key is not the sudo key
+    Err(format!(
+        "submitted {}, but spec_version is still {}; check the Sudid event of the block \
+         including it",
+        hash, context.spec_version
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_primitives::crypto::DEV_PHRASE;
+
+    #[test]
+    fn t_sign() {
+        let alice = sr25519::Pair::from_string(&format!("{}//Alice", DEV_PHRASE), None).unwrap();
+        let context = SigningContext {
+            genesis_hash: H256::repeat_byte(1),
+            spec_version: 7,
+            nonce: 3,
+        };
+        let call = set_code_call(WASM_MAGIC.to_vec());
+        let encoded = sign(call.clone(), &alice, context).encode();
+        let extrinsic = UncheckedExtrinsic::decode(&mut &encoded[..]).unwrap();
+        assert_eq!(extrinsic.function, call);
+        let (_, _, extra) = extrinsic.signature.unwrap();
+        assert_eq!(extra.3, system::CheckNonce::from(3));
+    }
+
+    #[test]
+    fn t_read_wasm() {
+        let dir = std::env::temp_dir();
+        let wasm = dir.join("t_read_wasm.wasm");
+        std::fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+        assert_eq!(read_wasm(&wasm).unwrap(), b"\0asm\x01\0\0\0".to_vec());
+        std::fs::write(&wasm, b"not wasm").unwrap();
+        read_wasm(&wasm).unwrap_err();
+        read_wasm(&dir.join("t_read_wasm_missing.wasm")).unwrap_err();
+    }
+}
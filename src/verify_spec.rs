@@ -0,0 +1,202 @@
+//! Checks of a chainspec before launching a chain from it, e.g. one operators edited by hand.
+//!
+//! Loading the spec checks that a human readable genesis deserializes into this runtime's
+//! `GenesisConfig`. The checks then run on the genesis storage, so raw specs are verified too,
+//! except for the balances and authorities listed in a human readable genesis, which raw specs
+//! no longer list.
+
+use crate::chain_spec::{genesis_hash_of_storage, validate_wasm};
+use crate::serializable_genesis::ChainSpec;
+use crate::ss58::to_ss58;
+use node_template_runtime::{Balance, GenesisConfig, Runtime, EXISTENTIAL_DEPOSIT, WASM_BINARY};
+use sr_primitives::BuildStorage;
+use std::collections::BTreeSet;
+use std::fmt;
+use substrate_primitives::storage::well_known_keys;
+use substrate_primitives::{Blake2Hasher, H256};
+
+type Balances = balances::Module<Runtime>;
+
+/// What a verified chainspec launches.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Verified {
+    pub genesis_hash: H256,
+    pub raw: bool,
+    /// Size of the genesis runtime.
+    pub wasm_len: usize,
+    /// Whether the genesis runtime is the one chaingen was built with.
+    pub builtin_runtime: bool,
+    pub authorities: usize,
+    pub total_issuance: Balance,
+}
+
+impl fmt::Display for Verified {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "genesis: {}",
+            if self.raw { "raw" } else { "human readable" }
+        )?;
+        write!(f, "runtime: {} bytes", self.wasm_len)?;
+        if self.builtin_runtime {
+            writeln!(f, ", the built-in runtime")?;
+        } else {
+            writeln!(f, ", not the built-in runtime")?;
+        }
+        writeln!(f, "babe authorities: {}", self.authorities)?;
+        writeln!(f, "total issuance: {}", self.total_issuance)?;
+        write!(f, "genesis hash: {:?}", self.genesis_hash)
+    }
+}
+
+/// Check `spec`, reporting every failed check at once.
+pub fn verify_spec(spec: ChainSpec<GenesisConfig>) -> Result<Verified, String> {
+    let mut failed = Vec::new();
+    let raw = spec.genesis_config().is_none();
+    if let Some(config) = spec.genesis_config() {
+        check_genesis_config(config, &mut failed);
+    }
+    // some modules panic building the storage of an unsound config
+    if !failed.is_empty() {
+        return Err(failed.join("\n"));
+    }
+
+    let storage = spec.build_storage()?;
+    let genesis_hash = genesis_hash_of_storage(storage.clone());
+    let wasm = storage
+        .0
+        .get(well_known_keys::CODE)
+        .cloned()
+        .unwrap_or_default();
+    if let Err(e) = validate_wasm(&wasm) {
+        failed.push(e.to_string());
+    }
+    let mut ext: sr_io::TestExternalities<Blake2Hasher> = storage.into();
+    let (authorities, total_issuance) = sr_io::with_externalities(&mut ext, || {
+        (
+            babe::Module::<Runtime>::authorities().len(),
+            Balances::total_issuance(),
+        )
+    });
+    if authorities == 0 {
+        failed.push("there are no babe authorities, no block could be authored".into());
+    }
+    if total_issuance == 0 {
+        failed.push("no account holds a balance, none could pay fees".into());
+    }
+
+    if !failed.is_empty() {
+        return Err(failed.join("\n"));
+    }
+    Ok(Verified {
+        genesis_hash,
+        raw,
+        wasm_len: wasm.len(),
+        builtin_runtime: wasm == WASM_BINARY,
+        authorities,
+        total_issuance,
+    })
+}
+
+fn check_genesis_config(config: &GenesisConfig, failed: &mut Vec<String>) {
+    let balances = config
+        .balances
+        .as_ref()
+        .map(|b| b.balances.clone())
+        .unwrap_or_default();
+    let mut endowed = BTreeSet::new();
+    for (account, balance) in &balances {
+        if !endowed.insert(account.clone()) {
+            failed.push(format!("{} is endowed more than once", to_ss58(account)));
+        }
+        if *balance < EXISTENTIAL_DEPOSIT {
+            failed.push(format!(
+                "{} is endowed with {}, below the existential deposit of {}",
+                to_ss58(account),
+                balance,
+                EXISTENTIAL_DEPOSIT
+            ));
+        }
+    }
+    let total = balances
+        .iter()
+        .try_fold(0 as Balance, |total, (_, balance)| {
+            total.checked_add(*balance)
+        });
+    if total.is_none() {
+        failed.push("the endowed balances overflow the total issuance".into());
+    }
+
+    let authorities = config
+        .session
+        .as_ref()
+        .map(|s| &s.keys[..])
+        .unwrap_or_default();
+    let mut keys = Vec::new();
+    for (account, session_keys) in authorities {
+        if !endowed.contains(account) {
+            failed.push(format!(
+                "authority {} has no balance to bond",
+                to_ss58(account)
+            ));
+        }
+        if keys.contains(&session_keys.babe) {
+            failed.push(format!(
+                "authority {} shares its babe key with another authority",
+                to_ss58(account)
+            ));
+        }
+        keys.push(session_keys.babe.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{genesis_hash, Chain, TestnetOptions};
+
+    fn local() -> ChainSpec<GenesisConfig> {
+        Chain::Local {
+            options: TestnetOptions::default(),
+        }
+        .generate()
+        .unwrap()
+    }
+
+    #[test]
+    fn t_verify_spec() {
+        let verified = verify_spec(local()).unwrap();
+        assert_eq!(verified.genesis_hash, genesis_hash(local()).unwrap());
+        assert!(!verified.raw);
+        assert!(verified.builtin_runtime);
+        assert_eq!(verified.authorities, 2);
+        assert!(verified.total_issuance > 0);
+    }
+
+    #[test]
+    fn t_verify_raw_spec() {
+        let path = std::env::temp_dir().join("substrate-warmup-t_verify_raw_spec.json");
+        std::fs::write(&path, local().into_json(true).unwrap()).unwrap();
+        let verified = verify_spec(ChainSpec::from_json_file(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(verified.raw);
+        assert_eq!(verified.genesis_hash, genesis_hash(local()).unwrap());
+    }
+
+    #[test]
+    fn t_verify_edited_spec() {
+        let mut spec = local();
+        let config = spec.genesis_config_mut().unwrap();
+        let balances = &mut config.balances.as_mut().unwrap().balances;
+        let first = balances[0].0.clone();
+        balances.push((first, EXISTENTIAL_DEPOSIT - 1));
+        let keys = &mut config.session.as_mut().unwrap().keys;
+        keys[1].1.babe = keys[0].1.babe.clone();
+        let failed = verify_spec(spec).unwrap_err();
+        let failed: Vec<&str> = failed.lines().collect();
+        assert_eq!(failed.len(), 3, "{:?}", failed);
+        assert!(failed[0].contains("endowed more than once"));
+        assert!(failed[1].contains("below the existential deposit"));
+        assert!(failed[2].contains("shares its babe key"));
+    }
+}